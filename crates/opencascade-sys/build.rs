@@ -9,6 +9,7 @@ const OCCT_LIBS: &[&str] = &[
     "TKernel",
     "TKDE",
     "TKFeat",
+    "TKHLR",
     "TKGeomBase",
     "TKG2d",
     "TKG3d",
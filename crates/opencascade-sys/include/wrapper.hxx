@@ -1,11 +1,15 @@
 #include "rust/cxx.h"
 #include <Adaptor3d_Curve.hxx>
+#include <BOPAlgo_CellsBuilder.hxx>
 #include <BOPAlgo_GlueEnum.hxx>
+#include <BRepAdaptor_CompCurve.hxx>
 #include <BRepAdaptor_Curve.hxx>
+#include <BRepAdaptor_Surface.hxx>
 #include <BRepAlgoAPI_Common.hxx>
 #include <BRepAlgoAPI_Cut.hxx>
 #include <BRepAlgoAPI_Fuse.hxx>
 #include <BRepAlgoAPI_Section.hxx>
+#include <BRepAlgoAPI_Splitter.hxx>
 #include <BRepBndLib.hxx>
 #include <BRepBuilderAPI_GTransform.hxx>
 #include <BRepBuilderAPI_MakeEdge.hxx>
@@ -14,12 +18,17 @@
 #include <BRepBuilderAPI_MakeSolid.hxx>
 #include <BRepBuilderAPI_MakeVertex.hxx>
 #include <BRepBuilderAPI_MakeWire.hxx>
+#include <BRepBuilderAPI_Sewing.hxx>
 #include <BRepBuilderAPI_Copy.hxx>
 #include <BRepBuilderAPI_Transform.hxx>
+#include <BRepCheck_Analyzer.hxx>
+#include <BRepFill.hxx>
+#include <BRepFill_TypeOfContact.hxx>
 #include <BRepExtrema_DistShapeShape.hxx>
 #include <BRepExtrema_ShapeProximity.hxx>
 #include <BRepFeat_MakeCylindricalHole.hxx>
 #include <BRepFeat_MakeDPrism.hxx>
+#include <BRepFeat_MakePrism.hxx>
 #include <BRepFilletAPI_MakeChamfer.hxx>
 #include <BRepFilletAPI_MakeFillet.hxx>
 #include <BRepFilletAPI_MakeFillet2d.hxx>
@@ -42,7 +51,9 @@
 #include <BRepPrimAPI_MakeSphere.hxx>
 #include <BRepPrimAPI_MakeTorus.hxx>
 #include <BRepTools.hxx>
+#include <BRepTools_History.hxx>
 #include <BRepTools_WireExplorer.hxx>
+#include <Extrema_ExtPC.hxx>
 #include <GCE2d_MakeSegment.hxx>
 #include <GCPnts_AbscissaPoint.hxx>
 #include <GCPnts_TangentialDeflection.hxx>
@@ -52,6 +63,9 @@
 #include <Geom2d_Ellipse.hxx>
 #include <Geom2d_TrimmedCurve.hxx>
 #include <GeomAPI_Interpolate.hxx>
+#include <HLRAlgo_Projector.hxx>
+#include <HLRBRep_Algo.hxx>
+#include <HLRBRep_HLRToShape.hxx>
 #include <GeomAPI_ProjectPointOnSurf.hxx>
 #include <GeomAbs_CurveType.hxx>
 #include <GeomAbs_JoinType.hxx>
@@ -64,6 +78,7 @@
 #include <GeomLProp_SLProps.hxx>
 #include <IGESControl_Reader.hxx>
 #include <IGESControl_Writer.hxx>
+#include <Interface_Static.hxx>
 #include <Law_Function.hxx>
 #include <Law_Interpol.hxx>
 #include <Message_ProgressRange.hxx>
@@ -75,27 +90,41 @@
 #include <STEPControl_Writer.hxx>
 #include <ShapeAnalysis_FreeBounds.hxx>
 #include <ShapeUpgrade_UnifySameDomain.hxx>
+#include <Standard_Failure.hxx>
 #include <Standard_Type.hxx>
 #include <StlAPI_Writer.hxx>
+#include <TColStd_Array1OfReal.hxx>
 #include <TColgp_Array1OfDir.hxx>
+#include <TColgp_Array1OfPnt.hxx>
 #include <TColgp_HArray1OfPnt.hxx>
 #include <TopAbs_ShapeEnum.hxx>
 #include <TopExp_Explorer.hxx>
 #include <TopTools_HSequenceOfShape.hxx>
+#include <TopTools_ListOfShape.hxx>
 #include <TopoDS.hxx>
+#include <TopoDS_Iterator.hxx>
 #include <TopoDS_Edge.hxx>
 #include <TopoDS_Face.hxx>
 #include <TopoDS_Shape.hxx>
+#include <TopoDS_TShape.hxx>
 #include <gp.hxx>
 #include <gp_Ax2.hxx>
 #include <gp_Ax3.hxx>
 #include <gp_Circ.hxx>
+#include <gp_Cone.hxx>
+#include <gp_Elips.hxx>
+#include <gp_Hypr.hxx>
 #include <gp_Lin.hxx>
+#include <gp_Mat.hxx>
+#include <gp_Parab.hxx>
 #include <gp_Pnt.hxx>
 #include <gp_Trsf.hxx>
 #include <gp_Vec.hxx>
 #include <gp_Quaternion.hxx>
+#include <ShapeBuild_ReShape.hxx>
 #include <ShapeFix_Shape.hxx>
+#include <ShapeFix_ShapeTolerance.hxx>
+#include <ShapeFix_Wire.hxx>
 
 // Generic template constructor
 template <typename T, typename... Args> std::unique_ptr<T> construct_unique(Args... args) {
@@ -107,6 +136,84 @@ template <typename T> std::unique_ptr<std::vector<T>> list_to_vector(const NColl
   return std::unique_ptr<std::vector<T>>(new std::vector<T>(list.begin(), list.end()));
 }
 
+// Boolean operations which never modify their arguments, so that shapes
+// sharing sub-shapes can safely be used from several threads at once.
+template <typename T> std::unique_ptr<T> non_destructive_boolean(const TopoDS_Shape &shape_1, const TopoDS_Shape &shape_2) {
+  auto operation = std::unique_ptr<T>(new T());
+
+  TopTools_ListOfShape arguments;
+  arguments.Append(shape_1);
+  TopTools_ListOfShape tools;
+  tools.Append(shape_2);
+
+  operation->SetArguments(arguments);
+  operation->SetTools(tools);
+  operation->SetNonDestructive(Standard_True);
+  operation->Build();
+
+  return operation;
+}
+
+inline std::unique_ptr<BRepAlgoAPI_Fuse> BRepAlgoAPI_Fuse_ctor(const TopoDS_Shape &shape_1, const TopoDS_Shape &shape_2) {
+  return non_destructive_boolean<BRepAlgoAPI_Fuse>(shape_1, shape_2);
+}
+
+inline std::unique_ptr<BRepAlgoAPI_Cut> BRepAlgoAPI_Cut_ctor(const TopoDS_Shape &shape_1, const TopoDS_Shape &shape_2) {
+  return non_destructive_boolean<BRepAlgoAPI_Cut>(shape_1, shape_2);
+}
+
+inline std::unique_ptr<BRepAlgoAPI_Common> BRepAlgoAPI_Common_ctor(const TopoDS_Shape &shape_1,
+                                                                   const TopoDS_Shape &shape_2) {
+  return non_destructive_boolean<BRepAlgoAPI_Common>(shape_1, shape_2);
+}
+
+inline std::unique_ptr<BRepAlgoAPI_Section> BRepAlgoAPI_Section_ctor(const TopoDS_Shape &shape_1,
+                                                                     const TopoDS_Shape &shape_2) {
+  return non_destructive_boolean<BRepAlgoAPI_Section>(shape_1, shape_2);
+}
+
+inline std::unique_ptr<BRepAlgoAPI_Splitter> BRepAlgoAPI_Splitter_ctor(const TopoDS_Shape &shape_1,
+                                                                       const TopoDS_Shape &shape_2) {
+  return non_destructive_boolean<BRepAlgoAPI_Splitter>(shape_1, shape_2);
+}
+
+// Split two shapes into the cells of their arrangement once, to then
+// collect several boolean results from the same cells.
+inline std::unique_ptr<BOPAlgo_CellsBuilder> BOPAlgo_CellsBuilder_ctor(const TopoDS_Shape &shape_1,
+                                                                       const TopoDS_Shape &shape_2) {
+  auto builder = std::unique_ptr<BOPAlgo_CellsBuilder>(new BOPAlgo_CellsBuilder());
+
+  builder->AddArgument(shape_1);
+  builder->AddArgument(shape_2);
+  builder->SetNonDestructive(Standard_True);
+  builder->Perform();
+
+  return builder;
+}
+
+// The cells which are in all of `take` and none of `avoid`.
+inline std::unique_ptr<TopoDS_Shape> BOPAlgo_CellsBuilder_cells(BOPAlgo_CellsBuilder &builder,
+                                                                const TopTools_ListOfShape &take,
+                                                                const TopTools_ListOfShape &avoid) {
+  builder.RemoveAllFromResult();
+  builder.AddToResult(take, avoid, 0, Standard_True);
+
+  // No cells at all is a null shape, which booleans fail on.
+  if (builder.Shape().IsNull()) {
+    TopoDS_Compound empty;
+    BRep_Builder().MakeCompound(empty);
+    return std::unique_ptr<TopoDS_Shape>(new TopoDS_Shape(empty));
+  }
+
+  return std::unique_ptr<TopoDS_Shape>(new TopoDS_Shape(builder.Shape()));
+}
+
+// Shape history of any algorithm with Modified/Generated/IsDeleted queries
+template <typename T>
+std::unique_ptr<BRepTools_History> BRepTools_History_from_algo(const TopTools_ListOfShape &arguments, T &algo) {
+  return std::unique_ptr<BRepTools_History>(new BRepTools_History(arguments, algo));
+}
+
 // Handles
 typedef opencascade::handle<Standard_Type> HandleStandardType;
 typedef opencascade::handle<Geom_Curve> HandleGeomCurve;
@@ -157,8 +264,14 @@ inline std::unique_ptr<HandleGeomPlane> new_HandleGeomPlane_from_HandleGeomSurfa
   return std::unique_ptr<HandleGeomPlane>(new opencascade::handle<Geom_Plane>(plane_handle));
 }
 
+// The number of handles to the TShape of `shape`, including its own.
+inline Standard_Integer TopoDS_Shape_share_count(const TopoDS_Shape &shape) {
+  return shape.IsNull() ? 0 : shape.TShape()->GetRefCount();
+}
+
 // Collections
 inline void shape_list_append_face(TopTools_ListOfShape &list, const TopoDS_Face &face) { list.Append(face); }
+inline void shape_list_append_shape(TopTools_ListOfShape &list, const TopoDS_Shape &shape) { list.Append(shape); }
 
 // Geometry
 inline const gp_Pnt &handle_geom_plane_location(const HandleGeomPlane &plane) { return plane->Location(); }
@@ -177,6 +290,21 @@ Geom_BezierCurve_to_handle(std::unique_ptr<Geom_BezierCurve> bezier_curve) {
   return std::unique_ptr<HandleGeomBezierCurve>(new HandleGeomBezierCurve(bezier_curve.release()));
 }
 
+inline std::unique_ptr<HandleGeomCurve> Geom_BezierCurve_rational_quadratic(const gp_Pnt &p0, const gp_Pnt &p1,
+                                                                             const gp_Pnt &p2, double weight) {
+  TColgp_Array1OfPnt poles(1, 3);
+  poles.SetValue(1, p0);
+  poles.SetValue(2, p1);
+  poles.SetValue(3, p2);
+
+  TColStd_Array1OfReal weights(1, 3);
+  weights.SetValue(1, 1.0);
+  weights.SetValue(2, weight);
+  weights.SetValue(3, 1.0);
+
+  return std::unique_ptr<HandleGeomCurve>(new opencascade::handle<Geom_Curve>(new Geom_BezierCurve(poles, weights)));
+}
+
 inline std::unique_ptr<HandleGeomSurface> cylinder_to_surface(const HandleGeom_CylindricalSurface &cylinder_handle) {
   return std::unique_ptr<HandleGeomSurface>(new opencascade::handle<Geom_Surface>(cylinder_handle));
 }
@@ -235,6 +363,14 @@ inline std::unique_ptr<gp_Pnt> BRepAdaptor_Curve_value(const BRepAdaptor_Curve &
   return std::unique_ptr<gp_Pnt>(new gp_Pnt(curve.Value(U)));
 }
 
+inline std::unique_ptr<gp_Pnt> BRepAdaptor_CompCurve_value(const BRepAdaptor_CompCurve &curve, const Standard_Real U) {
+  return std::unique_ptr<gp_Pnt>(new gp_Pnt(curve.Value(U)));
+}
+
+inline Standard_Real BRepAdaptor_CompCurve_length(const BRepAdaptor_CompCurve &curve) {
+  return GCPnts_AbscissaPoint::Length(curve);
+}
+
 inline std::unique_ptr<gp_Circ> GetCircle(const BRepAdaptor_Curve &curve) {
     return std::unique_ptr<gp_Circ>(new gp_Circ(curve.Circle()));
 }
@@ -247,6 +383,32 @@ inline std::unique_ptr<gp_Pnt> GetCenterpoint(const gp_Circ &circle) {
     return std::unique_ptr<gp_Pnt>(new gp_Pnt(circle.Location()));
 }
 
+inline Standard_Real BRepAdaptor_Surface_cone_semi_angle(const BRepAdaptor_Surface &surface) {
+    return surface.Cone().SemiAngle();
+}
+
+inline std::unique_ptr<gp_Ax3> BRepAdaptor_Surface_position(const BRepAdaptor_Surface &surface) {
+    switch (surface.GetType()) {
+    case GeomAbs_Cylinder:
+        return std::unique_ptr<gp_Ax3>(new gp_Ax3(surface.Cylinder().Position()));
+    case GeomAbs_Cone:
+        return std::unique_ptr<gp_Ax3>(new gp_Ax3(surface.Cone().Position()));
+    default:
+        return std::unique_ptr<gp_Ax3>(new gp_Ax3(surface.Plane().Position()));
+    }
+}
+
+inline Standard_Real BRepAdaptor_Surface_radius(const BRepAdaptor_Surface &surface) {
+    switch (surface.GetType()) {
+    case GeomAbs_Cylinder:
+        return surface.Cylinder().Radius();
+    case GeomAbs_Cone:
+        return surface.Cone().RefRadius();
+    default:
+        return 0.0;
+    }
+}
+
 // BRepLib
 inline bool BRepLibBuildCurves3d(const TopoDS_Shape &shape) { return BRepLib::BuildCurves3d(shape); }
 
@@ -317,6 +479,23 @@ inline const TopoDS_Shape &cast_compound_to_shape(const TopoDS_Compound &compoun
 inline std::unique_ptr<ShapeFix_Shape> ShapeFix_Shape_new(const TopoDS_Shape &shape) { return std::unique_ptr<ShapeFix_Shape>(new ShapeFix_Shape(shape)); }
 inline bool ShapeFix_Shape_perform(ShapeFix_Shape &shapefix) { return shapefix.Perform(); }
 inline std::unique_ptr<TopoDS_Shape> ShapeFix_Shape_shape(std::unique_ptr<ShapeFix_Shape> shapefix) { return std::unique_ptr<TopoDS_Shape>(new TopoDS_Shape(shapefix->Shape())); }
+inline std::unique_ptr<BRepTools_History> ShapeFix_Shape_history(const ShapeFix_Shape &shapefix) {
+  return std::unique_ptr<BRepTools_History>(new BRepTools_History(*shapefix.Context()->History()));
+}
+
+inline std::unique_ptr<ShapeFix_Wire> ShapeFix_Wire_new(const TopoDS_Wire &wire, double precision) {
+  auto shapefix = std::unique_ptr<ShapeFix_Wire>(new ShapeFix_Wire());
+  shapefix->Load(wire);
+  shapefix->SetPrecision(precision);
+  shapefix->SetMaxTolerance(precision);
+  return shapefix;
+}
+
+inline std::unique_ptr<TopoDS_Wire> ShapeFix_Wire_wire(const ShapeFix_Wire &shapefix) { return std::unique_ptr<TopoDS_Wire>(new TopoDS_Wire(shapefix.WireAPI())); }
+
+inline bool ShapeFix_Wire_failed(const ShapeFix_Wire &shapefix) {
+  return shapefix.StatusConnected(ShapeExtend_FAIL) || shapefix.StatusClosed(ShapeExtend_FAIL);
+}
 
 // Compound shapes
 inline std::unique_ptr<TopoDS_Shape> TopoDS_Compound_as_shape(std::unique_ptr<TopoDS_Compound> compound) {
@@ -341,10 +520,62 @@ inline std::unique_ptr<gp_Pnt> BRep_Tool_Pnt(const TopoDS_Vertex &vertex) {
   return std::unique_ptr<gp_Pnt>(new gp_Pnt(BRep_Tool::Pnt(vertex)));
 }
 
+inline Standard_Real BRep_Tool_Tolerance_vertex(const TopoDS_Vertex &vertex) { return BRep_Tool::Tolerance(vertex); }
+inline Standard_Real BRep_Tool_Tolerance_edge(const TopoDS_Edge &edge) { return BRep_Tool::Tolerance(edge); }
+inline Standard_Real BRep_Tool_Tolerance_face(const TopoDS_Face &face) { return BRep_Tool::Tolerance(face); }
+
+inline std::unique_ptr<TopoDS_Face> BRepFill_Face(const TopoDS_Edge &edge_1, const TopoDS_Edge &edge_2) {
+  return std::unique_ptr<TopoDS_Face>(new TopoDS_Face(BRepFill::Face(edge_1, edge_2)));
+}
+
+inline std::unique_ptr<TopoDS_Shell> BRepFill_Shell(const TopoDS_Wire &wire_1, const TopoDS_Wire &wire_2) {
+  return std::unique_ptr<TopoDS_Shell>(new TopoDS_Shell(BRepFill::Shell(wire_1, wire_2)));
+}
+
+inline std::unique_ptr<TopoDS_Edge> TopoDS_Edge_reversed(const TopoDS_Edge &edge) {
+  return std::unique_ptr<TopoDS_Edge>(new TopoDS_Edge(TopoDS::Edge(edge.Reversed())));
+}
+
+// A new edge on the same curve as `edge`, from parameter `first` to `last`, or a null edge if `edge` has no 3D
+// curve, as degenerate edges don't, or the edge can't be made.
+inline std::unique_ptr<TopoDS_Edge> TopoDS_Edge_trimmed(const TopoDS_Edge &edge, Standard_Real first,
+                                                        Standard_Real last) {
+  TopLoc_Location location;
+  Standard_Real edge_first, edge_last;
+  Handle(Geom_Curve) curve = BRep_Tool::Curve(edge, location, edge_first, edge_last);
+
+  if (curve.IsNull()) {
+    return std::unique_ptr<TopoDS_Edge>(new TopoDS_Edge());
+  }
+
+  if (!location.IsIdentity()) {
+    curve = Handle(Geom_Curve)::DownCast(curve->Transformed(location.Transformation()));
+  }
+
+  BRepBuilderAPI_MakeEdge make_edge(curve, first, last);
+  if (!make_edge.IsDone()) {
+    return std::unique_ptr<TopoDS_Edge>(new TopoDS_Edge());
+  }
+
+  return std::unique_ptr<TopoDS_Edge>(new TopoDS_Edge(make_edge.Edge()));
+}
+
+inline bool BRep_Tool_IsClosed(const TopoDS_Shape &shape) { return BRep_Tool::IsClosed(shape); }
+
 inline std::unique_ptr<gp_Trsf> TopLoc_Location_Transformation(const TopLoc_Location &location) {
   return std::unique_ptr<gp_Trsf>(new gp_Trsf(location.Transformation()));
 }
 
+// The location which moves shapes at `from` to `to`.
+inline std::unique_ptr<TopLoc_Location> TopLoc_Location_relative(const TopLoc_Location &from,
+                                                                 const TopLoc_Location &to) {
+  return std::unique_ptr<TopLoc_Location>(new TopLoc_Location(to * from.Inverted()));
+}
+
+inline std::unique_ptr<TopoDS_Shape> TopoDS_Shape_moved(const TopoDS_Shape &shape, const TopLoc_Location &location) {
+  return std::unique_ptr<TopoDS_Shape>(new TopoDS_Shape(shape.Moved(location)));
+}
+
 inline std::unique_ptr<HandlePoly_Triangulation>
 HandlePoly_Triangulation_ctor(std::unique_ptr<Poly_Triangulation> triangulation) {
   return std::unique_ptr<HandlePoly_Triangulation>(new HandlePoly_Triangulation(triangulation.release()));
@@ -427,6 +658,10 @@ inline bool write_iges(IGESControl_Writer &writer, rust::String theFileName) {
   return writer.Write(theFileName.c_str());
 }
 
+inline bool Interface_Static_SetCVal(rust::String name, rust::String value) {
+  return Interface_Static::SetCVal(name.c_str(), value.c_str());
+}
+
 inline bool write_stl(StlAPI_Writer &writer, const TopoDS_Shape &theShape, rust::String theFileName) {
   return writer.Write(theShape, theFileName.c_str());
 }
@@ -455,6 +690,10 @@ inline std::unique_ptr<gp_Pnt> GProp_GProps_CentreOfMass(const GProp_GProps &pro
   return std::unique_ptr<gp_Pnt>(new gp_Pnt(props.CentreOfMass()));
 }
 
+inline std::unique_ptr<gp_Mat> GProp_GProps_MatrixOfInertia(const GProp_GProps &props) {
+  return std::unique_ptr<gp_Mat>(new gp_Mat(props.MatrixOfInertia()));
+}
+
 inline void BRepGProp_LinearProperties(const TopoDS_Shape &shape, GProp_GProps &props) {
   BRepGProp::LinearProperties(shape, props);
 }
@@ -493,6 +732,27 @@ inline std::unique_ptr<TopoDS_Wire> outer_wire(const TopoDS_Face &face) {
   return std::unique_ptr<TopoDS_Wire>(new TopoDS_Wire(BRepTools::OuterWire(face)));
 }
 
+// A null shape if the file can't be read.
+inline std::unique_ptr<TopoDS_Shape> read_brep(rust::String filename) {
+  TopoDS_Shape shape;
+  BRep_Builder builder;
+
+  // A malformed file can throw part way through, rather than fail.
+  try {
+    if (!BRepTools::Read(shape, filename.c_str(), builder)) {
+      shape.Nullify();
+    }
+  } catch (const Standard_Failure &) {
+    shape.Nullify();
+  }
+
+  return std::unique_ptr<TopoDS_Shape>(new TopoDS_Shape(shape));
+}
+
+inline bool write_brep(const TopoDS_Shape &shape, rust::String filename) {
+  return BRepTools::Write(shape, filename.c_str());
+}
+
 // Collections
 inline void map_shapes(const TopoDS_Shape &S, const TopAbs_ShapeEnum T, TopTools_IndexedMapOfShape &M) {
   TopExp::MapShapes(S, T, M);
@@ -572,6 +832,27 @@ inline size_t BRepExtrema_ShapeProximity_OverlapCount(const BRepExtrema_ShapePro
     return shape_prox.OverlapSubShapes1().Size();
 }
 
+// Hidden line removal
+inline std::unique_ptr<HLRBRep_HLRToShape> HLRBRep_HLRToShape_project(const TopoDS_Shape &shape, const gp_Ax2 &view,
+                                                                      bool perspective, double focus) {
+  Handle(HLRBRep_Algo) algo = new HLRBRep_Algo();
+  algo->Add(shape);
+  algo->Projector(perspective ? HLRAlgo_Projector(view, focus) : HLRAlgo_Projector(view));
+  algo->Update();
+  algo->Hide();
+
+  return std::unique_ptr<HLRBRep_HLRToShape>(new HLRBRep_HLRToShape(algo));
+}
+
+inline std::unique_ptr<TopoDS_Shape> HLRBRep_HLRToShape_edges(HLRBRep_HLRToShape &hlr,
+                                                              HLRBRep_TypeOfResultingEdge edge_type, bool visible) {
+  return std::unique_ptr<TopoDS_Shape>(new TopoDS_Shape(hlr.CompoundOfEdges(edge_type, visible, false)));
+}
+
+inline std::unique_ptr<TopoDS_Face> TopoDS_Face_reversed(const TopoDS_Face &face) {
+  return std::unique_ptr<TopoDS_Face>(new TopoDS_Face(TopoDS::Face(face.Reversed())));
+}
+
 // Copy and pasted from from https://dev.opencascade.org/node/75681#comment-8102
 std::unique_ptr<gp_Dir> CalculateFaceNormalPlease(const TopoDS_Face &face)
 {
@@ -587,3 +868,37 @@ std::unique_ptr<gp_Dir> CalculateFaceNormalPlease(const TopoDS_Face &face)
 static double EdgeLength(const BRepAdaptor_Curve &theC) {
     return GCPnts_AbscissaPoint::Length(theC);
 }
+
+inline Standard_Real BRepAdaptor_Curve_length_between(const BRepAdaptor_Curve &curve, const Standard_Real u1,
+                                                      const Standard_Real u2) {
+  return GCPnts_AbscissaPoint::Length(curve, u1, u2);
+}
+
+inline Standard_Real BRepAdaptor_Curve_parameter_at_length(const BRepAdaptor_Curve &curve,
+                                                           const Standard_Real length) {
+  GCPnts_AbscissaPoint point(curve, length, curve.FirstParameter());
+  return point.Parameter();
+}
+
+// The parameter of the point on the curve nearest to `point`, ends included.
+inline Standard_Real BRepAdaptor_Curve_nearest_parameter(const BRepAdaptor_Curve &curve, const gp_Pnt &point) {
+  Standard_Real nearest = curve.FirstParameter();
+  Standard_Real nearest_distance = point.SquareDistance(curve.Value(nearest));
+
+  if (point.SquareDistance(curve.Value(curve.LastParameter())) < nearest_distance) {
+    nearest = curve.LastParameter();
+    nearest_distance = point.SquareDistance(curve.Value(nearest));
+  }
+
+  Extrema_ExtPC extrema(point, curve);
+  if (extrema.IsDone()) {
+    for (Standard_Integer i = 1; i <= extrema.NbExt(); i++) {
+      if (extrema.SquareDistance(i) < nearest_distance) {
+        nearest = extrema.Point(i).Parameter();
+        nearest_distance = extrema.SquareDistance(i);
+      }
+    }
+  }
+
+  return nearest;
+}
@@ -54,6 +54,42 @@ pub mod ffi {
         GeomAbs_OtherCurve,
     }
 
+    #[derive(Debug)]
+    #[repr(u32)]
+    pub enum GeomAbs_SurfaceType {
+        GeomAbs_Plane,
+        GeomAbs_Cylinder,
+        GeomAbs_Cone,
+        GeomAbs_Sphere,
+        GeomAbs_Torus,
+        GeomAbs_BezierSurface,
+        GeomAbs_BSplineSurface,
+        GeomAbs_SurfaceOfRevolution,
+        GeomAbs_SurfaceOfExtrusion,
+        GeomAbs_OffsetSurface,
+        GeomAbs_OtherSurface,
+    }
+
+    #[derive(Debug)]
+    #[repr(u32)]
+    pub enum BRepBuilderAPI_WireError {
+        BRepBuilderAPI_WireDone,
+        BRepBuilderAPI_EmptyWire,
+        BRepBuilderAPI_DisconnectedWire,
+        BRepBuilderAPI_NonManifoldWire,
+    }
+
+    #[derive(Debug)]
+    #[repr(u32)]
+    pub enum HLRBRep_TypeOfResultingEdge {
+        HLRBRep_Undefined,
+        HLRBRep_IsoLine,
+        HLRBRep_OutLine,
+        HLRBRep_Rg1Line,
+        HLRBRep_RgNLine,
+        HLRBRep_Sharp,
+    }
+
     #[repr(u32)]
     #[derive(Debug)]
     pub enum GeomAbs_JoinType {
@@ -77,6 +113,14 @@ pub mod ffi {
         Extrema_ExtAlgo_Tree,
     }
 
+    #[repr(u32)]
+    #[derive(Debug)]
+    pub enum BRepFill_TypeOfContact {
+        BRepFill_NoContact,
+        BRepFill_Contact,
+        BRepFill_ContactOnBorder,
+    }
+
     unsafe extern "C++" {
         // https://github.com/dtolnay/cxx/issues/280
 
@@ -154,6 +198,7 @@ pub mod ffi {
         #[cxx_name = "construct_unique"]
         pub fn new_list_of_shape() -> UniquePtr<TopTools_ListOfShape>;
         pub fn shape_list_append_face(list: Pin<&mut TopTools_ListOfShape>, face: &TopoDS_Face);
+        pub fn shape_list_append_shape(list: Pin<&mut TopTools_ListOfShape>, shape: &TopoDS_Shape);
         pub fn Size(self: &TopTools_ListOfShape) -> i32;
 
         #[cxx_name = "list_to_vector"]
@@ -167,6 +212,7 @@ pub mod ffi {
         pub fn new_indexed_map_of_shape() -> UniquePtr<TopTools_IndexedMapOfShape>;
         pub fn Extent(self: &TopTools_IndexedMapOfShape) -> i32;
         pub fn FindKey(self: &TopTools_IndexedMapOfShape, index: i32) -> &TopoDS_Shape;
+        pub fn FindIndex(self: &TopTools_IndexedMapOfShape, shape: &TopoDS_Shape) -> i32;
 
         pub fn map_shapes(
             shape: &TopoDS_Shape,
@@ -295,6 +341,7 @@ pub mod ffi {
             interpol: UniquePtr<Law_Interpol>,
         ) -> UniquePtr<Law_Function>;
         pub fn Set(self: Pin<&mut Law_Interpol>, array: &TColgp_Array1OfPnt2d, periodic: bool);
+        pub fn Value(self: Pin<&mut Law_Function>, x: f64) -> f64;
 
         // Geometry
         type Geom_TrimmedCurve;
@@ -382,6 +429,12 @@ pub mod ffi {
         // Edge types
         type GeomAbs_CurveType;
 
+        // Face types
+        type GeomAbs_SurfaceType;
+
+        // Wire building errors
+        type BRepBuilderAPI_WireError;
+
         // Segments
         type GC_MakeSegment;
         type GCE2d_MakeSegment;
@@ -424,6 +477,35 @@ pub mod ffi {
         pub fn GetCenterpoint(circle: &gp_Circ) -> UniquePtr<gp_Pnt>;
         pub fn Radius(self: &gp_Circ) -> f64;
 
+        // Conics
+        type gp_Elips;
+        type gp_Parab;
+        type gp_Hypr;
+
+        #[cxx_name = "construct_unique"]
+        pub fn gp_Elips_ctor(
+            axis: &gp_Ax2,
+            major_radius: f64,
+            minor_radius: f64,
+        ) -> UniquePtr<gp_Elips>;
+
+        #[cxx_name = "construct_unique"]
+        pub fn gp_Parab_ctor(axis: &gp_Ax2, focal: f64) -> UniquePtr<gp_Parab>;
+
+        #[cxx_name = "construct_unique"]
+        pub fn gp_Hypr_ctor(
+            axis: &gp_Ax2,
+            major_radius: f64,
+            minor_radius: f64,
+        ) -> UniquePtr<gp_Hypr>;
+
+        pub fn Geom_BezierCurve_rational_quadratic(
+            p0: &gp_Pnt,
+            p1: &gp_Pnt,
+            p2: &gp_Pnt,
+            weight: f64,
+        ) -> UniquePtr<HandleGeomCurve>;
+
         // Shapes
         type TopoDS_Vertex;
         type TopoDS_Edge;
@@ -466,17 +548,32 @@ pub mod ffi {
             raise_exception: bool,
         );
 
+        #[cxx_name = "Location"]
+        pub fn location(self: &TopoDS_Shape) -> &TopLoc_Location;
+
+        pub fn TopoDS_Shape_moved(
+            shape: &TopoDS_Shape,
+            location: &TopLoc_Location,
+        ) -> UniquePtr<TopoDS_Shape>;
+
         #[cxx_name = "construct_unique"]
         pub fn TopoDS_Vertex_to_owned(shape: &TopoDS_Vertex) -> UniquePtr<TopoDS_Vertex>;
 
         #[cxx_name = "construct_unique"]
         pub fn TopoDS_Edge_to_owned(shape: &TopoDS_Edge) -> UniquePtr<TopoDS_Edge>;
+        pub fn TopoDS_Edge_reversed(edge: &TopoDS_Edge) -> UniquePtr<TopoDS_Edge>;
+        pub fn TopoDS_Edge_trimmed(
+            edge: &TopoDS_Edge,
+            first: f64,
+            last: f64,
+        ) -> UniquePtr<TopoDS_Edge>;
 
         #[cxx_name = "construct_unique"]
         pub fn TopoDS_Wire_to_owned(shape: &TopoDS_Wire) -> UniquePtr<TopoDS_Wire>;
 
         #[cxx_name = "construct_unique"]
         pub fn TopoDS_Face_to_owned(shape: &TopoDS_Face) -> UniquePtr<TopoDS_Face>;
+        pub fn TopoDS_Face_reversed(face: &TopoDS_Face) -> UniquePtr<TopoDS_Face>;
 
         #[cxx_name = "construct_unique"]
         pub fn TopoDS_Shell_to_owned(shape: &TopoDS_Shell) -> UniquePtr<TopoDS_Shell>;
@@ -492,6 +589,9 @@ pub mod ffi {
 
         pub fn IsNull(self: &TopoDS_Shape) -> bool;
         pub fn IsEqual(self: &TopoDS_Shape, other: &TopoDS_Shape) -> bool;
+        pub fn IsSame(self: &TopoDS_Shape, other: &TopoDS_Shape) -> bool;
+        pub fn IsPartner(self: &TopoDS_Shape, other: &TopoDS_Shape) -> bool;
+        pub fn TopoDS_Shape_share_count(shape: &TopoDS_Shape) -> i32;
         pub fn ShapeType(self: &TopoDS_Shape) -> TopAbs_ShapeEnum;
 
         type TopAbs_Orientation;
@@ -505,6 +605,30 @@ pub mod ffi {
         pub fn ShapeFix_Shape_shape(shapefix: UniquePtr<ShapeFix_Shape>)
             -> UniquePtr<TopoDS_Shape>;
 
+        type ShapeFix_Wire;
+        pub fn ShapeFix_Wire_new(wire: &TopoDS_Wire, precision: f64) -> UniquePtr<ShapeFix_Wire>;
+        pub fn FixConnected(self: Pin<&mut ShapeFix_Wire>, precision: f64) -> bool;
+        pub fn FixClosed(self: Pin<&mut ShapeFix_Wire>, precision: f64) -> bool;
+        pub fn ShapeFix_Wire_wire(shapefix: &ShapeFix_Wire) -> UniquePtr<TopoDS_Wire>;
+        pub fn ShapeFix_Wire_failed(shapefix: &ShapeFix_Wire) -> bool;
+
+        type ShapeFix_ShapeTolerance;
+        #[cxx_name = "construct_unique"]
+        pub fn ShapeFix_ShapeTolerance_ctor() -> UniquePtr<ShapeFix_ShapeTolerance>;
+        pub fn SetTolerance(
+            self: &ShapeFix_ShapeTolerance,
+            shape: &TopoDS_Shape,
+            preci: f64,
+            styp: TopAbs_ShapeEnum,
+        );
+        pub fn LimitTolerance(
+            self: &ShapeFix_ShapeTolerance,
+            shape: &TopoDS_Shape,
+            tmin: f64,
+            tmax: f64,
+            styp: TopAbs_ShapeEnum,
+        ) -> bool;
+
         // Compound Shapes
         type TopoDS_Compound;
         pub fn TopoDS_Compound_as_shape(
@@ -522,12 +646,16 @@ pub mod ffi {
         #[cxx_name = "construct_unique"]
         pub fn TopoDS_Shell_ctor() -> UniquePtr<TopoDS_Shell>;
 
+        #[cxx_name = "construct_unique"]
+        pub fn TopoDS_Wire_ctor() -> UniquePtr<TopoDS_Wire>;
+
         #[cxx_name = "construct_unique"]
         pub fn BRep_Builder_ctor() -> UniquePtr<BRep_Builder>;
 
         pub fn BRep_Builder_upcast_to_topods_builder(builder: &BRep_Builder) -> &TopoDS_Builder;
         pub fn MakeCompound(self: &TopoDS_Builder, compound: Pin<&mut TopoDS_Compound>);
         pub fn MakeShell(self: &TopoDS_Builder, compound: Pin<&mut TopoDS_Shell>);
+        pub fn MakeWire(self: &TopoDS_Builder, wire: Pin<&mut TopoDS_Wire>);
         pub fn Add(self: &TopoDS_Builder, shape: Pin<&mut TopoDS_Shape>, compound: &TopoDS_Shape);
 
         // BRepBuilder
@@ -551,6 +679,32 @@ pub mod ffi {
             circle: &gp_Circ,
         ) -> UniquePtr<BRepBuilderAPI_MakeEdge>;
 
+        #[cxx_name = "construct_unique"]
+        pub fn BRepBuilderAPI_MakeEdge_circle_arc(
+            circle: &gp_Circ,
+            p1: f64,
+            p2: f64,
+        ) -> UniquePtr<BRepBuilderAPI_MakeEdge>;
+
+        #[cxx_name = "construct_unique"]
+        pub fn BRepBuilderAPI_MakeEdge_ellipse(
+            ellipse: &gp_Elips,
+        ) -> UniquePtr<BRepBuilderAPI_MakeEdge>;
+
+        #[cxx_name = "construct_unique"]
+        pub fn BRepBuilderAPI_MakeEdge_parabola(
+            parabola: &gp_Parab,
+            p1: f64,
+            p2: f64,
+        ) -> UniquePtr<BRepBuilderAPI_MakeEdge>;
+
+        #[cxx_name = "construct_unique"]
+        pub fn BRepBuilderAPI_MakeEdge_hyperbola(
+            hyperbola: &gp_Hypr,
+            p1: f64,
+            p2: f64,
+        ) -> UniquePtr<BRepBuilderAPI_MakeEdge>;
+
         #[cxx_name = "construct_unique"]
         pub fn BRepBuilderAPI_MakeEdge_gp_Pnt_gp_Pnt(
             p1: &gp_Pnt,
@@ -590,6 +744,7 @@ pub mod ffi {
         pub fn Wire(self: Pin<&mut BRepBuilderAPI_MakeWire>) -> &TopoDS_Wire;
         pub fn Build(self: Pin<&mut BRepBuilderAPI_MakeWire>, progress: &Message_ProgressRange);
         pub fn IsDone(self: &BRepBuilderAPI_MakeWire) -> bool;
+        pub fn Error(self: &BRepBuilderAPI_MakeWire) -> BRepBuilderAPI_WireError;
 
         type BRepBuilderAPI_MakeFace;
 
@@ -603,6 +758,12 @@ pub mod ffi {
             surface: &HandleGeomSurface,
             edge_tolerance: f64,
         ) -> UniquePtr<BRepBuilderAPI_MakeFace>;
+        #[cxx_name = "construct_unique"]
+        pub fn BRepBuilderAPI_MakeFace_surface_wire(
+            surface: &HandleGeomSurface,
+            wire: &TopoDS_Wire,
+            inside: bool,
+        ) -> UniquePtr<BRepBuilderAPI_MakeFace>;
 
         pub fn Face(self: &BRepBuilderAPI_MakeFace) -> &TopoDS_Face;
         pub fn Shape(self: Pin<&mut BRepBuilderAPI_MakeFace>) -> &TopoDS_Shape;
@@ -630,6 +791,41 @@ pub mod ffi {
         pub fn BRepAdaptor_Curve_value(curve: &BRepAdaptor_Curve, u: f64) -> UniquePtr<gp_Pnt>;
         pub fn GetType(self: &BRepAdaptor_Curve) -> GeomAbs_CurveType;
         pub fn GetCircle(curve: &BRepAdaptor_Curve) -> UniquePtr<gp_Circ>;
+        pub fn D1(self: &BRepAdaptor_Curve, u: f64, point: Pin<&mut gp_Pnt>, d1: Pin<&mut gp_Vec>);
+
+        type BRepAdaptor_Surface;
+
+        #[cxx_name = "construct_unique"]
+        pub fn BRepAdaptor_Surface_ctor(
+            face: &TopoDS_Face,
+            restriction: bool,
+        ) -> UniquePtr<BRepAdaptor_Surface>;
+        pub fn GetType(self: &BRepAdaptor_Surface) -> GeomAbs_SurfaceType;
+        pub fn BRepAdaptor_Surface_cone_semi_angle(surface: &BRepAdaptor_Surface) -> f64;
+        pub fn FirstUParameter(self: &BRepAdaptor_Surface) -> f64;
+        pub fn LastUParameter(self: &BRepAdaptor_Surface) -> f64;
+        pub fn BRepAdaptor_Surface_position(surface: &BRepAdaptor_Surface) -> UniquePtr<gp_Ax3>;
+        pub fn BRepAdaptor_Surface_radius(surface: &BRepAdaptor_Surface) -> f64;
+
+        type BRepAdaptor_CompCurve;
+
+        #[cxx_name = "construct_unique"]
+        pub fn BRepAdaptor_CompCurve_ctor(wire: &TopoDS_Wire) -> UniquePtr<BRepAdaptor_CompCurve>;
+        pub fn FirstParameter(self: &BRepAdaptor_CompCurve) -> f64;
+        pub fn LastParameter(self: &BRepAdaptor_CompCurve) -> f64;
+        pub fn BRepAdaptor_CompCurve_value(
+            curve: &BRepAdaptor_CompCurve,
+            u: f64,
+        ) -> UniquePtr<gp_Pnt>;
+        pub fn BRepAdaptor_CompCurve_length(curve: &BRepAdaptor_CompCurve) -> f64;
+        pub fn D1(self: &BRepAdaptor_CompCurve, u: f64, point: Pin<&mut gp_Pnt>, d1: Pin<&mut gp_Vec>);
+        pub fn D2(
+            self: &BRepAdaptor_CompCurve,
+            u: f64,
+            point: Pin<&mut gp_Pnt>,
+            d1: Pin<&mut gp_Vec>,
+            d2: Pin<&mut gp_Vec>,
+        );
 
         type Extrema_ExtFlag;
         type Extrema_ExtAlgo;
@@ -699,6 +895,23 @@ pub mod ffi {
         pub fn perform_with_height(self: Pin<&mut BRepFeat_MakeDPrism>, height: f64);
         pub fn Shape(self: Pin<&mut BRepFeat_MakeDPrism>) -> &TopoDS_Shape;
 
+        type BRepFeat_MakePrism;
+
+        #[cxx_name = "construct_unique"]
+        pub fn BRepFeat_MakePrism_ctor(
+            shape: &TopoDS_Shape,
+            profile_base: &TopoDS_Shape,
+            sketch_base: &TopoDS_Face,
+            direction: &gp_Dir,
+            fuse: i32, // 0 = subtractive, 1 = additive
+            modify: bool,
+        ) -> UniquePtr<BRepFeat_MakePrism>;
+
+        #[cxx_name = "Perform"]
+        pub fn perform_until_face(self: Pin<&mut BRepFeat_MakePrism>, until: &TopoDS_Shape);
+        pub fn Shape(self: Pin<&mut BRepFeat_MakePrism>) -> &TopoDS_Shape;
+        pub fn IsDone(self: &BRepFeat_MakePrism) -> bool;
+
         type BRepPrimAPI_MakeRevol;
 
         #[cxx_name = "construct_unique"]
@@ -923,6 +1136,19 @@ pub mod ffi {
 
         pub fn SetMode(self: Pin<&mut BRepOffsetAPI_MakePipeShell>, is_frenet: bool);
 
+        #[cxx_name = "SetMode"]
+        pub fn set_mode_binormal(self: Pin<&mut BRepOffsetAPI_MakePipeShell>, binormal: &gp_Dir);
+
+        type BRepFill_TypeOfContact;
+
+        #[cxx_name = "SetMode"]
+        pub fn set_mode_auxiliary_spine(
+            self: Pin<&mut BRepOffsetAPI_MakePipeShell>,
+            auxiliary_spine: &TopoDS_Wire,
+            curvilinear_equivalence: bool,
+            keep_contact: BRepFill_TypeOfContact,
+        );
+
         pub fn Add(
             self: Pin<&mut BRepOffsetAPI_MakePipeShell>,
             profile: &TopoDS_Shape,
@@ -939,7 +1165,13 @@ pub mod ffi {
         );
 
         pub fn Build(self: Pin<&mut BRepOffsetAPI_MakePipeShell>, progress: &Message_ProgressRange);
+        pub fn IsDone(self: &BRepOffsetAPI_MakePipeShell) -> bool;
         pub fn MakeSolid(self: Pin<&mut BRepOffsetAPI_MakePipeShell>) -> bool;
+        pub fn Simulate(
+            self: Pin<&mut BRepOffsetAPI_MakePipeShell>,
+            number_of_section: i32,
+            result: Pin<&mut TopTools_ListOfShape>,
+        );
         pub fn Shape(self: Pin<&mut BRepOffsetAPI_MakePipeShell>) -> &TopoDS_Shape;
 
         // Lofting
@@ -950,19 +1182,32 @@ pub mod ffi {
             is_solid: bool,
         ) -> UniquePtr<BRepOffsetAPI_ThruSections>;
 
+        #[cxx_name = "construct_unique"]
+        pub fn BRepOffsetAPI_ThruSections_ruled_ctor(
+            is_solid: bool,
+            ruled: bool,
+        ) -> UniquePtr<BRepOffsetAPI_ThruSections>;
+
         pub fn AddWire(self: Pin<&mut BRepOffsetAPI_ThruSections>, wire: &TopoDS_Wire);
+        pub fn AddVertex(self: Pin<&mut BRepOffsetAPI_ThruSections>, vertex: &TopoDS_Vertex);
         pub fn CheckCompatibility(self: Pin<&mut BRepOffsetAPI_ThruSections>, check: bool);
         pub fn Shape(self: Pin<&mut BRepOffsetAPI_ThruSections>) -> &TopoDS_Shape;
         pub fn Build(self: Pin<&mut BRepOffsetAPI_ThruSections>, progress: &Message_ProgressRange);
         pub fn IsDone(self: &BRepOffsetAPI_ThruSections) -> bool;
 
+        // Ruled surfaces
+        pub fn BRepFill_Face(edge_1: &TopoDS_Edge, edge_2: &TopoDS_Edge) -> UniquePtr<TopoDS_Face>;
+        pub fn BRepFill_Shell(
+            wire_1: &TopoDS_Wire,
+            wire_2: &TopoDS_Wire,
+        ) -> UniquePtr<TopoDS_Shell>;
+
         pub type BRepAlgoAPI_BuilderAlgo;
         pub fn SectionEdges(self: Pin<&mut BRepAlgoAPI_BuilderAlgo>) -> &TopTools_ListOfShape;
 
         pub type BRepAlgoAPI_Fuse;
         type BOPAlgo_GlueEnum;
 
-        #[cxx_name = "construct_unique"]
         pub fn BRepAlgoAPI_Fuse_ctor(
             shape_1: &TopoDS_Shape,
             shape_2: &TopoDS_Shape,
@@ -974,9 +1219,14 @@ pub mod ffi {
         pub fn SectionEdges(self: Pin<&mut BRepAlgoAPI_Fuse>) -> &TopTools_ListOfShape;
         pub fn SetGlue(self: Pin<&mut BRepAlgoAPI_Fuse>, glue: BOPAlgo_GlueEnum);
 
+        #[cxx_name = "construct_unique"]
+        pub fn BRepAlgoAPI_Fuse_new() -> UniquePtr<BRepAlgoAPI_Fuse>;
+        pub fn SetArguments(self: Pin<&mut BRepAlgoAPI_Fuse>, arguments: &TopTools_ListOfShape);
+        pub fn SetTools(self: Pin<&mut BRepAlgoAPI_Fuse>, tools: &TopTools_ListOfShape);
+        pub fn SetNonDestructive(self: Pin<&mut BRepAlgoAPI_Fuse>, flag: bool);
+
         type BRepAlgoAPI_Cut;
 
-        #[cxx_name = "construct_unique"]
         pub fn BRepAlgoAPI_Cut_ctor(
             shape_1: &TopoDS_Shape,
             shape_2: &TopoDS_Shape,
@@ -993,7 +1243,6 @@ pub mod ffi {
 
         type BRepAlgoAPI_Common;
 
-        #[cxx_name = "construct_unique"]
         pub fn BRepAlgoAPI_Common_ctor(
             shape_1: &TopoDS_Shape,
             shape_2: &TopoDS_Shape,
@@ -1006,7 +1255,6 @@ pub mod ffi {
 
         type BRepAlgoAPI_Section;
 
-        #[cxx_name = "construct_unique"]
         pub fn BRepAlgoAPI_Section_ctor(
             shape_1: &TopoDS_Shape,
             shape_2: &TopoDS_Shape,
@@ -1019,6 +1267,91 @@ pub mod ffi {
             section: UniquePtr<BRepAlgoAPI_Section>,
         ) -> UniquePtr<BRepAlgoAPI_BuilderAlgo>;
 
+        type BRepAlgoAPI_Splitter;
+
+        pub fn BRepAlgoAPI_Splitter_ctor(
+            shape_1: &TopoDS_Shape,
+            shape_2: &TopoDS_Shape,
+        ) -> UniquePtr<BRepAlgoAPI_Splitter>;
+
+        pub fn Shape(self: Pin<&mut BRepAlgoAPI_Splitter>) -> &TopoDS_Shape;
+        pub fn IsDone(self: &BRepAlgoAPI_Splitter) -> bool;
+
+        type BOPAlgo_CellsBuilder;
+
+        pub fn BOPAlgo_CellsBuilder_ctor(
+            shape_1: &TopoDS_Shape,
+            shape_2: &TopoDS_Shape,
+        ) -> UniquePtr<BOPAlgo_CellsBuilder>;
+
+        pub fn BOPAlgo_CellsBuilder_cells(
+            builder: Pin<&mut BOPAlgo_CellsBuilder>,
+            take: &TopTools_ListOfShape,
+            avoid: &TopTools_ListOfShape,
+        ) -> UniquePtr<TopoDS_Shape>;
+
+        pub fn HasErrors(self: &BOPAlgo_CellsBuilder) -> bool;
+
+        // Shape history
+        type BRepTools_History;
+
+        #[cxx_name = "BRepTools_History_from_algo"]
+        pub fn BRepTools_History_from_fuse(
+            arguments: &TopTools_ListOfShape,
+            algo: Pin<&mut BRepAlgoAPI_Fuse>,
+        ) -> UniquePtr<BRepTools_History>;
+        #[cxx_name = "BRepTools_History_from_algo"]
+        pub fn BRepTools_History_from_cut(
+            arguments: &TopTools_ListOfShape,
+            algo: Pin<&mut BRepAlgoAPI_Cut>,
+        ) -> UniquePtr<BRepTools_History>;
+        #[cxx_name = "BRepTools_History_from_algo"]
+        pub fn BRepTools_History_from_common(
+            arguments: &TopTools_ListOfShape,
+            algo: Pin<&mut BRepAlgoAPI_Common>,
+        ) -> UniquePtr<BRepTools_History>;
+        #[cxx_name = "BRepTools_History_from_algo"]
+        pub fn BRepTools_History_from_fillet(
+            arguments: &TopTools_ListOfShape,
+            algo: Pin<&mut BRepFilletAPI_MakeFillet>,
+        ) -> UniquePtr<BRepTools_History>;
+        #[cxx_name = "BRepTools_History_from_algo"]
+        pub fn BRepTools_History_from_chamfer(
+            arguments: &TopTools_ListOfShape,
+            algo: Pin<&mut BRepFilletAPI_MakeChamfer>,
+        ) -> UniquePtr<BRepTools_History>;
+        #[cxx_name = "BRepTools_History_from_algo"]
+        pub fn BRepTools_History_from_thick_solid(
+            arguments: &TopTools_ListOfShape,
+            algo: Pin<&mut BRepOffsetAPI_MakeThickSolid>,
+        ) -> UniquePtr<BRepTools_History>;
+        #[cxx_name = "BRepTools_History_from_algo"]
+        pub fn BRepTools_History_from_copy(
+            arguments: &TopTools_ListOfShape,
+            algo: Pin<&mut BRepBuilderAPI_Copy>,
+        ) -> UniquePtr<BRepTools_History>;
+        #[cxx_name = "BRepTools_History_from_algo"]
+        pub fn BRepTools_History_from_pipe_shell(
+            arguments: &TopTools_ListOfShape,
+            algo: Pin<&mut BRepOffsetAPI_MakePipeShell>,
+        ) -> UniquePtr<BRepTools_History>;
+        #[cxx_name = "BRepTools_History_from_algo"]
+        pub fn BRepTools_History_from_transform(
+            arguments: &TopTools_ListOfShape,
+            algo: Pin<&mut BRepBuilderAPI_Transform>,
+        ) -> UniquePtr<BRepTools_History>;
+        pub fn ShapeFix_Shape_history(shapefix: &ShapeFix_Shape) -> UniquePtr<BRepTools_History>;
+
+        pub fn Modified<'a>(
+            self: &'a BRepTools_History,
+            initial: &'a TopoDS_Shape,
+        ) -> &'a TopTools_ListOfShape;
+        pub fn Generated<'a>(
+            self: &'a BRepTools_History,
+            initial: &'a TopoDS_Shape,
+        ) -> &'a TopTools_ListOfShape;
+        pub fn IsRemoved(self: &BRepTools_History, initial: &TopoDS_Shape) -> bool;
+
         // Geometric processor
         type gp_Ax1;
         type gp_Ax2;
@@ -1032,6 +1365,10 @@ pub mod ffi {
         pub fn gp_DZ() -> &'static gp_Dir;
 
         pub fn Direction(self: &gp_Ax1) -> &gp_Dir;
+        pub fn Location(self: &gp_Ax3) -> &gp_Pnt;
+        pub fn Direction(self: &gp_Ax3) -> &gp_Dir;
+        pub fn XDirection(self: &gp_Ax3) -> &gp_Dir;
+        pub fn YDirection(self: &gp_Ax3) -> &gp_Dir;
 
         pub fn Transform(self: Pin<&mut gp_Dir>, transform: &gp_Trsf);
 
@@ -1047,6 +1384,13 @@ pub mod ffi {
         #[cxx_name = "construct_unique"]
         pub fn gp_Ax2_ctor(origin: &gp_Pnt, main_dir: &gp_Dir) -> UniquePtr<gp_Ax2>;
 
+        #[cxx_name = "construct_unique"]
+        pub fn gp_Ax2_with_x_dir(
+            origin: &gp_Pnt,
+            main_dir: &gp_Dir,
+            x_dir: &gp_Dir,
+        ) -> UniquePtr<gp_Ax2>;
+
         #[cxx_name = "construct_unique"]
         pub fn gp_Ax3_from_gp_Ax2(axis: &gp_Ax2) -> UniquePtr<gp_Ax3>;
 
@@ -1112,6 +1456,8 @@ pub mod ffi {
 
         #[rust_name = "set_mirror_axis"]
         pub fn SetMirror(self: Pin<&mut gp_Trsf>, axis: &gp_Ax1);
+        #[rust_name = "set_mirror_plane"]
+        pub fn SetMirror(self: Pin<&mut gp_Trsf>, plane: &gp_Ax2);
         pub fn SetRotation(self: Pin<&mut gp_Trsf>, axis: &gp_Ax1, angle: f64);
         pub fn SetScale(self: Pin<&mut gp_Trsf>, point: &gp_Pnt, scale: f64);
         pub fn SetTranslation(self: Pin<&mut gp_Trsf>, point1: &gp_Pnt, point2: &gp_Pnt);
@@ -1154,6 +1500,15 @@ pub mod ffi {
         pub fn Build(self: Pin<&mut BRepBuilderAPI_MakeSolid>, progress: &Message_ProgressRange);
         pub fn IsDone(self: &BRepBuilderAPI_MakeSolid) -> bool;
 
+        type BRepBuilderAPI_Sewing;
+
+        #[cxx_name = "construct_unique"]
+        pub fn BRepBuilderAPI_Sewing_ctor(tolerance: f64) -> UniquePtr<BRepBuilderAPI_Sewing>;
+
+        pub fn Add(self: Pin<&mut BRepBuilderAPI_Sewing>, shape: &TopoDS_Shape);
+        pub fn Perform(self: Pin<&mut BRepBuilderAPI_Sewing>, progress: &Message_ProgressRange);
+        pub fn SewedShape(self: &BRepBuilderAPI_Sewing) -> &TopoDS_Shape;
+
         type BRepBuilderAPI_MakeShapeOnMesh;
 
         #[cxx_name = "construct_unique"]
@@ -1220,6 +1575,38 @@ pub mod ffi {
         pub fn ExplorerCurrentShape(explorer: &TopExp_Explorer) -> UniquePtr<TopoDS_Shape>;
         pub fn Current(self: &TopExp_Explorer) -> &TopoDS_Shape;
 
+        // Direct children of a shape
+        type TopoDS_Iterator;
+
+        #[cxx_name = "construct_unique"]
+        pub fn TopoDS_Iterator_ctor(shape: &TopoDS_Shape) -> UniquePtr<TopoDS_Iterator>;
+        pub fn More(self: &TopoDS_Iterator) -> bool;
+        pub fn Next(self: Pin<&mut TopoDS_Iterator>);
+        pub fn Value(self: &TopoDS_Iterator) -> &TopoDS_Shape;
+
+        // Shape validity checks
+        type BRepCheck_Analyzer;
+
+        #[cxx_name = "construct_unique"]
+        pub fn BRepCheck_Analyzer_ctor(shape: &TopoDS_Shape) -> UniquePtr<BRepCheck_Analyzer>;
+        pub fn IsValid(self: &BRepCheck_Analyzer) -> bool;
+
+        // Hidden line removal
+        type HLRBRep_TypeOfResultingEdge;
+        type HLRBRep_HLRToShape;
+
+        pub fn HLRBRep_HLRToShape_project(
+            shape: &TopoDS_Shape,
+            view: &gp_Ax2,
+            perspective: bool,
+            focus: f64,
+        ) -> UniquePtr<HLRBRep_HLRToShape>;
+        pub fn HLRBRep_HLRToShape_edges(
+            hlr: Pin<&mut HLRBRep_HLRToShape>,
+            edge_type: HLRBRep_TypeOfResultingEdge,
+            visible: bool,
+        ) -> UniquePtr<TopoDS_Shape>;
+
         pub fn TopExp_FirstVertex(edge: &TopoDS_Edge) -> UniquePtr<TopoDS_Vertex>;
         pub fn TopExp_LastVertex(edge: &TopoDS_Edge) -> UniquePtr<TopoDS_Vertex>;
         pub fn TopExp_EdgeVertices(
@@ -1245,6 +1632,10 @@ pub mod ffi {
             last: &mut f64,
         ) -> UniquePtr<HandleGeomCurve>;
         pub fn BRep_Tool_Pnt(vertex: &TopoDS_Vertex) -> UniquePtr<gp_Pnt>;
+        pub fn BRep_Tool_Tolerance_vertex(vertex: &TopoDS_Vertex) -> f64;
+        pub fn BRep_Tool_Tolerance_edge(edge: &TopoDS_Edge) -> f64;
+        pub fn BRep_Tool_Tolerance_face(face: &TopoDS_Face) -> f64;
+        pub fn BRep_Tool_IsClosed(shape: &TopoDS_Shape) -> bool;
         pub fn BRep_Tool_Triangulation(
             face: &TopoDS_Face,
             location: Pin<&mut TopLoc_Location>,
@@ -1336,6 +1727,13 @@ pub mod ffi {
         ) -> IFSelect_ReturnStatus;
         pub fn write_iges(writer: Pin<&mut IGESControl_Writer>, filename: String) -> bool;
 
+        // OCCT's native BREP format
+        pub fn read_brep(filename: String) -> UniquePtr<TopoDS_Shape>;
+        pub fn write_brep(shape: &TopoDS_Shape, filename: String) -> bool;
+
+        // Translator parameters, such as "write.step.unit"
+        pub fn Interface_Static_SetCVal(name: String, value: String) -> bool;
+
         type StlAPI_Writer;
 
         #[cxx_name = "construct_unique"]
@@ -1368,6 +1766,10 @@ pub mod ffi {
         pub fn TopLoc_Location_from_transform(transform: &gp_Trsf) -> UniquePtr<TopLoc_Location>;
 
         pub fn TopLoc_Location_Transformation(location: &TopLoc_Location) -> UniquePtr<gp_Trsf>;
+        pub fn TopLoc_Location_relative(
+            from: &TopLoc_Location,
+            to: &TopLoc_Location,
+        ) -> UniquePtr<TopLoc_Location>;
 
         type HandlePoly_Triangulation;
 
@@ -1429,6 +1831,14 @@ pub mod ffi {
         pub fn compute_normals(face: &TopoDS_Face, triangulation: &HandlePoly_Triangulation);
 
         pub fn EdgeLength(curve: &BRepAdaptor_Curve) -> f64;
+        pub fn BRepAdaptor_Curve_length_between(curve: &BRepAdaptor_Curve, u1: f64, u2: f64)
+            -> f64;
+        pub fn BRepAdaptor_Curve_parameter_at_length(curve: &BRepAdaptor_Curve, length: f64)
+            -> f64;
+        pub fn BRepAdaptor_Curve_nearest_parameter(
+            curve: &BRepAdaptor_Curve,
+            point: &gp_Pnt,
+        ) -> f64;
 
         // Edge approximation
         type GCPnts_TangentialDeflection;
@@ -1457,6 +1867,10 @@ pub mod ffi {
         pub fn MomentOfInertia(self: &GProp_GProps, axis: &gp_Ax1) -> f64;
         pub fn RadiusOfGyration(self: &GProp_GProps, axis: &gp_Ax1) -> f64;
         pub fn GProp_GProps_CentreOfMass(props: &GProp_GProps) -> UniquePtr<gp_Pnt>;
+        pub fn GProp_GProps_MatrixOfInertia(props: &GProp_GProps) -> UniquePtr<gp_Mat>;
+
+        type gp_Mat;
+        pub fn Value(self: &gp_Mat, row: i32, col: i32) -> f64;
 
         pub fn BRepGProp_LinearProperties(shape: &TopoDS_Shape, props: Pin<&mut GProp_GProps>);
         pub fn BRepGProp_SurfaceProperties(shape: &TopoDS_Shape, props: Pin<&mut GProp_GProps>);
@@ -1517,8 +1931,18 @@ pub mod ffi {
         pub fn Bnd_Box_CornerMin(b: &Bnd_Box) -> UniquePtr<gp_Pnt>;
         pub fn Bnd_Box_CornerMax(b: &Bnd_Box) -> UniquePtr<gp_Pnt>;
         pub fn GetGap(self: &Bnd_Box) -> f64;
+        pub fn IsOut(self: &Bnd_Box, other: &Bnd_Box) -> bool;
         pub fn Set(self: Pin<&mut Bnd_Box>, p: &gp_Pnt);
         pub fn SetGap(self: Pin<&mut Bnd_Box>, gap: f64);
+        pub fn Update(
+            self: Pin<&mut Bnd_Box>,
+            xMin: f64,
+            yMin: f64,
+            zMin: f64,
+            xMax: f64,
+            yMax: f64,
+            zMax: f64,
+        );
 
         // BRepBndLib
         // Bounding boxes for curves and surfaces.
@@ -1528,15 +1952,6 @@ pub mod ffi {
     }
 }
 
-// Gross, but is this okay?
-unsafe impl Send for ffi::BRepBuilderAPI_MakeWire {}
-unsafe impl Send for ffi::TopoDS_Edge {}
-unsafe impl Send for ffi::TopoDS_Wire {}
-unsafe impl Send for ffi::TopoDS_Face {}
-unsafe impl Send for ffi::TopoDS_Shell {}
-unsafe impl Send for ffi::TopoDS_Solid {}
-unsafe impl Send for ffi::TopoDS_Compound {}
-unsafe impl Send for ffi::TopoDS_Shape {}
-
-unsafe impl Send for ffi::TopExp_Explorer {}
-unsafe impl Send for ffi::BRepFilletAPI_MakeChamfer {}
+// None of the OCCT types are `Send`: handles to topology may share their data
+// with others, and some `&self` operations write to it. `opencascade` sends
+// shapes between threads only once they've been deep copied.
@@ -0,0 +1,53 @@
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BatchSize;
+use criterion::Criterion;
+use nalgebra::point;
+use nalgebra::Vector3;
+use opencascade::primitives::Shape;
+
+const TOOLS: usize = 200;
+
+fn plate_and_tools() -> (Shape, Vec<Shape>) {
+    let plate = Shape::box_with_dimensions(100.0, 100.0, 5.0);
+    let tools = (0..TOOLS)
+        .map(|i| {
+            let (x, y) = ((i % 20) as f64 * 5.0 + 2.5, (i / 20) as f64 * 10.0 + 5.0);
+            Shape::cylinder(point![x, y, -1.0], 1.5, Vector3::z(), 7.0)
+        })
+        .collect();
+
+    (plate, tools)
+}
+
+fn cut_holes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cut 200 holes");
+    group.sample_size(10);
+
+    group.bench_function("sequential", |b| {
+        let (plate, tools) = plate_and_tools();
+
+        b.iter(|| {
+            let mut result = plate.subtract(&tools[0]).unwrap();
+            for tool in &tools[1..] {
+                result = result.subtract(tool).unwrap();
+            }
+            result
+        })
+    });
+
+    group.bench_function("cut_all_parallel", |b| {
+        let (plate, tools) = plate_and_tools();
+
+        b.iter_batched(
+            || tools.clone(),
+            |tools| plate.cut_all_parallel(tools).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, cut_holes);
+criterion_main!(benches);
@@ -0,0 +1,64 @@
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BatchSize;
+use criterion::Criterion;
+use nalgebra::point;
+use nalgebra::Vector3;
+use opencascade::mesh::MeshOptions;
+use opencascade::primitives::Compound;
+use opencascade::primitives::Shape;
+
+const COLUMNS: usize = 40;
+const ROWS: usize = 25;
+
+/// A plate with a grid of 1 000 holes, so just over 1 000 faces.
+fn perforated_plate() -> Shape {
+    let plate = Shape::box_with_dimensions(100.0, 100.0, 5.0);
+    let tools: Vec<_> = (0..COLUMNS * ROWS)
+        .map(|i| {
+            let (x, y) = (
+                (i % COLUMNS) as f64 * 2.5 + 1.25,
+                (i / COLUMNS) as f64 * 4.0 + 2.0,
+            );
+            Shape::cylinder(point![x, y, -1.0], 0.8, Vector3::z(), 7.0)
+        })
+        .collect();
+
+    plate
+        .subtract(&Compound::from_shapes(tools).into())
+        .unwrap()
+}
+
+fn remesh_after_small_cut(c: &mut Criterion) {
+    let mut group = c.benchmark_group("remesh 1 000 face part after a small cut");
+    group.sample_size(10);
+
+    let plate = perforated_plate();
+    let options = MeshOptions::default();
+    let previous = plate.mesh_with_options(&options).unwrap();
+
+    let notch = Shape::box_from_corners(point![99.0, 99.0, 4.0], point![101.0, 101.0, 6.0]);
+    let (notched, history) = plate.subtract_with_history(&notch).unwrap();
+
+    // Each shape caches its mesh, so mesh a fresh clone every time.
+    group.bench_function("full", |b| {
+        b.iter_batched(
+            || notched.clone(),
+            |notched| notched.mesh_with_options(&options).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("incremental", |b| {
+        b.iter(|| {
+            notched
+                .mesh_incremental(&previous, &history, &options)
+                .unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, remesh_after_small_cut);
+criterion_main!(benches);
@@ -0,0 +1,103 @@
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BatchSize;
+use criterion::Criterion;
+use nalgebra::Vector3;
+use opencascade::primitives::Face;
+use opencascade::primitives::Wire;
+use opencascade::workplane::Workplane;
+
+const SEGMENTS: usize = 2_000;
+
+/// A gear-like outline, as an external tool would generate it.
+fn outline() -> Vec<(f64, f64)> {
+    (0..SEGMENTS)
+        .map(|i| {
+            let angle = i as f64 / SEGMENTS as f64 * std::f64::consts::TAU;
+            let radius = if (i / 10) % 2 == 0 { 50.0 } else { 45.0 };
+            (radius * angle.cos(), radius * angle.sin())
+        })
+        .collect()
+}
+
+fn outline_wire() -> Wire {
+    let points = outline();
+
+    Workplane::xy()
+        .sketch()
+        .move_to(points[0].0, points[0].1)
+        .extend_lines(&points[1..])
+        .close()
+        .unwrap()
+}
+
+fn sketch_construction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("2 000 segment sketch");
+    group.sample_size(10);
+
+    let points = outline();
+    let (first, rest) = (points[0], &points[1..]);
+
+    group.bench_function("line_to", |b| {
+        b.iter(|| {
+            rest.iter().fold(
+                Workplane::xy().sketch().move_to(first.0, first.1),
+                |sketch, &(x, y)| sketch.line_to(x, y),
+            )
+        })
+    });
+
+    group.bench_function("extend_lines", |b| {
+        b.iter(|| {
+            Workplane::xy()
+                .sketch()
+                .move_to(first.0, first.1)
+                .extend_lines(rest)
+        })
+    });
+
+    group.finish();
+}
+
+fn wire_building(c: &mut Criterion) {
+    let mut group = c.benchmark_group("2 000 segment wire");
+    group.sample_size(10);
+
+    let points = outline();
+    let sketch = || {
+        Workplane::xy()
+            .sketch()
+            .move_to(points[0].0, points[0].1)
+            .extend_lines(&points[1..])
+    };
+
+    group.bench_function("close", |b| {
+        b.iter_batched(
+            sketch,
+            |sketch| sketch.close().unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+fn extrusion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("2 000 segment extrusion");
+    group.sample_size(10);
+
+    let wire = outline_wire();
+
+    group.bench_function("extrude", |b| {
+        b.iter(|| {
+            Face::from_wire(&wire)
+                .unwrap()
+                .extrude(Vector3::new(0.0, 0.0, 10.0))
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, sketch_construction, wire_building, extrusion);
+criterion_main!(benches);
@@ -0,0 +1,99 @@
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BatchSize;
+use criterion::Criterion;
+use nalgebra::point;
+use nalgebra::Point3;
+use opencascade::primitives::Compound;
+use opencascade::primitives::CompoundBuilder;
+use opencascade::primitives::Edge;
+use opencascade::primitives::Shape;
+use opencascade::primitives::Wire;
+use opencascade::primitives::WireBuilder;
+
+const SEGMENTS: usize = 10_000;
+
+/// A zigzag polyline, like a discretized curve from an imported file.
+fn polyline() -> Vec<Point3<f64>> {
+    (0..=SEGMENTS)
+        .map(|i| point![i as f64 * 0.1, (i % 2) as f64 * 0.1, 0.0])
+        .collect()
+}
+
+fn segments() -> Vec<Edge> {
+    polyline()
+        .windows(2)
+        .map(|pair| Edge::segment(pair[0], pair[1]))
+        .collect()
+}
+
+fn build_wire(c: &mut Criterion) {
+    let mut group = c.benchmark_group("10 000 segment wire");
+    group.sample_size(10);
+
+    group.bench_function("from_edges", |b| {
+        let edges = segments();
+
+        b.iter(|| Wire::from_edges(&edges).unwrap())
+    });
+
+    group.bench_function("from_edges_consuming", |b| {
+        b.iter_batched(
+            segments,
+            |edges| Wire::from_edges_consuming(edges).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("WireBuilder::push", |b| {
+        b.iter_batched(
+            segments,
+            |edges| {
+                let mut builder = WireBuilder::new();
+
+                for edge in edges {
+                    builder.push(edge).unwrap();
+                }
+
+                builder.build().unwrap()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+fn build_compound(c: &mut Criterion) {
+    let mut group = c.benchmark_group("10 000 shape compound");
+    group.sample_size(10);
+
+    let shapes = || -> Vec<Shape> { segments().into_iter().map(Shape::from).collect() };
+
+    group.bench_function("from_shapes", |b| {
+        let shapes = shapes();
+
+        b.iter(|| Compound::from_shapes(&shapes))
+    });
+
+    group.bench_function("CompoundBuilder::push", |b| {
+        b.iter_batched(
+            shapes,
+            |shapes| {
+                let mut builder = CompoundBuilder::new();
+
+                for shape in shapes {
+                    builder.push(shape);
+                }
+
+                builder.build()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, build_wire, build_compound);
+criterion_main!(benches);
@@ -0,0 +1,206 @@
+//! Boolean operations on many shapes at once.
+//!
+//! With the `rayon` feature enabled, independent parts of the work run on
+//! the rayon thread pool. The way shapes are grouped together only depends
+//! on their order, so results don't depend on thread scheduling.
+
+use crate::primitives::Detached;
+use crate::primitives::Shape;
+use crate::Error;
+use opencascade_sys::ffi;
+
+/// Groups of up to this many shapes are fused with a single n-ary fuse,
+/// which beats splitting them any further.
+const LEAF_SIZE: usize = 16;
+
+/// Fuse all of `shapes` together, by fusing halves of the list
+/// independently and then fusing the results.
+pub fn fuse_tree(shapes: Vec<Shape>) -> Result<Shape, Error> {
+    if shapes.len() <= LEAF_SIZE || !cfg!(feature = "rayon") {
+        return Shape::union_all(&shapes);
+    }
+
+    // The halves are fused on different threads, so they mustn't share any
+    // data, as patterned shapes do. Fusing copies gives the same result.
+    let shapes = shapes.into_iter().map(Shape::detach).collect();
+
+    Ok(fuse_detached(shapes)?.into_inner())
+}
+
+fn fuse_detached(mut shapes: Vec<Detached>) -> Result<Detached, Error> {
+    if shapes.len() > LEAF_SIZE {
+        let right = shapes.split_off(shapes.len() / 2);
+        let (left, right) = join(|| fuse_detached(shapes), || fuse_detached(right));
+
+        shapes = vec![left?, right?];
+    }
+
+    let shapes: Vec<Shape> = shapes.into_iter().map(Detached::into_inner).collect();
+    let fused = Shape::union_all(&shapes)?;
+    drop(shapes);
+
+    // SAFETY: The fused shape only shares data with the shapes it was made
+    // from, which shared nothing with anything else and have been dropped.
+    Ok(unsafe { Detached::from_unshared(fused) })
+}
+
+impl Shape {
+    /// Subtract every one of `tools` from this shape. The tools are fused
+    /// together first with `fuse_tree`, and then cut from the shape in one
+    /// go.
+    pub fn cut_all_parallel(&self, tools: Vec<Shape>) -> Result<Shape, Error> {
+        if tools.is_empty() {
+            return Ok(self.clone());
+        }
+
+        self.subtract(&fuse_tree(tools)?)
+    }
+
+    /// The parts of this shape and `other` which aren't in both.
+    pub fn symmetric_difference(&self, other: &Shape) -> Result<Shape, Error> {
+        let only_self = self.subtract(other)?;
+        let only_other = other.subtract(self)?;
+
+        Self::union_all([&only_self, &only_other])
+    }
+
+    /// The volume this shape shares with `other`. Shapes with disjoint
+    /// bounding boxes share nothing, which is known without computing their
+    /// intersection.
+    pub fn common_volume(&self, other: &Shape) -> Result<f64, Error> {
+        if !self.bounding_box().intersects(other.bounding_box()) {
+            return Ok(0.0);
+        }
+
+        Ok(self.intersect(other)?.volume())
+    }
+
+    /// This shape with `tool` cut out of it, and the part of it inside
+    /// `tool`, like `subtract` and `intersect` but from a single split of
+    /// the two shapes. Either may be an empty shape, if the tool misses
+    /// this shape or covers it entirely.
+    pub fn cut_keep_both(&self, tool: &Shape) -> Result<(Shape, Shape), Error> {
+        let mut builder = ffi::BOPAlgo_CellsBuilder_ctor(&self.inner, &tool.inner);
+        if builder.HasErrors() {
+            return Err(Error::NotDone);
+        }
+
+        let list = |shapes: &[&Shape]| {
+            let mut list = ffi::new_list_of_shape();
+            for shape in shapes {
+                ffi::shape_list_append_shape(list.pin_mut(), &shape.inner);
+            }
+            list
+        };
+
+        let outside =
+            ffi::BOPAlgo_CellsBuilder_cells(builder.pin_mut(), &list(&[self]), &list(&[tool]));
+        let inside =
+            ffi::BOPAlgo_CellsBuilder_cells(builder.pin_mut(), &list(&[self, tool]), &list(&[]));
+
+        Ok((Shape::from_shape(&outside), Shape::from_shape(&inside)))
+    }
+}
+
+#[cfg(feature = "rayon")]
+fn join<A, B>(a: impl FnOnce() -> A + Send, b: impl FnOnce() -> B + Send) -> (A, B)
+where
+    A: Send,
+    B: Send,
+{
+    rayon::join(a, b)
+}
+
+#[cfg(not(feature = "rayon"))]
+fn join<A, B>(a: impl FnOnce() -> A, b: impl FnOnce() -> B) -> (A, B) {
+    (a(), b())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nalgebra::point;
+    use nalgebra::Vector3;
+
+    fn hole_grid(count: usize) -> Vec<Shape> {
+        (0..count)
+            .map(|i| {
+                let (x, y) = ((i % 10) as f64 * 5.0 + 2.5, (i / 10) as f64 * 5.0 + 2.5);
+                Shape::cylinder(point![x, y, -1.0], 1.0, Vector3::z(), 7.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cut_all_matches_sequential_cuts() {
+        let plate = Shape::box_with_dimensions(50.0, 50.0, 5.0);
+        let tools = hole_grid(60);
+
+        let mut sequential = Shape::from_shape(&plate.inner);
+        for tool in &tools {
+            sequential = sequential.subtract(tool).unwrap();
+        }
+
+        let batched = plate.cut_all_parallel(tools).unwrap();
+
+        assert!((batched.volume() - sequential.volume()).abs() < 1.0e-6);
+        assert_eq!(batched.faces().count(), sequential.faces().count());
+    }
+
+    #[test]
+    fn overlapping_boxes() {
+        let a = Shape::box_from_corners(point![0.0, 0.0, 0.0], point![4.0, 3.0, 2.0]);
+        let b = Shape::box_from_corners(point![1.0, 2.0, 1.0], point![6.0, 5.0, 4.0]);
+
+        // The overlap is 3 x 1 x 1.
+        assert!((a.common_volume(&b).unwrap() - 3.0).abs() < 1.0e-9);
+        assert!((a.intersect(&b).unwrap().volume() - 3.0).abs() < 1.0e-9);
+
+        let difference = a.symmetric_difference(&b).unwrap();
+        assert!((difference.volume() - (24.0 + 45.0 - 2.0 * 3.0)).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn disjoint_boxes() {
+        let a = Shape::box_from_corners(point![0.0, 0.0, 0.0], point![1.0, 1.0, 1.0]);
+        let b = Shape::box_from_corners(point![5.0, 0.0, 0.0], point![6.0, 1.0, 1.0]);
+
+        assert_eq!(a.common_volume(&b).unwrap(), 0.0);
+        assert!((a.symmetric_difference(&b).unwrap().volume() - 2.0).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn cut_keep_both_matches_cut_and_common() {
+        let block = Shape::box_from_corners(point![0.0, 0.0, 0.0], point![10.0, 10.0, 10.0]);
+        let tool = Shape::cylinder(point![5.0, 5.0, 4.0], 3.0, Vector3::z(), 10.0);
+
+        let (outside, inside) = block.cut_keep_both(&tool).unwrap();
+        let cut = block.subtract(&tool).unwrap();
+        let common = block.intersect(&tool).unwrap();
+
+        assert!((outside.volume() - cut.volume()).abs() < 1.0e-6);
+        assert!((inside.volume() - common.volume()).abs() < 1.0e-6);
+        assert!((outside.volume() + inside.volume() - 1000.0).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn cut_keep_both_with_a_missed_tool() {
+        let a = Shape::box_from_corners(point![0.0, 0.0, 0.0], point![1.0, 1.0, 1.0]);
+        let b = Shape::box_from_corners(point![5.0, 0.0, 0.0], point![6.0, 1.0, 1.0]);
+
+        let (outside, inside) = a.cut_keep_both(&b).unwrap();
+        assert!((outside.volume() - 1.0).abs() < 1.0e-9);
+        assert_eq!(inside.volume(), 0.0);
+        assert_eq!(inside.faces().count(), 0);
+    }
+
+    #[test]
+    fn fuse_tree_is_deterministic() {
+        let first = fuse_tree(hole_grid(40)).unwrap();
+        let second = fuse_tree(hole_grid(40)).unwrap();
+
+        assert_eq!(first.solids().count(), 40);
+        assert_eq!(first.faces().count(), second.faces().count());
+        assert!((first.volume() - second.volume()).abs() < 1.0e-9);
+    }
+}
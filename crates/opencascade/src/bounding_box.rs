@@ -22,6 +22,15 @@ impl BoundingBox {
         }
     }
 
+    /// Create a box spanning from `min` to `max`, with no gap.
+    pub fn from_corners(min: Point3<f64>, max: Point3<f64>) -> BoundingBox {
+        let mut bb = Self::void();
+        bb.inner
+            .pin_mut()
+            .Update(min.x, min.y, min.z, max.x, max.y, max.z);
+        bb
+    }
+
     pub fn is_void(&self) -> bool {
         self.inner.IsVoid()
     }
@@ -40,12 +49,74 @@ impl BoundingBox {
         point![p.X(), p.Y(), p.Z()]
     }
 
+    /// Whether this box and `other`, gaps included, overlap or touch.
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        !self.inner.IsOut(&other.inner)
+    }
+
     /// Get a vector corresponding to the `gap` of this box in all dimensions.
     pub fn gap_vec(&self) -> Vector3<f64> {
         vector![1.0, 1.0, 1.0] * self.get_gap()
     }
 }
 
+/// The serde representation of `BoundingBox`: the corners of the box without
+/// its gap, as `[x_min, y_min, z_min, x_max, y_max, z_max]`, or `None` for a
+/// void box.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct FlatBoundingBox {
+    version: u32,
+    bounds: Option<[f64; 6]>,
+    gap: f64,
+}
+
+#[cfg(feature = "serde")]
+const FORMAT_VERSION: u32 = 1;
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for BoundingBox {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::Serialize as _;
+
+        let bounds = (!self.is_void()).then(|| {
+            let (min, max) = (self.min() + self.gap_vec(), self.max() - self.gap_vec());
+            [min.x, min.y, min.z, max.x, max.y, max.z]
+        });
+
+        FlatBoundingBox {
+            version: FORMAT_VERSION,
+            bounds,
+            gap: self.get_gap(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BoundingBox {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error as _;
+        use serde::Deserialize as _;
+
+        let flat = FlatBoundingBox::deserialize(deserializer)?;
+
+        if flat.version != FORMAT_VERSION {
+            return Err(D::Error::custom("unsupported bounding box format version"));
+        }
+
+        let mut bb = match flat.bounds {
+            Some([x_min, y_min, z_min, x_max, y_max, z_max]) => {
+                Self::from_corners(point![x_min, y_min, z_min], point![x_max, y_max, z_max])
+            }
+            None => Self::void(),
+        };
+        bb.inner.pin_mut().SetGap(flat.gap);
+
+        Ok(bb)
+    }
+}
+
 /// Compute the axis-aligned bounding box of `shape` using the `BRepBndLib`
 /// package.
 pub fn aabb(shape: &Shape) -> BoundingBox {
@@ -87,4 +158,32 @@ mod test {
         assert_eq!(bb.min(), point![0.0, 1.0, 2.0] - gap);
         assert_eq!(bb.max(), point![2.0, 3.0, 4.0] + gap);
     }
+
+    #[test]
+    fn intersecting_bounding_boxes() {
+        let a = BoundingBox::from_corners(point![0.0, 0.0, 0.0], point![2.0, 2.0, 2.0]);
+        let b = BoundingBox::from_corners(point![1.0, 1.0, 1.0], point![3.0, 3.0, 3.0]);
+        let c = BoundingBox::from_corners(point![2.5, 0.0, 0.0], point![3.0, 2.0, 2.0]);
+
+        assert!(a.intersects(&b) && b.intersects(&a));
+        assert!(b.intersects(&c));
+        assert!(!a.intersects(&c) && !c.intersects(&a));
+        assert!(!a.intersects(&BoundingBox::void()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn bounding_box_serde_round_trip() {
+        let bb = aabb(&Shape::sphere(1.0).build());
+        let decoded: BoundingBox =
+            serde_json::from_str(&serde_json::to_string(&bb).unwrap()).unwrap();
+
+        assert!((decoded.min() - bb.min()).norm() < 1.0e-12);
+        assert!((decoded.max() - bb.max()).norm() < 1.0e-12);
+        assert_eq!(decoded.get_gap(), bb.get_gap());
+
+        let void: BoundingBox =
+            serde_json::from_str(&serde_json::to_string(&BoundingBox::void()).unwrap()).unwrap();
+        assert!(void.is_void());
+    }
 }
@@ -0,0 +1,213 @@
+//! The unit and precision a model is built in.
+//!
+//! Shapes are always stored in millimeters. A `ModelContext` only changes
+//! how the numbers given to workplanes and sketches, and written by
+//! exporters, are read: `rect(1.0, 2.0)` on a workplane in an inch context
+//! is 25.4 x 50.8 millimeters. Lengths are converted when geometry is built,
+//! so shapes built in different contexts can be combined in booleans as
+//! they are.
+
+use crate::angle::Angle;
+use std::cell::RefCell;
+
+/// A unit of length.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Unit {
+    Micrometer,
+    #[default]
+    Millimeter,
+    Centimeter,
+    Meter,
+    Inch,
+    Foot,
+}
+
+impl Unit {
+    /// How many millimeters one of this unit is.
+    pub fn millimeters(self) -> f64 {
+        match self {
+            Unit::Micrometer => 0.001,
+            Unit::Millimeter => 1.0,
+            Unit::Centimeter => 10.0,
+            Unit::Meter => 1000.0,
+            Unit::Inch => 25.4,
+            Unit::Foot => 304.8,
+        }
+    }
+
+    /// The name of this unit for OCCT's "write.step.unit" parameter.
+    pub(crate) fn step_name(self) -> &'static str {
+        match self {
+            Unit::Micrometer => "UM",
+            Unit::Millimeter => "MM",
+            Unit::Centimeter => "CM",
+            Unit::Meter => "M",
+            Unit::Inch => "INCH",
+            Unit::Foot => "FT",
+        }
+    }
+
+    /// The name of this unit in the `unit` attribute of a 3MF model.
+    pub(crate) fn three_mf_name(self) -> &'static str {
+        match self {
+            Unit::Micrometer => "micron",
+            Unit::Millimeter => "millimeter",
+            Unit::Centimeter => "centimeter",
+            Unit::Meter => "meter",
+            Unit::Inch => "inch",
+            Unit::Foot => "foot",
+        }
+    }
+}
+
+/// The unit and tolerances a model is built in. Pass one to
+/// `Workplane::with_context` or `ExportPipeline::with_context`, or make it
+/// the default for everything built on this thread with `scoped`.
+#[derive(Debug, Copy, Clone)]
+pub struct ModelContext {
+    pub unit: Unit,
+    /// Points closer together than this, in `unit`, are the same.
+    pub linear_tolerance: f64,
+    /// Directions closer together than this are the same.
+    pub angular_tolerance: Angle,
+}
+
+impl Default for ModelContext {
+    /// Millimeters, with OCCT's `Precision::Confusion` and
+    /// `Precision::Angular` as the tolerances.
+    fn default() -> Self {
+        Self::new(Unit::Millimeter)
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<ModelContext> = RefCell::new(ModelContext::default());
+}
+
+impl ModelContext {
+    /// A context in `unit`, with the default tolerances.
+    pub fn new(unit: Unit) -> Self {
+        Self {
+            unit,
+            linear_tolerance: 1.0e-7,
+            angular_tolerance: Angle::Radians(1.0e-12),
+        }
+    }
+
+    /// The default context on this thread: the innermost one made `scoped`
+    /// which is still alive, or millimeters.
+    pub fn current() -> Self {
+        CURRENT.with(|current| *current.borrow())
+    }
+
+    /// Make this the default context on this thread until the returned guard
+    /// is dropped, for workplanes and exporters made without one.
+    #[must_use = "the context is only the default until the guard is dropped"]
+    pub fn scoped(self) -> ContextGuard {
+        let previous = CURRENT.with(|current| current.replace(self));
+
+        ContextGuard { previous }
+    }
+
+    /// `length`, in this context's unit, in millimeters.
+    pub fn to_millimeters(&self, length: f64) -> f64 {
+        length * self.unit.millimeters()
+    }
+
+    /// `length`, in millimeters, in this context's unit.
+    pub fn from_millimeters(&self, length: f64) -> f64 {
+        length / self.unit.millimeters()
+    }
+}
+
+/// Restores the previous default context when dropped. See
+/// `ModelContext::scoped`.
+pub struct ContextGuard {
+    previous: ModelContext,
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = self.previous);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::Shape;
+    use crate::workplane::Workplane;
+    use nalgebra::point;
+    use nalgebra::vector;
+
+    #[test]
+    fn scoped_contexts_nest() {
+        assert_eq!(ModelContext::current().unit, Unit::Millimeter);
+
+        {
+            let _inches = ModelContext::new(Unit::Inch).scoped();
+            assert_eq!(ModelContext::current().unit, Unit::Inch);
+
+            {
+                let _meters = ModelContext::new(Unit::Meter).scoped();
+                assert_eq!(ModelContext::current().unit, Unit::Meter);
+            }
+
+            assert_eq!(ModelContext::current().unit, Unit::Inch);
+            assert_eq!(ModelContext::current().to_millimeters(2.0), 50.8);
+        }
+
+        assert_eq!(ModelContext::current().unit, Unit::Millimeter);
+    }
+
+    #[test]
+    fn inch_workplane() {
+        let workplane = Workplane::xy().with_context(ModelContext::new(Unit::Inch));
+        let block = workplane
+            .sketch()
+            .line_to(1.0, 0.0)
+            .line_to(1.0, 2.0)
+            .line_to(0.0, 2.0)
+            .extrude_symmetric(3.0)
+            .unwrap();
+
+        let bounds = block.bounding_box();
+        let size = bounds.max() - bounds.min();
+        assert!((size - vector![25.4, 50.8, 76.2]).norm() < 1.0e-6, "{size}");
+
+        // Local positions round trip in inches.
+        let corner = workplane.to_world_pos(point![1.0, 2.0, 0.0]);
+        assert!((corner - point![25.4, 50.8, 0.0]).norm() < 1.0e-9);
+        assert!((workplane.to_local_pos(corner) - point![1.0, 2.0, 0.0]).norm() < 1.0e-9);
+    }
+
+    #[test]
+    fn inch_step_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "opencascade-inch-block-{}.step",
+            std::process::id()
+        ));
+
+        {
+            let _inches = ModelContext::new(Unit::Inch).scoped();
+            let block = Workplane::xy()
+                .sketch()
+                .line_to(1.0, 0.0)
+                .line_to(1.0, 2.0)
+                .line_to(0.0, 2.0)
+                .extrude_symmetric(3.0)
+                .unwrap();
+            block.write_step(&path).unwrap();
+        }
+
+        let step = std::fs::read_to_string(&path).unwrap();
+        assert!(step.contains("INCH"));
+
+        // Read back in millimeters, the block is its physical size.
+        let block = Shape::read_step(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let bounds = block.bounding_box();
+        let size = bounds.max() - bounds.min();
+        assert!((size - vector![25.4, 50.8, 76.2]).norm() < 1.0e-4, "{size}");
+    }
+}
@@ -0,0 +1,234 @@
+//! Conversions into the nalgebra types used throughout this crate.
+//!
+//! Public constructors accept any type implementing these traits, so, with
+//! the `glam` feature enabled, `glam::DVec3` and `glam::DQuat` can be passed
+//! directly wherever a point, vector or rotation is expected.
+
+use crate::Error;
+use nalgebra::Point3;
+use nalgebra::Quaternion;
+use nalgebra::RealField;
+use nalgebra::Scalar;
+use nalgebra::UnitQuaternion;
+use nalgebra::UnitVector3;
+use nalgebra::Vector3;
+
+pub trait IntoPoint3<F: Scalar = f64> {
+    fn into_point3(self) -> Point3<F>;
+}
+
+pub trait IntoVector3<F: Scalar = f64> {
+    fn into_vector3(self) -> Vector3<F>;
+}
+
+/// Directions, normalized on conversion. A zero vector has no direction,
+/// so converting one, or one which isn't finite, is an
+/// `Error::InvalidDirection`.
+pub trait IntoUnitVector3 {
+    fn into_unit_vector3(self) -> Result<UnitVector3<f64>, Error>;
+}
+
+impl<V: IntoVector3> IntoUnitVector3 for V {
+    fn into_unit_vector3(self) -> Result<UnitVector3<f64>, Error> {
+        let vector = self.into_vector3();
+
+        if !vector.iter().all(|component| component.is_finite()) {
+            return Err(Error::InvalidDirection);
+        }
+
+        UnitVector3::try_new(vector, 0.0).ok_or(Error::InvalidDirection)
+    }
+}
+
+/// Rotations, normalized on conversion, as required by `UnitQuaternion`. A
+/// zero quaternion has no rotation, so converting one, or one which isn't
+/// finite, is an `Error::InvalidRotation`.
+pub trait IntoUnitQuaternion<F: Scalar = f64> {
+    fn into_unit_quaternion(self) -> Result<UnitQuaternion<F>, Error>;
+}
+
+impl<F: Scalar> IntoPoint3<F> for Point3<F> {
+    fn into_point3(self) -> Point3<F> {
+        self
+    }
+}
+
+impl<F: Scalar> IntoVector3<F> for Vector3<F> {
+    fn into_vector3(self) -> Vector3<F> {
+        self
+    }
+}
+
+impl<F: Scalar> IntoVector3<F> for UnitVector3<F> {
+    fn into_vector3(self) -> Vector3<F> {
+        self.into_inner()
+    }
+}
+
+impl<F: Scalar> IntoUnitQuaternion<F> for UnitQuaternion<F> {
+    fn into_unit_quaternion(self) -> Result<UnitQuaternion<F>, Error> {
+        Ok(self)
+    }
+}
+
+impl<F: Scalar + RealField> IntoUnitQuaternion<F> for Quaternion<F> {
+    fn into_unit_quaternion(self) -> Result<UnitQuaternion<F>, Error> {
+        if !self.coords.iter().all(|component| component.is_finite()) {
+            return Err(Error::InvalidRotation);
+        }
+
+        UnitQuaternion::try_new(self, F::zero()).ok_or(Error::InvalidRotation)
+    }
+}
+
+/// Conversions from this crate's nalgebra types into glam types.
+#[cfg(feature = "glam")]
+pub trait IntoGlam {
+    type Output;
+
+    fn into_glam(self) -> Self::Output;
+}
+
+#[cfg(feature = "glam")]
+mod glam_impls {
+    use super::*;
+    use glam::DQuat;
+    use glam::DVec3;
+
+    impl IntoPoint3 for DVec3 {
+        fn into_point3(self) -> Point3<f64> {
+            Point3::new(self.x, self.y, self.z)
+        }
+    }
+
+    impl IntoVector3 for DVec3 {
+        fn into_vector3(self) -> Vector3<f64> {
+            Vector3::new(self.x, self.y, self.z)
+        }
+    }
+
+    impl IntoUnitQuaternion for DQuat {
+        fn into_unit_quaternion(self) -> Result<UnitQuaternion<f64>, Error> {
+            Quaternion::new(self.w, self.x, self.y, self.z).into_unit_quaternion()
+        }
+    }
+
+    impl IntoGlam for Point3<f64> {
+        type Output = DVec3;
+
+        fn into_glam(self) -> DVec3 {
+            DVec3::new(self.x, self.y, self.z)
+        }
+    }
+
+    impl IntoGlam for Vector3<f64> {
+        type Output = DVec3;
+
+        fn into_glam(self) -> DVec3 {
+            DVec3::new(self.x, self.y, self.z)
+        }
+    }
+
+    impl IntoGlam for UnitQuaternion<f64> {
+        type Output = DQuat;
+
+        fn into_glam(self) -> DQuat {
+            DQuat::from_xyzw(self.i, self.j, self.k, self.w)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(feature = "glam")]
+    use glam::DQuat;
+    #[cfg(feature = "glam")]
+    use glam::DVec3;
+
+    #[test]
+    fn directions_must_be_non_zero() {
+        let direction = Vector3::new(0.0, 3.0, 4.0).into_unit_vector3().unwrap();
+        assert!((direction.into_inner() - Vector3::new(0.0, 0.6, 0.8)).norm() < 1.0e-15);
+
+        for vector in [
+            Vector3::zeros(),
+            Vector3::new(f64::NAN, 0.0, 1.0),
+            Vector3::new(f64::INFINITY, 0.0, 0.0),
+        ] {
+            assert!(matches!(
+                vector.into_unit_vector3(),
+                Err(Error::InvalidDirection)
+            ));
+        }
+    }
+
+    #[test]
+    fn rotations_must_be_non_zero() {
+        for quaternion in [
+            Quaternion::new(0.0, 0.0, 0.0, 0.0),
+            Quaternion::new(f64::NAN, 0.0, 0.0, 1.0),
+            Quaternion::new(1.0, f64::INFINITY, 0.0, 0.0),
+        ] {
+            assert!(matches!(
+                quaternion.into_unit_quaternion(),
+                Err(Error::InvalidRotation)
+            ));
+        }
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn zero_glam_vectors_have_no_direction() {
+        assert!(matches!(
+            DVec3::ZERO.into_unit_vector3(),
+            Err(Error::InvalidDirection)
+        ));
+        assert!(matches!(
+            DQuat::from_xyzw(0.0, 0.0, 0.0, 0.0).into_unit_quaternion(),
+            Err(Error::InvalidRotation)
+        ));
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn points_and_vectors_round_trip() {
+        // Values which don't survive a trip through f32.
+        let values = DVec3::new(0.1, -1.0e-300, 123_456_789.123_456_789);
+
+        assert_eq!(values.into_point3().into_glam(), values);
+        assert_eq!(values.into_vector3().into_glam(), values);
+
+        let point = Point3::new(1.0 / 3.0, f64::MAX, -f64::MIN_POSITIVE);
+        assert_eq!(point.into_glam().into_point3(), point);
+
+        let vector = Vector3::new(std::f64::consts::PI, -0.0, 1.0e-12);
+        assert_eq!(vector.into_glam().into_vector3(), vector);
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn quaternions_round_trip() {
+        let rotation = DQuat::from_axis_angle(DVec3::new(1.0, 2.0, 3.0).normalize(), 0.7);
+        let converted = rotation.into_unit_quaternion().unwrap();
+
+        // Same rotation, applied to the same vector.
+        let v = DVec3::new(0.3, -4.0, 2.5);
+        let rotated = converted * v.into_vector3();
+        assert!((rotated.into_glam() - rotation * v).length() < 1.0e-12);
+
+        assert!(converted.into_glam().abs_diff_eq(rotation, 1.0e-15));
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn quaternions_are_normalized() {
+        let unnormalized = DQuat::from_xyzw(0.0, 0.0, 2.0, 2.0);
+        let converted = unnormalized.into_unit_quaternion().unwrap();
+
+        assert!((converted.norm() - 1.0).abs() < 1.0e-15);
+        assert!(converted
+            .into_glam()
+            .abs_diff_eq(unnormalized.normalize(), 1.0e-15));
+    }
+}
@@ -0,0 +1,230 @@
+use crate::primitives::Compound;
+use crate::primitives::Shape;
+use crate::primitives::Vertex;
+use crate::Error;
+use nalgebra::Point3;
+use std::fmt;
+
+/// How many of each kind of sub-shape a shape has.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct TopologyCounts {
+    pub solids: usize,
+    pub faces: usize,
+    pub edges: usize,
+    pub vertices: usize,
+}
+
+impl TopologyCounts {
+    pub fn of(shape: &Shape) -> Self {
+        Self {
+            solids: shape.solids().count(),
+            faces: shape.faces().count(),
+            edges: shape.edges().count(),
+            vertices: shape.vertices().count(),
+        }
+    }
+}
+
+/// How two shapes differ, made with `Shape::geometric_diff`. Each pair is
+/// the value for the shape compared, then for the other shape.
+#[derive(Debug, Clone)]
+pub struct DiffReport {
+    pub volume: (f64, f64),
+    pub surface_area: (f64, f64),
+    /// The corners of the bounding boxes, as `(min, max)`.
+    pub bounding_box: ((Point3<f64>, Point3<f64>), (Point3<f64>, Point3<f64>)),
+    pub topology: (TopologyCounts, TopologyCounts),
+    /// The furthest any sample on the surface of either shape is from the
+    /// surface of the other.
+    pub max_deviation: f64,
+    /// Where on the surface of one of the shapes `max_deviation` was
+    /// measured, if any samples were taken.
+    pub worst_location: Option<Point3<f64>>,
+}
+
+impl DiffReport {
+    /// The furthest apart the corners of the bounding boxes are.
+    pub fn bounding_box_deviation(&self) -> f64 {
+        let ((min_a, max_a), (min_b, max_b)) = self.bounding_box;
+
+        (min_a - min_b).norm().max((max_a - max_b).norm())
+    }
+
+    /// Whether the shapes are the same to within `tolerance`: they have as
+    /// many of each kind of sub-shape, and their surfaces and bounding boxes
+    /// are no further apart than `tolerance`. Volume and surface area aren't
+    /// compared, as the surfaces being close already bounds how much they
+    /// differ.
+    pub fn is_identical(&self, tolerance: f64) -> bool {
+        self.topology.0 == self.topology.1
+            && self.max_deviation <= tolerance
+            && self.bounding_box_deviation() <= tolerance
+    }
+}
+
+impl fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ((min_a, max_a), (min_b, max_b)) = self.bounding_box;
+        let (counts_a, counts_b) = self.topology;
+
+        writeln!(
+            f,
+            "volume: {} vs {} (difference {})",
+            self.volume.0,
+            self.volume.1,
+            self.volume.1 - self.volume.0
+        )?;
+        writeln!(
+            f,
+            "surface area: {} vs {} (difference {})",
+            self.surface_area.0,
+            self.surface_area.1,
+            self.surface_area.1 - self.surface_area.0
+        )?;
+        writeln!(
+            f,
+            "bounding box: {min_a} to {max_a} vs {min_b} to {max_b} (deviation {})",
+            self.bounding_box_deviation()
+        )?;
+        writeln!(
+            f,
+            "topology: {} vs {} solids, {} vs {} faces, {} vs {} edges, {} vs {} vertices",
+            counts_a.solids,
+            counts_b.solids,
+            counts_a.faces,
+            counts_b.faces,
+            counts_a.edges,
+            counts_b.edges,
+            counts_a.vertices,
+            counts_b.vertices
+        )?;
+
+        match self.worst_location {
+            Some(location) => write!(f, "max deviation: {} at {location}", self.max_deviation),
+            None => write!(f, "max deviation: no samples"),
+        }
+    }
+}
+
+impl Shape {
+    /// Compare this shape with `other`, to check that rebuilding a model
+    /// gives the same geometry. Along with their volumes, surface areas,
+    /// bounding boxes and numbers of sub-shapes, this measures how far apart
+    /// their surfaces are: the nodes of a mesh of each shape, with
+    /// `tolerance` as the deflection, are sampled, and the distance from
+    /// each to the faces of the other shape measured.
+    pub fn geometric_diff(&self, other: &Shape, tolerance: f64) -> Result<DiffReport, Error> {
+        let (to_other, at_self) = self.surface_deviation(other, tolerance)?;
+        let (to_self, at_other) = other.surface_deviation(self, tolerance)?;
+
+        let (max_deviation, worst_location) = if to_other >= to_self {
+            (to_other, at_self)
+        } else {
+            (to_self, at_other)
+        };
+
+        let corners = |shape: &Shape| {
+            let bounds = shape.bounding_box();
+            (bounds.min(), bounds.max())
+        };
+        let surface_area =
+            |shape: &Shape| shape.faces().map(|face| face.surface_area()).sum::<f64>();
+
+        Ok(DiffReport {
+            volume: (self.volume(), other.volume()),
+            surface_area: (surface_area(self), surface_area(other)),
+            bounding_box: (corners(self), corners(other)),
+            topology: (TopologyCounts::of(self), TopologyCounts::of(other)),
+            max_deviation,
+            worst_location,
+        })
+    }
+
+    /// The furthest any node of a mesh of this shape is from the faces of
+    /// `other`, and where that node is.
+    fn surface_deviation(
+        &self,
+        other: &Shape,
+        deflection: f64,
+    ) -> Result<(f64, Option<Point3<f64>>), Error> {
+        let mesh = self.mesh_with_tolerance(deflection)?;
+
+        // Measure to the faces, not the solids, which points inside are no
+        // distance from.
+        let other_faces: Vec<_> = other.faces().map(Shape::from).collect();
+        let other_faces = Shape::from(Compound::from_shapes(&other_faces));
+
+        let mut worst = (0.0, None);
+        for &point in &mesh.vertices {
+            let sample = Shape::from(Vertex::new(point));
+            let distance = sample
+                .distance_between(&other_faces)?
+                .unwrap_or(f64::INFINITY);
+
+            if worst.1.is_none() || distance > worst.0 {
+                worst = (distance, Some(point));
+            }
+        }
+
+        Ok(worst)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::Face;
+    use crate::primitives::Wire;
+    use nalgebra::point;
+    use nalgebra::Vector3;
+
+    /// An L-shaped bracket with its inside corner filleted to `radius`.
+    fn bracket(radius: f64) -> Shape {
+        let profile = Wire::from_ordered_points([
+            point![0.0, 0.0, 0.0],
+            point![30.0, 0.0, 0.0],
+            point![30.0, 4.0, 0.0],
+            point![4.0, 4.0, 0.0],
+            point![4.0, 20.0, 0.0],
+            point![0.0, 20.0, 0.0],
+        ])
+        .unwrap();
+        let bracket: Shape = Face::from_wire(&profile)
+            .unwrap()
+            .extrude(Vector3::new(0.0, 0.0, 10.0))
+            .into();
+
+        let corner = bracket
+            .edges()
+            .find(|edge| {
+                let (start, end) = (edge.start_point(), edge.end_point());
+                (start.xy() - point![4.0, 4.0]).norm() < 1.0e-9
+                    && (end.xy() - point![4.0, 4.0]).norm() < 1.0e-9
+            })
+            .unwrap();
+
+        bracket.fillet_edge(radius, &corner)
+    }
+
+    #[test]
+    fn identical_rebuilds() {
+        let report = bracket(2.0).geometric_diff(&bracket(2.0), 0.01).unwrap();
+
+        assert!(report.is_identical(1.0e-6), "{report}");
+        assert_eq!(report.volume.0, report.volume.1);
+    }
+
+    #[test]
+    fn detects_fillet_change() {
+        let report = bracket(2.0).geometric_diff(&bracket(2.1), 0.01).unwrap();
+
+        assert!(!report.is_identical(0.01), "{report}");
+        assert_eq!(report.topology.0, report.topology.1);
+        assert!(report.max_deviation > 0.02, "{report}");
+
+        // The worst deviation is on the fillet, in the inside corner.
+        let location = report.worst_location.unwrap();
+        assert!(location.x > 4.0 && location.x < 6.2, "{location}");
+        assert!(location.y > 4.0 && location.y < 6.2, "{location}");
+    }
+}
@@ -0,0 +1,287 @@
+//! 2D drawings of shapes, made with OCCT's hidden line removal.
+
+use crate::primitives::make_dir;
+use crate::primitives::make_point;
+use crate::primitives::Edge;
+use crate::primitives::EdgeIterator;
+use crate::primitives::Shape;
+use crate::workplane::Workplane;
+use crate::Error;
+use nalgebra::Point3;
+use opencascade_sys::ffi;
+
+mod sheet;
+mod silhouette;
+
+pub use sheet::*;
+pub use silhouette::*;
+
+/// How far a hidden edge may be from the visible edges and still be
+/// drawn over by them.
+const COVERED_TOLERANCE: f64 = 1.0e-6;
+
+/// The deflection edges are approximated with to tell whether hidden edges
+/// are drawn over, which adds to `COVERED_TOLERANCE`.
+const COVERED_DEFLECTION: f64 = 1.0e-4;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum Projection {
+    #[default]
+    Orthographic,
+    /// A central projection, from an eye `focus` units in front of the view
+    /// plane, along its normal.
+    Perspective { focus: f64 },
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct ProjectOptions {
+    pub projection: Projection,
+    /// Include edges between tangent faces, like the boundaries of a
+    /// fillet, with the sharp edges. Drawings usually leave them out.
+    pub smooth_edges: bool,
+}
+
+/// The edges of a shape as seen from a view, split into visible and hidden
+/// edges. Sharp edges are edges of the shape; outline edges are the
+/// silhouettes of curved faces, like the sides of a cylinder.
+///
+/// All edges are in view coordinates: x and y along the view's x and y
+/// directions, and z = 0. Hidden edges which lie wholly under visible
+/// ones, like the far end of a cylinder seen along its axis, are left out,
+/// as they'd be drawn over.
+pub struct ProjectedView {
+    visible_sharp: Vec<Edge>,
+    visible_outline: Vec<Edge>,
+    hidden_sharp: Vec<Edge>,
+    hidden_outline: Vec<Edge>,
+}
+
+impl ProjectedView {
+    pub fn visible_sharp_edges(&self) -> &[Edge] {
+        &self.visible_sharp
+    }
+
+    pub fn visible_outline_edges(&self) -> &[Edge] {
+        &self.visible_outline
+    }
+
+    pub fn hidden_sharp_edges(&self) -> &[Edge] {
+        &self.hidden_sharp
+    }
+
+    pub fn hidden_outline_edges(&self) -> &[Edge] {
+        &self.hidden_outline
+    }
+
+    pub fn visible_edges(&self) -> impl Iterator<Item = &Edge> {
+        self.visible_sharp.iter().chain(&self.visible_outline)
+    }
+
+    pub fn hidden_edges(&self) -> impl Iterator<Item = &Edge> {
+        self.hidden_sharp.iter().chain(&self.hidden_outline)
+    }
+}
+
+/// Project `shape` onto the plane of `view`, looking at it from the side
+/// the view's normal points to.
+pub fn project(
+    shape: &Shape,
+    view: &Workplane,
+    options: ProjectOptions,
+) -> Result<ProjectedView, Error> {
+    let (perspective, focus) = match options.projection {
+        Projection::Orthographic => (false, 0.0),
+        Projection::Perspective { focus } if focus > 0.0 => (true, focus),
+        Projection::Perspective { focus } => return Err(Error::InvalidFocus { focus }),
+    };
+
+    let axis = ffi::gp_Ax2_with_x_dir(
+        &make_point(Point3::from(view.origin())),
+        &make_dir(view.normal().into_inner()),
+        &make_dir(view.x_dir().into_inner()),
+    );
+    let mut hlr = ffi::HLRBRep_HLRToShape_project(&shape.inner, &axis, perspective, focus);
+
+    let mut edges = |edge_type, visible| {
+        let compound = ffi::HLRBRep_HLRToShape_edges(hlr.pin_mut(), edge_type, visible);
+
+        // Null if there are no edges of this kind, which explores as empty.
+        EdgeIterator::new(&Shape::from_shape(&compound)).collect::<Vec<_>>()
+    };
+
+    let mut sharp = |visible| {
+        let mut sharp = edges(ffi::HLRBRep_TypeOfResultingEdge::HLRBRep_Sharp, visible);
+        if options.smooth_edges {
+            sharp.extend(edges(
+                ffi::HLRBRep_TypeOfResultingEdge::HLRBRep_Rg1Line,
+                visible,
+            ));
+        }
+
+        sharp
+    };
+
+    let visible_sharp = sharp(true);
+    let hidden_sharp = sharp(false);
+    let visible_outline = edges(ffi::HLRBRep_TypeOfResultingEdge::HLRBRep_OutLine, true);
+    let hidden_outline = edges(ffi::HLRBRep_TypeOfResultingEdge::HLRBRep_OutLine, false);
+
+    let visible_segments: Vec<_> = visible_sharp
+        .iter()
+        .chain(&visible_outline)
+        .flat_map(chords)
+        .collect();
+    let uncovered = |edges: Vec<Edge>| -> Vec<Edge> {
+        edges
+            .into_iter()
+            .filter(|edge| !is_covered(edge, &visible_segments))
+            .collect()
+    };
+
+    Ok(ProjectedView {
+        hidden_sharp: uncovered(hidden_sharp),
+        hidden_outline: uncovered(hidden_outline),
+        visible_sharp,
+        visible_outline,
+    })
+}
+
+/// The chords of an approximation of `edge`.
+fn chords(edge: &Edge) -> Vec<(Point3<f64>, Point3<f64>)> {
+    let points: Vec<_> = edge.approximation_segments(COVERED_DEFLECTION).collect();
+
+    points.windows(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
+/// Whether every point of `edge` is on one of `segments`.
+fn is_covered(edge: &Edge, segments: &[(Point3<f64>, Point3<f64>)]) -> bool {
+    let tolerance = COVERED_TOLERANCE + COVERED_DEFLECTION;
+    let on_segments = |point: Point3<f64>| {
+        segments
+            .iter()
+            .any(|segment| distance_to_segment(point, segment) <= tolerance)
+    };
+
+    edge.approximation_segments(COVERED_DEFLECTION)
+        .all(on_segments)
+}
+
+fn distance_to_segment(point: Point3<f64>, (start, end): &(Point3<f64>, Point3<f64>)) -> f64 {
+    let chord = end - start;
+    let along = (point - start).dot(&chord) / chord.norm_squared().max(f64::MIN_POSITIVE);
+
+    (point - (start + chord * along.clamp(0.0, 1.0))).norm()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nalgebra::point;
+    use nalgebra::Vector3;
+
+    fn points<'a>(edges: impl IntoIterator<Item = &'a Edge>) -> Vec<Point3<f64>> {
+        edges
+            .into_iter()
+            .flat_map(|edge| edge.approximation_segments(0.01))
+            .collect()
+    }
+
+    #[test]
+    fn cylinder_along_its_axis() {
+        let cylinder = Shape::cylinder_radius_height(5.0, 10.0);
+        let view = project(&cylinder, &Workplane::xy(), ProjectOptions::default()).unwrap();
+
+        let visible = points(view.visible_edges());
+        assert!(!visible.is_empty());
+        for point in &visible {
+            assert!((point.coords.xy().norm() - 5.0).abs() < 1.0e-3, "{point}");
+            assert!(point.z.abs() < 1.0e-9);
+        }
+
+        // The outline is a whole circle.
+        let length: f64 = view.visible_edges().map(Edge::length).sum();
+        assert!((length - 10.0 * std::f64::consts::PI).abs() < 1.0e-3);
+
+        // The bottom circle is hidden under the top one.
+        assert_eq!(view.hidden_edges().count(), 0);
+    }
+
+    #[test]
+    fn cylinder_from_the_side() {
+        let cylinder = Shape::cylinder_radius_height(5.0, 10.0);
+        let view = project(&cylinder, &Workplane::xz(), ProjectOptions::default()).unwrap();
+
+        // The silhouette lines of the curved face.
+        let outlines = view.visible_outline_edges();
+        assert_eq!(outlines.len(), 2);
+        for outline in outlines {
+            let (start, end) = (outline.start_point(), outline.end_point());
+            assert!((start.x.abs() - 5.0).abs() < 1.0e-6);
+            assert!((start.x - end.x).abs() < 1.0e-6);
+            assert!(((start.y - end.y).abs() - 10.0).abs() < 1.0e-6);
+        }
+
+        // Everything visible lies on the 10 x 10 rectangle.
+        let visible = points(view.visible_edges());
+        for point in &visible {
+            let on_side = (point.x.abs() - 5.0).abs() < 1.0e-6;
+            let on_end = point.y.abs() < 1.0e-6 || (point.y - 10.0).abs() < 1.0e-6;
+            assert!(on_side || on_end, "{point}");
+        }
+        assert!(visible.iter().any(|point| point.y.abs() < 1.0e-6));
+        assert!(visible.iter().any(|point| (point.y - 10.0).abs() < 1.0e-6));
+    }
+
+    #[test]
+    fn hole_seen_from_the_side() {
+        let block = Shape::box_from_corners(point![-5.0, -5.0, 0.0], point![5.0, 5.0, 10.0]);
+        let hole = Shape::cylinder_radius_height(2.0, 10.0);
+        let drilled = block.subtract(&hole).unwrap();
+
+        let view = project(&drilled, &Workplane::xz(), ProjectOptions::default()).unwrap();
+
+        // Only the sides of the hole are hidden. Its ends are under the top
+        // and bottom of the block.
+        let hidden = points(view.hidden_edges());
+        assert!(!hidden.is_empty());
+        for point in &hidden {
+            assert!((point.x.abs() - 2.0).abs() < 1.0e-6, "{point}");
+        }
+    }
+
+    #[test]
+    fn smooth_edges_are_optional() {
+        let block = Shape::box_with_dimensions(10.0, 10.0, 10.0);
+        let edge = block.edges().next().unwrap();
+        let filleted = block.fillet_edge(2.0, &edge);
+
+        let view = Workplane::from_vectors(Vector3::x(), Vector3::new(1.0, 1.0, 1.0)).unwrap();
+        let without = project(&filleted, &view, ProjectOptions::default()).unwrap();
+        let with = project(
+            &filleted,
+            &view,
+            ProjectOptions {
+                smooth_edges: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert!(with.visible_sharp_edges().len() > without.visible_sharp_edges().len());
+    }
+
+    #[test]
+    fn perspective_needs_a_positive_focus() {
+        let block = Shape::box_with_dimensions(1.0, 1.0, 1.0);
+        let options = |focus| ProjectOptions {
+            projection: Projection::Perspective { focus },
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            project(&block, &Workplane::xy(), options(0.0)),
+            Err(Error::InvalidFocus { .. })
+        ));
+        assert!(project(&block, &Workplane::xy(), options(100.0)).is_ok());
+    }
+}
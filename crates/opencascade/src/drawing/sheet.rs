@@ -0,0 +1,357 @@
+use crate::drawing::project;
+use crate::drawing::ProjectOptions;
+use crate::drawing::ProjectedView;
+use crate::primitives::Edge;
+use crate::primitives::Shape;
+use crate::workplane::Workplane;
+use crate::Error;
+use nalgebra::Point2;
+use nalgebra::UnitVector3;
+use nalgebra::Vector3;
+use std::fmt::Write;
+
+/// Space around and between views, in mm on the page.
+const MARGIN: f64 = 15.0;
+
+/// Height of the view labels, in mm on the page.
+const LABEL_SIZE: f64 = 4.0;
+
+/// How far approximated curves may stray from the edges, in mm on the page.
+const DEFLECTION: f64 = 0.05;
+
+/// The views of a drawing, as seen by a viewer with +Z up.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StandardView {
+    /// Looking along +Y.
+    Front,
+    /// Looking down, along -Z.
+    Top,
+    /// Looking along -X.
+    Right,
+    /// Looking down from the front right.
+    Isometric,
+}
+
+impl StandardView {
+    pub fn workplane(&self) -> Workplane {
+        match self {
+            Self::Front => Workplane::xz(),
+            Self::Top => Workplane::xy(),
+            Self::Right => Workplane::new(Vector3::y_axis(), Vector3::x_axis()),
+            Self::Isometric => Workplane::new(
+                UnitVector3::new_normalize(Vector3::new(1.0, 1.0, 0.0)),
+                UnitVector3::new_normalize(Vector3::new(1.0, -1.0, 1.0)),
+            ),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Front => "FRONT",
+            Self::Top => "TOP",
+            Self::Right => "RIGHT",
+            Self::Isometric => "ISOMETRIC",
+        }
+    }
+
+    /// The (column, row) of the view in a third angle arrangement, with row 0
+    /// at the top of the page.
+    fn cell(&self) -> (usize, usize) {
+        match self {
+            Self::Front => (0, 1),
+            Self::Top => (0, 0),
+            Self::Right => (1, 1),
+            Self::Isometric => (1, 0),
+        }
+    }
+}
+
+/// A view, approximated by polylines in page units (mm), relative to the
+/// view's own coordinates.
+struct LaidOutView {
+    view: StandardView,
+    visible: Vec<Vec<Point2<f64>>>,
+    hidden: Vec<Vec<Point2<f64>>>,
+    min: Point2<f64>,
+    max: Point2<f64>,
+}
+
+impl LaidOutView {
+    fn new(view: StandardView, projected: &ProjectedView, scale: f64) -> Self {
+        let polyline = |edge: &Edge| -> Vec<_> {
+            edge.approximation_segments(DEFLECTION / scale)
+                .map(|point| Point2::new(point.x * scale, point.y * scale))
+                .collect()
+        };
+
+        let visible: Vec<_> = projected.visible_edges().map(polyline).collect();
+        let hidden: Vec<_> = projected.hidden_edges().map(polyline).collect();
+
+        let mut points = visible.iter().chain(&hidden).flatten();
+        let first = points.next().copied().unwrap_or_else(Point2::origin);
+        let (min, max) = points.fold((first, first), |(min, max), point| {
+            (min.inf(point), max.sup(point))
+        });
+
+        Self {
+            view,
+            visible,
+            hidden,
+            min,
+            max,
+        }
+    }
+
+    fn size(&self) -> (f64, f64) {
+        (self.max.x - self.min.x, self.max.y - self.min.y)
+    }
+}
+
+/// Draw `views` of `shape` on an SVG page, in the third angle arrangement:
+/// the top view above the front view, the right view to its right, and the
+/// isometric view in the top right corner. Visible edges are solid, hidden
+/// edges are dashed.
+///
+/// The page is in mm, and `scale` is the number of mm on the page per unit
+/// of the shape. Each view is a `<g>` element with the class `view` and an
+/// id like `view-front`, holding a `<path>` per edge.
+pub fn sheet(shape: &Shape, views: &[StandardView], scale: f64) -> Result<String, Error> {
+    if !(scale > 0.0 && scale.is_finite()) {
+        return Err(Error::InvalidScale { scale });
+    }
+
+    let laid_out = views
+        .iter()
+        .map(|view| {
+            let projected = project(shape, &view.workplane(), ProjectOptions::default())?;
+            Ok(LaidOutView::new(*view, &projected, scale))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    // Every view in a column is as wide as the widest, and every view in a
+    // row as tall as the tallest, so aligned views line up.
+    let mut column_widths = [0.0f64; 2];
+    let mut row_heights = [0.0f64; 2];
+    for view in &laid_out {
+        let (column, row) = view.view.cell();
+        let (width, height) = view.size();
+        column_widths[column] = column_widths[column].max(width);
+        row_heights[row] = row_heights[row].max(height + LABEL_SIZE * 2.0);
+    }
+
+    let offset = |sizes: &[f64; 2], index: usize| {
+        MARGIN + sizes[..index].iter().map(|size| size + MARGIN).sum::<f64>()
+    };
+    let page_width = offset(&column_widths, 2);
+    let page_height = offset(&row_heights, 2);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{page_width:.3}mm" height="{page_height:.3}mm" viewBox="0 0 {page_width:.3} {page_height:.3}">"#
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect class="frame" x="{x:.3}" y="{x:.3}" width="{width:.3}" height="{height:.3}" fill="none" stroke="black" stroke-width="0.5"/>"#,
+        x = MARGIN / 2.0,
+        width = page_width - MARGIN,
+        height = page_height - MARGIN,
+    );
+
+    for view in &laid_out {
+        let (column, row) = view.view.cell();
+        let (width, height) = view.size();
+
+        // Center the view in its cell, leaving room for the label below.
+        let cell_x = offset(&column_widths, column);
+        let cell_y = offset(&row_heights, row);
+        let left = cell_x + (column_widths[column] - width) / 2.0;
+        let top = cell_y + (row_heights[row] - LABEL_SIZE * 2.0 - height) / 2.0;
+
+        // SVG's y axis points down the page.
+        let to_page =
+            |point: &Point2<f64>| (left + point.x - view.min.x, top + view.max.y - point.y);
+
+        let name = view.view.label().to_lowercase();
+        let _ = writeln!(svg, r#"<g class="view" id="view-{name}">"#);
+
+        for (polylines, style) in [
+            (
+                &view.hidden,
+                r#"stroke-width="0.25" stroke-dasharray="2,1""#,
+            ),
+            (&view.visible, r#"stroke-width="0.5""#),
+        ] {
+            for polyline in polylines {
+                let mut d = String::new();
+                for (i, point) in polyline.iter().enumerate() {
+                    let (x, y) = to_page(point);
+                    let command = if i == 0 { 'M' } else { 'L' };
+                    let _ = write!(d, "{command}{x:.3} {y:.3} ");
+                }
+
+                let _ = writeln!(
+                    svg,
+                    r#"<path d="{}" fill="none" stroke="black" {style}/>"#,
+                    d.trim_end()
+                );
+            }
+        }
+
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.3}" y="{:.3}" font-family="sans-serif" font-size="{LABEL_SIZE}" text-anchor="middle">{}</text>"#,
+            cell_x + column_widths[column] / 2.0,
+            cell_y + row_heights[row] - LABEL_SIZE / 2.0,
+            view.view.label(),
+        );
+        let _ = writeln!(svg, "</g>");
+    }
+
+    svg.push_str("</svg>\n");
+
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nalgebra::Point3;
+
+    const ALL_VIEWS: [StandardView; 4] = [
+        StandardView::Front,
+        StandardView::Top,
+        StandardView::Right,
+        StandardView::Isometric,
+    ];
+
+    fn drawn_edges(projected: &ProjectedView) -> (usize, usize) {
+        (
+            projected.visible_edges().count(),
+            projected.hidden_edges().count(),
+        )
+    }
+
+    #[test]
+    fn one_path_per_edge() {
+        // A block with a hole through it, so some views have hidden edges.
+        let block = Shape::box_with_dimensions(40.0, 20.0, 10.0);
+        let hole = Shape::cylinder(Point3::new(20.0, 10.0, -1.0), 4.0, Vector3::z(), 12.0);
+        let part = block.subtract(&hole).unwrap();
+
+        let svg = sheet(&part, &ALL_VIEWS, 2.0).unwrap();
+        let document = roxmltree::Document::parse(&svg).unwrap();
+
+        let groups: Vec<_> = document
+            .descendants()
+            .filter(|node| node.attribute("class") == Some("view"))
+            .collect();
+        assert_eq!(groups.len(), 4);
+
+        for view in ALL_VIEWS {
+            let id = format!("view-{}", view.label().to_lowercase());
+            let group = groups
+                .iter()
+                .find(|group| group.attribute("id") == Some(&id))
+                .unwrap();
+
+            let paths: Vec<_> = group
+                .children()
+                .filter(|node| node.has_tag_name("path"))
+                .collect();
+            let dashed = paths
+                .iter()
+                .filter(|path| path.attribute("stroke-dasharray").is_some())
+                .count();
+
+            let projected = project(&part, &view.workplane(), ProjectOptions::default()).unwrap();
+            assert_eq!(
+                (paths.len() - dashed, dashed),
+                drawn_edges(&projected),
+                "{id}"
+            );
+
+            let label = group
+                .children()
+                .find(|node| node.has_tag_name("text"))
+                .unwrap();
+            assert_eq!(label.text(), Some(view.label()));
+        }
+
+        // The hole is hidden in the front view.
+        let front = project(
+            &part,
+            &StandardView::Front.workplane(),
+            ProjectOptions::default(),
+        )
+        .unwrap();
+        assert!(front.hidden_edges().count() > 0);
+
+        assert_eq!(
+            document
+                .descendants()
+                .filter(|node| node.attribute("class") == Some("frame"))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn views_are_aligned() {
+        let block = Shape::box_with_dimensions(40.0, 20.0, 10.0);
+        let svg = sheet(&block, &ALL_VIEWS, 1.0).unwrap();
+        let document = roxmltree::Document::parse(&svg).unwrap();
+
+        // The extent of a view's paths along the page's x (0) or y (1) axis.
+        let extent = |id: &str, axis: usize| {
+            let group = document
+                .descendants()
+                .find(|node| node.attribute("id") == Some(id))
+                .unwrap();
+
+            let values: Vec<f64> = group
+                .children()
+                .filter_map(|node| node.attribute("d"))
+                .flat_map(|d| {
+                    d.split(['M', 'L'])
+                        .filter(|pair| !pair.trim().is_empty())
+                        .map(|pair| {
+                            let pair: Vec<f64> = pair
+                                .split_whitespace()
+                                .map(|v| v.parse().unwrap())
+                                .collect();
+                            pair[axis]
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+
+            let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            (min, max)
+        };
+
+        // Top above front, with the same x extent.
+        let (front_x, top_x) = (extent("view-front", 0), extent("view-top", 0));
+        assert!((front_x.0 - top_x.0).abs() < 1.0e-3 && (front_x.1 - top_x.1).abs() < 1.0e-3);
+        assert!((front_x.1 - front_x.0 - 40.0).abs() < 1.0e-3);
+        assert!(extent("view-top", 1).1 < extent("view-front", 1).0);
+
+        // Right beside front, with the same y extent.
+        let (front_y, right_y) = (extent("view-front", 1), extent("view-right", 1));
+        assert!((front_y.0 - right_y.0).abs() < 1.0e-3 && (front_y.1 - right_y.1).abs() < 1.0e-3);
+        assert!(front_x.1 < extent("view-right", 0).0);
+    }
+
+    #[test]
+    fn scale_must_be_positive() {
+        let block = Shape::box_with_dimensions(1.0, 1.0, 1.0);
+
+        for scale in [0.0, -1.0, f64::NAN] {
+            assert!(matches!(
+                sheet(&block, &ALL_VIEWS, scale),
+                Err(Error::InvalidScale { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use crate::drawing::project;
+use crate::drawing::ProjectOptions;
+use crate::primitives::Compound;
+use crate::primitives::Face;
+use crate::primitives::Shape;
+use crate::primitives::Wire;
+use crate::workplane::Workplane;
+use crate::Error;
+use cxx::UniquePtr;
+use nalgebra::point;
+use nalgebra::Matrix3;
+use nalgebra::Point3;
+use nalgebra::UnitVector3;
+use nalgebra::Vector3;
+use opencascade_sys::ffi;
+
+/// How far the face split by the projected edges reaches past them.
+const MARGIN: f64 = 1.0;
+
+/// Directions closer than this to parallel, in `1 - cos(angle)`, project
+/// onto a plane without distortion.
+const PARALLEL_TOLERANCE: f64 = 1.0e-9;
+
+/// A closed wire of the outline of a shape, made with `Shape::silhouette`.
+pub struct SilhouetteWire {
+    pub wire: Wire,
+    /// Whether the wire is around an opening which can be seen through,
+    /// rather than around the outside of the silhouette.
+    pub is_hole: bool,
+}
+
+impl Shape {
+    /// The outline of this shape as seen along `direction`, projected along
+    /// it onto `plane`: the boundaries of the regions of the plane the
+    /// shape covers, as closed wires. Openings which go right through the
+    /// shape along the direction are separate wires, flagged as holes.
+    ///
+    /// The outline is made by splitting the view plane with the edges of
+    /// the hidden line removal projection, and keeping the pieces which a
+    /// ray along the direction through them shows the shape covers.
+    pub fn silhouette(
+        &self,
+        direction: UnitVector3<f64>,
+        plane: &Workplane,
+    ) -> Result<Vec<SilhouetteWire>, Error> {
+        let normal = plane.normal().into_inner();
+        let direction = direction.into_inner();
+        if direction.dot(&normal).abs() < PARALLEL_TOLERANCE {
+            return Err(Error::ProjectionParallelToPlane);
+        }
+
+        // Look along the direction, from the origin of the plane.
+        let x_dir = [plane.x_dir(), plane.y_dir()]
+            .into_iter()
+            .find_map(|axis| {
+                let axis = axis.into_inner();
+                (axis - direction * axis.dot(&direction)).try_normalize(1.0e-6)
+            })
+            .unwrap();
+        let mut view = Workplane::from_vectors(x_dir, -direction)?;
+        view.set_translation(plane.origin());
+
+        let projected = project(self, &view, ProjectOptions::default())?;
+        let edges: Vec<_> = projected
+            .visible_edges()
+            .chain(projected.hidden_edges())
+            .map(Shape::from)
+            .collect();
+        if edges.is_empty() {
+            return Ok(vec![]);
+        }
+        let edges = Shape::from(Compound::from_shapes(&edges));
+
+        let bounds = edges.bounding_box();
+        let (min, max) = (bounds.min(), bounds.max());
+        let sheet = Face::from_wire(&Wire::from_ordered_points([
+            point![min.x - MARGIN, min.y - MARGIN, 0.0],
+            point![max.x + MARGIN, min.y - MARGIN, 0.0],
+            point![max.x + MARGIN, max.y + MARGIN, 0.0],
+            point![min.x - MARGIN, max.y + MARGIN, 0.0],
+        ])?)?;
+
+        let mut splitter = ffi::BRepAlgoAPI_Splitter_ctor(&Shape::from(&sheet).inner, &edges.inner);
+        if !splitter.IsDone() {
+            return Err(Error::NotDone);
+        }
+        let pieces = Shape::from_shape(splitter.pin_mut().Shape());
+
+        let mut covered = vec![];
+        for piece in pieces.faces() {
+            let inside = interior_point(&piece)?;
+            let ray_origin = view.transform().transform_point(inside);
+
+            if !self.faces_along_line(ray_origin, direction).is_empty() {
+                covered.push(Shape::from(piece));
+            }
+        }
+
+        // Merge the pieces into as few faces as possible, and move them from
+        // the view onto the plane.
+        let mut outline = Shape::union_all(&covered)?.clean();
+        outline.transform(&view.transform());
+        let outline = project_onto_plane(&outline, direction, plane)?;
+
+        let mut wires = vec![];
+        for face in outline.faces() {
+            let outer_wire = face.outer_wire();
+            let outer_shape = ffi::cast_wire_to_shape(&outer_wire.inner);
+
+            for wire in face.wires() {
+                let is_hole = !ffi::cast_wire_to_shape(&wire.inner).IsSame(outer_shape);
+                wires.push(SilhouetteWire { wire, is_hole });
+            }
+        }
+
+        Ok(wires)
+    }
+}
+
+/// A point inside `face`, even if it isn't convex: the center of the
+/// biggest triangle of its mesh.
+fn interior_point(face: &Face) -> Result<Point3<f64>, Error> {
+    let mesh = Shape::from(face).mesh()?;
+
+    mesh.indices
+        .chunks_exact(3)
+        .map(|triangle| {
+            let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i]]);
+            let area = (b - a).cross(&(c - a)).norm();
+
+            (area, Point3::from((a.coords + b.coords + c.coords) / 3.0))
+        })
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, center)| center)
+        .ok_or(Error::TriangulationFailed)
+}
+
+/// Slide `shape`, which lies in a plane through the origin of `plane`
+/// normal to `direction`, along `direction` onto `plane`. Unless
+/// `direction` is normal to `plane`, this stretches the shape, and its
+/// curves are turned into B-splines.
+fn project_onto_plane(
+    shape: &Shape,
+    direction: Vector3<f64>,
+    plane: &Workplane,
+) -> Result<Shape, Error> {
+    let normal = plane.normal().into_inner();
+    let origin = plane.origin();
+    let along = direction.dot(&normal);
+
+    if 1.0 - along.abs() < PARALLEL_TOLERANCE {
+        return Ok(shape.clone());
+    }
+
+    // Each point moves along the direction by its height above the plane,
+    // measured along the direction.
+    let slide = Matrix3::identity() - direction * normal.transpose() / along;
+    let shift = direction * (origin.dot(&normal) / along);
+
+    let mut transform: UniquePtr<ffi::gp_GTrsf> = ffi::new_gp_GTrsf();
+    for row in 0..3 {
+        for column in 0..3 {
+            transform
+                .pin_mut()
+                .SetValue(row as i32 + 1, column as i32 + 1, slide[(row, column)]);
+        }
+        transform.pin_mut().SetValue(row as i32 + 1, 4, shift[row]);
+    }
+
+    let mut transformer = ffi::BRepBuilderAPI_GTransform_ctor(&shape.inner, &transform, true);
+    if !transformer.IsDone() {
+        return Err(Error::NotDone);
+    }
+
+    Ok(Shape::from_shape(transformer.pin_mut().Shape()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn area(wire: &Wire) -> f64 {
+        Face::from_wire(wire).unwrap().surface_area()
+    }
+
+    #[test]
+    fn cylinder_off_axis_is_a_stadium() {
+        let (radius, height) = (5.0, 10.0);
+        let cylinder = Shape::cylinder_radius_height(radius, height);
+
+        // 30 degrees off the axis, looking down.
+        let angle = PI / 6.0;
+        let direction = UnitVector3::new_normalize(Vector3::new(angle.sin(), 0.0, -angle.cos()));
+        let plane = Workplane::from_vectors(Vector3::y(), -direction.into_inner()).unwrap();
+
+        let wires = cylinder.silhouette(direction, &plane).unwrap();
+        assert_eq!(wires.len(), 1);
+        assert!(!wires[0].is_hole);
+        assert!(wires[0].wire.is_closed());
+
+        let expected = PI * radius * radius * angle.cos() + 2.0 * radius * height * angle.sin();
+        let outline_area = area(&wires[0].wire);
+        assert!(
+            (outline_area - expected).abs() < 1.0e-3 * expected,
+            "{outline_area}"
+        );
+
+        // Everything lies on the plane.
+        for point in wires[0]
+            .wire
+            .edges()
+            .flat_map(|edge| edge.approximation_segments(0.01))
+        {
+            assert!((point.coords - plane.origin()).dot(&plane.normal()).abs() < 1.0e-6);
+        }
+    }
+
+    #[test]
+    fn through_holes_are_holes() {
+        let plate = Shape::box_centered(20.0, 20.0, 5.0);
+        let hole = Shape::cylinder_centered(Point3::origin(), 3.0, Vector3::z(), 10.0);
+        let plate = plate.subtract(&hole).unwrap();
+
+        let wires = plate
+            .silhouette(-Vector3::z_axis(), &Workplane::xy())
+            .unwrap();
+        assert_eq!(wires.len(), 2);
+
+        let holes: Vec<_> = wires.iter().filter(|wire| wire.is_hole).collect();
+        assert_eq!(holes.len(), 1);
+        assert!((area(&holes[0].wire) - 9.0 * PI).abs() < 1.0e-6);
+
+        let outer = wires.iter().find(|wire| !wire.is_hole).unwrap();
+        assert!((area(&outer.wire) - 400.0).abs() < 1.0e-6);
+    }
+}
@@ -0,0 +1,471 @@
+//! Writing a shape to several mesh file formats from a single triangulation.
+
+use crate::context::ModelContext;
+use crate::context::Unit;
+use crate::mesh::Mesh;
+use crate::mesh::MeshOptions;
+use crate::primitives::Shape;
+use crate::Error;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+
+/// Triangulates a shape once, then writes the same `Mesh` to any number of
+/// formats. Every file written from one pipeline contains exactly the same
+/// vertices and triangles.
+pub struct ExportPipeline {
+    mesh: Mesh,
+    unit: Unit,
+}
+
+impl ExportPipeline {
+    /// Triangulate `shape`, to write in the unit of the default context.
+    pub fn new(shape: &Shape, options: &MeshOptions) -> Result<Self, Error> {
+        let mesh = shape.mesh_with_options(options)?;
+        let unit = ModelContext::current().unit;
+
+        Ok(Self { mesh, unit })
+    }
+
+    /// This pipeline, writing formats which declare their unit in the unit
+    /// of `context` rather than the default context's.
+    pub fn with_context(mut self, context: &ModelContext) -> Self {
+        self.unit = context.unit;
+        self
+    }
+
+    /// The mesh every format is written from.
+    pub fn mesh(&self) -> &Mesh {
+        &self.mesh
+    }
+
+    /// Write a binary STL file.
+    pub fn to_stl(&self, sink: impl Write) -> Result<(), Error> {
+        let mut sink = BufWriter::new(sink);
+
+        let mut header = [0u8; 80];
+        let name = b"opencascade-rs";
+        header[..name.len()].copy_from_slice(name);
+        sink.write_all(&header)?;
+        sink.write_all(&to_u32(self.triangle_count())?.to_le_bytes())?;
+
+        for triangle in self.mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| self.mesh.vertices[triangle[i]]);
+            let normal = (b - a)
+                .cross(&(c - a))
+                .try_normalize(0.0)
+                .unwrap_or_default();
+
+            for value in [normal.x, normal.y, normal.z] {
+                sink.write_all(&(value as f32).to_le_bytes())?;
+            }
+
+            for vertex in [a, b, c] {
+                for value in [vertex.x, vertex.y, vertex.z] {
+                    sink.write_all(&(value as f32).to_le_bytes())?;
+                }
+            }
+
+            // Attribute byte count, unused.
+            sink.write_all(&[0, 0])?;
+        }
+
+        sink.flush()?;
+
+        Ok(())
+    }
+
+    /// Write a Wavefront OBJ file.
+    pub fn to_obj(&self, sink: impl Write) -> Result<(), Error> {
+        let mut sink = BufWriter::new(sink);
+
+        for vertex in &self.mesh.vertices {
+            writeln!(sink, "v {} {} {}", vertex.x, vertex.y, vertex.z)?;
+        }
+
+        // OBJ indices start at 1.
+        for triangle in self.mesh.indices.chunks_exact(3) {
+            writeln!(
+                sink,
+                "f {} {} {}",
+                triangle[0] + 1,
+                triangle[1] + 1,
+                triangle[2] + 1
+            )?;
+        }
+
+        sink.flush()?;
+
+        Ok(())
+    }
+
+    /// Write a self-contained glTF file, with the vertex data embedded.
+    /// Coordinates are written as they are, so a model in millimeters is
+    /// shown 1000 times too large by viewers expecting meters. Fails with
+    /// `Error::EmptyMesh` if there's nothing to write, as glTF needs the
+    /// bounds of the vertices.
+    pub fn to_gltf(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.write_gltf(File::create(path)?)
+    }
+
+    /// Write a 3MF file, in the pipeline's unit.
+    pub fn to_3mf(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.write_3mf(File::create(path)?)
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.mesh.indices.len() / 3
+    }
+
+    fn write_gltf(&self, sink: impl Write) -> Result<(), Error> {
+        if self.mesh.vertices.is_empty() {
+            return Err(Error::EmptyMesh);
+        }
+
+        let mut sink = BufWriter::new(sink);
+
+        let mut buffer = vec![];
+        let (mut min, mut max) = ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]);
+
+        for vertex in &self.mesh.vertices {
+            for (i, value) in [vertex.x, vertex.y, vertex.z].into_iter().enumerate() {
+                let value = value as f32;
+                min[i] = min[i].min(value);
+                max[i] = max[i].max(value);
+                buffer.extend(value.to_le_bytes());
+            }
+        }
+
+        let positions_length = buffer.len();
+
+        for &index in &self.mesh.indices {
+            buffer.extend(to_u32(index)?.to_le_bytes());
+        }
+
+        let indices_length = buffer.len() - positions_length;
+
+        write!(
+            sink,
+            concat!(
+                r#"{{"asset":{{"version":"2.0","generator":"opencascade-rs"}},"#,
+                r#""scene":0,"scenes":[{{"nodes":[0]}}],"nodes":[{{"mesh":0}}],"#,
+                r#""meshes":[{{"primitives":[{{"attributes":{{"POSITION":0}},"indices":1,"mode":4}}]}}],"#,
+                r#""accessors":["#,
+                r#"{{"bufferView":0,"componentType":5126,"count":{vertices},"type":"VEC3","min":[{min}],"max":[{max}]}},"#,
+                r#"{{"bufferView":1,"componentType":5125,"count":{indices},"type":"SCALAR"}}],"#,
+                r#""bufferViews":["#,
+                r#"{{"buffer":0,"byteOffset":0,"byteLength":{positions_length},"target":34962}},"#,
+                r#"{{"buffer":0,"byteOffset":{positions_length},"byteLength":{indices_length},"target":34963}}],"#,
+                r#""buffers":[{{"byteLength":{buffer_length},"uri":"data:application/octet-stream;base64,{data}"}}]}}"#,
+            ),
+            vertices = self.mesh.vertices.len(),
+            indices = self.mesh.indices.len(),
+            min = json_floats(&min),
+            max = json_floats(&max),
+            buffer_length = buffer.len(),
+            data = base64(&buffer),
+        )?;
+
+        sink.flush()?;
+
+        Ok(())
+    }
+
+    fn write_3mf(&self, mut sink: impl Write) -> Result<(), Error> {
+        let content_types = concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+            r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+            r#"<Default Extension="model" ContentType="application/vnd.ms-package.3dmanufacturing-3dmodel+xml"/>"#,
+            r#"</Types>"#,
+        );
+        let relationships = concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+            r#"<Relationship Target="/3D/3dmodel.model" Id="rel0" Type="http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel"/>"#,
+            r#"</Relationships>"#,
+        );
+
+        let mut model = format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+                r#"<model unit="{unit}" xml:lang="en-US" xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02">"#,
+                r#"<resources><object id="1" type="model"><mesh><vertices>"#,
+            ),
+            unit = self.unit.three_mf_name(),
+        );
+
+        let scale = self.unit.millimeters();
+        for vertex in &self.mesh.vertices {
+            let vertex = *vertex / scale;
+            model += &format!(
+                r#"<vertex x="{}" y="{}" z="{}"/>"#,
+                vertex.x, vertex.y, vertex.z
+            );
+        }
+
+        model += "</vertices><triangles>";
+
+        for triangle in self.mesh.indices.chunks_exact(3) {
+            model += &format!(
+                r#"<triangle v1="{}" v2="{}" v3="{}"/>"#,
+                triangle[0], triangle[1], triangle[2]
+            );
+        }
+
+        model += r#"</triangles></mesh></object></resources><build><item objectid="1"/></build></model>"#;
+
+        let archive = stored_zip(&[
+            ("[Content_Types].xml", content_types.as_bytes()),
+            ("_rels/.rels", relationships.as_bytes()),
+            ("3D/3dmodel.model", model.as_bytes()),
+        ])?;
+
+        sink.write_all(&archive)?;
+
+        Ok(())
+    }
+}
+
+/// `value` as a `u32`, as the formats store counts, indices and sizes, or
+/// `Error::TooLargeToExport` if it doesn't fit.
+fn to_u32(value: usize) -> Result<u32, Error> {
+    u32::try_from(value).map_err(|_| Error::TooLargeToExport { value })
+}
+
+/// Like `to_u32`, for the counts and lengths zip stores in 16 bits.
+fn to_u16(value: usize) -> Result<u16, Error> {
+    u16::try_from(value).map_err(|_| Error::TooLargeToExport { value })
+}
+
+fn json_floats(values: &[f32]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[((bits >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+/// A zip archive of `files`, stored without compression, which is all 3MF
+/// readers require.
+fn stored_zip(files: &[(&str, &[u8])]) -> Result<Vec<u8>, Error> {
+    // 1980-01-01, the earliest date zip can represent.
+    const DOS_DATE: u16 = (1 << 5) | 1;
+
+    let mut archive = vec![];
+    let mut central_directory = vec![];
+
+    for (name, data) in files {
+        let offset = to_u32(archive.len())?;
+        let size = to_u32(data.len())?;
+        let name_length = to_u16(name.len())?;
+        let crc = crc32(data);
+
+        // Local file header.
+        archive.extend(0x04034b50u32.to_le_bytes());
+        archive.extend(20u16.to_le_bytes()); // Version needed to extract.
+        archive.extend(0u16.to_le_bytes()); // Flags.
+        archive.extend(0u16.to_le_bytes()); // Stored.
+        archive.extend(0u16.to_le_bytes()); // Time.
+        archive.extend(DOS_DATE.to_le_bytes());
+        archive.extend(crc.to_le_bytes());
+        archive.extend(size.to_le_bytes()); // Compressed size.
+        archive.extend(size.to_le_bytes()); // Uncompressed size.
+        archive.extend(name_length.to_le_bytes());
+        archive.extend(0u16.to_le_bytes()); // Extra field length.
+        archive.extend(name.as_bytes());
+        archive.extend(*data);
+
+        central_directory.extend(0x02014b50u32.to_le_bytes());
+        central_directory.extend(20u16.to_le_bytes()); // Version made by.
+        central_directory.extend(20u16.to_le_bytes()); // Version needed to extract.
+        central_directory.extend(0u16.to_le_bytes()); // Flags.
+        central_directory.extend(0u16.to_le_bytes()); // Stored.
+        central_directory.extend(0u16.to_le_bytes()); // Time.
+        central_directory.extend(DOS_DATE.to_le_bytes());
+        central_directory.extend(crc.to_le_bytes());
+        central_directory.extend(size.to_le_bytes()); // Compressed size.
+        central_directory.extend(size.to_le_bytes()); // Uncompressed size.
+        central_directory.extend(name_length.to_le_bytes());
+        central_directory.extend(0u16.to_le_bytes()); // Extra field length.
+        central_directory.extend(0u16.to_le_bytes()); // Comment length.
+        central_directory.extend(0u16.to_le_bytes()); // Disk number.
+        central_directory.extend(0u16.to_le_bytes()); // Internal attributes.
+        central_directory.extend(0u32.to_le_bytes()); // External attributes.
+        central_directory.extend(offset.to_le_bytes());
+        central_directory.extend(name.as_bytes());
+    }
+
+    let central_directory_offset = to_u32(archive.len())?;
+    let central_directory_length = to_u32(central_directory.len())?;
+    let file_count = to_u16(files.len())?;
+    archive.extend(&central_directory);
+
+    // End of central directory record.
+    archive.extend(0x06054b50u32.to_le_bytes());
+    archive.extend(0u16.to_le_bytes()); // This disk.
+    archive.extend(0u16.to_le_bytes()); // Disk with the central directory.
+    archive.extend(file_count.to_le_bytes()); // Entries on this disk.
+    archive.extend(file_count.to_le_bytes()); // Entries in total.
+    archive.extend(central_directory_length.to_le_bytes());
+    archive.extend(central_directory_offset.to_le_bytes());
+    archive.extend(0u16.to_le_bytes()); // Comment length.
+
+    Ok(archive)
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in data {
+        crc ^= u32::from(byte);
+
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nalgebra::point;
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn all_formats_share_one_triangulation() {
+        let shape = Shape::box_with_dimensions(10.0, 20.0, 30.0);
+        let pipeline = ExportPipeline::new(&shape, &MeshOptions::default()).unwrap();
+        assert_eq!(shape.cache.computations.load(Ordering::Relaxed), 1);
+
+        // Welding leaves one vertex per corner.
+        let vertex_count = pipeline.mesh().vertices.len();
+        assert_eq!(vertex_count, 8);
+
+        let mut stl = vec![];
+        pipeline.to_stl(&mut stl).unwrap();
+        let stl_triangles = u32::from_le_bytes(stl[80..84].try_into().unwrap()) as usize;
+        assert_eq!(stl_triangles, pipeline.mesh().indices.len() / 3);
+        assert_eq!(stl.len(), 84 + 50 * stl_triangles);
+
+        let mut obj = vec![];
+        pipeline.to_obj(&mut obj).unwrap();
+        let obj = String::from_utf8(obj).unwrap();
+        assert_eq!(
+            obj.lines().filter(|line| line.starts_with("v ")).count(),
+            vertex_count
+        );
+
+        let mut gltf = vec![];
+        pipeline.write_gltf(&mut gltf).unwrap();
+        let gltf = String::from_utf8(gltf).unwrap();
+        let count = gltf.split(r#""count":"#).nth(1).unwrap();
+        let count: usize = count[..count.find(',').unwrap()].parse().unwrap();
+        assert_eq!(count, vertex_count);
+
+        let mut three_mf = vec![];
+        pipeline.write_3mf(&mut three_mf).unwrap();
+        let three_mf = String::from_utf8_lossy(&three_mf);
+        assert_eq!(three_mf.matches("<vertex ").count(), vertex_count);
+
+        // Nothing was triangulated again for the exports.
+        assert_eq!(shape.cache.computations.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn three_mf_in_inches() {
+        let shape = Shape::box_with_dimensions(25.4, 50.8, 76.2);
+        let pipeline = ExportPipeline::new(&shape, &MeshOptions::default())
+            .unwrap()
+            .with_context(&ModelContext::new(Unit::Inch));
+
+        let mut three_mf = vec![];
+        pipeline.write_3mf(&mut three_mf).unwrap();
+        let three_mf = String::from_utf8_lossy(&three_mf);
+        assert!(three_mf.contains(r#"<model unit="inch""#));
+
+        // The far corner of the box is at (1, 2, 3) inches, give or take the
+        // rounding from the conversion.
+        let position = |vertex: &str| {
+            let attribute = |name: &str| -> f64 {
+                let value = vertex.split(&format!(r#"{name}=""#)).nth(1).unwrap();
+                value[..value.find('"').unwrap()].parse().unwrap()
+            };
+            point![attribute("x"), attribute("y"), attribute("z")]
+        };
+        let far_corner = three_mf
+            .split("<vertex ")
+            .skip(1)
+            .map(position)
+            .find(|corner| corner.x > 0.5 && corner.y > 0.5 && corner.z > 0.5)
+            .unwrap();
+        assert!((far_corner - point![1.0, 2.0, 3.0]).norm() < 1.0e-9);
+    }
+
+    #[test]
+    fn empty_mesh_has_no_gltf_bounds() {
+        let pipeline = ExportPipeline {
+            mesh: Mesh {
+                vertices: vec![],
+                uvs: vec![],
+                normals: vec![],
+                indices: vec![],
+                face_ranges: vec![],
+            },
+            unit: Unit::Millimeter,
+        };
+
+        let mut gltf = vec![];
+        assert!(matches!(
+            pipeline.write_gltf(&mut gltf),
+            Err(Error::EmptyMesh)
+        ));
+        assert!(gltf.is_empty());
+
+        assert!(matches!(
+            to_u32(u32::MAX as usize + 1),
+            Err(Error::TooLargeToExport { .. })
+        ));
+    }
+
+    #[test]
+    fn base64_padding() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+    }
+}
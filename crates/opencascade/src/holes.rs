@@ -0,0 +1,513 @@
+//! Standard holes for metric screws: clearance holes, counterbores,
+//! countersinks and tap drill holes.
+//!
+//! Holes are placed on a `Workplane` and drilled against its normal, so a
+//! workplane taken from a face with `Face::workplane` drills into the solid.
+
+use crate::angle::Angle;
+use crate::bounding_box;
+use crate::context::ModelContext;
+use crate::context::Unit;
+use crate::primitives::Compound;
+use crate::primitives::Face;
+use crate::primitives::Shape;
+use crate::primitives::Wire;
+use crate::workplane::Workplane;
+use crate::Error;
+use nalgebra::point;
+
+/// Included angle of a standard twist drill tip.
+const DRILL_TIP_ANGLE: f64 = 118.0;
+
+/// How far hole tools reach above the workplane, so they don't share a face
+/// with the solid being cut.
+const TOOL_OVERSHOOT: f64 = 1.0;
+
+/// Standard metric screw sizes. Their dimensions are in millimeters.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IsoScrew {
+    M2,
+    M2_5,
+    M3,
+    M4,
+    M5,
+    M6,
+    M8,
+    M10,
+    M12,
+}
+
+/// How much room a clearance hole leaves around its screw, following the
+/// fine, medium and coarse series of ISO 273.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HoleFit {
+    Close,
+    Normal,
+    Loose,
+}
+
+impl IsoScrew {
+    /// The nominal diameter of the thread.
+    pub fn diameter(&self) -> f64 {
+        match self {
+            Self::M2 => 2.0,
+            Self::M2_5 => 2.5,
+            Self::M3 => 3.0,
+            Self::M4 => 4.0,
+            Self::M5 => 5.0,
+            Self::M6 => 6.0,
+            Self::M8 => 8.0,
+            Self::M10 => 10.0,
+            Self::M12 => 12.0,
+        }
+    }
+
+    /// The coarse thread pitch.
+    pub fn pitch(&self) -> f64 {
+        match self {
+            Self::M2 => 0.4,
+            Self::M2_5 => 0.45,
+            Self::M3 => 0.5,
+            Self::M4 => 0.7,
+            Self::M5 => 0.8,
+            Self::M6 => 1.0,
+            Self::M8 => 1.25,
+            Self::M10 => 1.5,
+            Self::M12 => 1.75,
+        }
+    }
+
+    pub fn clearance_diameter(&self, fit: HoleFit) -> f64 {
+        let (close, normal, loose) = match self {
+            Self::M2 => (2.2, 2.4, 2.6),
+            Self::M2_5 => (2.7, 2.9, 3.1),
+            Self::M3 => (3.2, 3.4, 3.6),
+            Self::M4 => (4.3, 4.5, 4.8),
+            Self::M5 => (5.3, 5.5, 5.8),
+            Self::M6 => (6.4, 6.6, 7.0),
+            Self::M8 => (8.4, 9.0, 10.0),
+            Self::M10 => (10.5, 11.0, 12.0),
+            Self::M12 => (13.0, 13.5, 14.5),
+        };
+
+        match fit {
+            HoleFit::Close => close,
+            HoleFit::Normal => normal,
+            HoleFit::Loose => loose,
+        }
+    }
+
+    /// The drill size for cutting a thread with a tap.
+    pub fn tap_drill_diameter(&self) -> f64 {
+        self.diameter() - self.pitch()
+    }
+
+    /// The head diameter of a socket head cap screw (ISO 4762). The head is
+    /// as tall as the nominal diameter.
+    pub fn socket_head_diameter(&self) -> f64 {
+        match self {
+            Self::M2 => 3.8,
+            Self::M2_5 => 4.5,
+            Self::M3 => 5.5,
+            Self::M4 => 7.0,
+            Self::M5 => 8.5,
+            Self::M6 => 10.0,
+            Self::M8 => 13.0,
+            Self::M10 => 16.0,
+            Self::M12 => 18.0,
+        }
+    }
+
+    /// The head diameter of a 90° countersunk screw (ISO 10642).
+    pub fn countersunk_head_diameter(&self) -> f64 {
+        match self {
+            Self::M2 => 4.4,
+            Self::M2_5 => 5.5,
+            Self::M3 => 6.72,
+            Self::M4 => 8.96,
+            Self::M5 => 11.2,
+            Self::M6 => 13.44,
+            Self::M8 => 17.92,
+            Self::M10 => 22.4,
+            Self::M12 => 26.88,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum HoleDepth {
+    /// All the way through the solid being cut.
+    Through,
+    /// Down to `depth` below the workplane, optionally ending in a 118°
+    /// drill tip.
+    Blind { depth: f64, drill_tip: bool },
+}
+
+/// The shape of a hole. Its depth is in the unit of the `ModelContext` of
+/// the workplane it's cut on, like the position of the hole. Its diameters
+/// and counterbore depth are in `unit`, or also in the workplane's unit if
+/// that is `None`.
+#[derive(Debug, Copy, Clone)]
+pub enum HoleSpec {
+    Simple {
+        diameter: f64,
+        depth: HoleDepth,
+        unit: Option<Unit>,
+    },
+    Counterbored {
+        diameter: f64,
+        depth: HoleDepth,
+        cb_diameter: f64,
+        cb_depth: f64,
+        unit: Option<Unit>,
+    },
+    Countersunk {
+        diameter: f64,
+        depth: HoleDepth,
+        /// The included angle of the countersink.
+        angle: Angle,
+        /// The diameter of the countersink where it meets the workplane.
+        head_diameter: f64,
+        unit: Option<Unit>,
+    },
+}
+
+impl HoleSpec {
+    /// A through clearance hole for `screw`. The sizes of this and the
+    /// other screw holes are in millimeters, whatever the unit of the
+    /// workplane they're cut on.
+    pub fn for_screw(screw: IsoScrew, fit: HoleFit) -> Self {
+        Self::Simple {
+            diameter: screw.clearance_diameter(fit),
+            depth: HoleDepth::Through,
+            unit: Some(Unit::Millimeter),
+        }
+    }
+
+    /// A through clearance hole for `screw`, counterbored so a socket head
+    /// cap screw sits flush with the workplane.
+    pub fn counterbored_for_screw(screw: IsoScrew, fit: HoleFit) -> Self {
+        let diameter = screw.clearance_diameter(fit);
+        let allowance = diameter - screw.diameter();
+
+        Self::Counterbored {
+            diameter,
+            depth: HoleDepth::Through,
+            cb_diameter: screw.socket_head_diameter() + 2.0 * allowance,
+            cb_depth: screw.diameter() + allowance,
+            unit: Some(Unit::Millimeter),
+        }
+    }
+
+    /// A through clearance hole for `screw`, countersunk so a flat head
+    /// screw sits flush with the workplane.
+    pub fn countersunk_for_screw(screw: IsoScrew, fit: HoleFit) -> Self {
+        let diameter = screw.clearance_diameter(fit);
+        let allowance = diameter - screw.diameter();
+
+        Self::Countersunk {
+            diameter,
+            depth: HoleDepth::Through,
+            angle: Angle::Degrees(90.0),
+            head_diameter: screw.countersunk_head_diameter() + allowance,
+            unit: Some(Unit::Millimeter),
+        }
+    }
+
+    /// A blind hole of the tap drill size for `screw`, ready to be tapped
+    /// to `depth`.
+    pub fn tapped(screw: IsoScrew, depth: f64) -> Self {
+        Self::Simple {
+            diameter: screw.tap_drill_diameter(),
+            depth: HoleDepth::Blind {
+                depth,
+                drill_tip: true,
+            },
+            unit: Some(Unit::Millimeter),
+        }
+    }
+
+    #[must_use]
+    pub fn with_depth(mut self, new_depth: HoleDepth) -> Self {
+        match &mut self {
+            Self::Simple { depth, .. }
+            | Self::Counterbored { depth, .. }
+            | Self::Countersunk { depth, .. } => *depth = new_depth,
+        }
+
+        self
+    }
+
+    pub fn diameter(&self) -> f64 {
+        match self {
+            Self::Simple { diameter, .. }
+            | Self::Counterbored { diameter, .. }
+            | Self::Countersunk { diameter, .. } => *diameter,
+        }
+    }
+
+    pub fn depth(&self) -> HoleDepth {
+        match self {
+            Self::Simple { depth, .. }
+            | Self::Counterbored { depth, .. }
+            | Self::Countersunk { depth, .. } => *depth,
+        }
+    }
+
+    pub fn unit(&self) -> Option<Unit> {
+        match self {
+            Self::Simple { unit, .. }
+            | Self::Counterbored { unit, .. }
+            | Self::Countersunk { unit, .. } => *unit,
+        }
+    }
+
+    /// The cross section of the hole as (radius, height) pairs, going
+    /// around the axis from above the workplane, down through the bottom
+    /// of the hole, in millimeters. The workplane is at height zero, and
+    /// `context` is its context. A through hole goes `through_depth`
+    /// millimeters deep.
+    fn profile(&self, through_depth: f64, context: &ModelContext) -> Vec<(f64, f64)> {
+        let size = |length: f64| match self.unit() {
+            Some(unit) => length * unit.millimeters(),
+            None => context.to_millimeters(length),
+        };
+        let radius = size(self.diameter()) / 2.0;
+        let mut profile = vec![(0.0, TOOL_OVERSHOOT)];
+
+        match *self {
+            Self::Simple { .. } => {
+                profile.push((radius, TOOL_OVERSHOOT));
+            }
+            Self::Counterbored {
+                cb_diameter,
+                cb_depth,
+                ..
+            } => {
+                let (cb_radius, cb_depth) = (size(cb_diameter) / 2.0, size(cb_depth));
+
+                profile.push((cb_radius, TOOL_OVERSHOOT));
+                profile.push((cb_radius, -cb_depth));
+                profile.push((radius, -cb_depth));
+            }
+            Self::Countersunk {
+                angle,
+                head_diameter,
+                ..
+            } => {
+                let slope = (angle.radians() / 2.0).tan();
+                let head_radius = size(head_diameter) / 2.0;
+
+                profile.push((head_radius + TOOL_OVERSHOOT * slope, TOOL_OVERSHOOT));
+                profile.push((radius, -(head_radius - radius) / slope));
+            }
+        }
+
+        match self.depth() {
+            HoleDepth::Through => {
+                profile.push((radius, -through_depth));
+                profile.push((0.0, -through_depth));
+            }
+            HoleDepth::Blind {
+                depth,
+                drill_tip: false,
+            } => {
+                let depth = context.to_millimeters(depth);
+
+                profile.push((radius, -depth));
+                profile.push((0.0, -depth));
+            }
+            HoleDepth::Blind {
+                depth,
+                drill_tip: true,
+            } => {
+                let depth = context.to_millimeters(depth);
+                let tip_length = radius / (DRILL_TIP_ANGLE.to_radians() / 2.0).tan();
+
+                profile.push((radius, -depth));
+                profile.push((0.0, -depth - tip_length));
+            }
+        }
+
+        profile
+    }
+}
+
+/// Where to drill a hole on a workplane: `(x, y)` in the workplane's
+/// coordinates and context unit, or a `DatumPoint`.
+pub trait HolePosition {
+    /// This position in `workplane`'s coordinates.
+    fn on(&self, workplane: &Workplane) -> (f64, f64);
+}
+
+impl HolePosition for (f64, f64) {
+    fn on(&self, _workplane: &Workplane) -> (f64, f64) {
+        *self
+    }
+}
+
+impl Shape {
+    /// Cut a hole into this shape at `at` on `workplane`, drilled against
+    /// the workplane normal.
+    pub fn hole(
+        &self,
+        workplane: &Workplane,
+        at: impl HolePosition,
+        spec: HoleSpec,
+    ) -> Result<Shape, Error> {
+        self.holes(workplane, &[at], spec)
+    }
+
+    /// Cut the same hole at each of `points` on `workplane`, with a single
+    /// boolean operation.
+    pub fn holes(
+        &self,
+        workplane: &Workplane,
+        points: &[impl HolePosition],
+        spec: HoleSpec,
+    ) -> Result<Shape, Error> {
+        if points.is_empty() {
+            return Ok(self.clone());
+        }
+
+        let bounds = bounding_box::aabb(self);
+        let (min, max) = (bounds.min(), bounds.max());
+        let normal = workplane.normal().into_inner();
+        let x_dir = workplane.x_dir().into_inner();
+
+        let tools = points
+            .iter()
+            .map(|point| {
+                let (x, y) = point.on(workplane);
+                let center = workplane.to_world_pos(point![x, y, 0.0]);
+
+                // Deep enough to clear the far side of the bounding box.
+                let through_depth = [min.x, max.x]
+                    .into_iter()
+                    .flat_map(|x| [min.y, max.y].map(|y| (x, y)))
+                    .flat_map(|(x, y)| [min.z, max.z].map(|z| point![x, y, z]))
+                    .map(|corner| (center - corner).dot(&normal))
+                    .fold(0.0, f64::max)
+                    + TOOL_OVERSHOOT;
+
+                let profile = Wire::from_ordered_points(
+                    spec.profile(through_depth, workplane.context())
+                        .into_iter()
+                        .map(|(radius, height)| center + x_dir * radius + normal * height),
+                )?;
+                let face = Face::from_wire(&profile)?;
+
+                Ok(face
+                    .revolve_about((center, workplane.normal()), None)
+                    .into())
+            })
+            .collect::<Result<Vec<Shape>, Error>>()?;
+
+        self.subtract(&Compound::from_shapes(tools).into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::EdgeType;
+    use crate::primitives::FaceType;
+    use nalgebra::vector;
+
+    #[test]
+    fn countersunk_m3_hole() {
+        let block = Shape::box_with_dimensions(20.0, 20.0, 10.0);
+        let top = Workplane::xy().translated(vector![10.0, 10.0, 10.0]);
+
+        let spec = HoleSpec::countersunk_for_screw(IsoScrew::M3, HoleFit::Normal);
+        let HoleSpec::Countersunk { head_diameter, .. } = spec else {
+            panic!("Expected a countersunk hole");
+        };
+
+        let shape = block.hole(&top, (0.0, 0.0), spec).unwrap();
+
+        let cones: Vec<_> = shape
+            .faces()
+            .filter(|face| face.face_type() == FaceType::Cone)
+            .collect();
+        assert_eq!(cones.len(), 1);
+
+        let half_angle = cones[0].cone_half_angle().unwrap();
+        assert!((half_angle.degrees() - 45.0).abs() < 1.0e-6);
+
+        let entry_radius = cones[0]
+            .edges()
+            .filter(|edge| edge.edge_type() == EdgeType::Circle)
+            .filter_map(|edge| edge.centerpoint_axis_radius())
+            .map(|(_, _, radius)| radius)
+            .fold(0.0, f64::max);
+        assert!((entry_radius * 2.0 - head_diameter).abs() < 1.0e-6);
+
+        let holes: Vec<_> = shape
+            .faces()
+            .filter(|face| face.face_type() == FaceType::Cylinder)
+            .collect();
+        assert_eq!(holes.len(), 1);
+    }
+
+    #[test]
+    fn batched_holes() {
+        let block = Shape::box_with_dimensions(30.0, 10.0, 10.0);
+        let top = Workplane::xy().translated(vector![0.0, 5.0, 10.0]);
+
+        let spec = HoleSpec::tapped(IsoScrew::M4, 6.0);
+        let shape = block
+            .holes(&top, &[(5.0, 0.0), (15.0, 0.0), (25.0, 0.0)], spec)
+            .unwrap();
+
+        let cylinders = shape
+            .faces()
+            .filter(|face| face.face_type() == FaceType::Cylinder)
+            .count();
+        let tips = shape
+            .faces()
+            .filter(|face| face.face_type() == FaceType::Cone)
+            .count();
+        assert_eq!(cylinders, 3);
+        assert_eq!(tips, 3);
+        assert!(shape.volume() < block.volume());
+    }
+
+    #[test]
+    fn holes_in_inches() {
+        let _inches = ModelContext::new(Unit::Inch).scoped();
+        let block = Shape::box_with_dimensions(50.8, 50.8, 25.4);
+        let top = Workplane::xy().translated(vector![0.0, 0.0, 25.4]);
+
+        let spec = HoleSpec::Simple {
+            diameter: 0.25,
+            depth: HoleDepth::Through,
+            unit: None,
+        };
+        let shape = block.hole(&top, (1.0, 0.5), spec).unwrap();
+
+        let (center, _, radius) = shape
+            .faces()
+            .filter(|face| face.face_type() == FaceType::Cylinder)
+            .flat_map(|face| face.edges())
+            .find_map(|edge| edge.centerpoint_axis_radius())
+            .unwrap();
+        assert!((radius - 3.175).abs() < 1.0e-6, "{radius}");
+        assert!((center.x - 25.4).abs() < 1.0e-6, "{center}");
+        assert!((center.y - 12.7).abs() < 1.0e-6, "{center}");
+
+        // Screw holes are the same size whatever the unit, even on a
+        // workplane in a different unit from the current context.
+        let top = top.with_context(ModelContext::new(Unit::Meter));
+        let m3 = HoleSpec::for_screw(IsoScrew::M3, HoleFit::Normal);
+        let shape = block.hole(&top, (0.01, 0.01), m3).unwrap();
+
+        let (_, _, radius) = shape
+            .faces()
+            .filter(|face| face.face_type() == FaceType::Cylinder)
+            .flat_map(|face| face.edges())
+            .find_map(|edge| edge.centerpoint_axis_radius())
+            .unwrap();
+        assert!((radius - 1.7).abs() < 1.0e-6, "{radius}");
+    }
+}
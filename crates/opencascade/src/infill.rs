@@ -0,0 +1,349 @@
+//! Lightweight lattice infill for solids, in the style of 3D printer slicers.
+
+use crate::bounding_box;
+use crate::bounding_box::BoundingBox;
+use crate::primitives::Edge;
+use crate::primitives::Face;
+use crate::primitives::Shape;
+use crate::primitives::Wire;
+use crate::Error;
+use nalgebra::point;
+use nalgebra::vector;
+use nalgebra::Point3;
+
+/// Number of points per wavelength used to approximate `Gyroidish` walls.
+const WAVE_SAMPLES: usize = 16;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InfillPattern {
+    /// Straight vertical walls in X and Y, forming square cells.
+    Grid,
+    /// Vertical walls forming hexagonal cells.
+    Honeycomb,
+    /// Layers of wavy vertical walls, alternating between running along X
+    /// and along Y. A rough approximation of a gyroid.
+    Gyroidish,
+}
+
+impl Shape {
+    /// Hollow out this shape, leaving a skin `shell_thickness` thick, and
+    /// fill the inside with a lattice of walls `strut_thickness` thick
+    /// repeating every `cell_size`.
+    ///
+    /// Fails with `Error::InvalidInfill` unless all three lengths are
+    /// positive.
+    pub fn infill(
+        &self,
+        pattern: InfillPattern,
+        cell_size: f64,
+        strut_thickness: f64,
+        shell_thickness: f64,
+    ) -> Result<Shape, Error> {
+        let invalid = |reason| Err(Error::InvalidInfill { reason });
+
+        // Written so that NaNs fail every check.
+        if !cell_size.is_finite() || cell_size <= 0.0 {
+            return invalid("the cell size must be positive");
+        }
+        if !strut_thickness.is_finite() || strut_thickness <= 0.0 {
+            return invalid("the struts must have a positive thickness");
+        }
+        if !shell_thickness.is_finite() || shell_thickness <= 0.0 {
+            return invalid("the shell must have a positive thickness");
+        }
+
+        let cavity = self.offset_surface(-shell_thickness);
+
+        if cavity.volume() <= 0.0 {
+            return Err(Error::NotDone);
+        }
+
+        let bounds = bounding_box::aabb(&cavity);
+        let (min, max) = (bounds.min(), bounds.max());
+
+        // Walls are built a little larger than the cavity so their ends
+        // never land exactly on its faces.
+        let margin = strut_thickness + cell_size;
+        let (min, max) = (
+            min - vector![margin, margin, margin],
+            max + vector![margin, margin, margin],
+        );
+
+        let walls = match pattern {
+            InfillPattern::Grid => grid_walls(min, max, &bounds, cell_size, strut_thickness),
+            InfillPattern::Honeycomb => honeycomb_walls(min, max, cell_size, strut_thickness)?,
+            InfillPattern::Gyroidish => gyroidish_walls(min, max, cell_size, strut_thickness)?,
+        };
+
+        let lattice = Shape::union_all(&walls)?;
+        let lattice: Shape = lattice.intersect(&cavity)?.into();
+        let skin = self.subtract(&cavity)?;
+
+        Shape::union_all([&skin, &lattice])
+    }
+}
+
+/// Walls spanning `min` to `max`, skipping those which miss `cavity`
+/// entirely.
+fn grid_walls(
+    min: Point3<f64>,
+    max: Point3<f64>,
+    cavity: &BoundingBox,
+    cell_size: f64,
+    thickness: f64,
+) -> Vec<Shape> {
+    let half = thickness / 2.0;
+    let (cavity_min, cavity_max) = (cavity.min(), cavity.max());
+
+    let along_x = steps(min.y, max.y, cell_size)
+        .filter(|y| y + half >= cavity_min.y && y - half <= cavity_max.y)
+        .map(|y| {
+            Shape::box_from_corners(
+                point![min.x, y - half, min.z],
+                point![max.x, y + half, max.z],
+            )
+        });
+    let along_y = steps(min.x, max.x, cell_size)
+        .filter(|x| x + half >= cavity_min.x && x - half <= cavity_max.x)
+        .map(|x| {
+            Shape::box_from_corners(
+                point![x - half, min.y, min.z],
+                point![x + half, max.y, max.z],
+            )
+        });
+
+    along_x.chain(along_y).collect()
+}
+
+fn honeycomb_walls(
+    min: Point3<f64>,
+    max: Point3<f64>,
+    cell_size: f64,
+    thickness: f64,
+) -> Result<Vec<Shape>, Error> {
+    // Flat topped hexagons, `cell_size` across the flats.
+    let radius = cell_size / 3.0f64.sqrt();
+    let corner = |center: Point3<f64>, i: usize| {
+        let angle = (i as f64 * 60.0).to_radians();
+        center + vector![radius * angle.cos(), radius * angle.sin(), 0.0]
+    };
+
+    let mut walls = vec![];
+
+    for (column, x) in steps(min.x - radius, max.x + radius, 1.5 * radius).enumerate() {
+        let offset = if column % 2 == 0 {
+            0.0
+        } else {
+            cell_size / 2.0
+        };
+
+        for y in steps(min.y - cell_size + offset, max.y + cell_size, cell_size) {
+            let center = point![x, y, min.z];
+
+            // The upper three sides of each cell. The lower three are the
+            // upper sides of the neighbouring cells.
+            for i in 0..3 {
+                let (start, end) = (corner(center, i), corner(center, i + 1));
+
+                // Skip walls entirely outside the cavity's bounds.
+                if start.x.max(end.x) < min.x
+                    || start.x.min(end.x) > max.x
+                    || start.y.max(end.y) < min.y
+                    || start.y.min(end.y) > max.y
+                {
+                    continue;
+                }
+
+                walls.push(vertical_wall(&[start, end], thickness, max.z - min.z)?);
+            }
+        }
+    }
+
+    Ok(walls)
+}
+
+fn gyroidish_walls(
+    min: Point3<f64>,
+    max: Point3<f64>,
+    cell_size: f64,
+    thickness: f64,
+) -> Result<Vec<Shape>, Error> {
+    let amplitude = cell_size / 4.0;
+    let layer_height = cell_size / 2.0;
+    let mut walls = vec![];
+
+    for (layer, z) in steps(min.z, max.z, layer_height).enumerate() {
+        // Alternate the direction of the walls, and shift the phase of the
+        // waves, in every layer.
+        let along_x = layer % 2 == 0;
+        let phase = if layer % 4 < 2 {
+            0.0
+        } else {
+            std::f64::consts::PI
+        };
+
+        let (start, end, across_min, across_max) = if along_x {
+            (min.x, max.x, min.y, max.y)
+        } else {
+            (min.y, max.y, min.x, max.x)
+        };
+
+        let samples = ((end - start) / cell_size * WAVE_SAMPLES as f64).ceil() as usize;
+
+        for across in steps(across_min, across_max, cell_size) {
+            let centerline: Vec<_> = (0..=samples)
+                .map(|i| {
+                    let along = start + (end - start) * i as f64 / samples as f64;
+                    let angle = std::f64::consts::TAU * along / cell_size + phase;
+                    let across = across + amplitude * angle.sin();
+
+                    if along_x {
+                        point![along, across, z]
+                    } else {
+                        point![across, along, z]
+                    }
+                })
+                .collect();
+
+            walls.push(vertical_wall(&centerline, thickness, layer_height)?);
+        }
+    }
+
+    Ok(walls)
+}
+
+/// A wall `thickness` thick and `height` tall, centered on the open polyline
+/// (or smooth curve, for more than two points) through `centerline`.
+fn vertical_wall(centerline: &[Point3<f64>], thickness: f64, height: f64) -> Result<Shape, Error> {
+    let half = thickness / 2.0;
+
+    let side = |sign: f64| -> Vec<Point3<f64>> {
+        centerline
+            .iter()
+            .enumerate()
+            .map(|(i, point)| {
+                let previous = centerline[i.saturating_sub(1)];
+                let next = centerline[(i + 1).min(centerline.len() - 1)];
+                let tangent = (next - previous).normalize();
+                let normal = vector![-tangent.y, tangent.x, 0.0];
+
+                // Extend straight walls by half their thickness, so that
+                // walls meeting at an angle overlap instead of leaving a
+                // notch.
+                let extension = match i {
+                    0 if centerline.len() == 2 => -tangent * half,
+                    1 if centerline.len() == 2 => tangent * half,
+                    _ => vector![0.0, 0.0, 0.0],
+                };
+
+                point + normal * half * sign + extension
+            })
+            .collect()
+    };
+
+    let (left, mut right) = (side(1.0), side(-1.0));
+    right.reverse();
+
+    let outline = if centerline.len() == 2 {
+        Wire::from_ordered_points(left.into_iter().chain(right))?
+    } else {
+        let (left_start, left_end) = (left[0], left[left.len() - 1]);
+        let (right_start, right_end) = (right[0], right[right.len() - 1]);
+
+        Wire::from_edges_consuming([
+            Edge::spline_from_points(left, None),
+            Edge::segment(left_end, right_start),
+            Edge::spline_from_points(right, None),
+            Edge::segment(right_end, left_start),
+        ])?
+    };
+
+    let face = Face::from_wire(&outline)?;
+
+    Ok(face.extrude(vector![0.0, 0.0, height]).into())
+}
+
+/// Values from `start` up to and including `end`, `step` apart.
+fn steps(start: f64, end: f64, step: f64) -> impl Iterator<Item = f64> {
+    let count = ((end - start) / step).floor().max(0.0) as usize;
+
+    (0..=count).map(move |i| start + i as f64 * step)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn infill_sweep() {
+        let block = Shape::box_with_dimensions(40.0, 30.0, 20.0);
+        let original_volume = block.volume();
+
+        for pattern in [
+            InfillPattern::Grid,
+            InfillPattern::Honeycomb,
+            InfillPattern::Gyroidish,
+        ] {
+            for cell_size in [5.0, 8.0, 12.0] {
+                let infilled = block
+                    .infill(pattern, cell_size, 1.0, 2.0)
+                    .unwrap_or_else(|err| panic!("{pattern:?} at {cell_size} failed: {err}"));
+
+                // The fuse gives a compound, holding the lattice and skin
+                // as one solid.
+                assert_eq!(
+                    infilled.solids().count(),
+                    1,
+                    "{pattern:?} at {cell_size} came apart"
+                );
+                assert!(
+                    infilled.is_closed_solid(),
+                    "{pattern:?} at {cell_size} isn't watertight"
+                );
+                assert!(
+                    infilled.volume() < original_volume * 0.75,
+                    "{pattern:?} at {cell_size} only reduced the volume to {}",
+                    infilled.volume()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_infill() {
+        let block = Shape::box_with_dimensions(40.0, 30.0, 20.0);
+
+        for (cell_size, strut, shell) in [
+            (0.0, 1.0, 2.0),
+            (-5.0, 1.0, 2.0),
+            (f64::NAN, 1.0, 2.0),
+            (f64::INFINITY, 1.0, 2.0),
+            (5.0, 0.0, 2.0),
+            (5.0, f64::NAN, 2.0),
+            (5.0, 1.0, 0.0),
+            (5.0, 1.0, f64::NAN),
+        ] {
+            assert!(
+                matches!(
+                    block.infill(InfillPattern::Grid, cell_size, strut, shell),
+                    Err(Error::InvalidInfill { .. })
+                ),
+                "{cell_size} {strut} {shell}"
+            );
+        }
+    }
+
+    #[test]
+    fn grid_walls_are_culled() {
+        let cavity = Shape::box_with_dimensions(10.0, 10.0, 10.0);
+        let bounds = bounding_box::aabb(&cavity);
+        let (min, max) = (
+            bounds.min() - vector![20.0, 20.0, 1.0],
+            bounds.max() + vector![20.0, 20.0, 1.0],
+        );
+
+        // Only the walls at 0, 5 and 10 in each direction reach the cavity.
+        let walls = grid_walls(min, max, &bounds, 5.0, 1.0);
+        assert_eq!(walls.len(), 6);
+    }
+}
@@ -3,6 +3,7 @@ use nalgebra::point;
 use opencascade_sys::ffi;
 
 use crate::primitives::make_point2d;
+use crate::Error;
 
 #[must_use]
 pub(crate) fn law_function_from_graph(
@@ -22,3 +23,121 @@ pub(crate) fn law_function_from_graph(
     interpol.pin_mut().Set(&array, is_periodic);
     ffi::Law_Interpol_into_Law_Function(interpol)
 }
+
+/// A scalar function of a normalized parameter `t` in `[0, 1]`, defined by
+/// interpolating a graph of `(t, value)` pairs. Used to modulate sweeps along
+/// their spine (scaling, twisting).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Law {
+    pairs: Vec<(f64, f64)>,
+}
+
+impl Law {
+    /// A law which evaluates to `value` everywhere.
+    pub fn constant(value: f64) -> Self {
+        Self {
+            pairs: vec![(0.0, value), (1.0, value)],
+        }
+    }
+
+    /// A law which goes linearly from `start` at t = 0 to `end` at t = 1.
+    pub fn linear(start: f64, end: f64) -> Self {
+        Self {
+            pairs: vec![(0.0, start), (1.0, end)],
+        }
+    }
+
+    /// A law interpolating the given `(t, value)` pairs. There must be at
+    /// least two pairs, all finite, with strictly increasing `t` values.
+    pub fn from_graph(pairs: impl IntoIterator<Item = (f64, f64)>) -> Result<Self, Error> {
+        let pairs: Vec<_> = pairs.into_iter().collect();
+        let invalid = |reason| Err(Error::InvalidLaw { reason });
+
+        if pairs.len() < 2 {
+            return invalid("a law needs at least two (t, value) pairs");
+        }
+        if pairs
+            .iter()
+            .any(|(t, value)| !t.is_finite() || !value.is_finite())
+        {
+            return invalid("the (t, value) pairs must be finite");
+        }
+        if !pairs.windows(2).all(|pair| pair[0].0 < pair[1].0) {
+            return invalid("the t values must be strictly increasing");
+        }
+
+        Ok(Self { pairs })
+    }
+
+    pub fn pairs(&self) -> &[(f64, f64)] {
+        &self.pairs
+    }
+
+    /// Evaluate the law at the normalized parameter `t`, using the same
+    /// interpolation OCCT uses when the law is applied to a sweep.
+    pub fn value_at(&self, t: f64) -> f64 {
+        self.evaluator().value_at(t)
+    }
+
+    pub(crate) fn evaluator(&self) -> LawEvaluator {
+        let first = self.pairs.first().map(|(t, _)| *t).unwrap_or(0.0);
+        let last = self.pairs.last().map(|(t, _)| *t).unwrap_or(1.0);
+
+        LawEvaluator {
+            inner: self.to_law_function(),
+            first,
+            last,
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn to_law_function(&self) -> UniquePtr<ffi::Law_Function> {
+        law_function_from_graph(self.pairs.iter().copied())
+    }
+}
+
+/// Evaluates a `Law` repeatedly without rebuilding the OCCT interpolation
+/// for every sample.
+pub(crate) struct LawEvaluator {
+    inner: UniquePtr<ffi::Law_Function>,
+    first: f64,
+    last: f64,
+}
+
+impl LawEvaluator {
+    pub(crate) fn value_at(&mut self, t: f64) -> f64 {
+        let x = self.first + t.clamp(0.0, 1.0) * (self.last - self.first);
+
+        self.inner.pin_mut().Value(x)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn graph_laws() {
+        let law = Law::from_graph([(0.0, 1.0), (0.5, 2.0), (1.0, 1.0)]).unwrap();
+
+        assert_eq!(law.pairs().len(), 3);
+        assert!((law.value_at(0.5) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn invalid_graph_laws() {
+        for pairs in [
+            vec![],
+            vec![(0.0, 1.0)],
+            vec![(0.0, 1.0), (0.0, 2.0)],
+            vec![(0.5, 1.0), (0.0, 2.0)],
+            vec![(0.0, 1.0), (f64::NAN, 2.0)],
+            vec![(0.0, 1.0), (1.0, f64::INFINITY)],
+        ] {
+            assert!(matches!(
+                Law::from_graph(pairs),
+                Err(Error::InvalidLaw { .. })
+            ));
+        }
+    }
+}
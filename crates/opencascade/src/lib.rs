@@ -1,5 +1,10 @@
 use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
 
+use crate::convert::IntoUnitQuaternion;
+use crate::convert::IntoVector3;
 use crate::primitives::Edge;
 use crate::primitives::Shape;
 use crate::primitives::Wire;
@@ -21,13 +26,28 @@ use simba::scalar::SubsetOf;
 use thiserror::Error;
 
 pub mod angle;
+pub mod boolean;
 pub mod bounding_box;
+pub mod context;
+pub mod convert;
+pub mod diff;
+pub mod drawing;
+pub mod export;
+pub mod holes;
+pub mod infill;
+pub mod law_function;
 pub mod mesh;
+pub mod part;
 pub mod primitives;
+pub mod robotics;
+#[cfg(feature = "serde")]
+pub mod scene;
 pub mod section;
+pub mod sweep;
+pub mod thickness;
+pub mod threads;
 pub mod workplane;
 
-mod law_function;
 mod make_pipe_shell;
 
 #[derive(Error, Debug)]
@@ -42,6 +62,10 @@ pub enum Error {
     StepWriteFailed,
     #[error("failed to write IGES file")]
     IgesWriteFailed,
+    #[error("failed to read BREP file")]
+    BrepReadFailed,
+    #[error("failed to write BREP file")]
+    BrepWriteFailed,
     #[error("failed to triangulate Shape")]
     TriangulationFailed,
     #[error("encountered a face with no triangulation")]
@@ -52,6 +76,110 @@ pub enum Error {
     NotDone,
     #[error("start and end point are identical")]
     StackedPoints,
+    #[error("cannot make a solid from an open profile")]
+    OpenProfile,
+    #[error("shape is not closed")]
+    NotClosed,
+    #[error("the current selection is not valid for this operation")]
+    InvalidSelection,
+    #[error("the rib doesn't reach its target, missing it by {gap}")]
+    RibMissesTarget { gap: f64 },
+    #[error("a pattern needs at least two instances")]
+    TooFewInstances,
+    #[error("edge {index} isn't connected to the edges before it")]
+    DisconnectedEdge { index: usize },
+    #[error("edge {index} would make the wire branch")]
+    NonManifoldEdge { index: usize },
+    #[error("the ends of the wires are {gap} apart")]
+    WiresApart { gap: f64 },
+    #[error("edges {first} and {second} are {gap} apart")]
+    EdgesApart { first: usize, second: usize, gap: f64 },
+    #[error("a closed wire has no ends to join")]
+    ClosedWire,
+    #[error("parameter {parameter} is out of range")]
+    ParameterOutOfRange { parameter: f64 },
+    #[error("the wire has no length")]
+    ZeroLengthWire,
+    #[error("the point is {distance} away from the wire")]
+    PointOffWire { distance: f64 },
+    #[error("expected a planar face, not a {face_type:?} face")]
+    NotPlanar { face_type: primitives::FaceType },
+    #[error("the sketch is {distance} away from the face")]
+    SketchOutsideFace { distance: f64 },
+    #[error("the face cuts the sketch into {pieces} pieces")]
+    SketchSplitByFace { pieces: usize },
+    #[error("expected a cylindrical face, not a {face_type:?} face")]
+    NotCylindrical { face_type: primitives::FaceType },
+    #[error("a {edge_type:?} edge has no axis")]
+    NoEdgeAxis { edge_type: primitives::EdgeType },
+    #[error("the faces are {angle} radians from parallel")]
+    FacesNotParallel { angle: f64 },
+    #[error("the profile doesn't reach the limiting face when extruded")]
+    ProfileMissesFace,
+    #[error("can't project along a direction parallel to the plane")]
+    ProjectionParallelToPlane,
+    #[error("the tangent lines don't meet")]
+    TangentsDoNotMeet,
+    #[error("the mesh has {face_ranges} face ranges, not one for each of the {faces} faces")]
+    MeshDoesNotMatch { faces: usize, face_ranges: usize },
+    #[error("couldn't make the profile at station {station}: {source}")]
+    ProfileFailed { station: usize, source: Box<Error> },
+    #[error(transparent)]
+    Face(#[from] primitives::FaceError),
+    #[error("the mesh is empty")]
+    EmptyMesh,
+    #[error("{value} is too large to store in the export format")]
+    TooLargeToExport { value: usize },
+    #[error("malformed mesh data: {reason}")]
+    MalformedMesh { reason: &'static str },
+    #[error("{value} doesn't fit in an f32")]
+    OutOfF32Range { value: f64 },
+    #[error("a perspective projection needs a positive focus, not {focus}")]
+    InvalidFocus { focus: f64 },
+    #[error("a drawing needs a positive scale, not {scale}")]
+    InvalidScale { scale: f64 },
+    #[error("an extrusion needs a positive height, not {height}")]
+    InvalidHeight { height: f64 },
+    #[error("a direction must be a finite, non-zero vector")]
+    InvalidDirection,
+    #[error("a rotation must be a finite, non-zero quaternion")]
+    InvalidRotation,
+    #[error("a tolerance must be positive, not {tolerance}")]
+    InvalidTolerance { tolerance: f64 },
+    #[error("a sample spacing must be positive, not {spacing}")]
+    InvalidSpacing { spacing: f64 },
+    #[error("invalid airfoil: {reason}")]
+    InvalidAirfoil { reason: String },
+    #[error("invalid spring: {reason}")]
+    InvalidSpring { reason: &'static str },
+    #[error("invalid thread: {reason}")]
+    InvalidThread { reason: &'static str },
+    #[error("invalid infill: {reason}")]
+    InvalidInfill { reason: &'static str },
+    #[error("invalid law: {reason}")]
+    InvalidLaw { reason: &'static str },
+    #[error("invalid conic: {reason}")]
+    InvalidConic { reason: &'static str },
+    #[error("a convex hull needs at least two distinct points")]
+    DegenerateHull,
+    #[error("a {face_type:?} face can't be unrolled without stretching it")]
+    NotDevelopable { face_type: primitives::FaceType },
+    #[error("couldn't read the BREP files of these scene entries: {failures:?}")]
+    SceneBrepFailed { failures: Vec<(String, std::path::PathBuf)> },
+    #[error("the planes are parallel, {distance} apart")]
+    ParallelPlanes { distance: f64 },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// The STEP and IGES translators keep their settings in global tables, so
+/// only one of them may run at a time.
+static TRANSLATOR_LOCK: Mutex<()> = Mutex::new(());
+
+pub(crate) fn translator_lock() -> MutexGuard<'static, ()> {
+    TRANSLATOR_LOCK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
 }
 
 pub(crate) const X_NORMAL: UnitVector3<f64> = UnitVector3::new_unchecked(vector![1.0, 0.0, 0.0]);
@@ -59,6 +187,10 @@ pub(crate) const Y_NORMAL: UnitVector3<f64> = UnitVector3::new_unchecked(vector!
 pub(crate) const Z_NORMAL: UnitVector3<f64> = UnitVector3::new_unchecked(vector![0.0, 0.0, 1.0]);
 pub(crate) const BASE_NORMAL: UnitVector3<f64> = Z_NORMAL;
 
+/// Points closer together than this, in millimeters, are the same, and
+/// lengths shorter than it are nothing, like OCCT's `Precision::Confusion`.
+pub(crate) const CONFUSION: f64 = 1.0e-7;
+
 pub struct TopExpExplorerIter {
     explorer: UniquePtr<ffi::TopExp_Explorer>,
 }
@@ -139,14 +271,23 @@ impl<F: Scalar + RealField + Clone + Copy + From<f32>> Default for TandR<F> {
 }
 
 impl<F: Scalar + RealField + Clone + Copy + From<f32>> TandR<F> {
-    pub fn new(translation: Vector3<F>, rotation_quat: UnitQuaternion<F>) -> Self {
+    pub fn new(translation: impl IntoVector3<F>, rotation_quat: UnitQuaternion<F>) -> Self {
         Self {
-            translation,
+            translation: translation.into_vector3(),
             rotation_quat,
             inverse: false,
         }
     }
 
+    /// Like `new`, with a rotation which needn't be normalized. A zero or
+    /// non-finite rotation is an `Error::InvalidRotation`.
+    pub fn from_quaternion(
+        translation: impl IntoVector3<F>,
+        rotation_quat: impl IntoUnitQuaternion<F>,
+    ) -> Result<Self, Error> {
+        Ok(Self::new(translation, rotation_quat.into_unit_quaternion()?))
+    }
+
     pub fn translation(mut self, translation: Vector3<F>) -> Self {
         self.translation = translation;
 
@@ -220,6 +361,20 @@ impl<F: Scalar + RealField + Clone + Copy + From<f32>> TandR<F> {
         }
     }
 
+    /// Transform all of `points` in place, like `transform_point`, converting
+    /// the rotation to a matrix once for all of them.
+    pub fn transform_points(&self, points: &mut [Point3<F>]) {
+        let rotation = self.rotation_quat.to_rotation_matrix();
+
+        for point in points.iter_mut() {
+            *point = if self.inverse {
+                rotation * (*point + self.translation)
+            } else {
+                rotation * *point + self.translation
+            };
+        }
+    }
+
     pub fn transform_point2d(&self, point: Point2<F>) -> Point2<F> {
         let point3d = point![point.x, point.y, 0.0.into()];
         let new_point = self.transform_point(point3d);
@@ -1,3 +1,4 @@
+use crate::primitives::Face;
 use crate::primitives::FaceOrientation;
 use crate::primitives::Shape;
 use crate::Error;
@@ -8,13 +9,136 @@ use nalgebra::Point3;
 use nalgebra::Vector2;
 use nalgebra::Vector3;
 use opencascade_sys::ffi;
+use std::collections::HashMap;
+use std::ops::Range;
 
-#[derive(Debug)]
+mod encoding;
+mod incremental;
+mod overrides;
+mod single_precision;
+#[cfg(feature = "stl_io")]
+mod stl;
+
+pub use overrides::*;
+pub use single_precision::*;
+
+#[derive(Debug, Clone)]
 pub struct Mesh {
     pub vertices: Vec<Point3<f64>>,
     pub uvs: Vec<Vector2<f64>>,
     pub normals: Vec<Vector3<f64>>,
     pub indices: Vec<usize>,
+    /// The range of `indices` holding the triangles of each face of the
+    /// meshed shape, in the order the faces were visited.
+    pub face_ranges: Vec<Range<usize>>,
+}
+
+impl Mesh {
+    /// Merge vertices closer together than `tolerance`, which must be
+    /// positive, so triangles of neighbouring faces share their boundary
+    /// vertices. Each merged vertex keeps the UV and normal of the first
+    /// vertex merged into it. Triangles which collapse are dropped.
+    ///
+    /// A mesh without face ranges, like one read from an STL file, is
+    /// welded as a single face.
+    pub fn welded(&self, tolerance: f64) -> Result<Mesh, Error> {
+        if tolerance.is_nan() || tolerance <= 0.0 {
+            return Err(Error::InvalidTolerance { tolerance });
+        }
+
+        let cell = |p: &Point3<f64>| {
+            (
+                (p.x / tolerance).floor() as i64,
+                (p.y / tolerance).floor() as i64,
+                (p.z / tolerance).floor() as i64,
+            )
+        };
+
+        let mut welded = Mesh {
+            vertices: vec![],
+            uvs: vec![],
+            normals: vec![],
+            indices: vec![],
+            face_ranges: vec![],
+        };
+        let mut grid: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+        let mut remap = Vec::with_capacity(self.vertices.len());
+
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            let (x, y, z) = cell(vertex);
+
+            // A vertex within `tolerance` is in the same or a neighbouring
+            // cell.
+            let existing = (-1..=1)
+                .flat_map(|dx| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| (dx, dy, dz))))
+                .filter_map(|(dx, dy, dz)| grid.get(&(x + dx, y + dy, z + dz)))
+                .flatten()
+                .find(|&&j| (welded.vertices[j] - vertex).norm() <= tolerance)
+                .copied();
+
+            let index = existing.unwrap_or_else(|| {
+                let index = welded.vertices.len();
+                welded.vertices.push(*vertex);
+                welded.uvs.extend(self.uvs.get(i));
+                welded.normals.extend(self.normals.get(i));
+                grid.entry((x, y, z)).or_default().push(index);
+                index
+            });
+
+            remap.push(index);
+        }
+
+        let whole = [0..self.indices.len()];
+        let ranges = if self.face_ranges.is_empty() {
+            &whole[..]
+        } else {
+            &self.face_ranges[..]
+        };
+
+        for range in ranges {
+            let start = welded.indices.len();
+
+            for triangle in self.indices[range.clone()].chunks_exact(3) {
+                let (a, b, c) = (remap[triangle[0]], remap[triangle[1]], remap[triangle[2]]);
+
+                if a != b && b != c && c != a {
+                    welded.indices.extend([a, b, c]);
+                }
+            }
+
+            if !self.face_ranges.is_empty() {
+                welded.face_ranges.push(start..welded.indices.len());
+            }
+        }
+
+        Ok(welded)
+    }
+}
+
+/// How to turn a shape into a `Mesh`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshOptions {
+    /// The maximum distance between the mesh and the surfaces it
+    /// approximates.
+    pub deflection: f64,
+    /// Merge vertices closer together than this after triangulating, see
+    /// `Mesh::welded`. `None` leaves the vertices of each face separate.
+    pub weld_tolerance: Option<f64>,
+    /// Deflections for particular faces, used instead of `deflection`. A
+    /// face gets the deflection of the first selector matching it. Edges
+    /// between faces meshed with different deflections are divided as the
+    /// finer of them needs, so the faces still meet.
+    pub overrides: Vec<(FaceSelector, f64)>,
+}
+
+impl Default for MeshOptions {
+    fn default() -> Self {
+        Self {
+            deflection: 0.01,
+            weld_tolerance: Some(1.0e-6),
+            overrides: vec![],
+        }
+    }
 }
 
 pub struct Mesher {
@@ -23,7 +147,15 @@ pub struct Mesher {
 
 impl Mesher {
     pub fn try_new(shape: &Shape, triangulation_tolerance: f64) -> Result<Self, Error> {
-        let inner = ffi::BRepMesh_IncrementalMesh_ctor(&shape.inner, triangulation_tolerance);
+        // Meshing stores triangulations on the faces it meshes. Mesh a copy
+        // of the topology, so faces shared with other shapes are never
+        // written to.
+        let copy_geometry = false;
+        let copy_mesh = false;
+        let mut copier = ffi::BRepBuilderAPI_Copy_new(&shape.inner, copy_geometry, copy_mesh);
+
+        let inner =
+            ffi::BRepMesh_IncrementalMesh_ctor(copier.pin_mut().Shape(), triangulation_tolerance);
 
         if inner.IsDone() {
             Ok(Self { inner })
@@ -37,94 +169,158 @@ impl Mesher {
     }
 
     pub fn mesh(self) -> Result<Mesh, Error> {
-        let mut vertices = vec![];
-        let mut uvs = vec![];
-        let mut normals = vec![];
-        let mut indices = vec![];
-
         let triangulated_shape = self.get_shape();
 
-        for face in triangulated_shape.faces() {
-            let mut location = ffi::TopLoc_Location_ctor();
+        mesh_triangulated(&triangulated_shape)
+    }
+}
+
+/// Gather the triangulations stored on the faces of `triangulated_shape`
+/// into a `Mesh`.
+fn mesh_triangulated(triangulated_shape: &Shape) -> Result<Mesh, Error> {
+    let mut mesh = Mesh {
+        vertices: vec![],
+        uvs: vec![],
+        normals: vec![],
+        indices: vec![],
+        face_ranges: vec![],
+    };
 
-            let triangulation_handle =
-                ffi::BRep_Tool_Triangulation(&face.inner, location.pin_mut());
+    for face in triangulated_shape.faces() {
+        append_triangulated_face(&mut mesh, &face)?;
+    }
 
-            let triangulation = ffi::HandlePoly_Triangulation_Get(&triangulation_handle)
-                .map_err(|_| Error::UntriangulatedFace)?;
+    Ok(mesh)
+}
 
-            let index_offset = vertices.len();
-            let face_point_count = triangulation.NbNodes();
+/// Add the triangulation stored on `face` to the end of `mesh`, as a new
+/// face range.
+fn append_triangulated_face(mesh: &mut Mesh, face: &Face) -> Result<(), Error> {
+    let mut location = ffi::TopLoc_Location_ctor();
 
-            for i in 1..=face_point_count {
-                let mut point = ffi::Poly_Triangulation_Node(triangulation, i);
-                point
-                    .pin_mut()
-                    .Transform(&ffi::TopLoc_Location_Transformation(&location));
-                vertices.push(point![point.X(), point.Y(), point.Z()]);
-            }
+    let triangulation_handle = ffi::BRep_Tool_Triangulation(&face.inner, location.pin_mut());
 
-            let mut u_min = f64::INFINITY;
-            let mut v_min = f64::INFINITY;
+    let triangulation = ffi::HandlePoly_Triangulation_Get(&triangulation_handle)
+        .map_err(|_| Error::UntriangulatedFace)?;
 
-            let mut u_max = f64::NEG_INFINITY;
-            let mut v_max = f64::NEG_INFINITY;
+    let index_offset = mesh.vertices.len();
+    let indices_start = mesh.indices.len();
+    let face_point_count = triangulation.NbNodes();
 
-            for i in 1..=(face_point_count) {
-                let uv = ffi::Poly_Triangulation_UV(triangulation, i);
-                let (u, v) = (uv.X(), uv.Y());
+    for i in 1..=face_point_count {
+        let mut point = ffi::Poly_Triangulation_Node(triangulation, i);
+        point
+            .pin_mut()
+            .Transform(&ffi::TopLoc_Location_Transformation(&location));
+        mesh.vertices.push(point![point.X(), point.Y(), point.Z()]);
+    }
 
-                u_min = u_min.min(u);
-                v_min = v_min.min(v);
+    let mut u_min = f64::INFINITY;
+    let mut v_min = f64::INFINITY;
 
-                u_max = u_max.max(u);
-                v_max = v_max.max(v);
+    let mut u_max = f64::NEG_INFINITY;
+    let mut v_max = f64::NEG_INFINITY;
 
-                uvs.push(vector![u, v]);
-            }
+    for i in 1..=(face_point_count) {
+        let uv = ffi::Poly_Triangulation_UV(triangulation, i);
+        let (u, v) = (uv.X(), uv.Y());
 
-            // Normalize the newly added UV coordinates.
-            for uv in &mut uvs[index_offset..(index_offset + face_point_count as usize)] {
-                uv.x = (uv.x - u_min) / (u_max - u_min);
-                uv.y = (uv.y - v_min) / (v_max - v_min);
+        u_min = u_min.min(u);
+        v_min = v_min.min(v);
 
-                if face.orientation() != FaceOrientation::Forward {
-                    uv.x = 1.0 - uv.x;
-                }
-            }
+        u_max = u_max.max(u);
+        v_max = v_max.max(v);
 
-            // Add in the normals.
-            // TODO(bschwind) - Use `location` to transform the normals.
-            let normal_array = ffi::TColgp_Array1OfDir_ctor(0, face_point_count);
+        mesh.uvs.push(vector![u, v]);
+    }
 
-            ffi::compute_normals(&face.inner, &triangulation_handle);
+    // Normalize the newly added UV coordinates.
+    for uv in &mut mesh.uvs[index_offset..(index_offset + face_point_count as usize)] {
+        uv.x = (uv.x - u_min) / (u_max - u_min);
+        uv.y = (uv.y - v_min) / (v_max - v_min);
 
-            // TODO(bschwind) - Why do we start at 1 here?
-            for i in 1..(normal_array.Length() as usize) {
-                let normal = ffi::Poly_Triangulation_Normal(triangulation, i as i32);
-                normals.push(vector![normal.X(), normal.Y(), normal.Z()]);
-            }
+        if face.orientation() != FaceOrientation::Forward {
+            uv.x = 1.0 - uv.x;
+        }
+    }
 
-            for i in 1..=triangulation.NbTriangles() {
-                let triangle = triangulation.Triangle(i);
-
-                if face.orientation() == FaceOrientation::Forward {
-                    indices.push(index_offset + triangle.Value(1) as usize - 1);
-                    indices.push(index_offset + triangle.Value(2) as usize - 1);
-                    indices.push(index_offset + triangle.Value(3) as usize - 1);
-                } else {
-                    indices.push(index_offset + triangle.Value(3) as usize - 1);
-                    indices.push(index_offset + triangle.Value(2) as usize - 1);
-                    indices.push(index_offset + triangle.Value(1) as usize - 1);
-                }
-            }
+    // Add in the normals.
+    // TODO(bschwind) - Use `location` to transform the normals.
+    let normal_array = ffi::TColgp_Array1OfDir_ctor(0, face_point_count);
+
+    ffi::compute_normals(&face.inner, &triangulation_handle);
+
+    // TODO(bschwind) - Why do we start at 1 here?
+    for i in 1..(normal_array.Length() as usize) {
+        let normal = ffi::Poly_Triangulation_Normal(triangulation, i as i32);
+        mesh.normals
+            .push(vector![normal.X(), normal.Y(), normal.Z()]);
+    }
+
+    for i in 1..=triangulation.NbTriangles() {
+        let triangle = triangulation.Triangle(i);
+
+        if face.orientation() == FaceOrientation::Forward {
+            mesh.indices
+                .push(index_offset + triangle.Value(1) as usize - 1);
+            mesh.indices
+                .push(index_offset + triangle.Value(2) as usize - 1);
+            mesh.indices
+                .push(index_offset + triangle.Value(3) as usize - 1);
+        } else {
+            mesh.indices
+                .push(index_offset + triangle.Value(3) as usize - 1);
+            mesh.indices
+                .push(index_offset + triangle.Value(2) as usize - 1);
+            mesh.indices
+                .push(index_offset + triangle.Value(1) as usize - 1);
+        }
+    }
+
+    mesh.face_ranges.push(indices_start..mesh.indices.len());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Two triangles making a square, each with vertices of its own, and no
+    /// face ranges.
+    fn split_square() -> Mesh {
+        Mesh {
+            vertices: vec![
+                point![0.0, 0.0, 0.0],
+                point![1.0, 0.0, 0.0],
+                point![1.0, 1.0, 0.0],
+                point![0.0, 0.0, 0.0],
+                point![1.0, 1.0, 0.0],
+                point![0.0, 1.0, 0.0],
+            ],
+            uvs: vec![],
+            normals: vec![],
+            indices: vec![0, 1, 2, 3, 4, 5],
+            face_ranges: vec![],
         }
+    }
 
-        Ok(Mesh {
-            vertices,
-            uvs,
-            normals,
-            indices,
-        })
+    #[test]
+    fn weld_mesh_without_face_ranges() {
+        let welded = split_square().welded(1.0e-6).unwrap();
+
+        assert_eq!(welded.vertices.len(), 4);
+        assert_eq!(welded.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert!(welded.face_ranges.is_empty());
+    }
+
+    #[test]
+    fn weld_tolerance_must_be_positive() {
+        for tolerance in [0.0, -1.0, f64::NAN] {
+            assert!(matches!(
+                split_square().welded(tolerance),
+                Err(Error::InvalidTolerance { .. })
+            ));
+        }
     }
 }
@@ -0,0 +1,365 @@
+//! Compact encodings of `Mesh`: a simple binary layout, and (with the `serde`
+//! feature) a serde representation made of flat arrays.
+//!
+//! The binary layout is, with all values little endian:
+//!
+//! | Field                                   | Type              |
+//! |-----------------------------------------|-------------------|
+//! | Magic, `b"OCMS"`                        | 4 bytes           |
+//! | Format version                          | `u32`             |
+//! | Vertex, normal, UV, index, face counts  | 5 × `u32`         |
+//! | Positions, x y z                        | 3 × `f64` each    |
+//! | Normals, x y z                          | 3 × `f64` each    |
+//! | UVs, u v                                | 2 × `f64` each    |
+//! | Indices                                 | `u32` each        |
+//! | Face ranges, start end                  | 2 × `u32` each    |
+
+use crate::mesh::Mesh;
+use crate::Error;
+use nalgebra::point;
+use nalgebra::vector;
+
+const MAGIC: &[u8; 4] = b"OCMS";
+
+/// Bumped whenever either encoding changes.
+const FORMAT_VERSION: u32 = 1;
+
+/// `Mesh`, with every array flattened to scalars.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct FlatMesh {
+    version: u32,
+    positions: Vec<f64>,
+    normals: Vec<f64>,
+    uvs: Vec<f64>,
+    indices: Vec<u32>,
+    face_ranges: Vec<u32>,
+}
+
+impl FlatMesh {
+    fn new(mesh: &Mesh) -> Result<Self, Error> {
+        let to_u32 = |value: usize| {
+            u32::try_from(value).map_err(|_| Error::MalformedMesh {
+                reason: "more than u32::MAX indices",
+            })
+        };
+
+        Ok(Self {
+            version: FORMAT_VERSION,
+            positions: mesh.vertices.iter().flat_map(|p| [p.x, p.y, p.z]).collect(),
+            normals: mesh.normals.iter().flat_map(|n| [n.x, n.y, n.z]).collect(),
+            uvs: mesh.uvs.iter().flat_map(|uv| [uv.x, uv.y]).collect(),
+            indices: mesh
+                .indices
+                .iter()
+                .map(|&i| to_u32(i))
+                .collect::<Result<_, _>>()?,
+            face_ranges: mesh
+                .face_ranges
+                .iter()
+                .flat_map(|range| [range.start, range.end])
+                .map(to_u32)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<FlatMesh> for Mesh {
+    type Error = Error;
+
+    fn try_from(flat: FlatMesh) -> Result<Self, Error> {
+        let malformed = |reason| Err(Error::MalformedMesh { reason });
+
+        if flat.version != FORMAT_VERSION {
+            return malformed("unsupported format version");
+        }
+
+        if flat.positions.len() % 3 != 0
+            || flat.normals.len() % 3 != 0
+            || flat.uvs.len() % 2 != 0
+            || flat.indices.len() % 3 != 0
+            || flat.face_ranges.len() % 2 != 0
+        {
+            return malformed("array length isn't a whole number of elements");
+        }
+
+        let vertex_count = flat.positions.len() / 3;
+
+        if !flat.normals.is_empty() && flat.normals.len() / 3 != vertex_count {
+            return malformed("normal count doesn't match vertex count");
+        }
+
+        if !flat.uvs.is_empty() && flat.uvs.len() / 2 != vertex_count {
+            return malformed("UV count doesn't match vertex count");
+        }
+
+        if flat.indices.iter().any(|&i| i as usize >= vertex_count) {
+            return malformed("index out of bounds");
+        }
+
+        let face_ranges: Vec<_> = flat
+            .face_ranges
+            .chunks_exact(2)
+            .map(|range| range[0] as usize..range[1] as usize)
+            .collect();
+
+        if face_ranges
+            .iter()
+            .any(|range| range.start > range.end || range.end > flat.indices.len())
+        {
+            return malformed("face range out of bounds");
+        }
+
+        Ok(Mesh {
+            vertices: flat
+                .positions
+                .chunks_exact(3)
+                .map(|p| point![p[0], p[1], p[2]])
+                .collect(),
+            uvs: flat
+                .uvs
+                .chunks_exact(2)
+                .map(|uv| vector![uv[0], uv[1]])
+                .collect(),
+            normals: flat
+                .normals
+                .chunks_exact(3)
+                .map(|n| vector![n[0], n[1], n[2]])
+                .collect(),
+            indices: flat.indices.into_iter().map(|i| i as usize).collect(),
+            face_ranges,
+        })
+    }
+}
+
+impl Mesh {
+    /// Encode this mesh in the binary layout described in this module.
+    ///
+    /// # Panics
+    ///
+    /// If the mesh has more than `u32::MAX` vertices or indices.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let flat = FlatMesh::new(self).expect("mesh is too large to encode");
+
+        let counts = [
+            self.vertices.len(),
+            self.normals.len(),
+            self.uvs.len(),
+            flat.indices.len(),
+            self.face_ranges.len(),
+        ];
+
+        let mut bytes = Vec::with_capacity(
+            4 + 4 * 6
+                + 8 * (flat.positions.len() + flat.normals.len() + flat.uvs.len())
+                + 4 * (flat.indices.len() + flat.face_ranges.len()),
+        );
+
+        bytes.extend(MAGIC);
+        bytes.extend(flat.version.to_le_bytes());
+
+        for count in counts {
+            let count = u32::try_from(count).expect("mesh is too large to encode");
+            bytes.extend(count.to_le_bytes());
+        }
+
+        for value in flat.positions.iter().chain(&flat.normals).chain(&flat.uvs) {
+            bytes.extend(value.to_le_bytes());
+        }
+
+        for value in flat.indices.iter().chain(&flat.face_ranges) {
+            bytes.extend(value.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Decode a mesh written by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader { bytes };
+
+        if reader.take(4)? != MAGIC {
+            return Err(Error::MalformedMesh {
+                reason: "not an encoded mesh",
+            });
+        }
+
+        let version = reader.u32()?;
+
+        let mut counts = [0usize; 5];
+        for count in &mut counts {
+            *count = reader.u32()? as usize;
+        }
+        let [vertices, normals, uvs, indices, face_ranges] = counts;
+
+        let flat = FlatMesh {
+            version,
+            positions: reader.f64s(3 * vertices)?,
+            normals: reader.f64s(3 * normals)?,
+            uvs: reader.f64s(2 * uvs)?,
+            indices: reader.u32s(indices)?,
+            face_ranges: reader.u32s(2 * face_ranges)?,
+        };
+
+        if !reader.bytes.is_empty() {
+            return Err(Error::MalformedMesh {
+                reason: "trailing bytes",
+            });
+        }
+
+        flat.try_into()
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], Error> {
+        if count > self.bytes.len() {
+            return Err(Error::MalformedMesh {
+                reason: "unexpected end of data",
+            });
+        }
+
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u32s(&mut self, count: usize) -> Result<Vec<u32>, Error> {
+        let bytes = self.take(count.saturating_mul(4))?;
+
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect())
+    }
+
+    fn f64s(&mut self, count: usize) -> Result<Vec<f64>, Error> {
+        let bytes = self.take(count.saturating_mul(8))?;
+
+        Ok(bytes
+            .chunks_exact(8)
+            .map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Mesh {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error as _;
+        use serde::Serialize as _;
+
+        FlatMesh::new(self)
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Mesh {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error as _;
+        use serde::Deserialize as _;
+
+        FlatMesh::deserialize(deserializer)?
+            .try_into()
+            .map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::Shape;
+
+    fn empty() -> Mesh {
+        Mesh {
+            vertices: vec![],
+            uvs: vec![],
+            normals: vec![],
+            indices: vec![],
+            face_ranges: vec![],
+        }
+    }
+
+    fn without_normals() -> Mesh {
+        Mesh {
+            vertices: vec![
+                point![0.0, 0.0, 0.0],
+                point![1.0, 0.0, 0.0],
+                point![0.0, 1.0, 0.1],
+            ],
+            uvs: vec![vector![0.0, 0.0], vector![1.0, 0.0], vector![0.0, 1.0]],
+            normals: vec![],
+            indices: vec![0, 1, 2],
+            face_ranges: vec![0..3],
+        }
+    }
+
+    fn meshed_box() -> Mesh {
+        Shape::box_with_dimensions(1.0, 2.0, 3.0).mesh().unwrap()
+    }
+
+    fn assert_same(a: &Mesh, b: &Mesh) {
+        assert_eq!(a.vertices, b.vertices);
+        assert_eq!(a.uvs, b.uvs);
+        assert_eq!(a.normals, b.normals);
+        assert_eq!(a.indices, b.indices);
+        assert_eq!(a.face_ranges, b.face_ranges);
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        for mesh in [empty(), without_normals(), meshed_box()] {
+            let decoded = Mesh::from_bytes(&mesh.to_bytes()).unwrap();
+            assert_same(&mesh, &decoded);
+        }
+    }
+
+    #[test]
+    fn binary_rejects_bad_data() {
+        let bytes = without_normals().to_bytes();
+
+        assert!(Mesh::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Mesh::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+        assert!(Mesh::from_bytes(b"nope").is_err());
+
+        // An index past the last vertex.
+        let mut bad_index = bytes.clone();
+        let index_offset = bytes.len() - 4 * (3 + 2);
+        bad_index[index_offset..index_offset + 4].copy_from_slice(&3u32.to_le_bytes());
+        assert!(Mesh::from_bytes(&bad_index).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_json_round_trip() {
+        for mesh in [empty(), without_normals(), meshed_box()] {
+            let json = serde_json::to_string(&mesh).unwrap();
+            let decoded: Mesh = serde_json::from_str(&json).unwrap();
+            assert_same(&mesh, &decoded);
+        }
+
+        let json = serde_json::to_value(without_normals()).unwrap();
+        assert_eq!(json["version"], FORMAT_VERSION);
+        assert_eq!(json["positions"].as_array().unwrap().len(), 9);
+        assert_eq!(json["normals"].as_array().unwrap().len(), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_other_versions() {
+        let mut json = serde_json::to_value(without_normals()).unwrap();
+        json["version"] = (FORMAT_VERSION + 1).into();
+
+        assert!(serde_json::from_value::<Mesh>(json).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+use crate::mesh::append_triangulated_face;
+use crate::mesh::Mesh;
+use crate::mesh::MeshOptions;
+use crate::primitives::Compound;
+use crate::primitives::Face;
+use crate::primitives::History;
+use crate::primitives::Shape;
+use crate::Error;
+use opencascade_sys::ffi;
+use std::collections::HashMap;
+
+impl Shape {
+    /// Mesh this shape, the result of an operation which reported
+    /// `history`, reusing the triangles of `previous`, a mesh of the shape
+    /// the operation was performed on, for the faces the operation left
+    /// unchanged. Only the new and modified faces are triangulated.
+    ///
+    /// The unchanged faces next to them are triangulated along with them,
+    /// though their triangles are still taken from `previous`, so the edges
+    /// between reused and new faces are divided as a full re-mesh would
+    /// divide them, and the faces are welded together along them.
+    ///
+    /// `previous` must have been made with the same deflection, and have a
+    /// face range for each face of the original shape, as meshes made with
+    /// `mesh_with_options` do. Deflection overrides aren't applied.
+    pub fn mesh_incremental(
+        &self,
+        previous: &Mesh,
+        history: &History,
+        options: &MeshOptions,
+    ) -> Result<Mesh, Error> {
+        let mut original_faces = ffi::new_indexed_map_of_shape();
+        ffi::map_shapes(
+            &history.original,
+            ffi::TopAbs_ShapeEnum::TopAbs_FACE,
+            original_faces.pin_mut(),
+        );
+
+        let face_count = original_faces.Extent() as usize;
+        if face_count != previous.face_ranges.len() {
+            return Err(Error::MeshDoesNotMatch {
+                faces: face_count,
+                face_ranges: previous.face_ranges.len(),
+            });
+        }
+
+        // For each face, the index of the face of the original it came
+        // through the operation unchanged as, if it did.
+        let faces: Vec<Face> = self.faces().collect();
+        let reused: Vec<Option<usize>> = faces
+            .iter()
+            .map(|face| {
+                let index = original_faces.FindIndex(ffi::cast_face_to_shape(&face.inner));
+                let unchanged = index > 0 && history.is_unchanged(original_faces.FindKey(index));
+
+                unchanged.then(|| index as usize - 1)
+            })
+            .collect();
+
+        // Meshing stores triangulations on the faces it meshes, so mesh a
+        // copy, as `Mesher` does. Its faces are in the same order.
+        let copy_geometry = false;
+        let copy_mesh = false;
+        let mut copier = ffi::BRepBuilderAPI_Copy_new(&self.inner, copy_geometry, copy_mesh);
+        let copy = Shape::from_shape(copier.pin_mut().Shape());
+        let copied_faces: Vec<Face> = copy.faces().collect();
+
+        let to_mesh = faces_and_neighbours(&copy, &copied_faces, |i| reused[i].is_none());
+        let group: Vec<_> = copied_faces
+            .iter()
+            .zip(&to_mesh)
+            .filter(|&(_, &mesh)| mesh)
+            .map(|(face, _)| Shape::from(face))
+            .collect();
+
+        if !group.is_empty() {
+            let group = Shape::from(Compound::from_shapes(&group));
+            let mesher = ffi::BRepMesh_IncrementalMesh_ctor(&group.inner, options.deflection);
+
+            if !mesher.IsDone() {
+                return Err(Error::TriangulationFailed);
+            }
+        }
+
+        let mut mesh = Mesh {
+            vertices: vec![],
+            uvs: vec![],
+            normals: vec![],
+            indices: vec![],
+            face_ranges: vec![],
+        };
+
+        for (i, face) in copied_faces.iter().enumerate() {
+            match reused[i] {
+                Some(original) => {
+                    let original_face = original_faces.FindKey(original as i32 + 1);
+                    let flipped = original_face.Orientation()
+                        != ffi::cast_face_to_shape(&faces[i].inner).Orientation();
+
+                    append_patch(&mut mesh, previous, original, flipped);
+                }
+                None => append_triangulated_face(&mut mesh, face)?,
+            }
+        }
+
+        Ok(match options.weld_tolerance {
+            Some(tolerance) => mesh.welded(tolerance)?,
+            None => mesh,
+        })
+    }
+}
+
+/// Which of `faces`, the faces of `shape`, are `selected` or share an edge
+/// with a face which is.
+fn faces_and_neighbours(
+    shape: &Shape,
+    faces: &[Face],
+    selected: impl Fn(usize) -> bool,
+) -> Vec<bool> {
+    let mut face_indices = ffi::new_indexed_map_of_shape();
+    ffi::map_shapes(
+        &shape.inner,
+        ffi::TopAbs_ShapeEnum::TopAbs_FACE,
+        face_indices.pin_mut(),
+    );
+
+    let mut edge_faces = ffi::new_indexed_data_map_of_shape_list_of_shape();
+    ffi::map_shapes_and_ancestors(
+        &shape.inner,
+        ffi::TopAbs_ShapeEnum::TopAbs_EDGE,
+        ffi::TopAbs_ShapeEnum::TopAbs_FACE,
+        edge_faces.pin_mut(),
+    );
+
+    let mut marked = vec![false; faces.len()];
+
+    for (i, face) in faces.iter().enumerate() {
+        if !selected(i) {
+            continue;
+        }
+
+        marked[i] = true;
+
+        for edge in face.edges() {
+            let edge = ffi::cast_edge_to_shape(&edge.inner);
+            let neighbours = ffi::shape_list_to_vector(edge_faces.FindFromKey(edge));
+
+            for neighbour in neighbours.iter() {
+                let index = face_indices.FindIndex(neighbour);
+                if index > 0 {
+                    marked[index as usize - 1] = true;
+                }
+            }
+        }
+    }
+
+    marked
+}
+
+/// Add the triangles of face `face_index` of `previous` to the end of
+/// `mesh`, as a new face range, turning them over if `flipped`.
+fn append_patch(mesh: &mut Mesh, previous: &Mesh, face_index: usize, flipped: bool) {
+    let start = mesh.indices.len();
+    let mut remap = HashMap::new();
+
+    for triangle in previous.indices[previous.face_ranges[face_index].clone()].chunks_exact(3) {
+        let mut corners = [triangle[0], triangle[1], triangle[2]].map(|index| {
+            *remap.entry(index).or_insert_with(|| {
+                mesh.vertices.push(previous.vertices[index]);
+                mesh.uvs.extend(previous.uvs.get(index));
+                mesh.normals.extend(previous.normals.get(index));
+                mesh.vertices.len() - 1
+            })
+        });
+
+        if flipped {
+            corners.swap(0, 2);
+        }
+
+        mesh.indices.extend(corners);
+    }
+
+    mesh.face_ranges.push(start..mesh.indices.len());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nalgebra::point;
+    use nalgebra::Point3;
+    use nalgebra::Vector3;
+
+    /// The furthest any vertex of `a` is from the nearest vertex of `b`.
+    fn furthest_vertex(a: &Mesh, b: &Mesh) -> f64 {
+        let nearest = |point: &Point3<f64>| {
+            b.vertices
+                .iter()
+                .map(|other| (other - point).norm())
+                .fold(f64::INFINITY, f64::min)
+        };
+
+        a.vertices.iter().map(nearest).fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn small_cut_matches_full_remesh() {
+        let tools: Vec<_> = (0..6)
+            .map(|i| {
+                Shape::cylinder(
+                    point![10.0 + i as f64 * 15.0, 20.0, -1.0],
+                    3.0,
+                    Vector3::z(),
+                    12.0,
+                )
+            })
+            .collect();
+        let part = Shape::box_with_dimensions(100.0, 40.0, 10.0)
+            .subtract(&Compound::from_shapes(tools).into())
+            .unwrap();
+
+        let options = MeshOptions::default();
+        let previous = part.mesh_with_options(&options).unwrap();
+
+        // A small notch in one corner, which only touches three faces.
+        let notch = Shape::box_from_corners(point![95.0, 35.0, 8.0], point![101.0, 41.0, 11.0]);
+        let (notched, history) = part.subtract_with_history(&notch).unwrap();
+
+        let incremental = notched
+            .mesh_incremental(&previous, &history, &options)
+            .unwrap();
+        let full = notched.mesh_with_options(&options).unwrap();
+
+        assert_eq!(incremental.face_ranges.len(), full.face_ranges.len());
+        assert_eq!(incremental.vertices.len(), full.vertices.len());
+        assert_eq!(incremental.indices.len(), full.indices.len());
+
+        let tolerance = options.weld_tolerance.unwrap();
+        assert!(furthest_vertex(&incremental, &full) <= tolerance);
+        assert!(furthest_vertex(&full, &incremental) <= tolerance);
+    }
+
+    #[test]
+    fn mismatched_previous_mesh() {
+        let block = Shape::box_with_dimensions(10.0, 10.0, 10.0);
+        let notch = Shape::box_from_corners(point![8.0, 8.0, 8.0], point![11.0, 11.0, 11.0]);
+        let (notched, history) = block.subtract_with_history(&notch).unwrap();
+
+        let options = MeshOptions::default();
+        let unrelated = Shape::sphere(5.0)
+            .build()
+            .mesh_with_options(&options)
+            .unwrap();
+
+        assert!(matches!(
+            notched.mesh_incremental(&unrelated, &history, &options),
+            Err(Error::MeshDoesNotMatch { faces: 6, .. })
+        ));
+    }
+}
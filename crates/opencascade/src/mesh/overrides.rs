@@ -0,0 +1,159 @@
+use crate::mesh::mesh_triangulated;
+use crate::mesh::Mesh;
+use crate::mesh::MeshOptions;
+use crate::primitives::Compound;
+use crate::primitives::Face;
+use crate::primitives::FaceType;
+use crate::primitives::Shape;
+use crate::Error;
+use opencascade_sys::ffi;
+
+/// Which faces of a shape a deflection in `MeshOptions::overrides` is for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FaceSelector {
+    /// The faces carrying this tag, see `Shape::tag_face`.
+    Tagged(String),
+    /// The faces on this type of surface.
+    Type(FaceType),
+    /// The faces with at least this area.
+    AreaAtLeast(f64),
+    /// The faces with less than this area.
+    AreaBelow(f64),
+}
+
+impl FaceSelector {
+    fn matches(&self, shape: &Shape, face: &Face) -> bool {
+        match self {
+            FaceSelector::Tagged(name) => {
+                let face = ffi::cast_face_to_shape(&face.inner);
+
+                shape
+                    .find_tagged(name)
+                    .iter()
+                    .any(|tagged| ffi::cast_face_to_shape(&tagged.inner).IsSame(face))
+            }
+            FaceSelector::Type(face_type) => face.face_type() == *face_type,
+            FaceSelector::AreaAtLeast(area) => face.surface_area() >= *area,
+            FaceSelector::AreaBelow(area) => face.surface_area() < *area,
+        }
+    }
+}
+
+impl Shape {
+    /// Mesh this shape with the deflections `options` gives each face.
+    ///
+    /// The faces are meshed in groups, finest deflection first, on one copy
+    /// of the topology. When BRepMesh divides an edge which a face meshed
+    /// earlier already divided finely enough, it reuses those nodes, so each
+    /// edge is divided once, as its finest face needs, and faces meet along
+    /// it node for node.
+    pub(crate) fn mesh_with_overrides(&self, options: &MeshOptions) -> Result<Mesh, Error> {
+        let deflections: Vec<f64> = self
+            .faces()
+            .map(|face| {
+                options
+                    .overrides
+                    .iter()
+                    .find(|(selector, _)| selector.matches(self, &face))
+                    .map_or(options.deflection, |&(_, deflection)| deflection)
+            })
+            .collect();
+
+        // Meshing stores triangulations on the faces it meshes, so mesh a
+        // copy, as `Mesher` does. Its faces are in the same order.
+        let copy_geometry = false;
+        let copy_mesh = false;
+        let mut copier = ffi::BRepBuilderAPI_Copy_new(&self.inner, copy_geometry, copy_mesh);
+        let copy = Shape::from_shape(copier.pin_mut().Shape());
+        let faces: Vec<_> = copy.faces().collect();
+
+        let mut levels = deflections.clone();
+        levels.sort_by(f64::total_cmp);
+        levels.dedup();
+
+        for level in levels {
+            let group: Vec<_> = faces
+                .iter()
+                .zip(&deflections)
+                .filter(|&(_, &deflection)| deflection == level)
+                .map(|(face, _)| Shape::from(face))
+                .collect();
+            let group = Shape::from(Compound::from_shapes(&group));
+
+            let mesher = ffi::BRepMesh_IncrementalMesh_ctor(&group.inner, level);
+            if !mesher.IsDone() {
+                return Err(Error::TriangulationFailed);
+            }
+        }
+
+        mesh_triangulated(&copy)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn triangle_count(mesh: &Mesh, face_index: usize) -> usize {
+        mesh.face_ranges[face_index].len() / 3
+    }
+
+    /// Whether every edge of the mesh's triangles is shared by exactly two
+    /// of them.
+    fn is_watertight(mesh: &Mesh) -> bool {
+        let mut uses: HashMap<(usize, usize), usize> = HashMap::new();
+
+        for triangle in mesh.indices.chunks_exact(3) {
+            for (a, b) in [(0, 1), (1, 2), (2, 0)] {
+                let (a, b) = (triangle[a], triangle[b]);
+                *uses.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+
+        uses.values().all(|&count| count == 2)
+    }
+
+    #[test]
+    fn overrides_refine_selected_faces() {
+        let mut cylinder = Shape::cylinder_radius_height(5.0, 10.0);
+        let faces: Vec<_> = cylinder.faces().collect();
+        let side = faces
+            .iter()
+            .position(|face| face.face_type() == FaceType::Cylinder)
+            .unwrap();
+        let top = faces
+            .iter()
+            .position(|face| face.face_type() == FaceType::Plane && face.center_of_mass().z > 5.0)
+            .unwrap();
+        cylinder.tag_face(&faces[top], "top");
+
+        let coarse_options = MeshOptions {
+            deflection: 0.5,
+            ..Default::default()
+        };
+        let coarse = cylinder.mesh_with_options(&coarse_options).unwrap();
+        assert!(is_watertight(&coarse));
+
+        let fine_side = cylinder
+            .mesh_with_options(&MeshOptions {
+                overrides: vec![(FaceSelector::Type(FaceType::Cylinder), 0.001)],
+                ..coarse_options.clone()
+            })
+            .unwrap();
+        assert!(triangle_count(&fine_side, side) > 10 * triangle_count(&coarse, side));
+        assert!(is_watertight(&fine_side));
+
+        // Only the top is fine, but the side shares its edge with it, so
+        // takes the top's nodes along that edge.
+        let fine_top = cylinder
+            .mesh_with_options(&MeshOptions {
+                overrides: vec![(FaceSelector::Tagged("top".to_string()), 0.001)],
+                ..coarse_options
+            })
+            .unwrap();
+        assert!(triangle_count(&fine_top, top) > 10 * triangle_count(&coarse, top));
+        assert!(triangle_count(&fine_top, side) < triangle_count(&fine_side, side));
+        assert!(is_watertight(&fine_top));
+    }
+}
@@ -0,0 +1,237 @@
+use crate::mesh::Mesh;
+use crate::Error;
+use crate::TandR;
+use nalgebra::Point3;
+
+/// A `Mesh` with single precision positions and normals, as GPU pipelines
+/// want them.
+#[derive(Debug, Clone)]
+pub struct MeshF32 {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub indices: Vec<u32>,
+    /// The transform from `positions` back to the coordinates of the
+    /// original mesh. Apply it in double precision (e.g. by rendering
+    /// relative to the camera), or the precision gained by re-centering is
+    /// lost again.
+    pub offset: TandR<f64>,
+}
+
+/// What to do with coordinates too large to represent as an `f32`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum OutOfRange {
+    /// Fail the conversion with `Error::OutOfF32Range`.
+    #[default]
+    Error,
+    /// Clamp to the largest finite `f32` of the same sign.
+    Clamp,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct F32Options {
+    /// Move the center of the mesh's bounding box to the origin before
+    /// converting. `f32` has about 7 significant digits, so a part 10 km
+    /// from the origin would otherwise be rounded to the nearest mm or so.
+    pub recenter: bool,
+    pub out_of_range: OutOfRange,
+}
+
+impl Mesh {
+    /// Convert the positions and normals of this mesh to `f32`.
+    pub fn as_f32(&self, options: F32Options) -> Result<MeshF32, Error> {
+        let center = if options.recenter {
+            bounding_box_center(&self.vertices)
+        } else {
+            Point3::origin()
+        };
+
+        let to_f32 = |value: f64| -> Result<f32, Error> {
+            if value.abs() <= f32::MAX as f64 || value.is_nan() {
+                Ok(value as f32)
+            } else {
+                match options.out_of_range {
+                    OutOfRange::Error => Err(Error::OutOfF32Range { value }),
+                    OutOfRange::Clamp => Ok(f32::MAX.copysign(value as f32)),
+                }
+            }
+        };
+
+        let positions = self
+            .vertices
+            .iter()
+            .map(|vertex| {
+                // Subtract in double precision, so only the small remainder
+                // is rounded.
+                let local = vertex - center;
+                Ok([to_f32(local.x)?, to_f32(local.y)?, to_f32(local.z)?])
+            })
+            .collect::<Result<_, Error>>()?;
+
+        let normals = self
+            .normals
+            .iter()
+            .map(|normal| [normal.x as f32, normal.y as f32, normal.z as f32])
+            .collect();
+
+        let indices = self
+            .indices
+            .iter()
+            .map(|&index| {
+                u32::try_from(index).map_err(|_| Error::MalformedMesh {
+                    reason: "more than u32::MAX vertices",
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(MeshF32 {
+            positions,
+            normals,
+            indices,
+            offset: TandR::default().translation(center.coords),
+        })
+    }
+}
+
+/// The center of the axis-aligned bounding box of `points`, or the origin
+/// if there are none.
+fn bounding_box_center(points: &[Point3<f64>]) -> Point3<f64> {
+    let Some(first) = points.first() else {
+        return Point3::origin();
+    };
+
+    let (min, max) = points.iter().fold((*first, *first), |(min, max), point| {
+        (min.inf(point), max.sup(point))
+    });
+
+    // Halve before adding, so huge coordinates can't overflow.
+    Point3::from(min.coords / 2.0 + max.coords / 2.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nalgebra::point;
+    use nalgebra::Vector3;
+
+    /// A 1 mm triangle, `distance` from the origin along every axis.
+    fn far_triangle(distance: f64) -> Mesh {
+        let base = point![distance, distance, distance];
+
+        Mesh {
+            vertices: vec![
+                base,
+                base + Vector3::new(1.0e-3, 0.0, 0.0),
+                base + Vector3::new(0.0, 1.0e-3, 0.0),
+            ],
+            uvs: vec![],
+            normals: vec![Vector3::z(); 3],
+            indices: vec![0, 1, 2],
+            face_ranges: vec![0..3],
+        }
+    }
+
+    fn edge_lengths(positions: &[[f32; 3]]) -> [f64; 2] {
+        let to_f64 = |p: [f32; 3]| Vector3::new(p[0] as f64, p[1] as f64, p[2] as f64);
+        let (a, b, c) = (
+            to_f64(positions[0]),
+            to_f64(positions[1]),
+            to_f64(positions[2]),
+        );
+
+        [(b - a).norm(), (c - a).norm()]
+    }
+
+    #[test]
+    fn recentering_preserves_small_features_far_from_origin() {
+        let mesh = far_triangle(1.0e7);
+
+        // Without re-centering, f32 can't tell the vertices apart at 10 km.
+        let plain = mesh.as_f32(F32Options::default()).unwrap();
+        assert_eq!(edge_lengths(&plain.positions), [0.0, 0.0]);
+        assert_eq!(plain.offset, TandR::default());
+
+        let recentered = mesh
+            .as_f32(F32Options {
+                recenter: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        for length in edge_lengths(&recentered.positions) {
+            assert!((length - 1.0e-3).abs() < 1.0e-9, "{length}");
+        }
+
+        // Centered on the bounding box.
+        let max_coordinate = recentered
+            .positions
+            .iter()
+            .flatten()
+            .fold(0.0f32, |max, value| max.max(value.abs()));
+        assert!(max_coordinate <= 0.5e-3 + 1.0e-9);
+
+        // The offset takes the positions back to where they were.
+        for (position, original) in recentered.positions.iter().zip(&mesh.vertices) {
+            let local = point![position[0] as f64, position[1] as f64, position[2] as f64];
+            let restored = recentered.offset.transform_point(local);
+            assert!((restored - original).norm() < 1.0e-9);
+        }
+
+        // Normals are directions, and aren't moved.
+        assert_eq!(recentered.normals, vec![[0.0, 0.0, 1.0]; 3]);
+        assert_eq!(recentered.indices, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn recentering_handles_empty_and_single_point_meshes() {
+        let mut mesh = far_triangle(0.0);
+        mesh.vertices.clear();
+        mesh.normals.clear();
+        mesh.indices.clear();
+
+        let options = F32Options {
+            recenter: true,
+            ..Default::default()
+        };
+        assert_eq!(mesh.as_f32(options).unwrap().offset, TandR::default());
+
+        mesh.vertices.push(point![-3.0, 4.0, 1.0e20]);
+        let single = mesh.as_f32(options).unwrap();
+        assert_eq!(single.positions, vec![[0.0, 0.0, 0.0]]);
+        assert_eq!(single.offset.translation, Vector3::new(-3.0, 4.0, 1.0e20));
+    }
+
+    #[test]
+    fn out_of_range_policy() {
+        let mesh = far_triangle(1.0e39);
+
+        assert!(matches!(
+            mesh.as_f32(F32Options::default()),
+            Err(Error::OutOfF32Range { .. })
+        ));
+
+        let clamped = mesh
+            .as_f32(F32Options {
+                out_of_range: OutOfRange::Clamp,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(clamped
+            .positions
+            .iter()
+            .flatten()
+            .all(|&value| value == f32::MAX));
+
+        // Re-centering brings the same mesh back into range.
+        let recentered = mesh
+            .as_f32(F32Options {
+                recenter: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(recentered
+            .positions
+            .iter()
+            .flatten()
+            .all(|value| value.is_finite()));
+    }
+}
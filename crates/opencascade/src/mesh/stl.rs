@@ -0,0 +1,174 @@
+use crate::mesh::F32Options;
+use crate::mesh::Mesh;
+use crate::Error;
+use crate::TandR;
+use nalgebra::Point3;
+use nalgebra::Vector3;
+use stl_io::IndexedMesh;
+use stl_io::IndexedTriangle;
+use stl_io::Vector;
+
+impl Mesh {
+    /// Convert to an `stl_io` mesh, e.g. to write it with
+    /// `stl_io::write_stl`, along with the transform from its coordinates
+    /// back to this mesh's. STL stores `f32` coordinates, so positions are
+    /// converted as `as_f32` converts them, following `options`. Face
+    /// normals are computed from the winding of each triangle.
+    pub fn to_indexed_mesh(&self, options: F32Options) -> Result<(IndexedMesh, TandR<f64>), Error> {
+        let single = self.as_f32(options)?;
+        let vertices = single.positions.into_iter().map(Vector::new).collect();
+
+        let faces = self
+            .indices
+            .chunks_exact(3)
+            .map(|triangle| {
+                let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+                let normal = (self.vertices[b] - self.vertices[a])
+                    .cross(&(self.vertices[c] - self.vertices[a]))
+                    .try_normalize(0.0)
+                    .unwrap_or_else(Vector3::zeros);
+
+                IndexedTriangle {
+                    normal: Vector::new([normal.x as f32, normal.y as f32, normal.z as f32]),
+                    vertices: [a, b, c],
+                }
+            })
+            .collect();
+
+        Ok((IndexedMesh { vertices, faces }, single.offset))
+    }
+
+    /// Convert from an `stl_io` mesh, e.g. one read with `stl_io::read_stl`.
+    /// STL only has face normals, so the mesh has no (per vertex) normals,
+    /// UVs or face ranges.
+    pub fn from_indexed_mesh(mesh: &IndexedMesh) -> Result<Mesh, Error> {
+        let vertices: Vec<_> = mesh
+            .vertices
+            .iter()
+            .map(|vertex| Point3::new(vertex[0] as f64, vertex[1] as f64, vertex[2] as f64))
+            .collect();
+
+        let indices: Vec<_> = mesh.faces.iter().flat_map(|face| face.vertices).collect();
+
+        if indices.iter().any(|&index| index >= vertices.len()) {
+            return Err(Error::MalformedMesh {
+                reason: "index out of range",
+            });
+        }
+
+        Ok(Mesh {
+            vertices,
+            uvs: vec![],
+            normals: vec![],
+            indices,
+            face_ranges: vec![],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mesh::OutOfRange;
+    use crate::primitives::Shape;
+
+    #[test]
+    fn indexed_mesh_round_trip() {
+        let mesh = Shape::box_with_dimensions(1.0, 2.0, 3.0)
+            .mesh()
+            .unwrap()
+            .welded(1.0e-6)
+            .unwrap();
+        let (indexed, offset) = mesh.to_indexed_mesh(F32Options::default()).unwrap();
+        assert_eq!(offset, TandR::default());
+
+        assert_eq!(indexed.vertices.len(), 8);
+        assert_eq!(indexed.faces.len(), 12);
+
+        // Every triangle of a box is axis aligned, so normals are exact, and
+        // point out of the box.
+        let center = Vector3::new(0.5, 1.0, 1.5);
+        for face in &indexed.faces {
+            let normal = Vector3::new(face.normal[0], face.normal[1], face.normal[2]);
+            assert_eq!(normal.abs().sum(), 1.0);
+
+            let corner = mesh.vertices[face.vertices[0]].coords;
+            assert!(normal.cast::<f64>().dot(&(corner - center)) > 0.0);
+        }
+
+        // The box coordinates are exact in f32, so nothing is lost.
+        let restored = Mesh::from_indexed_mesh(&indexed).unwrap();
+        assert_eq!(restored.vertices, mesh.vertices);
+        assert_eq!(restored.indices, mesh.indices);
+        assert!(restored.normals.is_empty());
+
+        // And it survives a trip through an actual STL file.
+        let mut stl = vec![];
+        let triangles = indexed.faces.iter().map(|face| stl_io::Triangle {
+            normal: face.normal,
+            vertices: face.vertices.map(|index| indexed.vertices[index]),
+        });
+        stl_io::write_stl(&mut stl, triangles).unwrap();
+
+        let read = stl_io::read_stl(&mut std::io::Cursor::new(stl)).unwrap();
+        let reread = Mesh::from_indexed_mesh(&read).unwrap();
+        assert_eq!(reread.vertices.len(), 8);
+        assert_eq!(reread.indices.len(), mesh.indices.len());
+    }
+
+    #[test]
+    fn indexed_mesh_out_of_range() {
+        let mesh = Mesh {
+            vertices: vec![
+                Point3::new(1.0e39, 0.0, 0.0),
+                Point3::new(1.0e39, 1.0, 0.0),
+                Point3::new(1.0e39, 0.0, 1.0),
+            ],
+            uvs: vec![],
+            normals: vec![],
+            indices: vec![0, 1, 2],
+            face_ranges: vec![0..3],
+        };
+
+        assert!(matches!(
+            mesh.to_indexed_mesh(F32Options::default()),
+            Err(Error::OutOfF32Range { .. })
+        ));
+
+        let (clamped, _) = mesh
+            .to_indexed_mesh(F32Options {
+                out_of_range: OutOfRange::Clamp,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(clamped.vertices.iter().all(|vertex| vertex[0] == f32::MAX));
+
+        // Re-centered, the triangle keeps its shape, and the offset puts it
+        // back where it was.
+        let (recentered, offset) = mesh
+            .to_indexed_mesh(F32Options {
+                recenter: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(recentered.vertices[1][1] - recentered.vertices[0][1], 1.0);
+        assert_eq!(offset.translation.x, 1.0e39);
+        assert_eq!(recentered.faces[0].normal[0], 1.0);
+    }
+
+    #[test]
+    fn out_of_range_indices_are_rejected() {
+        let mesh = IndexedMesh {
+            vertices: vec![Vector::new([0.0; 3]); 2],
+            faces: vec![IndexedTriangle {
+                normal: Vector::new([0.0, 0.0, 1.0]),
+                vertices: [0, 1, 2],
+            }],
+        };
+
+        assert!(matches!(
+            Mesh::from_indexed_mesh(&mesh),
+            Err(Error::MalformedMesh { .. })
+        ));
+    }
+}
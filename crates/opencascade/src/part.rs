@@ -0,0 +1,454 @@
+//! A fluent, chainable API for building up a solid one feature at a time,
+//! in the style of CadQuery.
+//!
+//! ```no_run
+//! use opencascade::part::Part;
+//! use opencascade::workplane::Workplane;
+//!
+//! let shape = Part::on(Workplane::xy())
+//!     .rect(30.0, 20.0)
+//!     .extrude(5.0)
+//!     .faces_top()
+//!     .workplane()
+//!     .circle(0.0, 0.0, 3.0)
+//!     .cut_through_all()
+//!     .edges_vertical()
+//!     .fillet(1.0)
+//!     .into_shape()
+//!     .unwrap();
+//! ```
+//!
+//! Every stage carries the current solid, the current selection, the active
+//! workplane, and any pending 2D wires drawn on that workplane. The first
+//! error encountered is kept and returned from `into_shape`, and all the
+//! following operations are skipped.
+
+use crate::bounding_box;
+use crate::primitives::Direction;
+use crate::primitives::Edge;
+use crate::primitives::EdgeType;
+use crate::primitives::Face;
+use crate::primitives::Shape;
+use crate::primitives::Wire;
+use crate::workplane::Workplane;
+use crate::Error;
+use nalgebra::point;
+use nalgebra::Vector3;
+use opencascade_sys::ffi;
+
+/// Sub-shapes of the current solid which the next operation applies to.
+pub enum Selection {
+    None,
+    Faces(Vec<Face>),
+    Edges(Vec<Edge>),
+}
+
+struct PartState {
+    solid: Option<Shape>,
+    selection: Selection,
+    workplane: Workplane,
+    pending: Vec<Wire>,
+}
+
+pub struct Part {
+    state: Result<PartState, Error>,
+}
+
+impl Part {
+    /// Start an empty part, with `workplane` as the active workplane.
+    pub fn on(workplane: Workplane) -> Self {
+        Self {
+            state: Ok(PartState {
+                solid: None,
+                selection: Selection::None,
+                workplane,
+                pending: vec![],
+            }),
+        }
+    }
+
+    /// Start a part from an existing shape.
+    pub fn from_shape(shape: Shape, workplane: Workplane) -> Self {
+        let mut part = Self::on(workplane);
+
+        if let Ok(state) = &mut part.state {
+            state.solid = Some(shape);
+        }
+
+        part
+    }
+
+    fn map(self, f: impl FnOnce(PartState) -> Result<PartState, Error>) -> Self {
+        Self {
+            state: self.state.and_then(f),
+        }
+    }
+
+    /// Returns the error which stopped the chain, if any.
+    pub fn error(&self) -> Option<&Error> {
+        self.state.as_ref().err()
+    }
+
+    pub fn active_workplane(&self) -> Option<&Workplane> {
+        self.state.as_ref().ok().map(|state| &state.workplane)
+    }
+
+    pub fn selection(&self) -> Option<&Selection> {
+        self.state.as_ref().ok().map(|state| &state.selection)
+    }
+
+    /// Finish the chain, returning the current solid.
+    pub fn into_shape(self) -> Result<Shape, Error> {
+        self.state?.solid.ok_or(Error::InvalidSelection)
+    }
+
+    // Sketching on the active workplane.
+
+    /// Add a `width` x `height` rectangle centered on the workplane origin.
+    pub fn rect(self, width: f64, height: f64) -> Self {
+        self.map(|mut state| {
+            state.pending.push(state.workplane.rect(width, height)?);
+            Ok(state)
+        })
+    }
+
+    /// Add a circle centered at (`x`, `y`) on the workplane.
+    pub fn circle(self, x: f64, y: f64, radius: f64) -> Self {
+        self.map(|mut state| {
+            state.pending.push(state.workplane.circle(x, y, radius)?);
+            Ok(state)
+        })
+    }
+
+    /// Add an arbitrary closed wire, in world coordinates.
+    pub fn wire(self, wire: &Wire) -> Self {
+        let wire = Wire::from_wire(&wire.inner);
+
+        self.map(|mut state| {
+            state.pending.push(wire);
+            Ok(state)
+        })
+    }
+
+    // Features. Each one consumes the pending wires.
+
+    /// Extrude the pending wires `distance` along the workplane normal, and
+    /// union the result with the current solid.
+    pub fn extrude(self, distance: f64) -> Self {
+        self.map(|mut state| {
+            let offset = state.workplane.normal().into_inner() * distance;
+            let tool = state.take_pending_prism(offset, 0.0)?;
+
+            state.solid = Some(match state.solid.take() {
+                Some(solid) => solid.union(&tool).into(),
+                None => tool,
+            });
+            state.selection = Selection::None;
+
+            Ok(state)
+        })
+    }
+
+    /// Cut the pending wires `depth` into the current solid, against the
+    /// workplane normal.
+    pub fn cut_blind(self, depth: f64) -> Self {
+        self.map(|mut state| {
+            let offset = -state.workplane.normal().into_inner() * depth;
+            let tool = state.take_pending_prism(offset, 0.0)?;
+
+            state.cut(&tool)
+        })
+    }
+
+    /// Cut the pending wires through the entire current solid, in both
+    /// directions along the workplane normal. The depth is inferred from
+    /// the bounding box of the solid.
+    pub fn cut_through_all(self) -> Self {
+        self.map(|mut state| {
+            let solid = state.solid.as_ref().ok_or(Error::InvalidSelection)?;
+            let normal = state.workplane.normal().into_inner();
+            let origin = state.workplane.origin();
+
+            let bounds = bounding_box::aabb(solid);
+            let (min, max) = (bounds.min(), bounds.max());
+
+            let (near, far) = [
+                point![min.x, min.y, min.z],
+                point![max.x, min.y, min.z],
+                point![min.x, max.y, min.z],
+                point![max.x, max.y, min.z],
+                point![min.x, min.y, max.z],
+                point![max.x, min.y, max.z],
+                point![min.x, max.y, max.z],
+                point![max.x, max.y, max.z],
+            ]
+            .iter()
+            .map(|corner| (corner.coords - origin).dot(&normal))
+            .fold((f64::MAX, f64::MIN), |(near, far), d| {
+                (near.min(d), far.max(d))
+            });
+
+            // Overshoot a little on both sides so no sliver faces are left.
+            let margin = 1.0 + (far - near) * 0.01;
+            let (start, end) = (near - margin, far + margin);
+
+            let tool = state.take_pending_prism(normal * (end - start), start)?;
+
+            state.cut(&tool)
+        })
+    }
+
+    // Selections.
+
+    /// Select all faces of the current solid.
+    pub fn faces(self) -> Self {
+        self.map(|mut state| {
+            let solid = state.solid.as_ref().ok_or(Error::InvalidSelection)?;
+            state.selection = Selection::Faces(solid.faces().collect());
+
+            Ok(state)
+        })
+    }
+
+    /// Select the face of the current solid which is farthest along
+    /// `direction`, measured from its center of mass.
+    pub fn faces_farthest(self, direction: Direction) -> Self {
+        self.map(|mut state| {
+            let solid = state.solid.as_ref().ok_or(Error::InvalidSelection)?;
+            let face = solid
+                .faces()
+                .try_farthest(direction)
+                .ok_or(Error::InvalidSelection)?;
+            state.selection = Selection::Faces(vec![face]);
+
+            Ok(state)
+        })
+    }
+
+    /// Select the face farthest along +Z.
+    pub fn faces_top(self) -> Self {
+        self.faces_farthest(Direction::PosZ)
+    }
+
+    /// Select the face farthest along -Z.
+    pub fn faces_bottom(self) -> Self {
+        self.faces_farthest(Direction::NegZ)
+    }
+
+    /// Select the edges of the current selection of faces, or all edges of
+    /// the current solid if no faces are selected. Seam edges (like the one
+    /// running along a cylinder) are never selected.
+    pub fn edges(self) -> Self {
+        self.map(|mut state| {
+            state.selection = Selection::Edges(state.candidate_edges()?);
+            Ok(state)
+        })
+    }
+
+    /// Like `edges`, but only straight edges parallel to `direction`.
+    pub fn edges_parallel_to(self, direction: Direction) -> Self {
+        const PARALLEL_TOLERANCE: f64 = 1.0e-6;
+
+        let direction = direction.normalized_vec();
+
+        self.map(|mut state| {
+            let edges = state
+                .candidate_edges()?
+                .into_iter()
+                .filter(|edge| {
+                    edge.edge_type() == EdgeType::Line
+                        && (edge.end_point() - edge.start_point())
+                            .try_normalize(PARALLEL_TOLERANCE)
+                            .is_some_and(|dir| dir.cross(&direction).norm() < PARALLEL_TOLERANCE)
+                })
+                .collect();
+
+            state.selection = Selection::Edges(edges);
+            Ok(state)
+        })
+    }
+
+    /// Select straight edges parallel to the Z axis.
+    pub fn edges_vertical(self) -> Self {
+        self.edges_parallel_to(Direction::PosZ)
+    }
+
+    /// Make the selected face the active workplane, centered on the face's
+    /// center of mass with its normal pointing out of the solid. Exactly
+    /// one face must be selected.
+    pub fn workplane(self) -> Self {
+        self.map(|mut state| {
+            state.workplane = match &state.selection {
+                Selection::Faces(faces) if faces.len() == 1 => faces[0].workplane(),
+                _ => return Err(Error::InvalidSelection),
+            };
+            state.selection = Selection::None;
+
+            Ok(state)
+        })
+    }
+
+    /// Offset the active workplane along its normal.
+    pub fn workplane_offset(self, offset: f64) -> Self {
+        self.map(|mut state| {
+            state.workplane = state.workplane.translated(Vector3::z() * offset);
+            Ok(state)
+        })
+    }
+
+    // Modifiers acting on the selection.
+
+    /// Fillet the selected edges (or the edges of the selected faces).
+    pub fn fillet(self, radius: f64) -> Self {
+        self.map(|mut state| {
+            let edges = state.selected_edges()?;
+            let solid = state.solid.take().ok_or(Error::InvalidSelection)?;
+
+            state.solid = Some(solid.fillet_edges(radius, &edges));
+            state.selection = Selection::None;
+
+            Ok(state)
+        })
+    }
+
+    /// Chamfer the selected edges (or the edges of the selected faces).
+    pub fn chamfer(self, distance: f64) -> Self {
+        self.map(|mut state| {
+            let edges = state.selected_edges()?;
+            let solid = state.solid.take().ok_or(Error::InvalidSelection)?;
+
+            state.solid = Some(solid.chamfer_edges(distance, &edges));
+            state.selection = Selection::None;
+
+            Ok(state)
+        })
+    }
+}
+
+impl PartState {
+    /// Turn the pending wires into faces, move them `start` along the
+    /// workplane normal, and sweep them by `offset`.
+    fn take_pending_prism(&mut self, offset: Vector3<f64>, start: f64) -> Result<Shape, Error> {
+        if self.pending.is_empty() {
+            return Err(Error::InvalidSelection);
+        }
+
+        let start = self.workplane.normal().into_inner() * start;
+        let mut tool: Option<Shape> = None;
+
+        for wire in self.pending.drain(..) {
+            let face = Face::from_wire(&wire.translate(start))?;
+            let prism: Shape = face.extrude(offset).into();
+
+            tool = Some(match tool {
+                Some(tool) => tool.union(&prism).into(),
+                None => prism,
+            });
+        }
+
+        tool.ok_or(Error::InvalidSelection)
+    }
+
+    fn cut(mut self, tool: &Shape) -> Result<Self, Error> {
+        let solid = self.solid.take().ok_or(Error::InvalidSelection)?;
+
+        self.solid = Some(solid.subtract(tool)?);
+        self.selection = Selection::None;
+
+        Ok(self)
+    }
+
+    /// Edges of the selected faces, or of the whole solid, without
+    /// duplicates and without seam edges.
+    fn candidate_edges(&self) -> Result<Vec<Edge>, Error> {
+        let solid = self.solid.as_ref().ok_or(Error::InvalidSelection)?;
+
+        let mut edge_map = ffi::new_indexed_data_map_of_shape_list_of_shape();
+        ffi::map_shapes_and_unique_ancestors(
+            &solid.inner,
+            ffi::TopAbs_ShapeEnum::TopAbs_EDGE,
+            ffi::TopAbs_ShapeEnum::TopAbs_FACE,
+            edge_map.pin_mut(),
+        );
+
+        let selected_faces = match &self.selection {
+            Selection::Faces(faces) => Some(faces),
+            _ => None,
+        };
+
+        let edges = (1..=edge_map.Extent())
+            .filter(|&i| edge_map.FindFromIndex(i).Size() > 1)
+            .map(|i| edge_map.FindKey(i))
+            .filter(|edge| match selected_faces {
+                Some(faces) => faces.iter().any(|face| {
+                    face.edges()
+                        .any(|face_edge| ffi::cast_edge_to_shape(&face_edge.inner).IsSame(edge))
+                }),
+                None => true,
+            })
+            .map(|edge| Edge::from_edge(ffi::TopoDS_cast_to_edge(edge)))
+            .collect();
+
+        Ok(edges)
+    }
+
+    fn selected_edges(&self) -> Result<Vec<Edge>, Error> {
+        let edges = match &self.selection {
+            Selection::Edges(edges) => edges
+                .iter()
+                .map(|edge| Edge::from_edge(&edge.inner))
+                .collect(),
+            Selection::Faces(_) => self.candidate_edges()?,
+            Selection::None => return Err(Error::InvalidSelection),
+        };
+
+        if edges.is_empty() {
+            return Err(Error::InvalidSelection);
+        }
+
+        Ok(edges)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plate_with_hole_and_rounded_corners() {
+        let shape = Part::on(Workplane::xy())
+            .rect(30.0, 20.0)
+            .extrude(5.0)
+            .faces_top()
+            .workplane()
+            .circle(0.0, 0.0, 3.0)
+            .cut_through_all()
+            .edges_vertical()
+            .fillet(1.0)
+            .into_shape()
+            .unwrap();
+
+        // Cutting and filleting give a compound, holding a single solid.
+        assert_eq!(shape.solids().count(), 1);
+        assert!(shape.is_closed_solid());
+
+        // 6 box faces, 4 fillets, and the hole.
+        assert_eq!(shape.faces().count(), 11);
+
+        let bounds = bounding_box::aabb(&shape);
+        assert!((bounds.max().z - 5.0).abs() < 1.0e-3);
+        assert!(bounds.min().z.abs() < 1.0e-3);
+    }
+
+    #[test]
+    fn errors_propagate_through_the_chain() {
+        let part = Part::on(Workplane::xy())
+            .rect(10.0, 10.0)
+            .extrude(5.0)
+            .workplane()
+            .circle(0.0, 0.0, 1.0);
+
+        assert!(matches!(part.error(), Some(Error::InvalidSelection)));
+        assert!(part.into_shape().is_err());
+    }
+}
@@ -4,30 +4,92 @@ use nalgebra::Point3;
 use nalgebra::Vector3;
 use opencascade_sys::ffi;
 
+pub mod airfoil;
+
+mod arc_length;
 mod boolean_shape;
+mod cache;
 mod compound;
+mod datum;
 mod edge;
+mod enclosing;
+mod extrude;
 mod face;
+mod history;
+mod hull;
+mod orientation;
+mod ruled;
 mod shape;
 mod shell;
+mod simplify;
 mod solid;
+mod spring;
 mod surface;
+mod tags;
+mod tolerance;
+mod topology;
+mod unroll;
 mod vertex;
 mod wire;
 
+pub use arc_length::*;
 pub use boolean_shape::*;
 pub use compound::*;
+pub use datum::*;
 pub use edge::*;
+pub use enclosing::*;
 pub use face::*;
+pub use history::*;
 pub use shape::*;
 pub use shell::*;
 pub use solid::*;
+pub use spring::*;
 pub use surface::*;
+pub use tolerance::*;
+pub use topology::*;
+pub use unroll::*;
 pub use vertex::*;
 pub use wire::*;
 
 use crate::TopExpExplorerIter;
 
+/// A shape which shares no data with any other, made by `Shape::detach`,
+/// which can be sent to another thread.
+///
+/// The shape types themselves aren't `Send`. They own handles to OCCT data
+/// which may be shared with other handles: sub-shapes share their data with
+/// their parent shape, as do `shallow_copy`s and patterns. Some operations
+/// taking `&self` write to that data: making a face on a surface adds pcurves
+/// to its edges, `ShapeFix` and `UnifySameDomain` fix shapes in place, and
+/// tolerance changes update vertices and edges. Nothing stops a handle
+/// sharing the data from being used on this thread while a sent one is used
+/// on another, so only shapes known to share nothing may be sent.
+pub struct Detached(Shape);
+
+// SAFETY: A `Detached` is only made from a deep copy, which shares no data
+// with any other handle, and it gives no access to its shape until it is
+// taken apart with `into_inner`. OCCT reference counts with atomic
+// operations, so its handles can be moved to and dropped on any thread. The
+// STEP and IGES translators, which keep their settings in global tables, are
+// serialized by `translator_lock`.
+unsafe impl Send for Detached {}
+
+impl Detached {
+    /// Wrap `shape` without copying it.
+    ///
+    /// # Safety
+    ///
+    /// No other handle may share any data with `shape`.
+    pub(crate) unsafe fn from_unshared(shape: Shape) -> Self {
+        Self(shape)
+    }
+
+    /// The shape, to use on this thread.
+    pub fn into_inner(self) -> Shape {
+        self.0
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ShapeType {
     /// Abstract topological data structure describes a basic entity.
@@ -99,7 +161,7 @@ pub fn make_point2d(p: Point2<f64>) -> UniquePtr<ffi::gp_Pnt2d> {
     ffi::new_point_2d(p.x, p.y)
 }
 
-fn make_dir(p: Vector3<f64>) -> UniquePtr<ffi::gp_Dir> {
+pub(crate) fn make_dir(p: Vector3<f64>) -> UniquePtr<ffi::gp_Dir> {
     ffi::gp_Dir_ctor(p.x, p.y, p.z)
 }
 
@@ -328,3 +390,136 @@ impl From<JoinType> for ffi::GeomAbs_JoinType {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TandR;
+    use nalgebra::point;
+    use std::sync::atomic::Ordering;
+    use std::thread;
+
+    #[test]
+    fn detached_shapes_are_send() {
+        fn assert_send<T: Send>() {}
+
+        assert_send::<Detached>();
+    }
+
+    #[test]
+    fn booleans_from_many_threads() {
+        const THREADS: usize = 8;
+        const ITERATIONS: usize = 10;
+
+        let base = Shape::box_with_dimensions(10.0, 10.0, 10.0);
+        let corner = Shape::box_from_corners(point![9.0, 9.0, 9.0], point![12.0, 12.0, 12.0]);
+
+        // Each thread gets deep copies, which share nothing with `base` or
+        // with each other.
+        let handles: Vec<_> = (0..THREADS)
+            .map(|i| {
+                let (base, corner) = (base.shallow_copy().detach(), corner.shallow_copy().detach());
+
+                thread::spawn(move || {
+                    let (base, corner) = (base.into_inner(), corner.into_inner());
+                    let faces: Vec<Face> = base.faces().collect();
+                    let mut part = Shape::empty();
+
+                    for j in 0..ITERATIONS {
+                        let radius = 2.0 + (i * ITERATIONS + j) as f64 * 0.01;
+                        let hole =
+                            Shape::cylinder(point![5.0, 5.0, -1.0], radius, Vector3::z(), 12.0);
+
+                        part = base.subtract(&hole).unwrap().union(&corner).into();
+                        part.mesh().unwrap();
+                        faces[i % faces.len()].surface_area();
+                    }
+
+                    (part.volume(), part.detach())
+                })
+            })
+            .collect();
+
+        let parts: Vec<(f64, Detached)> = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+
+        for (i, (volume, _)) in parts.iter().enumerate() {
+            let radius = 2.0 + (i * ITERATIONS + ITERATIONS - 1) as f64 * 0.01;
+            let expected = 1000.0 - std::f64::consts::PI * radius * radius * 10.0 + 26.0;
+
+            assert!(
+                (volume - expected).abs() < 1.0e-3,
+                "thread {i}: {volume} != {expected}"
+            );
+        }
+
+        // Shapes built on other threads can be used on this one.
+        let shifted: Vec<Shape> = parts
+            .into_iter()
+            .enumerate()
+            .map(|(i, (_, part))| {
+                let mut part = part.into_inner();
+                part.set_global_translation(Vector3::x() * 20.0 * i as f64);
+                part
+            })
+            .collect();
+
+        let all = Shape::union_all(&shifted).unwrap();
+        assert_eq!(all.solids().count(), THREADS);
+        assert!(base.is_closed_solid());
+    }
+
+    #[test]
+    fn derived_data_is_computed_once() {
+        let computations = |shape: &Shape| shape.cache.computations.load(Ordering::Relaxed);
+        let mut shape = Shape::box_with_dimensions(10.0, 10.0, 10.0);
+        assert_eq!(computations(&shape), 0);
+
+        assert!((shape.volume() - 1000.0).abs() < 1.0e-6);
+        assert!((shape.center_of_mass() - point![5.0, 5.0, 5.0]).norm() < 1.0e-6);
+        shape.volume();
+        assert_eq!(computations(&shape), 1);
+
+        shape.bounding_box();
+        shape.bounding_box();
+        assert_eq!(computations(&shape), 2);
+
+        let mesh = shape.mesh_with_tolerance(0.1).unwrap();
+        let again = shape.mesh_with_tolerance(0.1).unwrap();
+        assert_eq!(mesh.indices, again.indices);
+        assert_eq!(computations(&shape), 3);
+
+        // A different deflection is a different mesh.
+        shape.mesh_with_tolerance(0.05).unwrap();
+        assert_eq!(computations(&shape), 4);
+
+        // Changing the shape throws its cache away.
+        shape.transform(&TandR::default().translation(Vector3::x() * 10.0));
+        assert_eq!(computations(&shape), 0);
+        assert!((shape.bounding_box().min().x - 10.0).abs() < 1.0e-3);
+        assert!((shape.center_of_mass() - point![15.0, 5.0, 5.0]).norm() < 1.0e-6);
+        assert_eq!(computations(&shape), 2);
+    }
+
+    #[test]
+    fn precompute_on_worker_thread() {
+        let shape = Shape::sphere(5.0).build().detach();
+
+        let shape = thread::spawn(move || {
+            let shape = shape.into_inner();
+            shape.precompute(PrecomputeFlags::ALL).unwrap();
+            shape.detach()
+        })
+        .join()
+        .unwrap()
+        .into_inner();
+        assert_eq!(shape.cache.computations.load(Ordering::Relaxed), 3);
+
+        shape.bounding_box();
+        shape.volume();
+        shape.mesh().unwrap();
+        assert_eq!(shape.cache.computations.load(Ordering::Relaxed), 3);
+    }
+}
@@ -0,0 +1,303 @@
+//! Airfoil profiles.
+
+use crate::primitives::Edge;
+use crate::primitives::Wire;
+use crate::workplane::Workplane;
+use crate::Error;
+use nalgebra::point;
+use nalgebra::Point2;
+use std::f64::consts::PI;
+
+/// The x^4 coefficient of the published NACA thickness distribution, which
+/// leaves a blunt trailing edge 2.1% of the maximum thickness thick.
+const OPEN_TRAILING_EDGE: f64 = 0.1015;
+
+/// The x^4 coefficient which makes the thickness exactly zero at the
+/// trailing edge.
+const CLOSED_TRAILING_EDGE: f64 = 0.1036;
+
+/// A NACA 4-digit airfoil ("MPTT"): maximum camber M% of the chord at P/10
+/// of the chord, and maximum thickness TT% of the chord.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Naca4 {
+    max_camber: f64,
+    max_camber_position: f64,
+    thickness: f64,
+}
+
+impl Naca4 {
+    fn parse(code: &str) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidAirfoil {
+            reason: format!("\"{code}\" {reason}"),
+        };
+
+        let digits: Vec<u32> = code
+            .chars()
+            .map(|c| c.to_digit(10))
+            .collect::<Option<_>>()
+            .ok_or_else(|| invalid("has characters which aren't digits"))?;
+
+        let [m, p, t1, t2] = digits[..] else {
+            return Err(invalid("doesn't have exactly 4 digits"));
+        };
+
+        if m > 0 && p == 0 {
+            return Err(invalid(
+                "is cambered, but has its maximum camber at the leading edge",
+            ));
+        }
+
+        if m == 0 && p > 0 {
+            return Err(invalid("has a maximum camber position, but no camber"));
+        }
+
+        if t1 == 0 && t2 == 0 {
+            return Err(invalid("has zero thickness"));
+        }
+
+        Ok(Self {
+            max_camber: m as f64 / 100.0,
+            max_camber_position: p as f64 / 10.0,
+            thickness: (t1 * 10 + t2) as f64 / 100.0,
+        })
+    }
+
+    /// The half thickness at `x`, as a fraction of the chord.
+    fn half_thickness(&self, x: f64, closed_trailing_edge: bool) -> f64 {
+        let a4 = if closed_trailing_edge {
+            CLOSED_TRAILING_EDGE
+        } else {
+            OPEN_TRAILING_EDGE
+        };
+
+        5.0 * self.thickness
+            * (0.2969 * x.sqrt() - 0.1260 * x - 0.3516 * x.powi(2) + 0.2843 * x.powi(3)
+                - a4 * x.powi(4))
+    }
+
+    /// The height and slope of the camber line at `x`.
+    fn camber(&self, x: f64) -> (f64, f64) {
+        let (m, p) = (self.max_camber, self.max_camber_position);
+
+        if m == 0.0 {
+            (0.0, 0.0)
+        } else if x < p {
+            (
+                m / p.powi(2) * (2.0 * p * x - x.powi(2)),
+                2.0 * m / p.powi(2) * (p - x),
+            )
+        } else {
+            (
+                m / (1.0 - p).powi(2) * ((1.0 - 2.0 * p) + 2.0 * p * x - x.powi(2)),
+                2.0 * m / (1.0 - p).powi(2) * (p - x),
+            )
+        }
+    }
+
+    /// Points on the upper and lower surfaces, from the leading edge to the
+    /// trailing edge, for a unit chord. The stations are cosine spaced, to
+    /// resolve the curvature around the leading edge.
+    fn surfaces(
+        &self,
+        points_per_side: usize,
+        closed_trailing_edge: bool,
+    ) -> (Vec<Point2<f64>>, Vec<Point2<f64>>) {
+        (0..points_per_side)
+            .map(|i| {
+                let x = (1.0 - (PI * i as f64 / (points_per_side - 1) as f64).cos()) / 2.0;
+
+                // Pin the last station, so a closed trailing edge is closed
+                // exactly.
+                let (x, half_thickness) = if i == points_per_side - 1 {
+                    (
+                        1.0,
+                        if closed_trailing_edge {
+                            0.0
+                        } else {
+                            self.half_thickness(1.0, false)
+                        },
+                    )
+                } else {
+                    (x, self.half_thickness(x, closed_trailing_edge))
+                };
+
+                let (camber, slope) = self.camber(x);
+                let (sin, cos) = slope.atan().sin_cos();
+
+                (
+                    point![x - half_thickness * sin, camber + half_thickness * cos],
+                    point![x + half_thickness * sin, camber - half_thickness * cos],
+                )
+            })
+            .unzip()
+    }
+}
+
+/// A NACA 4-digit airfoil, like "2412", with its leading edge at the origin
+/// of `workplane` and its chord along the workplane's x axis.
+///
+/// The surfaces are a single spline through `points_per_side` cosine spaced
+/// points per side, running from the trailing edge over the upper surface to
+/// the leading edge and back along the lower surface. With
+/// `closed_trailing_edge`, the thickness distribution is adjusted to close
+/// the trailing edge exactly, which keeps lofts through several sections
+/// watertight. Otherwise the blunt trailing edge of the published profile is
+/// closed with a straight edge.
+pub fn naca4(
+    code: &str,
+    chord: f64,
+    points_per_side: u32,
+    closed_trailing_edge: bool,
+    workplane: &Workplane,
+) -> Result<Wire, Error> {
+    let airfoil = Naca4::parse(code)?;
+
+    if points_per_side < 3 {
+        return Err(Error::InvalidAirfoil {
+            reason: format!("at least 3 points per side are needed, not {points_per_side}"),
+        });
+    }
+
+    if !chord.is_finite() || chord <= 0.0 {
+        return Err(Error::InvalidAirfoil {
+            reason: format!("the chord must be positive, not {chord}"),
+        });
+    }
+
+    let (upper, lower) = airfoil.surfaces(points_per_side as usize, closed_trailing_edge);
+    let to_world = |p: &Point2<f64>| workplane.to_world_pos(point![p.x * chord, p.y * chord, 0.0]);
+
+    // Both surfaces start at the leading edge, so skip it on the way back.
+    let points: Vec<_> = upper
+        .iter()
+        .rev()
+        .chain(&lower[1..])
+        .map(to_world)
+        .collect();
+    let (first, last) = (points[0], points[points.len() - 1]);
+
+    let surfaces = Edge::spline_from_points(points, None);
+
+    if closed_trailing_edge {
+        Wire::from_edges([&surfaces])
+    } else {
+        Wire::from_edges([&surfaces, &Edge::segment(last, first)])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn naca_2412_matches_published_formula() {
+        let airfoil = Naca4::parse("2412").unwrap();
+        assert_eq!(
+            airfoil,
+            Naca4 {
+                max_camber: 0.02,
+                max_camber_position: 0.4,
+                thickness: 0.12,
+            }
+        );
+
+        let (upper, lower) = airfoil.surfaces(401, false);
+
+        // Upper and lower points are the thickness apart, perpendicular to
+        // the camber line. The maximum is t at 30% of the chord.
+        let (x, thickness) = upper
+            .iter()
+            .zip(&lower)
+            .map(|(upper, lower)| ((upper.x + lower.x) / 2.0, (upper - lower).norm()))
+            .fold(
+                (0.0, 0.0),
+                |max, station| if station.1 > max.1 { station } else { max },
+            );
+        assert!((x - 0.3).abs() < 0.01, "{x}");
+        assert!((thickness - 0.12).abs() < 1.0e-4, "{thickness}");
+
+        // The middle of each pair is on the camber line, which peaks at m at
+        // p.
+        let (x, camber) = upper
+            .iter()
+            .zip(&lower)
+            .map(|(upper, lower)| ((upper.x + lower.x) / 2.0, (upper.y + lower.y) / 2.0))
+            .fold(
+                (0.0, 0.0),
+                |max, station| if station.1 > max.1 { station } else { max },
+            );
+        assert!((x - 0.4).abs() < 0.01, "{x}");
+        assert!((camber - 0.02).abs() < 1.0e-5, "{camber}");
+
+        // Both surfaces start at the leading edge.
+        assert_eq!(upper[0], point![0.0, 0.0]);
+        assert_eq!(lower[0], point![0.0, 0.0]);
+    }
+
+    #[test]
+    fn trailing_edge() {
+        let airfoil = Naca4::parse("0012").unwrap();
+
+        let (upper, lower) = airfoil.surfaces(50, true);
+        assert_eq!(upper.last(), lower.last());
+
+        // The published profile is 2.1% of the thickness thick.
+        let (upper, lower) = airfoil.surfaces(50, false);
+        let gap = (upper.last().unwrap() - lower.last().unwrap()).norm();
+        assert!((gap - 0.021 * 0.12).abs() < 1.0e-9, "{gap}");
+    }
+
+    #[test]
+    fn airfoil_wires() {
+        let closed = naca4("2412", 100.0, 60, true, &Workplane::xy()).unwrap();
+        assert!(closed.is_closed());
+        assert_eq!(closed.edges().count(), 1);
+
+        let open = naca4("2412", 100.0, 60, false, &Workplane::xy()).unwrap();
+        assert!(open.is_closed());
+        assert_eq!(open.edges().count(), 2);
+
+        // The chord runs along the workplane's x axis.
+        let points: Vec<_> = closed
+            .edges()
+            .flat_map(|edge| edge.approximation_segments(0.01))
+            .collect();
+        let min_x = points.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+        let max_x = points.iter().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max);
+        assert!(min_x.abs() < 0.05 && (max_x - 100.0).abs() < 0.05);
+        assert!(points.iter().all(|p| p.z.abs() < 1.0e-9));
+
+        // The area of a 4-digit section is about 0.685 t c^2.
+        let area = closed.to_face().surface_area();
+        assert!((area - 0.685 * 0.12 * 100.0 * 100.0).abs() < 5.0, "{area}");
+    }
+
+    #[test]
+    fn invalid_codes() {
+        for (code, reason) in [
+            ("24", "exactly 4 digits"),
+            ("24120", "exactly 4 digits"),
+            ("24a2", "aren't digits"),
+            ("2012", "leading edge"),
+            ("0412", "no camber"),
+            ("2400", "zero thickness"),
+        ] {
+            let error = naca4(code, 1.0, 20, false, &Workplane::xy()).unwrap_err();
+            let message = error.to_string();
+            assert!(
+                message.contains(code) && message.contains(reason),
+                "{message}"
+            );
+        }
+
+        assert!(matches!(
+            naca4("2412", 1.0, 2, false, &Workplane::xy()),
+            Err(Error::InvalidAirfoil { .. })
+        ));
+
+        for chord in [0.0, -1.0, f64::NAN] {
+            let error = naca4("2412", chord, 20, false, &Workplane::xy()).unwrap_err();
+            assert!(error.to_string().contains("chord"), "{error}");
+        }
+    }
+}
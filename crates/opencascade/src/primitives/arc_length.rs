@@ -0,0 +1,312 @@
+use crate::primitives::Edge;
+use crate::primitives::Wire;
+use crate::sweep::any_perpendicular;
+use crate::sweep::rotation_minimizing_normal;
+use crate::sweep::SpineFrame;
+use crate::Error;
+use crate::TandR;
+use crate::CONFUSION;
+use cxx::UniquePtr;
+use nalgebra::point;
+use nalgebra::vector;
+use nalgebra::Point3;
+use nalgebra::Rotation3;
+use nalgebra::UnitQuaternion;
+use nalgebra::UnitVector3;
+use opencascade_sys::ffi;
+
+/// The number of frames kept along each edge, which `frame_at_length`
+/// carries the normal on from.
+const FRAMES_PER_EDGE: usize = 16;
+
+/// Lengths this far outside a wire are taken to be at its end.
+const LENGTH_TOLERANCE: f64 = 1.0e-9;
+
+/// A wire parameterized by arc length, made with `Wire::arc_length_param`.
+///
+/// Curve parameters can be far from proportional to length, along B-splines
+/// especially, so features which space things evenly along a wire should
+/// measure along it with this.
+///
+/// Where two edges meet at a corner, the tangent jumps. At the exact length
+/// of the corner, the tangent and frame are those at the end of the edge
+/// coming into it, except at the very start of the wire, where there is no
+/// such edge.
+pub struct ArcLengthMap {
+    segments: Vec<Segment>,
+    total_length: f64,
+}
+
+struct Segment {
+    curve: UniquePtr<ffi::BRepAdaptor_Curve>,
+    /// Whether the wire runs this edge against its curve.
+    reversed: bool,
+    /// The length along the wire at which this edge starts.
+    start: f64,
+    length: f64,
+    /// `FRAMES_PER_EDGE + 1` frames evenly spaced along the edge, ends
+    /// included.
+    frames: Vec<SpineFrame>,
+}
+
+impl Wire {
+    /// Measure this wire, for walking along it by arc length. The lengths of
+    /// its edges are found once, up front.
+    pub fn arc_length_param(&self) -> ArcLengthMap {
+        ArcLengthMap::from_edges(self.edges())
+    }
+}
+
+impl ArcLengthMap {
+    /// Measure `edges`, which run on from one another in order. Edges
+    /// shorter than `CONFUSION` are left out.
+    pub(crate) fn from_edges(edges: impl IntoIterator<Item = Edge>) -> Self {
+        let mut segments: Vec<Segment> = vec![];
+        let mut start = 0.0;
+
+        for edge in edges {
+            let curve = ffi::BRepAdaptor_Curve_ctor(&edge.inner);
+            let length = ffi::EdgeLength(&curve);
+
+            if length <= CONFUSION {
+                continue;
+            }
+
+            let mut segment = Segment {
+                curve,
+                reversed: edge.is_reversed(),
+                start,
+                length,
+                frames: Vec::with_capacity(FRAMES_PER_EDGE + 1),
+            };
+
+            for i in 0..=FRAMES_PER_EDGE {
+                let (point, tangent) = segment.evaluate(length * i as f64 / FRAMES_PER_EDGE as f64);
+
+                let previous = segment
+                    .frames
+                    .last()
+                    .or_else(|| segments.last().and_then(|previous| previous.frames.last()));
+
+                let normal = match previous {
+                    Some(previous) => rotation_minimizing_normal(previous, &point, &tangent),
+                    None => initial_normal(&segment, &tangent),
+                };
+
+                segment.frames.push(SpineFrame {
+                    point,
+                    tangent,
+                    normal,
+                });
+            }
+
+            start += length;
+            segments.push(segment);
+        }
+
+        ArcLengthMap {
+            segments,
+            total_length: start,
+        }
+    }
+
+    pub fn total_length(&self) -> f64 {
+        self.total_length
+    }
+
+    /// The point `length` along the wire from its start.
+    pub fn point_at_length(&self, length: f64) -> Result<Point3<f64>, Error> {
+        Ok(self.point_and_tangent_at_length(length)?.0)
+    }
+
+    /// The unit tangent of the wire, in the direction it runs, `length`
+    /// along it from its start.
+    pub fn tangent_at_length(&self, length: f64) -> Result<UnitVector3<f64>, Error> {
+        Ok(self.point_and_tangent_at_length(length)?.1)
+    }
+
+    /// Both `point_at_length` and `tangent_at_length`, for the price of one.
+    pub fn point_and_tangent_at_length(
+        &self,
+        length: f64,
+    ) -> Result<(Point3<f64>, UnitVector3<f64>), Error> {
+        let (segment, distance) = self.locate(length)?;
+
+        Ok(segment.evaluate(distance))
+    }
+
+    /// The parameter, on the curve of the edge it's on, of the point
+    /// `length` along the wire from its start.
+    pub(crate) fn parameter_at_length(&self, length: f64) -> Result<f64, Error> {
+        let (segment, distance) = self.locate(length)?;
+
+        Ok(segment.parameter(distance))
+    }
+
+    /// A frame for placing a profile `length` along the wire, as the
+    /// transform from the XY plane to it. Its origin is on the wire, its Z
+    /// axis is the tangent, and its X axis is the normal of a corrected
+    /// Frenet frame, which turns as little as it can about the tangent from
+    /// the start of the wire, as `FrameMode::CorrectedFrenet` sweeps do.
+    pub fn frame_at_length(&self, length: f64) -> Result<TandR<f64>, Error> {
+        let (segment, distance) = self.locate(length)?;
+        let (point, tangent) = segment.evaluate(distance);
+
+        // Carry the normal on from the last frame kept before this point.
+        let step = segment.length / FRAMES_PER_EDGE as f64;
+        let previous = &segment.frames[((distance / step) as usize).min(FRAMES_PER_EDGE)];
+        let normal = rotation_minimizing_normal(previous, &point, &tangent);
+
+        let rotation = Rotation3::from_basis_unchecked(&[
+            normal.into_inner(),
+            tangent.cross(&normal),
+            tangent.into_inner(),
+        ]);
+
+        Ok(TandR::new(
+            point.coords,
+            UnitQuaternion::from_rotation_matrix(&rotation),
+        ))
+    }
+
+    /// The edge `length` along the wire is on, taking the edge coming into a
+    /// corner at the corner, and how far along that edge it is.
+    fn locate(&self, length: f64) -> Result<(&Segment, f64), Error> {
+        if !(-LENGTH_TOLERANCE..=self.total_length + LENGTH_TOLERANCE).contains(&length)
+            || self.segments.is_empty()
+        {
+            return Err(Error::ParameterOutOfRange { parameter: length });
+        }
+
+        let index = self
+            .segments
+            .partition_point(|segment| segment.start + segment.length < length)
+            .min(self.segments.len() - 1);
+        let segment = &self.segments[index];
+
+        Ok((segment, (length - segment.start).clamp(0.0, segment.length)))
+    }
+}
+
+impl Segment {
+    /// The point `distance` along this edge, in the direction the wire runs,
+    /// and the unit tangent there.
+    fn evaluate(&self, distance: f64) -> (Point3<f64>, UnitVector3<f64>) {
+        let u = self.parameter(distance);
+
+        let mut p = ffi::new_point(0.0, 0.0, 0.0);
+        let mut d1 = ffi::new_vec(0.0, 0.0, 0.0);
+        self.curve.D1(u, p.pin_mut(), d1.pin_mut());
+
+        let d1 = vector![d1.X(), d1.Y(), d1.Z()];
+        let tangent = UnitVector3::new_normalize(if self.reversed { -d1 } else { d1 });
+
+        (point![p.X(), p.Y(), p.Z()], tangent)
+    }
+
+    /// The curve parameter of the point `distance` along this edge, in the
+    /// direction the wire runs.
+    fn parameter(&self, distance: f64) -> f64 {
+        let along_curve = if self.reversed {
+            self.length - distance
+        } else {
+            distance
+        };
+
+        ffi::BRepAdaptor_Curve_parameter_at_length(&self.curve, along_curve)
+    }
+}
+
+/// The normal of the frame at the start of the wire: towards the side the
+/// first edge curves to, or any direction square to the tangent if it's
+/// straight.
+fn initial_normal(segment: &Segment, tangent: &UnitVector3<f64>) -> UnitVector3<f64> {
+    let (_, next_tangent) = segment.evaluate(segment.length / FRAMES_PER_EDGE as f64);
+    let turn = next_tangent.into_inner() - tangent.into_inner();
+
+    UnitVector3::try_new(turn - tangent.into_inner() * turn.dot(tangent), 1.0e-9)
+        .unwrap_or_else(|| any_perpendicular(tangent))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::Edge;
+    use nalgebra::Vector3;
+
+    #[test]
+    fn spline_is_walked_evenly() {
+        // Bunched up control points make the curve's parameter run very
+        // unevenly along it.
+        let spline = Edge::spline_from_points(
+            [
+                point![0.0, 0.0, 0.0],
+                point![0.5, 0.2, 0.0],
+                point![1.0, 0.3, 0.0],
+                point![30.0, 10.0, 5.0],
+                point![60.0, -5.0, 0.0],
+            ],
+            None,
+        );
+        let wire = Wire::from_edges([&spline]).unwrap();
+        let map = wire.arc_length_param();
+
+        assert!((map.total_length() - spline.length()).abs() < 1.0e-6);
+        assert!((map.point_at_length(0.0).unwrap() - spline.start_point()).norm() < 1.0e-6);
+        assert!(
+            (map.point_at_length(map.total_length()).unwrap() - spline.end_point()).norm() < 1.0e-6
+        );
+
+        const STEPS: usize = 400;
+        let step = map.total_length() / STEPS as f64;
+        let points: Vec<_> = (0..=STEPS)
+            .map(|i| map.point_at_length(step * i as f64).unwrap())
+            .collect();
+
+        for pair in points.windows(2) {
+            let chord = (pair[1] - pair[0]).norm();
+            assert!(
+                chord <= step + 1.0e-6 && chord > step * 0.98,
+                "{chord} vs {step}"
+            );
+        }
+
+        assert!(map.point_at_length(-1.0).is_err());
+        assert!(map.point_at_length(map.total_length() + 1.0).is_err());
+    }
+
+    #[test]
+    fn corners_take_the_incoming_tangent() {
+        let wire = Wire::from_edges([
+            &Edge::segment(point![0.0, 0.0, 0.0], point![10.0, 0.0, 0.0]),
+            &Edge::segment(point![10.0, 0.0, 0.0], point![10.0, 10.0, 0.0]),
+        ])
+        .unwrap();
+        let map = wire.arc_length_param();
+        assert!((map.total_length() - 20.0).abs() < 1.0e-9);
+
+        let x = Vector3::x();
+        let y = Vector3::y();
+
+        assert!((map.tangent_at_length(0.0).unwrap().into_inner() - x).norm() < 1.0e-9);
+        assert!((map.tangent_at_length(10.0).unwrap().into_inner() - x).norm() < 1.0e-9);
+        assert!((map.tangent_at_length(10.0 + 1.0e-6).unwrap().into_inner() - y).norm() < 1.0e-9);
+        assert!((map.point_at_length(10.0).unwrap() - point![10.0, 0.0, 0.0]).norm() < 1.0e-9);
+
+        // The frame turns about Z at the corner, keeping its normal, so a
+        // profile keeps facing the same way.
+        let before = map.frame_at_length(5.0).unwrap();
+        let after = map.frame_at_length(15.0).unwrap();
+
+        let z_before = before.rotation_quat * Vector3::z();
+        let z_after = after.rotation_quat * Vector3::z();
+        assert!((z_before - x).norm() < 1.0e-9);
+        assert!((z_after - y).norm() < 1.0e-9);
+
+        let normal_before = before.rotation_quat * Vector3::x();
+        let normal_after = after.rotation_quat * Vector3::x();
+        assert!((normal_before - normal_after).norm() < 1.0e-9);
+
+        assert!((after.translation - vector![10.0, 5.0, 0.0]).norm() < 1.0e-9);
+    }
+}
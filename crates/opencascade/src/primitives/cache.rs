@@ -0,0 +1,84 @@
+use crate::bounding_box::BoundingBox;
+use crate::mesh::Mesh;
+use crate::Error;
+use nalgebra::Point3;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::sync::PoisonError;
+
+#[cfg(test)]
+use std::sync::atomic::AtomicUsize;
+#[cfg(test)]
+use std::sync::atomic::Ordering;
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct MassProperties {
+    pub(crate) volume: f64,
+    pub(crate) center_of_mass: Point3<f64>,
+}
+
+/// Data derived from the geometry of a `Shape`, computed on first use.
+///
+/// Each value is computed at most once, and meshes are kept per deflection.
+/// The cache moves over to the copy when its shape is detached to be sent to
+/// another thread.
+///
+/// Anything that replaces the geometry of the shape must `clear` the cache.
+#[derive(Default)]
+pub(crate) struct ShapeCache {
+    bounding_box: OnceLock<BoundingBox>,
+    mass_properties: OnceLock<MassProperties>,
+    meshes: Mutex<Vec<(f64, Mesh)>>,
+    /// How many values have been computed, rather than read from the cache.
+    #[cfg(test)]
+    pub(crate) computations: AtomicUsize,
+}
+
+impl ShapeCache {
+    pub(crate) fn bounding_box(&self, compute: impl FnOnce() -> BoundingBox) -> &BoundingBox {
+        self.bounding_box.get_or_init(|| {
+            self.count();
+            compute()
+        })
+    }
+
+    pub(crate) fn mass_properties(
+        &self,
+        compute: impl FnOnce() -> MassProperties,
+    ) -> MassProperties {
+        *self.mass_properties.get_or_init(|| {
+            self.count();
+            compute()
+        })
+    }
+
+    pub(crate) fn mesh(
+        &self,
+        deflection: f64,
+        compute: impl FnOnce() -> Result<Mesh, Error>,
+    ) -> Result<Mesh, Error> {
+        let mut meshes = self.meshes.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some((_, mesh)) = meshes.iter().find(|(key, _)| *key == deflection) {
+            return Ok(mesh.clone());
+        }
+
+        self.count();
+        let mesh = compute()?;
+        meshes.push((deflection, mesh.clone()));
+
+        Ok(mesh)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    #[cfg(test)]
+    fn count(&self) {
+        self.computations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[cfg(not(test))]
+    fn count(&self) {}
+}
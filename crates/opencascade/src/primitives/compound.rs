@@ -41,3 +41,52 @@ impl Compound {
         Self::from_compound(compound)
     }
 }
+
+/// Builds a compound one shape at a time.
+pub struct CompoundBuilder {
+    builder: UniquePtr<ffi::BRep_Builder>,
+    compound: UniquePtr<ffi::TopoDS_Shape>,
+    len: usize,
+}
+
+impl Default for CompoundBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompoundBuilder {
+    pub fn new() -> Self {
+        let mut compound = ffi::TopoDS_Compound_ctor();
+        let builder = ffi::BRep_Builder_ctor();
+        ffi::BRep_Builder_upcast_to_topods_builder(&builder).MakeCompound(compound.pin_mut());
+
+        Self {
+            builder,
+            compound: ffi::TopoDS_Compound_as_shape(compound),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn add_shape(&mut self, shape: &Shape) {
+        let builder = ffi::BRep_Builder_upcast_to_topods_builder(&self.builder);
+        builder.Add(self.compound.pin_mut(), &shape.inner);
+        self.len += 1;
+    }
+
+    pub fn push(&mut self, shape: Shape) {
+        self.add_shape(&shape);
+    }
+
+    pub fn build(self) -> Compound {
+        Compound::from_compound(ffi::TopoDS_cast_to_compound(&self.compound))
+    }
+}
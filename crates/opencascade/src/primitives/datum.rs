@@ -0,0 +1,521 @@
+use crate::angle::Angle;
+use crate::convert::IntoPoint3;
+use crate::holes::HolePosition;
+use crate::primitives::make_axis_1;
+use crate::primitives::make_axis_2;
+use crate::primitives::Edge;
+use crate::primitives::EdgeType;
+use crate::primitives::Face;
+use crate::primitives::FaceType;
+use crate::primitives::Shape;
+use crate::primitives::Vertex;
+use crate::primitives::Wire;
+use crate::workplane::Workplane;
+use crate::Error;
+use crate::TandR;
+use cxx::UniquePtr;
+use nalgebra::point;
+use nalgebra::Point3;
+use nalgebra::UnitVector3;
+use nalgebra::Vector3;
+use opencascade_sys::ffi;
+use std::ops::Deref;
+
+/// Faces whose normals are closer than this many radians to parallel are
+/// parallel.
+const PARALLEL_TOLERANCE: f64 = 1.0e-6;
+
+/// A named point, which isn't part of any shape.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DatumPoint {
+    pub point: Point3<f64>,
+}
+
+impl DatumPoint {
+    pub fn new(point: Point3<f64>) -> Self {
+        Self { point }
+    }
+
+    pub fn from_vertex(vertex: &Vertex) -> Self {
+        Self::new(vertex.coords())
+    }
+}
+
+/// An infinite line, which isn't part of any shape, to revolve and pattern
+/// around.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DatumAxis {
+    pub origin: Point3<f64>,
+    pub direction: UnitVector3<f64>,
+}
+
+impl DatumAxis {
+    pub fn new(origin: Point3<f64>, direction: UnitVector3<f64>) -> Self {
+        Self { origin, direction }
+    }
+
+    pub fn x() -> Self {
+        Self::new(Point3::origin(), Vector3::x_axis())
+    }
+
+    pub fn y() -> Self {
+        Self::new(Point3::origin(), Vector3::y_axis())
+    }
+
+    pub fn z() -> Self {
+        Self::new(Point3::origin(), Vector3::z_axis())
+    }
+
+    /// The axis of a cylindrical face, with its origin level with the
+    /// middle of the face.
+    pub fn from_cylindrical_face(face: &Face) -> Result<Self, Error> {
+        let face_type = face.face_type();
+        if face_type != FaceType::Cylinder {
+            return Err(Error::NotCylindrical { face_type });
+        }
+
+        let surface = ffi::BRepAdaptor_Surface_ctor(&face.inner, true);
+        let position = ffi::BRepAdaptor_Surface_position(&surface);
+        let location = position.Location();
+        let direction = position.Direction();
+
+        let axis = Self::new(
+            point![location.X(), location.Y(), location.Z()],
+            UnitVector3::new_normalize(Vector3::new(direction.X(), direction.Y(), direction.Z())),
+        );
+
+        Ok(Self::new(
+            axis.project(face.center_of_mass()),
+            axis.direction,
+        ))
+    }
+
+    /// The line along a straight edge, from its start to its end, or the
+    /// axis of a circular edge, through its center.
+    pub fn from_edge(edge: &Edge) -> Result<Self, Error> {
+        let edge_type = edge.edge_type();
+
+        match edge_type {
+            EdgeType::Line => {
+                let start = edge.start_point();
+
+                UnitVector3::try_new(edge.end_point() - start, f64::EPSILON)
+                    .map(|direction| Self::new(start, direction))
+                    .ok_or(Error::NoEdgeAxis { edge_type })
+            }
+            EdgeType::Circle => {
+                let (center, axis, _) = edge
+                    .centerpoint_axis_radius()
+                    .ok_or(Error::NoEdgeAxis { edge_type })?;
+
+                Ok(Self::new(center, UnitVector3::new_normalize(axis)))
+            }
+            _ => Err(Error::NoEdgeAxis { edge_type }),
+        }
+    }
+
+    /// The point on the axis closest to `point`.
+    pub fn project(&self, point: Point3<f64>) -> Point3<f64> {
+        let direction = self.direction.into_inner();
+
+        self.origin + direction * (point - self.origin).dot(&direction)
+    }
+
+    pub fn distance_to(&self, point: Point3<f64>) -> f64 {
+        (point - self.project(point)).norm()
+    }
+}
+
+/// A plane, which isn't part of any shape. Dereferences to its `Workplane`,
+/// so it can be used anywhere a workplane is expected, such as
+/// `Shape::hole`.
+#[derive(Debug, Clone)]
+pub struct DatumPlane(Workplane);
+
+impl DatumPlane {
+    pub fn new(workplane: Workplane) -> Self {
+        Self(workplane)
+    }
+
+    /// The plane of a planar face, with its origin at the center of the
+    /// face and its normal pointing out of it.
+    pub fn from_face(face: &Face) -> Result<Self, Error> {
+        let face_type = face.face_type();
+        if face_type != FaceType::Plane {
+            return Err(Error::NotPlanar { face_type });
+        }
+
+        Ok(Self(face.workplane()))
+    }
+
+    /// The plane halfway between two parallel planar faces, such as the
+    /// opposite sides of a box, with its normal along the normal of
+    /// `first` and its origin halfway between the centers of the faces.
+    pub fn midplane_between(first: &Face, second: &Face) -> Result<Self, Error> {
+        let first = Self::from_face(first)?;
+        let second = Self::from_face(second)?;
+
+        let normal = first.normal().into_inner();
+        let angle = normal.angle(&second.normal().into_inner());
+        let angle = angle.min(std::f64::consts::PI - angle);
+        if angle > PARALLEL_TOLERANCE {
+            return Err(Error::FacesNotParallel { angle });
+        }
+
+        let middle = (first.origin() + second.origin()) / 2.0;
+
+        let mut workplane = first.0;
+        workplane.set_translation(middle);
+
+        Ok(Self(workplane))
+    }
+
+    pub fn workplane(&self) -> &Workplane {
+        &self.0
+    }
+
+    /// The point on the plane closest to `point`.
+    pub fn project(&self, point: Point3<f64>) -> Point3<f64> {
+        let normal = self.normal().into_inner();
+        let height = (point.coords - self.origin()).dot(&normal);
+
+        point - normal * height
+    }
+}
+
+impl Deref for DatumPlane {
+    type Target = Workplane;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Workplane> for DatumPlane {
+    fn from(workplane: Workplane) -> Self {
+        Self(workplane)
+    }
+}
+
+/// An axis to revolve or pattern around: a `DatumAxis`, or an origin and a
+/// direction. The direction is a `UnitVector3`, so it can't be zero.
+pub trait IntoAxis {
+    fn into_axis(self) -> DatumAxis;
+}
+
+impl IntoAxis for DatumAxis {
+    fn into_axis(self) -> DatumAxis {
+        self
+    }
+}
+
+impl IntoAxis for &DatumAxis {
+    fn into_axis(self) -> DatumAxis {
+        *self
+    }
+}
+
+impl<P: IntoPoint3> IntoAxis for (P, UnitVector3<f64>) {
+    fn into_axis(self) -> DatumAxis {
+        DatumAxis::new(self.0.into_point3(), self.1)
+    }
+}
+
+/// Something to reflect shapes in. A `DatumPlane` reflects through the
+/// plane, and a `DatumAxis` through the line.
+pub trait Mirror {
+    fn mirror_transform(&self) -> UniquePtr<ffi::gp_Trsf>;
+}
+
+impl Mirror for DatumPlane {
+    fn mirror_transform(&self) -> UniquePtr<ffi::gp_Trsf> {
+        let plane = make_axis_2(Point3::from(self.origin()), self.normal().into_inner());
+
+        let mut transform = ffi::new_transform();
+        transform.pin_mut().set_mirror_plane(&plane);
+
+        transform
+    }
+}
+
+impl Mirror for DatumAxis {
+    fn mirror_transform(&self) -> UniquePtr<ffi::gp_Trsf> {
+        let axis = make_axis_1(self.origin, self.direction.into_inner());
+
+        let mut transform = ffi::new_transform();
+        transform.pin_mut().set_mirror_axis(&axis);
+
+        transform
+    }
+}
+
+/// A datum point is drilled where it projects onto the workplane, so its
+/// height above the workplane is ignored.
+impl HolePosition for DatumPoint {
+    fn on(&self, workplane: &Workplane) -> (f64, f64) {
+        let local = workplane.to_local_pos(self.point);
+
+        (local.x, local.y)
+    }
+}
+
+impl HolePosition for &DatumPoint {
+    fn on(&self, workplane: &Workplane) -> (f64, f64) {
+        (*self).on(workplane)
+    }
+}
+
+impl Wire {
+    /// Reflect this wire in a datum plane or axis.
+    #[must_use]
+    pub fn mirrored(&self, mirror: &impl Mirror) -> Wire {
+        let transform = mirror.mirror_transform();
+        let wire_shape = ffi::cast_wire_to_shape(&self.inner);
+
+        let mut brep_transform = ffi::BRepBuilderAPI_Transform_ctor(wire_shape, &transform, true);
+        let mirrored_wire = ffi::TopoDS_cast_to_wire(brep_transform.pin_mut().Shape());
+
+        Wire::from_wire(mirrored_wire)
+    }
+}
+
+impl Shape {
+    /// Reflect this shape in a datum plane or axis.
+    #[must_use]
+    pub fn mirrored(&self, mirror: &impl Mirror) -> Shape {
+        let transform = mirror.mirror_transform();
+
+        let mut brep_transform = ffi::BRepBuilderAPI_Transform_ctor(&self.inner, &transform, true);
+
+        Shape::from_shape(brep_transform.pin_mut().Shape())
+    }
+
+    /// Fuse `count` copies of this shape, this one included, each turned
+    /// by `step` around `axis` from the one before. The step defaults to a
+    /// full turn divided by `count`. The copies share this shape's topology,
+    /// see `shallow_copy`.
+    pub fn circular_pattern(
+        &self,
+        axis: impl IntoAxis,
+        count: usize,
+        step: Option<Angle>,
+    ) -> Result<Shape, Error> {
+        self.circular_pattern_with(axis.into_axis(), count, step, false)
+    }
+
+    /// Like `circular_pattern`, but with copies which share nothing with
+    /// this shape or each other, see `deep_copy`.
+    pub fn circular_pattern_deep(
+        &self,
+        axis: impl IntoAxis,
+        count: usize,
+        step: Option<Angle>,
+    ) -> Result<Shape, Error> {
+        self.circular_pattern_with(axis.into_axis(), count, step, true)
+    }
+
+    fn circular_pattern_with(
+        &self,
+        axis: DatumAxis,
+        count: usize,
+        step: Option<Angle>,
+        deep: bool,
+    ) -> Result<Shape, Error> {
+        let step = step
+            .map(Angle::radians)
+            .unwrap_or(std::f64::consts::TAU / count.max(1) as f64);
+
+        let copies: Vec<_> = (0..count)
+            .map(|i| {
+                let mut copy = if deep {
+                    self.deep_copy()
+                } else {
+                    self.shallow_copy()
+                };
+                copy.transform(&TandR::from_axis_angle_origin(
+                    &axis.direction,
+                    step * i as f64,
+                    axis.origin,
+                ));
+                copy
+            })
+            .collect();
+
+        Shape::union_all(copies)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::holes::HoleDepth;
+    use crate::holes::HoleSpec;
+    use nalgebra::vector;
+
+    fn faces_of_type(shape: &Shape, face_type: FaceType) -> Vec<Face> {
+        shape
+            .faces()
+            .filter(|face| face.face_type() == face_type)
+            .collect()
+    }
+
+    #[test]
+    fn axis_from_cylindrical_face() {
+        let cylinder = Shape::cylinder(point![1.0, 2.0, 3.0], 4.0, vector![0.0, 1.0, 1.0], 10.0);
+        let mut faces = faces_of_type(&cylinder, FaceType::Cylinder);
+        assert_eq!(faces.len(), 1);
+        let face = faces.remove(0);
+
+        let axis = DatumAxis::from_cylindrical_face(&face).unwrap();
+        let expected_direction = vector![0.0, 1.0, 1.0].normalize();
+        assert!(axis.direction.dot(&expected_direction).abs() > 1.0 - 1.0e-9);
+
+        let expected_origin = point![1.0, 2.0, 3.0] + expected_direction * 5.0;
+        assert!(
+            (axis.origin - expected_origin).norm() < 1.0e-6,
+            "{}",
+            axis.origin
+        );
+
+        let plane = faces_of_type(&cylinder, FaceType::Plane).remove(0);
+        assert!(matches!(
+            DatumAxis::from_cylindrical_face(&plane),
+            Err(Error::NotCylindrical {
+                face_type: FaceType::Plane
+            })
+        ));
+    }
+
+    #[test]
+    fn axis_from_edges() {
+        let segment = Edge::segment(point![1.0, 1.0, 0.0], point![1.0, 4.0, 0.0]);
+        let axis = DatumAxis::from_edge(&segment).unwrap();
+        assert_eq!(axis.origin, point![1.0, 1.0, 0.0]);
+        assert!((axis.direction.into_inner() - Vector3::y()).norm() < 1.0e-12);
+
+        let circle = Edge::circle(point![2.0, 3.0, 4.0], Vector3::x(), 5.0).unwrap();
+        let axis = DatumAxis::from_edge(&circle).unwrap();
+        assert!((axis.origin - point![2.0, 3.0, 4.0]).norm() < 1.0e-9);
+        assert!(axis.direction.dot(&Vector3::x()).abs() > 1.0 - 1.0e-9);
+        assert!((axis.distance_to(point![0.0, 3.0, 9.0]) - 5.0).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn midplane_of_a_box() {
+        let shape = Shape::box_from_corners(point![0.0, 0.0, 0.0], point![4.0, 6.0, 10.0]);
+        let face_along = |normal: Vector3<f64>| {
+            shape
+                .faces()
+                .find(|face| (face.normal_at_center().into_inner() - normal).norm() < 1.0e-9)
+                .unwrap()
+        };
+        let (bottom, top, side) = (
+            face_along(-Vector3::z()),
+            face_along(Vector3::z()),
+            face_along(Vector3::x()),
+        );
+
+        let midplane = DatumPlane::midplane_between(&bottom, &top).unwrap();
+        assert!((midplane.origin() - vector![2.0, 3.0, 5.0]).norm() < 1.0e-9);
+        assert!(midplane.normal().dot(&Vector3::z()).abs() > 1.0 - 1.0e-9);
+        assert!((midplane.project(point![7.0, 8.0, 0.0]) - point![7.0, 8.0, 5.0]).norm() < 1.0e-9);
+
+        assert!(matches!(
+            DatumPlane::midplane_between(&bottom, &side),
+            Err(Error::FacesNotParallel { .. })
+        ));
+    }
+
+    #[test]
+    fn datums_drive_features() {
+        let block = Shape::box_from_corners(point![5.0, -1.0, 0.0], point![7.0, 1.0, 2.0]);
+
+        let pattern = block.circular_pattern(&DatumAxis::z(), 6, None).unwrap();
+        assert!((pattern.volume() - 6.0 * block.volume()).abs() < 1.0e-6);
+
+        let plane = DatumPlane::new(Workplane::yz());
+        let mirrored = block.mirrored(&plane);
+        let bounds = mirrored.bounding_box();
+        assert!((bounds.min().x + 7.0).abs() < 1.0e-6);
+        assert!((bounds.max().x + 5.0).abs() < 1.0e-6);
+        assert!((mirrored.volume() - block.volume()).abs() < 1.0e-6);
+
+        let around_line = block
+            .circular_pattern((Point3::origin(), Vector3::z_axis()), 6, None)
+            .unwrap();
+        assert!((around_line.volume() - pattern.volume()).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn revolve_about_a_datum() {
+        let profile = Wire::from_ordered_points([
+            point![5.0, 0.0, 0.0],
+            point![7.0, 0.0, 0.0],
+            point![7.0, 0.0, 2.0],
+            point![5.0, 0.0, 2.0],
+        ])
+        .unwrap();
+        let face = Face::from_wire(&profile).unwrap();
+        let ring_volume = std::f64::consts::PI * (7.0 * 7.0 - 5.0 * 5.0) * 2.0;
+
+        let about_datum = Shape::from(face.revolve_about(DatumAxis::z(), None));
+        assert!((about_datum.volume() - ring_volume).abs() < 1.0e-6);
+
+        let about_line =
+            Shape::from(face.revolve_about((Point3::origin(), Vector3::z_axis()), None));
+        assert!((about_line.volume() - ring_volume).abs() < 1.0e-6);
+
+        let about_vector = Shape::from(face.revolve(Point3::origin(), Vector3::z(), None));
+        assert!((about_vector.volume() - ring_volume).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn mirror_wires_in_planes_and_axes() {
+        let wire = Wire::from_ordered_points([
+            point![1.0, 2.0, 3.0],
+            point![4.0, 2.0, 3.0],
+            point![4.0, 5.0, 3.0],
+        ])
+        .unwrap();
+
+        let in_plane = Shape::from(&wire.mirrored(&DatumPlane::new(Workplane::yz())));
+        let bounds = in_plane.bounding_box();
+        assert!((bounds.min() - point![-4.0, 2.0, 3.0]).norm() < 1.0e-6);
+        assert!((bounds.max() - point![-1.0, 5.0, 3.0]).norm() < 1.0e-6);
+
+        // Reflecting through the y axis negates both x and z.
+        let in_axis = Shape::from(&wire.mirrored(&DatumAxis::y()));
+        let bounds = in_axis.bounding_box();
+        assert!((bounds.min() - point![-4.0, 2.0, -3.0]).norm() < 1.0e-6);
+        assert!((bounds.max() - point![-1.0, 5.0, -3.0]).norm() < 1.0e-6);
+
+        let along_axis = Shape::from(&wire.mirror_along_axis(Point3::origin(), Vector3::y()));
+        let along_bounds = along_axis.bounding_box();
+        assert!((along_bounds.min() - bounds.min()).norm() < 1.0e-6);
+        assert!((along_bounds.max() - bounds.max()).norm() < 1.0e-6);
+    }
+
+    #[test]
+    fn hole_at_a_datum_point() {
+        let block = Shape::box_from_corners(point![0.0, 0.0, 0.0], point![10.0, 10.0, 5.0]);
+        let top = block
+            .faces()
+            .find(|face| (face.normal_at_center().into_inner() - Vector3::z()).norm() < 1.0e-9)
+            .unwrap();
+        let plane = DatumPlane::from_face(&top).unwrap();
+
+        // Well above the block, to be projected onto its top.
+        let point = DatumPoint::new(point![2.0, 3.0, 100.0]);
+        let spec = HoleSpec::Simple {
+            diameter: 1.0,
+            depth: HoleDepth::Through,
+            unit: None,
+        };
+        let shape = block.hole(&plane, &point, spec).unwrap();
+
+        let hole = faces_of_type(&shape, FaceType::Cylinder).remove(0);
+        let axis = DatumAxis::from_cylindrical_face(&hole).unwrap();
+        assert!(axis.distance_to(point![2.0, 3.0, 0.0]) < 1.0e-6);
+        assert!(axis.direction.dot(&Vector3::z()).abs() > 1.0 - 1.0e-9);
+    }
+}
@@ -1,7 +1,15 @@
+use crate::angle::Angle;
+use crate::convert::IntoPoint3;
+use crate::convert::IntoUnitVector3;
 use crate::primitives::make_axis_2;
+use crate::primitives::make_dir;
 use crate::primitives::make_point;
+use crate::primitives::ArcLengthMap;
 use crate::primitives::Shape;
+use crate::primitives::Vertex;
 use crate::primitives::VertexIterator;
+use crate::Error;
+use crate::CONFUSION;
 use cxx::UniquePtr;
 use nalgebra::point;
 use nalgebra::Point3;
@@ -9,9 +17,14 @@ use nalgebra::UnitVector3;
 use nalgebra::Vector3;
 use nalgebra::vector;
 use opencascade_sys::ffi;
+use std::f64::consts::TAU;
 
 use super::make_vec;
 
+/// How close the tangent lines of a conic arc must come to be taken to meet,
+/// relative to the distance between the ends of the arc.
+const TANGENT_TOLERANCE: f64 = 1.0e-6;
+
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Hash, Eq)]
 pub enum EdgeType {
@@ -47,6 +60,19 @@ pub struct Edge {
     pub(crate) inner: UniquePtr<ffi::TopoDS_Edge>,
 }
 
+/// The circle a circular edge lies on, and the part of it the edge covers.
+/// See `Edge::circle_params`.
+#[derive(Debug, Copy, Clone)]
+pub struct CircleParams {
+    pub center: Point3<f64>,
+    pub axis: UnitVector3<f64>,
+    pub radius: f64,
+    /// The angles around `axis` of the start and the end of the edge's
+    /// curve, measured as `Edge::arc_from_center` measures them. The start
+    /// is within a turn of zero, and the end is after it.
+    pub angle_range: (Angle, Angle),
+}
+
 impl AsRef<Edge> for Edge {
     fn as_ref(&self) -> &Edge {
         self
@@ -64,7 +90,8 @@ impl Edge {
         Self::from_edge(make_edge.pin_mut().Edge())
     }
 
-    pub fn segment(p1: Point3<f64>, p2: Point3<f64>) -> Self {
+    pub fn segment(p1: impl IntoPoint3, p2: impl IntoPoint3) -> Self {
+        let (p1, p2) = (p1.into_point3(), p2.into_point3());
         let make_edge =
             ffi::BRepBuilderAPI_MakeEdge_gp_Pnt_gp_Pnt(&make_point(p1), &make_point(p2));
 
@@ -89,16 +116,158 @@ impl Edge {
         Self::from_edge(edge)
     }
 
-    pub fn circle(center: Point3<f64>, normal: UnitVector3<f64>, radius: f64) -> Self {
-        let axis = make_axis_2(center, normal.into_inner());
+    pub fn circle(
+        center: impl IntoPoint3,
+        normal: impl IntoUnitVector3,
+        radius: f64,
+    ) -> Result<Self, Error> {
+        let normal = normal.into_unit_vector3()?;
+        let axis = make_axis_2(center.into_point3(), normal.into_inner());
 
         let make_circle = ffi::gp_Circ_ctor(&axis, radius);
         let make_edge = ffi::BRepBuilderAPI_MakeEdge_circle(&make_circle);
 
-        Self::from_make_edge(make_edge)
+        Ok(Self::from_make_edge(make_edge))
     }
 
-    pub fn ellipse() {}
+    /// An ellipse around `center`, in the plane facing `normal`, with its
+    /// major axis along `major_direction`. `major_radius` must be at least
+    /// `minor_radius`, which must be positive.
+    pub fn ellipse(
+        center: impl IntoPoint3,
+        normal: UnitVector3<f64>,
+        major_direction: UnitVector3<f64>,
+        major_radius: f64,
+        minor_radius: f64,
+    ) -> Result<Self, Error> {
+        let invalid = |reason| Err(Error::InvalidConic { reason });
+
+        check_conic_axes(&normal, &major_direction)?;
+        if minor_radius.is_nan() || minor_radius <= 0.0 {
+            return invalid("the minor radius must be positive");
+        }
+        if major_radius.is_nan() || major_radius < minor_radius || major_radius.is_infinite() {
+            return invalid("the major radius must be at least the minor radius");
+        }
+
+        let axis = ffi::gp_Ax2_with_x_dir(
+            &make_point(center.into_point3()),
+            &make_dir(normal.into_inner()),
+            &make_dir(major_direction.into_inner()),
+        );
+
+        let ellipse = ffi::gp_Elips_ctor(&axis, major_radius, minor_radius);
+        let make_edge = ffi::BRepBuilderAPI_MakeEdge_ellipse(&ellipse);
+
+        Ok(Self::from_make_edge(make_edge))
+    }
+
+    /// The part of the parabola with its apex at `apex` and its focus at
+    /// `focus`, in the plane facing `normal`, between the parameters in
+    /// `t_range`. The parameter is the distance from the axis, so the
+    /// points are at `apex + t^2 / (4 f) x + t y`, where `f` is the focal
+    /// length, `x` points from the apex to the focus and `y` is
+    /// `normal.cross(x)`.
+    ///
+    /// The focus must be away from the apex, off the normal through it, and
+    /// `t_range` must run from a lower parameter to a higher one.
+    pub fn parabola(
+        focus: Point3<f64>,
+        apex: Point3<f64>,
+        normal: UnitVector3<f64>,
+        t_range: (f64, f64),
+    ) -> Result<Self, Error> {
+        let Some(axis_direction) = UnitVector3::try_new(focus - apex, CONFUSION) else {
+            return Err(Error::InvalidConic {
+                reason: "the focus must be away from the apex",
+            });
+        };
+        check_conic_axes(&normal, &axis_direction)?;
+        check_conic_range(t_range)?;
+
+        let axis = ffi::gp_Ax2_with_x_dir(
+            &make_point(apex),
+            &make_dir(normal.into_inner()),
+            &make_dir(focus - apex),
+        );
+        let focal = (focus - apex).norm();
+
+        let parabola = ffi::gp_Parab_ctor(&axis, focal);
+        let make_edge = ffi::BRepBuilderAPI_MakeEdge_parabola(&parabola, t_range.0, t_range.1);
+
+        Ok(Self::from_make_edge(make_edge))
+    }
+
+    /// The part of the branch of the hyperbola centered on `center` which
+    /// crosses its major axis, between the parameters in `t_range`. `axes`
+    /// are the direction of the major axis and the normal of the plane the
+    /// hyperbola is in, and `radii` the major and minor radii. The points are
+    /// at `center + major cosh(t) x + minor sinh(t) y`, where `x` is the
+    /// major axis and `y` is `normal.cross(x)`.
+    ///
+    /// The radii must be positive, and `t_range` must run from a lower
+    /// parameter to a higher one.
+    pub fn hyperbola(
+        center: Point3<f64>,
+        axes: (UnitVector3<f64>, UnitVector3<f64>),
+        radii: (f64, f64),
+        t_range: (f64, f64),
+    ) -> Result<Self, Error> {
+        let (major_direction, normal) = axes;
+        let positive = |radius: f64| radius.is_finite() && radius > 0.0;
+
+        check_conic_axes(&normal, &major_direction)?;
+        if !positive(radii.0) || !positive(radii.1) {
+            return Err(Error::InvalidConic {
+                reason: "the radii must be positive",
+            });
+        }
+        check_conic_range(t_range)?;
+
+        let axis = ffi::gp_Ax2_with_x_dir(
+            &make_point(center),
+            &make_dir(normal.into_inner()),
+            &make_dir(major_direction.into_inner()),
+        );
+
+        let hyperbola = ffi::gp_Hypr_ctor(&axis, radii.0, radii.1);
+        let make_edge = ffi::BRepBuilderAPI_MakeEdge_hyperbola(&hyperbola, t_range.0, t_range.1);
+
+        Ok(Self::from_make_edge(make_edge))
+    }
+
+    /// A conic arc from `p0` to `p1`, leaving `p0` along `t0` and arriving
+    /// at `p1` along `t1`, as CAD programs draw them. The arc passes through
+    /// the point `rho` of the way from the middle of `p0` and `p1` to where
+    /// the tangent lines meet: below 0.5 it's elliptical, at 0.5 parabolic
+    /// and above 0.5 hyperbolic.
+    ///
+    /// `rho` must be between 0 and 1, and the tangent lines must meet.
+    pub fn conic_arc_from_points_and_tangents(
+        p0: Point3<f64>,
+        t0: Vector3<f64>,
+        p1: Point3<f64>,
+        t1: Vector3<f64>,
+        rho: f64,
+    ) -> Result<Self, Error> {
+        if !(rho > 0.0 && rho < 1.0) {
+            return Err(Error::ParameterOutOfRange { parameter: rho });
+        }
+
+        let apex = tangent_intersection(p0, t0, p1, t1).ok_or(Error::TangentsDoNotMeet)?;
+        let weight = rho / (1.0 - rho);
+
+        let curve_handle = ffi::Geom_BezierCurve_rational_quadratic(
+            &make_point(p0),
+            &make_point(apex),
+            &make_point(p1),
+            weight,
+        );
+
+        let make_edge = ffi::BRepBuilderAPI_MakeEdge_HandleGeomCurve(&curve_handle);
+
+        Ok(Self::from_make_edge(make_edge))
+    }
 
     pub fn spline_from_points(
         points: impl IntoIterator<Item = Point3<f64>>,
@@ -131,7 +300,8 @@ impl Edge {
         Self::from_edge(edge)
     }
 
-    pub fn arc(p1: Point3<f64>, p2: Point3<f64>, p3: Point3<f64>) -> Self {
+    pub fn arc(p1: impl IntoPoint3, p2: impl IntoPoint3, p3: impl IntoPoint3) -> Self {
+        let (p1, p2, p3) = (p1.into_point3(), p2.into_point3(), p3.into_point3());
         let make_arc = ffi::GC_MakeArcOfCircle_point_point_point(
             &make_point(p1),
             &make_point(p2),
@@ -147,6 +317,39 @@ impl Edge {
         Self::from_make_edge(make_edge)
     }
 
+    /// The arc of the circle around `center` facing `axis`, going
+    /// counterclockwise about `axis` from `start_angle` to `end_angle`.
+    /// Angles are measured from the direction OCCT takes as the x direction
+    /// of a plane facing `axis`, which is where `Edge::circle` starts, so an
+    /// arc rebuilt from its `circle_params` is the same arc.
+    ///
+    /// `radius` must be positive, and the angles finite and different.
+    pub fn arc_from_center(
+        center: impl IntoPoint3,
+        axis: impl IntoUnitVector3,
+        radius: f64,
+        start_angle: Angle,
+        end_angle: Angle,
+    ) -> Result<Self, Error> {
+        let invalid = |reason| Err(Error::InvalidConic { reason });
+
+        // Written so that NaNs fail every check.
+        if !radius.is_finite() || radius <= 0.0 {
+            return invalid("the radius must be positive");
+        }
+        let (start, end) = (start_angle.radians(), end_angle.radians());
+        if !start.is_finite() || !end.is_finite() || start == end {
+            return invalid("the arc must go between two different, finite angles");
+        }
+
+        let axis = make_axis_2(center.into_point3(), axis.into_unit_vector3()?.into_inner());
+
+        let circle = ffi::gp_Circ_ctor(&axis, radius);
+        let make_edge = ffi::BRepBuilderAPI_MakeEdge_circle_arc(&circle, start, end);
+
+        Ok(Self::from_make_edge(make_edge))
+    }
+
     pub fn start_point(&self) -> Point3<f64> {
         let curve = ffi::BRepAdaptor_Curve_ctor(&self.inner);
         let start_param = curve.FirstParameter();
@@ -163,6 +366,20 @@ impl Edge {
         point![point.X(), point.Y(), point.Z()]
     }
 
+    /// The vertex at the start of this edge's curve, where `start_point` is.
+    pub fn start_vertex(&self) -> Vertex {
+        Vertex {
+            inner: ffi::TopExp_FirstVertex(&self.inner),
+        }
+    }
+
+    /// The vertex at the end of this edge's curve, where `end_point` is.
+    pub fn end_vertex(&self) -> Vertex {
+        Vertex {
+            inner: ffi::TopExp_LastVertex(&self.inner),
+        }
+    }
+
     pub fn approximation_segments(&self, tolerance: f64) -> ApproximationSegmentIterator {
         let adaptor_curve = ffi::BRepAdaptor_Curve_ctor(&self.inner);
         let approximator = ffi::GCPnts_TangentialDeflection_ctor(
@@ -203,11 +420,127 @@ impl Edge {
         Some((point![centerpoint.X(), centerpoint.Y(), centerpoint.Z()], vector![axis_norm.X(), axis_norm.Y(), axis_norm.Z()], radius))
     }
 
+    /// The circle this edge lies on and the angles it covers, or `None` if
+    /// it isn't a circle or an arc of one.
+    pub fn circle_params(&self) -> Option<CircleParams> {
+        let (center, axis, radius) = self.centerpoint_axis_radius()?;
+        let axis = UnitVector3::new_normalize(axis);
+
+        // The curve's parameter is the angle from the circle's own x
+        // direction, which needn't be the one `arc_from_center` uses, so
+        // measure the start angle from the position of the start point.
+        let reference = ffi::gp_Ax3_from_gp_Ax2(&make_axis_2(center, axis.into_inner()));
+        let x_dir = reference.XDirection();
+        let x_dir = vector![x_dir.X(), x_dir.Y(), x_dir.Z()];
+        let y_dir = axis.cross(&x_dir);
+
+        let start = self.start_point() - center;
+        let start_angle = start.dot(&y_dir).atan2(start.dot(&x_dir)).rem_euclid(TAU);
+
+        let curve = ffi::BRepAdaptor_Curve_ctor(&self.inner);
+        let sweep = curve.LastParameter() - curve.FirstParameter();
+
+        Some(CircleParams {
+            center,
+            axis,
+            radius,
+            angle_range: (
+                Angle::Radians(start_angle),
+                Angle::Radians(start_angle + sweep),
+            ),
+        })
+    }
+
+    /// The angle a circular edge turns through around its center, a full
+    /// turn for a whole circle, or `None` if it isn't a circle or an arc of
+    /// one.
+    pub fn arc_angle(&self) -> Option<Angle> {
+        if self.edge_type() != EdgeType::Circle {
+            return None;
+        }
+
+        let curve = ffi::BRepAdaptor_Curve_ctor(&self.inner);
+
+        Some(Angle::Radians(
+            curve.LastParameter() - curve.FirstParameter(),
+        ))
+    }
+
     pub fn length(&self) -> f64 {
         let adaptor_curve = ffi::BRepAdaptor_Curve_ctor(&self.inner);
         ffi::EdgeLength(&adaptor_curve)
     }
 
+    /// How far from this edge's curve its curves on the faces it bounds,
+    /// and its vertices, may be and still be taken to lie on it.
+    pub fn tolerance(&self) -> f64 {
+        ffi::BRep_Tool_Tolerance_edge(&self.inner)
+    }
+
+    /// This edge, running the other way.
+    #[must_use]
+    pub fn reversed(&self) -> Edge {
+        Edge {
+            inner: ffi::TopoDS_Edge_reversed(&self.inner),
+        }
+    }
+
+    /// Whether this edge runs against its curve, as edges in a wire may.
+    pub(crate) fn is_reversed(&self) -> bool {
+        ffi::cast_edge_to_shape(&self.inner).Orientation()
+            == ffi::TopAbs_Orientation::TopAbs_REVERSED
+    }
+
+    /// The piece of this edge between the points at curve parameters `from`
+    /// and `to`, running the same way as this edge. `trimmed` and the wire
+    /// splitting methods all cut edges with this. Fails for degenerate
+    /// edges, which have no curve to cut.
+    pub(crate) fn between_parameters(&self, from: f64, to: f64) -> Result<Edge, Error> {
+        let inner = ffi::TopoDS_Edge_trimmed(&self.inner, from.min(to), from.max(to));
+        if ffi::cast_edge_to_shape(&inner).IsNull() {
+            return Err(Error::NotDone);
+        }
+
+        // The trimmed edge runs along the curve, whatever this edge's
+        // orientation.
+        let piece = Edge { inner };
+        if self.is_reversed() {
+            Ok(piece.reversed())
+        } else {
+            Ok(piece)
+        }
+    }
+
+    /// The part of this edge from `start` to `end`, which are fractions of
+    /// its length from its start, with `0.0 <= start < end <= 1.0`. The
+    /// piece is on the same curve as this edge.
+    pub fn trimmed(&self, start: f64, end: f64) -> Result<Edge, Error> {
+        if !(0.0..=1.0).contains(&start) {
+            return Err(Error::ParameterOutOfRange { parameter: start });
+        }
+        if !(0.0..=1.0).contains(&end) || end <= start {
+            return Err(Error::ParameterOutOfRange { parameter: end });
+        }
+
+        let map = ArcLengthMap::from_edges([Edge::from_edge(&self.inner)]);
+        let from = map.parameter_at_length(start * map.total_length())?;
+        let to = map.parameter_at_length(end * map.total_length())?;
+
+        self.between_parameters(from, to)
+    }
+
+    /// This edge in two pieces, split `fraction` of its length from its
+    /// start, with `0.0 < fraction < 1.0`.
+    pub fn split_at(&self, fraction: f64) -> Result<(Edge, Edge), Error> {
+        if !(fraction > 0.0 && fraction < 1.0) {
+            return Err(Error::ParameterOutOfRange {
+                parameter: fraction,
+            });
+        }
+
+        Ok((self.trimmed(0.0, fraction)?, self.trimmed(fraction, 1.0)?))
+    }
+
     pub fn vertices(&self) -> VertexIterator {
         let shape: Shape = self.into();
 
@@ -215,6 +548,58 @@ impl Edge {
     }
 }
 
+/// Check that the in-plane axis of a conic, `x_direction`, isn't along the
+/// `normal` of its plane, which would leave the plane undefined.
+fn check_conic_axes(
+    normal: &UnitVector3<f64>,
+    x_direction: &UnitVector3<f64>,
+) -> Result<(), Error> {
+    if normal.cross(x_direction).norm() < CONFUSION {
+        return Err(Error::InvalidConic {
+            reason: "the axis must not be along the normal",
+        });
+    }
+
+    Ok(())
+}
+
+/// Check that a conic's parameter range is finite and not empty.
+fn check_conic_range((first, last): (f64, f64)) -> Result<(), Error> {
+    if !first.is_finite() || !last.is_finite() || last <= first {
+        return Err(Error::InvalidConic {
+            reason: "the parameter range must be finite and not empty",
+        });
+    }
+
+    Ok(())
+}
+
+/// Where the line through `p0` along `t0` meets the line through `p1` along
+/// `t1`, if it does ahead of `p0` and behind `p1`.
+fn tangent_intersection(
+    p0: Point3<f64>,
+    t0: Vector3<f64>,
+    p1: Point3<f64>,
+    t1: Vector3<f64>,
+) -> Option<Point3<f64>> {
+    let between = p1 - p0;
+    let (a, b, c) = (t0.dot(&t0), t0.dot(&t1), t1.dot(&t1));
+    let (d, e) = (t0.dot(&between), t1.dot(&between));
+
+    let denominator = a * c - b * b;
+    if denominator <= TANGENT_TOLERANCE * a * c {
+        return None;
+    }
+
+    let s = (d * c - b * e) / denominator;
+    let r = (b * d - a * e) / denominator;
+    let (q0, q1) = (p0 + t0 * s, p1 + t1 * r);
+
+    let skew = (q0 - q1).norm();
+
+    (s > 0.0 && r < 0.0 && skew <= TANGENT_TOLERANCE * between.norm()).then_some(q0)
+}
+
 pub struct ApproximationSegmentIterator {
     count: usize,
     approximator: UniquePtr<ffi::GCPnts_TangentialDeflection>,
@@ -251,3 +636,312 @@ impl From<&Shape> for Edge {
         Edge::from_edge(topo_edge)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::make_axis_1;
+    use crate::primitives::FaceType;
+
+    #[test]
+    fn arc_from_center_round_trips_through_circle_params() {
+        let center = point![1.0, 2.0, 3.0];
+        let axis = UnitVector3::new_normalize(vector![0.0, 1.0, 1.0]);
+        let arc = Edge::arc_from_center(
+            center,
+            axis.into_inner(),
+            4.0,
+            Angle::Degrees(30.0),
+            Angle::Degrees(135.0),
+        )
+        .unwrap();
+
+        let params = arc.circle_params().unwrap();
+        assert!((params.center - center).norm() < 1.0e-9);
+        assert!((params.axis.into_inner() - axis.into_inner()).norm() < 1.0e-9);
+        assert!((params.radius - 4.0).abs() < 1.0e-9);
+        assert!((params.angle_range.0.degrees() - 30.0).abs() < 1.0e-9);
+        assert!((params.angle_range.1.degrees() - 135.0).abs() < 1.0e-9);
+
+        assert!((arc.arc_angle().unwrap().degrees() - 105.0).abs() < 1.0e-9);
+        assert!((arc.length() - 4.0 * 105.0f64.to_radians()).abs() < 1.0e-9);
+
+        // Rebuilding the arc from its parameters gives the same arc.
+        let rebuilt = Edge::arc_from_center(
+            params.center,
+            params.axis.into_inner(),
+            params.radius,
+            params.angle_range.0,
+            params.angle_range.1,
+        )
+        .unwrap();
+        assert!((rebuilt.start_point() - arc.start_point()).norm() < 1.0e-9);
+        assert!((rebuilt.end_point() - arc.end_point()).norm() < 1.0e-9);
+    }
+
+    #[test]
+    fn invalid_arcs_from_center() {
+        let arc = |radius, start, end| {
+            Edge::arc_from_center(
+                Point3::origin(),
+                Vector3::z(),
+                radius,
+                Angle::Degrees(start),
+                Angle::Degrees(end),
+            )
+        };
+
+        for (radius, start, end) in [
+            (0.0, 0.0, 90.0),
+            (-1.0, 0.0, 90.0),
+            (f64::NAN, 0.0, 90.0),
+            (f64::INFINITY, 0.0, 90.0),
+            (1.0, 45.0, 45.0),
+            (1.0, f64::NAN, 90.0),
+            (1.0, 0.0, f64::INFINITY),
+        ] {
+            assert!(
+                matches!(arc(radius, start, end), Err(Error::InvalidConic { .. })),
+                "{radius} {start} {end}"
+            );
+        }
+        assert!(arc(1.0, 0.0, 90.0).is_ok());
+    }
+
+    #[test]
+    fn circle_params_of_other_edges() {
+        let circle = Edge::circle(point![0.0, 0.0, 5.0], Vector3::z(), 2.0).unwrap();
+        let params = circle.circle_params().unwrap();
+        assert!(params.angle_range.0.radians().abs() < 1.0e-9);
+        assert!((params.angle_range.1.radians() - TAU).abs() < 1.0e-9);
+        assert!((circle.arc_angle().unwrap().radians() - TAU).abs() < 1.0e-9);
+
+        let segment = Edge::segment(Point3::origin(), point![1.0, 0.0, 0.0]);
+        assert!(segment.circle_params().is_none());
+        assert!(segment.arc_angle().is_none());
+    }
+
+    #[test]
+    fn revolved_parabola_reflects_rays_to_its_focus() {
+        let focus = point![0.0, 0.0, 2.5];
+        let apex = point![0.0, 0.0, 0.0];
+        let parabola = Edge::parabola(focus, apex, Vector3::y_axis(), (0.0, 10.0)).unwrap();
+        assert_eq!(parabola.edge_type(), EdgeType::Parabola);
+
+        let copy = false;
+        let mut make_revol = ffi::BRepPrimAPI_MakeRevol_ctor(
+            ffi::cast_edge_to_shape(&parabola.inner),
+            &make_axis_1(apex, Vector3::z()),
+            std::f64::consts::TAU,
+            copy,
+        );
+        let reflector = Shape::from_shape(make_revol.pin_mut().Shape());
+
+        let faces: Vec<_> = reflector.faces().collect();
+        assert_eq!(faces.len(), 1);
+        assert_eq!(faces[0].face_type(), FaceType::SurfaceOfRevolution);
+
+        // Rays coming straight down the axis bounce off towards the focus.
+        let down = -Vector3::z();
+        for (x, y) in [(1.0, 0.0), (3.0, 2.0), (-5.0, 4.0), (0.5, -8.0)] {
+            let hits = reflector.faces_along_line(point![x, y, 20.0], down);
+            assert_eq!(hits.len(), 1);
+
+            let hit = &hits[0];
+            let normal = hit.face.normal_at(hit.point).into_inner();
+            let reflected = down - 2.0 * down.dot(&normal) * normal;
+
+            let miss = (focus - hit.point).cross(&reflected).norm();
+            assert!(miss < 1.0e-6, "ray at ({x}, {y}) misses by {miss}");
+        }
+    }
+
+    #[test]
+    fn revolved_parabola_is_an_exact_paraboloid_in_step() {
+        let apex = point![0.0, 0.0, 0.0];
+        let parabola =
+            Edge::parabola(point![0.0, 0.0, 2.5], apex, Vector3::y_axis(), (0.0, 10.0)).unwrap();
+
+        let copy = false;
+        let mut make_revol = ffi::BRepPrimAPI_MakeRevol_ctor(
+            ffi::cast_edge_to_shape(&parabola.inner),
+            &make_axis_1(apex, Vector3::z()),
+            std::f64::consts::TAU,
+            copy,
+        );
+        let reflector = Shape::from_shape(make_revol.pin_mut().Shape());
+
+        let path = std::env::temp_dir().join(format!(
+            "opencascade-paraboloid-{}.step",
+            std::process::id()
+        ));
+        reflector.write_step(&path).unwrap();
+        let step = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // The surface is the parabola itself, swept round, not a B-spline
+        // approximating it.
+        assert!(step.contains("SURFACE_OF_REVOLUTION"));
+        assert!(step.contains("PARABOLA"));
+        assert!(!step.contains("B_SPLINE"));
+    }
+
+    #[test]
+    fn conics_are_checked() {
+        let origin = Point3::origin();
+        let invalid = |edge: Result<Edge, Error>| matches!(edge, Err(Error::InvalidConic { .. }));
+
+        assert!(invalid(Edge::ellipse(
+            origin,
+            Vector3::z_axis(),
+            Vector3::x_axis(),
+            2.0,
+            3.0
+        )));
+        assert!(invalid(Edge::ellipse(
+            origin,
+            Vector3::z_axis(),
+            Vector3::x_axis(),
+            3.0,
+            0.0
+        )));
+        assert!(invalid(Edge::ellipse(
+            origin,
+            Vector3::z_axis(),
+            Vector3::x_axis(),
+            f64::NAN,
+            1.0
+        )));
+        assert!(invalid(Edge::ellipse(
+            origin,
+            Vector3::z_axis(),
+            Vector3::z_axis(),
+            3.0,
+            2.0
+        )));
+
+        let focus = point![0.0, 0.0, 1.0];
+        assert!(invalid(Edge::parabola(
+            origin,
+            origin,
+            Vector3::y_axis(),
+            (0.0, 1.0)
+        )));
+        assert!(invalid(Edge::parabola(
+            focus,
+            origin,
+            Vector3::z_axis(),
+            (0.0, 1.0)
+        )));
+        assert!(invalid(Edge::parabola(
+            focus,
+            origin,
+            Vector3::y_axis(),
+            (1.0, 1.0)
+        )));
+        assert!(invalid(Edge::parabola(
+            focus,
+            origin,
+            Vector3::y_axis(),
+            (0.0, f64::NAN)
+        )));
+
+        let axes = (Vector3::x_axis(), Vector3::z_axis());
+        assert!(invalid(Edge::hyperbola(
+            origin,
+            axes,
+            (3.0, -2.0),
+            (-1.0, 1.0)
+        )));
+        assert!(invalid(Edge::hyperbola(
+            origin,
+            axes,
+            (3.0, 2.0),
+            (1.0, -1.0)
+        )));
+    }
+
+    #[test]
+    fn hyperbola_points() {
+        let center = point![1.0, 2.0, 3.0];
+        let (major, minor) = (3.0, 2.0);
+        let hyperbola = Edge::hyperbola(
+            center,
+            (Vector3::x_axis(), Vector3::z_axis()),
+            (major, minor),
+            (-1.0, 1.0),
+        )
+        .unwrap();
+        assert_eq!(hyperbola.edge_type(), EdgeType::Hyperbola);
+
+        let start = hyperbola.start_point();
+        let expected = center + Vector3::new(major * 1.0f64.cosh(), -minor * 1.0f64.sinh(), 0.0);
+        assert!((start - expected).norm() < 1.0e-9, "{start}");
+
+        // Every point is on (x / a)^2 - (y / b)^2 = 1.
+        for point in hyperbola.approximation_segments(0.01) {
+            let local = point - center;
+            let value = (local.x / major).powi(2) - (local.y / minor).powi(2);
+            assert!((value - 1.0).abs() < 1.0e-6, "{point}");
+        }
+    }
+
+    #[test]
+    fn ellipse_length() {
+        let ellipse = Edge::ellipse(
+            point![0.0, 0.0, 0.0],
+            Vector3::z_axis(),
+            Vector3::x_axis(),
+            5.0,
+            5.0,
+        )
+        .unwrap();
+        assert_eq!(ellipse.edge_type(), EdgeType::Ellipse);
+        assert!((ellipse.length() - std::f64::consts::TAU * 5.0).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn rho_conic() {
+        let (p0, p1) = (point![0.0, 0.0, 0.0], point![10.0, 0.0, 0.0]);
+        let (t0, t1) = (Vector3::new(1.0, 1.0, 0.0), Vector3::new(1.0, -1.0, 0.0));
+        let apex = point![5.0, 5.0, 0.0];
+        let middle = point![5.0, 0.0, 0.0];
+
+        for rho in [0.3, 0.5, 0.8] {
+            let arc = Edge::conic_arc_from_points_and_tangents(p0, t0, p1, t1, rho).unwrap();
+            assert!((arc.start_point() - p0).norm() < 1.0e-9);
+            assert!((arc.end_point() - p1).norm() < 1.0e-9);
+
+            // By symmetry, the shoulder of the arc is halfway along it.
+            let shoulder = arc.split_at(0.5).unwrap().0.end_point();
+            let expected = middle + rho * (apex - middle);
+            assert!((shoulder - expected).norm() < 1.0e-6, "{rho}: {shoulder}");
+        }
+
+        let parallel = Edge::conic_arc_from_points_and_tangents(p0, t0, p1, t0, 0.5);
+        assert!(matches!(parallel, Err(Error::TangentsDoNotMeet)));
+
+        for rho in [0.0, 1.0, f64::NAN] {
+            let flat = Edge::conic_arc_from_points_and_tangents(p0, t0, p1, t1, rho);
+            assert!(matches!(flat, Err(Error::ParameterOutOfRange { .. })));
+        }
+    }
+
+    #[test]
+    fn rho_conic_tangents_meet_at_any_scale() {
+        // A tangent tilted 1e-5 out of the plane misses the other, whatever
+        // the size of the sketch.
+        for scale in [1.0e-4, 1.0, 1.0e6] {
+            let (p0, p1) = (Point3::origin(), point![10.0, 0.0, 0.0] * scale);
+            let (t0, t1) = (vector![1.0, 1.0, 0.0], vector![1.0, -1.0, 0.0]);
+            let arc = Edge::conic_arc_from_points_and_tangents(p0, t0, p1, t1, 0.5).unwrap();
+            assert!((arc.end_point() - p1).norm() < 1.0e-9 * scale.max(1.0));
+
+            let skew = vector![1.0, -1.0, 1.0e-5];
+            assert!(matches!(
+                Edge::conic_arc_from_points_and_tangents(p0, t0, p1, skew, 0.5),
+                Err(Error::TangentsDoNotMeet)
+            ));
+        }
+    }
+}
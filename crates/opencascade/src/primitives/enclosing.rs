@@ -0,0 +1,410 @@
+use crate::primitives::Shape;
+use crate::Error;
+use nalgebra::Matrix3;
+use nalgebra::Point3;
+use nalgebra::UnitVector3;
+use nalgebra::Vector3;
+
+/// Points closer to the surface of a ball than this fraction of the size of
+/// the point cloud are considered to be on it.
+const RELATIVE_TOLERANCE: f64 = 1.0e-9;
+
+/// The smallest cylinder around a shape, for a given axis direction.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EnclosingCylinder {
+    /// The center of the bottom cap.
+    pub base: Point3<f64>,
+    /// From the bottom cap to the top cap.
+    pub axis: UnitVector3<f64>,
+    pub radius: f64,
+    pub height: f64,
+}
+
+impl EnclosingCylinder {
+    pub fn volume(&self) -> f64 {
+        std::f64::consts::PI * self.radius.powi(2) * self.height
+    }
+}
+
+impl Shape {
+    /// The center and radius of the smallest sphere around this shape,
+    /// computed with Welzl's algorithm over the vertices of its mesh.
+    ///
+    /// Mesh vertices lie on the surface, so the sphere is exact for shapes
+    /// whose extreme points are vertices or edges, and otherwise can be
+    /// short of curved faces by up to the mesh deflection (0.01). A shape
+    /// with nothing to mesh, such as an empty one, is an
+    /// `Error::TriangulationFailed`.
+    pub fn min_enclosing_sphere(&self) -> Result<(Point3<f64>, f64), Error> {
+        let points = self.mesh()?.vertices;
+
+        if points.is_empty() {
+            return Err(Error::TriangulationFailed);
+        }
+
+        let ball = min_ball(&points);
+
+        Ok((ball.center, ball.radius))
+    }
+
+    /// The smallest cylinder around this shape along `axis_hint`, or, if
+    /// there's no hint, the one with the smallest volume along any of the
+    /// principal axes of the shape's mesh vertices. Those are only a
+    /// heuristic: they follow the spread of the vertices, so the cylinder
+    /// along them isn't always the smallest one around the shape. Like
+    /// `min_enclosing_sphere`, this works on the mesh vertices, with the
+    /// same tolerance, and fails the same way for a shape with nothing to
+    /// mesh.
+    pub fn min_enclosing_cylinder(
+        &self,
+        axis_hint: Option<UnitVector3<f64>>,
+    ) -> Result<EnclosingCylinder, Error> {
+        let points = self.mesh()?.vertices;
+
+        if points.is_empty() {
+            return Err(Error::TriangulationFailed);
+        }
+
+        let cylinder = |axis| enclosing_cylinder(&points, axis);
+
+        Ok(match axis_hint {
+            Some(axis) => cylinder(axis),
+            None => principal_axes(&points)
+                .into_iter()
+                .map(cylinder)
+                .min_by(|a, b| a.volume().total_cmp(&b.volume()))
+                .unwrap(),
+        })
+    }
+}
+
+fn enclosing_cylinder(points: &[Point3<f64>], axis: UnitVector3<f64>) -> EnclosingCylinder {
+    // Any two directions perpendicular to the axis.
+    let axis_dir = axis.into_inner();
+    let helper = if axis.x.abs() < 0.9 {
+        Vector3::x()
+    } else {
+        Vector3::y()
+    };
+    let u = axis_dir.cross(&helper).normalize();
+    let v = axis_dir.cross(&u);
+
+    // The smallest ball around points in a plane is centered in the plane,
+    // so its cross section is the smallest circle around them.
+    let projected: Vec<_> = points
+        .iter()
+        .map(|p| Point3::new(p.coords.dot(&u), p.coords.dot(&v), 0.0))
+        .collect();
+    let circle = min_ball(&projected);
+
+    let heights = points.iter().map(|p| p.coords.dot(&axis_dir));
+    let bottom = heights.clone().fold(f64::INFINITY, f64::min);
+    let top = heights.fold(f64::NEG_INFINITY, f64::max);
+
+    EnclosingCylinder {
+        base: Point3::from(u * circle.center.x + v * circle.center.y + axis_dir * bottom),
+        axis,
+        radius: circle.radius,
+        height: top - bottom,
+    }
+}
+
+/// The eigenvectors of the covariance of `points`.
+fn principal_axes(points: &[Point3<f64>]) -> [UnitVector3<f64>; 3] {
+    let mean = points.iter().map(|p| p.coords).sum::<Vector3<f64>>() / points.len() as f64;
+    let covariance = points
+        .iter()
+        .map(|p| (p.coords - mean) * (p.coords - mean).transpose())
+        .sum::<Matrix3<f64>>();
+
+    let eigenvectors = covariance.symmetric_eigen().eigenvectors;
+
+    [0, 1, 2].map(|i| UnitVector3::new_normalize(eigenvectors.column(i).into_owned()))
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Ball {
+    center: Point3<f64>,
+    radius: f64,
+}
+
+impl Ball {
+    fn contains(&self, point: &Point3<f64>, epsilon: f64) -> bool {
+        (point - self.center).norm() <= self.radius + epsilon
+    }
+
+    fn from_2(a: &Point3<f64>, b: &Point3<f64>) -> Self {
+        Self {
+            center: nalgebra::center(a, b),
+            radius: (a - b).norm() / 2.0,
+        }
+    }
+
+    /// The smallest ball with `a`, `b` and `c` on its surface.
+    fn from_3(a: &Point3<f64>, b: &Point3<f64>, c: &Point3<f64>) -> Self {
+        let (ab, ac) = (b - a, c - a);
+        let normal = ab.cross(&ac);
+        let denominator = 2.0 * normal.norm_squared();
+
+        if denominator <= f64::EPSILON * ab.norm_squared() * ac.norm_squared() {
+            // Collinear, so the ends of the longest side.
+            return Self::enclosing(
+                [Self::from_2(a, b), Self::from_2(a, c), Self::from_2(b, c)],
+                &[*a, *b, *c],
+            );
+        }
+
+        let offset = (normal.cross(&ab) * ac.norm_squared()
+            + ac.cross(&normal) * ab.norm_squared())
+            / denominator;
+
+        Self {
+            center: a + offset,
+            radius: offset.norm(),
+        }
+    }
+
+    /// The ball with `a`, `b`, `c` and `d` on its surface.
+    fn from_4(a: &Point3<f64>, b: &Point3<f64>, c: &Point3<f64>, d: &Point3<f64>) -> Self {
+        let (ab, ac, ad) = (b - a, c - a, d - a);
+        let matrix = Matrix3::from_rows(&[ab.transpose(), ac.transpose(), ad.transpose()]);
+        let rhs = Vector3::new(ab.norm_squared(), ac.norm_squared(), ad.norm_squared()) / 2.0;
+
+        match matrix.try_inverse() {
+            Some(inverse)
+                if matrix.determinant().abs()
+                    > f64::EPSILON * ab.norm() * ac.norm() * ad.norm() =>
+            {
+                let offset = inverse * rhs;
+                Self {
+                    center: a + offset,
+                    radius: offset.norm(),
+                }
+            }
+            // Coplanar, so the smallest of the balls through three of them
+            // which contains the fourth.
+            _ => Self::enclosing(
+                [
+                    Self::from_3(a, b, c),
+                    Self::from_3(a, b, d),
+                    Self::from_3(a, c, d),
+                    Self::from_3(b, c, d),
+                ],
+                &[*a, *b, *c, *d],
+            ),
+        }
+    }
+
+    /// The smallest of `candidates` which contains all of `points`, or the
+    /// largest candidate if none do.
+    fn enclosing<const N: usize>(candidates: [Ball; N], points: &[Point3<f64>]) -> Self {
+        let by_radius = |a: &Ball, b: &Ball| a.radius.total_cmp(&b.radius);
+        let epsilon = candidates
+            .iter()
+            .map(|ball| ball.radius)
+            .fold(0.0, f64::max)
+            * RELATIVE_TOLERANCE;
+
+        candidates
+            .iter()
+            .filter(|ball| points.iter().all(|point| ball.contains(point, epsilon)))
+            .min_by(|a, b| by_radius(a, b))
+            .or_else(|| candidates.iter().max_by(|a, b| by_radius(a, b)))
+            .copied()
+            .unwrap()
+    }
+}
+
+/// Welzl's algorithm, in its iterative form: whenever a point is outside the
+/// ball around the points before it, it must be on the surface of the
+/// smallest ball around them all.
+fn min_ball(points: &[Point3<f64>]) -> Ball {
+    let Some(first) = points.first() else {
+        return Ball {
+            center: Point3::origin(),
+            radius: 0.0,
+        };
+    };
+
+    // Visit the points in a scrambled (but deterministic) order, which makes
+    // the expected running time linear.
+    let mut points = points.to_vec();
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    for i in (1..points.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        points.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+
+    let size = points
+        .iter()
+        .map(|p| (p - first).norm())
+        .fold(0.0, f64::max);
+    let epsilon = size * RELATIVE_TOLERANCE;
+
+    let mut ball = Ball {
+        center: points[0],
+        radius: 0.0,
+    };
+    for (i, p) in points.iter().enumerate().skip(1) {
+        if ball.contains(p, epsilon) {
+            continue;
+        }
+
+        ball = Ball {
+            center: *p,
+            radius: 0.0,
+        };
+        for (j, q) in points[..i].iter().enumerate() {
+            if ball.contains(q, epsilon) {
+                continue;
+            }
+
+            ball = Ball::from_2(p, q);
+            for (k, r) in points[..j].iter().enumerate() {
+                if ball.contains(r, epsilon) {
+                    continue;
+                }
+
+                ball = Ball::from_3(p, q, r);
+                for s in &points[..k] {
+                    if !ball.contains(s, epsilon) {
+                        ball = Ball::from_4(p, q, r, s);
+                    }
+                }
+            }
+        }
+    }
+
+    ball
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::Compound;
+    use crate::TandR;
+    use nalgebra::point;
+    use nalgebra::UnitQuaternion;
+
+    fn rotation() -> UnitQuaternion<f64> {
+        UnitQuaternion::from_euler_angles(0.3, -0.7, 1.1)
+    }
+
+    /// A 10 x 20 x 30 box, rotated and moved away from the origin.
+    fn rotated_box() -> (Shape, TandR<f64>) {
+        let placement = TandR::new(Vector3::new(5.0, -3.0, 8.0), rotation());
+        let mut block =
+            Shape::box_from_corners(point![-5.0, -10.0, -15.0], point![5.0, 10.0, 15.0]);
+        block.transform(&placement);
+
+        (block, placement)
+    }
+
+    #[test]
+    fn empty_shapes_have_no_enclosure() {
+        let empty = Shape::empty();
+
+        assert!(matches!(
+            empty.min_enclosing_sphere(),
+            Err(Error::TriangulationFailed)
+        ));
+        assert!(matches!(
+            empty.min_enclosing_cylinder(None),
+            Err(Error::TriangulationFailed)
+        ));
+    }
+
+    #[test]
+    fn sphere_around_rotated_box() {
+        let (block, placement) = rotated_box();
+        let (center, radius) = block.min_enclosing_sphere().unwrap();
+
+        // Half the diagonal, around the center of the box.
+        assert!((radius - 1400.0f64.sqrt() / 2.0).abs() < 1.0e-6, "{radius}");
+        assert!((center - placement.transform_point(Point3::origin())).norm() < 1.0e-6);
+    }
+
+    #[test]
+    fn cylinder_around_rotated_box() {
+        let (block, placement) = rotated_box();
+
+        // Along the long side, around the diagonal of the 10 x 20 face.
+        let long_side = placement.rotate_norm(UnitVector3::new_unchecked(Vector3::z()));
+        let cylinder = block.min_enclosing_cylinder(Some(long_side)).unwrap();
+        assert!((cylinder.radius - 500.0f64.sqrt() / 2.0).abs() < 1.0e-6);
+        assert!((cylinder.height - 30.0).abs() < 1.0e-6);
+        let bottom = placement.transform_point(point![0.0, 0.0, -15.0]);
+        assert!((cylinder.base - bottom).norm() < 1.0e-6);
+
+        // Without a hint, the smallest is along the short side, around the
+        // diagonal of the 20 x 30 face: 325 pi x 10 beats 125 pi x 30 and
+        // 250 pi x 20.
+        let cylinder = block.min_enclosing_cylinder(None).unwrap();
+        let short_side = placement.rotate_norm(UnitVector3::new_unchecked(Vector3::x()));
+        assert!(cylinder.axis.dot(&short_side.into_inner()).abs() > 1.0 - 1.0e-6);
+        assert!((cylinder.radius - 1300.0f64.sqrt() / 2.0).abs() < 1.0e-6);
+        assert!((cylinder.height - 10.0).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn capsule() {
+        // A radius 5 rod from z = 0 to 20, with hemispherical ends.
+        let rod = Shape::cylinder_radius_height(5.0, 20.0);
+        let bottom = Shape::sphere(5.0).build();
+        let top = Shape::sphere(5.0).at(point![0.0, 0.0, 20.0]).build();
+        let capsule = rod.union(&bottom).union(&top);
+
+        let (center, radius) = capsule.min_enclosing_sphere().unwrap();
+        assert!((radius - 15.0).abs() < 1.0e-3, "{radius}");
+        assert!((center - point![0.0, 0.0, 10.0]).norm() < 1.0e-3);
+
+        let cylinder = capsule
+            .min_enclosing_cylinder(Some(Vector3::z_axis()))
+            .unwrap();
+        assert!((cylinder.radius - 5.0).abs() < 1.0e-3);
+        assert!((cylinder.height - 30.0).abs() < 1.0e-3);
+        assert!((cylinder.base - point![0.0, 0.0, -5.0]).norm() < 1.0e-3);
+
+        // The principal axes find the same cylinder.
+        let found = capsule.min_enclosing_cylinder(None).unwrap();
+        assert!(found.axis.dot(&Vector3::z()).abs() > 1.0 - 1.0e-6);
+        assert!((found.volume() - cylinder.volume()).abs() < 1.0e-3 * cylinder.volume());
+    }
+
+    #[test]
+    fn compounds() {
+        let a = Shape::sphere(1.0).at(point![-10.0, 0.0, 0.0]).build();
+        let b = Shape::sphere(2.0).at(point![10.0, 0.0, 0.0]).build();
+        let compound: Shape = Compound::from_shapes([a, b]).into();
+
+        let (center, radius) = compound.min_enclosing_sphere().unwrap();
+        assert!((radius - 11.5).abs() < 1.0e-2, "{radius}");
+        assert!((center - point![0.5, 0.0, 0.0]).norm() < 1.0e-2);
+    }
+
+    #[test]
+    fn welzl_is_exact_for_random_points() {
+        let mut state = 1u64;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let points: Vec<_> = (0..1000).map(|_| point![next(), next(), next()]).collect();
+        let ball = min_ball(&points);
+
+        // Contains everything, with at least two points on its surface.
+        let distances: Vec<_> = points.iter().map(|p| (p - ball.center).norm()).collect();
+        assert!(distances.iter().all(|d| *d <= ball.radius + 1.0e-9));
+        assert!(
+            distances
+                .iter()
+                .filter(|d| (**d - ball.radius).abs() < 1.0e-9)
+                .count()
+                >= 2
+        );
+    }
+}
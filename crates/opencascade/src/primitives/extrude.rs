@@ -0,0 +1,227 @@
+use crate::mesh::Mesh;
+use crate::primitives::make_dir;
+use crate::primitives::Face;
+use crate::primitives::Shape;
+use crate::primitives::Wire;
+use crate::workplane::Workplane;
+use crate::Error;
+use crate::TandR;
+use nalgebra::UnitQuaternion;
+use nalgebra::Vector3;
+use opencascade_sys::ffi;
+
+/// How far through-all tools reach past the far sides of the shape they cut.
+const THROUGH_ALL_MARGIN: f64 = 1.0;
+
+/// The deflection `extrude_up_to` meshes the part of the limit in front of
+/// the profile with, to measure how much of the profile it covers.
+const COVERAGE_DEFLECTION: f64 = 0.01;
+
+impl Shape {
+    /// Cut `profile`, which lies on `workplane`, straight through this
+    /// shape along the workplane normal, however thick the shape is. The
+    /// tool is extruded both ways from the profile, to past the far sides of
+    /// the shape's bounding box.
+    pub fn cut_profile_through_all(
+        &self,
+        profile: &Wire,
+        workplane: &Workplane,
+    ) -> Result<Shape, Error> {
+        let normal = workplane.normal().into_inner();
+        let (near, far) = self.extent_along(profile, normal);
+
+        let tool = extruded_between(profile, normal, near, far)?;
+
+        self.subtract(&tool)
+    }
+
+    /// Extrude `profile`, which lies on `workplane`, along the workplane
+    /// normal until it reaches `limit`, and fuse the result with this shape.
+    /// The limit may be on either side of the workplane, but must cover the
+    /// profile, or this returns `Error::ProfileMissesFace`.
+    pub fn extrude_up_to(
+        &self,
+        profile: &Wire,
+        workplane: &Workplane,
+        limit: &Face,
+    ) -> Result<Shape, Error> {
+        let normal = workplane.normal().into_inner();
+        let limit_shape = Shape::from(limit);
+
+        // An extrusion which misses the limit would run on forever, so
+        // check the profile reaches it first, and which way it lies.
+        let (near, far) = limit_shape.extent_along(profile, normal);
+        let tool = extruded_between(profile, normal, near, far)?;
+        let footprint = tool.intersect(&limit_shape)?.shape;
+
+        let Some(side) = footprint
+            .faces()
+            .map(|face| (face.center_of_mass() - profile.start_point()).dot(&normal))
+            .reduce(|a, b| if a.abs() > b.abs() { a } else { b })
+        else {
+            return Err(Error::ProfileMissesFace);
+        };
+        let direction = if side < 0.0 { -normal } else { normal };
+
+        // The limit covers the profile when the part of it in front of the
+        // profile, seen along the normal, is as big as the profile. Meshing
+        // cuts corners off curved edges by up to the deflection.
+        let profile_face = Face::from_wire(profile)?;
+        let covered = projected_area(&footprint.mesh_with_tolerance(COVERAGE_DEFLECTION)?, normal);
+        if profile_face.surface_area() - covered > profile.length() * COVERAGE_DEFLECTION {
+            return Err(Error::ProfileMissesFace);
+        }
+        let sketch_base = ffi::TopoDS_Face_ctor();
+        let fuse = 1; // 0 = subtractive, 1 = additive
+        let modify = false;
+
+        let mut make_prism = ffi::BRepFeat_MakePrism_ctor(
+            &self.inner,
+            ffi::cast_face_to_shape(&profile_face.inner),
+            &sketch_base,
+            &make_dir(direction),
+            fuse,
+            modify,
+        );
+
+        make_prism
+            .pin_mut()
+            .perform_until_face(ffi::cast_face_to_shape(&limit.inner));
+
+        if !make_prism.IsDone() {
+            return Err(Error::NotDone);
+        }
+
+        Ok(Shape::from_shape(make_prism.pin_mut().Shape()))
+    }
+
+    /// How far this shape's bounding box reaches behind and in front of
+    /// `profile` along `direction`, with a margin.
+    fn extent_along(&self, profile: &Wire, direction: Vector3<f64>) -> (f64, f64) {
+        let bounds = self.bounding_box();
+        let (min, max) = (bounds.min(), bounds.max());
+        let start = profile.start_point();
+
+        let (near, far) = [min.x, max.x]
+            .into_iter()
+            .flat_map(|x| [min.y, max.y].map(|y| (x, y)))
+            .flat_map(|(x, y)| [min.z, max.z].map(|z| Vector3::new(x, y, z)))
+            .map(|corner| (corner - start.coords).dot(&direction))
+            .fold((0.0, 0.0), |(near, far), height: f64| {
+                (height.min(near), height.max(far))
+            });
+
+        (near - THROUGH_ALL_MARGIN, far + THROUGH_ALL_MARGIN)
+    }
+}
+
+/// The area of the triangles of `mesh`, projected onto a plane
+/// perpendicular to `direction`, which must be a unit vector.
+fn projected_area(mesh: &Mesh, direction: Vector3<f64>) -> f64 {
+    mesh.indices
+        .chunks_exact(3)
+        .map(|triangle| {
+            let [a, b, c] = [0, 1, 2].map(|corner| mesh.vertices[triangle[corner]]);
+            (b - a).cross(&(c - a)).dot(&direction).abs() / 2.0
+        })
+        .sum()
+}
+
+/// The solid swept by `profile` from `near` to `far` along `direction`.
+fn extruded_between(
+    profile: &Wire,
+    direction: Vector3<f64>,
+    near: f64,
+    far: f64,
+) -> Result<Shape, Error> {
+    let face = Face::from_wire(profile)?;
+    let mut tool: Shape = face.extrude(direction * (far - near)).into();
+    tool.transform(&TandR::new(direction * near, UnitQuaternion::identity()));
+
+    Ok(tool)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nalgebra::point;
+    use nalgebra::vector;
+
+    /// A 20 x 10 block whose top slopes up from 2 high at x = 0 to 12 high
+    /// at x = 20.
+    fn wedge() -> Shape {
+        let side = Wire::from_ordered_points([
+            point![0.0, 0.0, 0.0],
+            point![20.0, 0.0, 0.0],
+            point![20.0, 0.0, 12.0],
+            point![0.0, 0.0, 2.0],
+        ])
+        .unwrap();
+
+        Face::from_wire(&side)
+            .unwrap()
+            .extrude(vector![0.0, 10.0, 0.0])
+            .into()
+    }
+
+    fn passes_through(shape: &Shape, x: f64, y: f64) -> bool {
+        shape
+            .faces_along_line(point![x, y, 0.0], Vector3::z())
+            .is_empty()
+    }
+
+    #[test]
+    fn cut_through_a_wedge() {
+        let wedge = wedge();
+
+        // A 12 x 4 slot from x = 4 to 16, through 4 to 10 of thickness.
+        let workplane = Workplane::xy().translated(vector![10.0, 5.0, 6.0]);
+        let slot = workplane.rect(12.0, 4.0).unwrap();
+        let cut = wedge.cut_profile_through_all(&slot, &workplane).unwrap();
+
+        // The slot is 7 thick on average.
+        let removed = wedge.volume() - cut.volume();
+        assert!((removed - 12.0 * 4.0 * 7.0).abs() < 1.0e-6, "{removed}");
+
+        for x in [5.0, 10.0, 15.0] {
+            assert!(passes_through(&cut, x, 5.0), "closed at x = {x}");
+        }
+        assert!(!passes_through(&cut, 2.0, 5.0));
+
+        // Flipping the workplane doesn't change the cut.
+        let flipped = Workplane::from_vectors(Vector3::x(), -Vector3::z())
+            .unwrap()
+            .translated(vector![10.0, 5.0, 6.0]);
+        let cut = wedge.cut_profile_through_all(&slot, &flipped).unwrap();
+        assert!((wedge.volume() - cut.volume() - removed).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn extrude_up_to_a_face() {
+        let plate = Shape::box_from_corners(point![0.0, 0.0, 0.0], point![20.0, 20.0, 2.0]);
+        let top = Workplane::xy().translated(vector![10.0, 10.0, 2.0]);
+        let profile = top.rect(5.0, 5.0).unwrap();
+
+        let ceiling = Workplane::xy().translated(vector![10.0, 10.0, 10.0]);
+        let limit = Face::from_wire(&ceiling.rect(40.0, 40.0).unwrap()).unwrap();
+
+        let boss = plate.extrude_up_to(&profile, &top, &limit).unwrap();
+        assert!((boss.volume() - (800.0 + 25.0 * 8.0)).abs() < 1.0e-6);
+        assert!((boss.bounding_box().max().z - 10.0).abs() < 1.0e-6);
+
+        let elsewhere = Workplane::xy().translated(vector![100.0, 100.0, 10.0]);
+        let missed = Face::from_wire(&elsewhere.rect(5.0, 5.0).unwrap()).unwrap();
+        assert!(matches!(
+            plate.extrude_up_to(&profile, &top, &missed),
+            Err(Error::ProfileMissesFace)
+        ));
+
+        // A limit covering only half the profile isn't enough either.
+        let half = Workplane::xy().translated(vector![11.25, 10.0, 10.0]);
+        let partial = Face::from_wire(&half.rect(2.5, 5.0).unwrap()).unwrap();
+        assert!(matches!(
+            plate.extrude_up_to(&profile, &top, &partial),
+            Err(Error::ProfileMissesFace)
+        ));
+    }
+}
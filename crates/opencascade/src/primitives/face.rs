@@ -5,23 +5,39 @@ use crate::primitives::make_axis_1;
 use crate::primitives::make_point;
 use crate::primitives::make_vec;
 use crate::primitives::EdgeIterator;
+use crate::primitives::IntoAxis;
 use crate::primitives::JoinType;
 use crate::primitives::Shape;
 use crate::primitives::Solid;
 use crate::primitives::Surface;
 use crate::primitives::Wire;
 use crate::primitives::WireIterator;
+use crate::workplane::FaceSketch;
 use crate::workplane::Workplane;
 use crate::Error;
 use crate::TandR;
 use cxx::UniquePtr;
 use nalgebra::point;
+use nalgebra::Matrix3;
 use nalgebra::vector;
 use nalgebra::Point3;
 use nalgebra::UnitVector3;
 use nalgebra::Vector3;
 use opencascade_sys::ffi::{self};
 
+const DEFAULT_PLANARITY_TOLERANCE: f64 = 1.0e-6;
+
+/// The reasons a wire can't be turned into a face.
+#[derive(thiserror::Error, Debug)]
+pub enum FaceError {
+    #[error("wire is not closed")]
+    NotClosed,
+    #[error("wire is not planar: it deviates {max_deviation} from its best fit plane, tolerance is {tolerance}")]
+    NotPlanar { max_deviation: f64, tolerance: f64 },
+    #[error("failed to build a face from the wire")]
+    ConstructionFailed,
+}
+
 pub struct Face {
     pub(crate) inner: UniquePtr<ffi::TopoDS_Face>,
 }
@@ -54,6 +70,86 @@ impl Face {
         Self::from_make_face(make_face)
     }
 
+    /// Build a planar face from a closed, planar wire, reporting precisely
+    /// why that isn't possible if it isn't. The face normal follows the
+    /// winding of the wire (counter-clockwise when looking down the normal).
+    pub fn try_from_wire(wire: &Wire) -> Result<Self, FaceError> {
+        Self::try_from_wire_with_tolerance(wire, DEFAULT_PLANARITY_TOLERANCE)
+    }
+
+    /// Like `try_from_wire`, but with a custom tolerance for how far the
+    /// wire may stray from its best fit plane.
+    pub fn try_from_wire_with_tolerance(wire: &Wire, tolerance: f64) -> Result<Self, FaceError> {
+        if !wire.is_closed() {
+            return Err(FaceError::NotClosed);
+        }
+
+        let points = wire_sample_points(wire, tolerance);
+
+        let (centroid, plane_normal) =
+            best_fit_plane(&points).ok_or(FaceError::ConstructionFailed)?;
+
+        let max_deviation = points
+            .iter()
+            .map(|p| (p - centroid).dot(&plane_normal).abs())
+            .fold(0.0, f64::max);
+
+        if max_deviation > tolerance {
+            return Err(FaceError::NotPlanar { max_deviation, tolerance });
+        }
+
+        let only_plane = true;
+        let make_face = ffi::BRepBuilderAPI_MakeFace_wire(&wire.inner, only_plane);
+
+        if !make_face.IsDone() {
+            return Err(FaceError::ConstructionFailed);
+        }
+
+        let face = Self::from_face(make_face.Face());
+
+        // Newell's method gives a normal whose direction follows the winding
+        // of the (ordered) sample points.
+        let winding_normal = points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .fold(Vector3::zeros(), |normal, (a, b)| normal + a.coords.cross(&b.coords));
+
+        if face.normal_at_center().dot(&winding_normal) < 0.0 {
+            return Ok(Self { inner: ffi::TopoDS_Face_reversed(&face.inner) });
+        }
+
+        Ok(face)
+    }
+
+    /// Build a face bounded by `wire` on `surface`, for wires lying on a
+    /// known, possibly curved, surface.
+    pub fn from_wire_on_surface(wire: &Wire, surface: &Surface) -> Result<Self, FaceError> {
+        if !wire.is_closed() {
+            return Err(FaceError::NotClosed);
+        }
+
+        let inside = true;
+        let make_face =
+            ffi::BRepBuilderAPI_MakeFace_surface_wire(&surface.inner, &wire.inner, inside);
+
+        if !make_face.IsDone() {
+            return Err(FaceError::ConstructionFailed);
+        }
+
+        // Edges which don't already lie on `surface` may be missing their
+        // curves in its parameter space.
+        let mut shape_fix = ffi::ShapeFix_Shape_new(ffi::cast_face_to_shape(make_face.Face()));
+        ffi::ShapeFix_Shape_perform(shape_fix.pin_mut());
+        let fixed_shape = ffi::ShapeFix_Shape_shape(shape_fix);
+
+        Ok(Self::from_face(ffi::TopoDS_cast_to_face(&fixed_shape)))
+    }
+
+    /// The underlying surface of this face.
+    pub fn surface(&self) -> Surface {
+        Surface { inner: ffi::BRep_Tool_Surface(&self.inner) }
+    }
+
     pub fn from_surface(surface: &Surface) -> Result<Self, Error> {
         const EDGE_TOLERANCE: f64 = 0.0001;
 
@@ -78,6 +174,37 @@ impl Face {
         Solid::from_solid(solid)
     }
 
+    /// Extrude this face both ways along its normal, `total_height / 2.0`
+    /// to each side, so the solid is centered on the face's plane.
+    pub fn extrude_symmetric(&self, total_height: f64) -> Result<Shape, Error> {
+        self.extrude_two_sided(Extent::Symmetric(total_height))
+    }
+
+    /// Extrude this face along its normal to both sides of it, as far as
+    /// `extent` says. The total height must be positive, or this returns
+    /// `Error::InvalidHeight`.
+    pub fn extrude_two_sided(&self, extent: Extent) -> Result<Shape, Error> {
+        self.extrude_along(self.normal_at_center(), extent)
+    }
+
+    /// Extrude this face to both sides of it along `direction`. It's one
+    /// prism, from the back to the front, so there's no seam face where the
+    /// face was, as there would be fusing a prism to each side.
+    pub(crate) fn extrude_along(
+        &self,
+        direction: UnitVector3<f64>,
+        extent: Extent,
+    ) -> Result<Shape, Error> {
+        let (front, back) = extent.front_and_back()?;
+        let direction = direction.into_inner();
+
+        let back_shift = TandR::default().translation(-direction * back);
+        let shifted = back_shift.transform_shape(ffi::cast_face_to_shape(&self.inner));
+        let start = Face::from_face(ffi::TopoDS_cast_to_face(&shifted));
+
+        Ok(start.extrude(direction * (front + back)).into())
+    }
+
     #[must_use]
     pub fn loft(&self, other: &Self) -> Result<Solid, Error> {
         let start_wire = self.outer_wire();
@@ -164,6 +291,15 @@ impl Face {
         Solid::from_solid(solid)
     }
 
+    /// Revolve this face around `axis`, a `DatumAxis` or an origin and a
+    /// direction, by `angle`, a full turn by default.
+    #[must_use]
+    pub fn revolve_about(&self, axis: impl IntoAxis, angle: Option<Angle>) -> Solid {
+        let axis = axis.into_axis();
+
+        self.revolve(axis.origin, axis.direction.into_inner(), angle)
+    }
+
     /// Fillets the face edges by a given radius at each vertex
     #[must_use]
     pub fn fillet(&self, radius: f64) -> Self {
@@ -350,19 +486,32 @@ impl Face {
 
         let center = self.center_of_mass();
         let normal = self.normal_at(center);
-        let mut x_dir = UnitVector3::new_normalize(Vector3::z().cross(&normal));
+        let x_dir = Vector3::z().cross(&normal);
 
-        if x_dir.magnitude() < NORMAL_DIFF_TOLERANCE {
+        let x_dir = if x_dir.magnitude() < NORMAL_DIFF_TOLERANCE {
             // The normal of this face is too close to the same direction
             // as the global Z axis. Use the global X axis for X instead.
-            x_dir = UnitVector3::new_unchecked(Vector3::x());
-        }
+            UnitVector3::new_unchecked(Vector3::x())
+        } else {
+            UnitVector3::new_normalize(x_dir)
+        };
 
         let mut workplane = Workplane::new(x_dir, normal);
         workplane.set_translation(center.coords);
         workplane
     }
 
+    /// Start a sketch on this face, which must be planar, in the coordinates
+    /// of `workplane`. Closing the sketch clips it to the face.
+    pub fn sketch(&self) -> Result<FaceSketch, Error> {
+        let face_type = self.face_type();
+        if face_type != FaceType::Plane {
+            return Err(Error::NotPlanar { face_type });
+        }
+
+        Ok(FaceSketch::new(self))
+    }
+
     pub fn union(&self, other: &Face) -> CompoundFace {
         let inner_shape = ffi::cast_face_to_shape(&self.inner);
         let other_inner_shape = ffi::cast_face_to_shape(&other.inner);
@@ -413,10 +562,36 @@ impl Face {
         props.Mass()
     }
 
+    /// How far from its surface this face's edges and vertices may be and
+    /// still be taken to lie on it.
+    pub fn tolerance(&self) -> f64 {
+        ffi::BRep_Tool_Tolerance_face(&self.inner)
+    }
+
     pub fn orientation(&self) -> FaceOrientation {
         FaceOrientation::from(self.inner.Orientation())
     }
 
+    pub fn face_type(&self) -> FaceType {
+        let surface = ffi::BRepAdaptor_Surface_ctor(&self.inner, true);
+
+        FaceType::from(surface.GetType())
+    }
+
+    /// The angle between the axis of a conical face and its surface, or
+    /// `None` if the face isn't conical.
+    pub fn cone_half_angle(&self) -> Option<Angle> {
+        if self.face_type() != FaceType::Cone {
+            return None;
+        }
+
+        let surface = ffi::BRepAdaptor_Surface_ctor(&self.inner, true);
+
+        Some(Angle::Radians(
+            ffi::BRepAdaptor_Surface_cone_semi_angle(&surface).abs(),
+        ))
+    }
+
     #[must_use]
     pub fn outer_wire(&self) -> Wire {
         let inner = ffi::outer_wire(&self.inner);
@@ -523,6 +698,14 @@ impl CompoundFace {
         Shape::from_shape(revolved_shape)
     }
 
+    /// Like `Face::revolve_about`.
+    #[must_use]
+    pub fn revolve_about(&self, axis: impl IntoAxis, angle: Option<Angle>) -> Shape {
+        let axis = axis.into_axis();
+
+        self.revolve(axis.origin, axis.direction.into_inner(), angle)
+    }
+
     #[must_use]
     pub fn union(&self, other: &CompoundFace) -> CompoundFace {
         let inner_shape = ffi::cast_compound_to_shape(&self.inner);
@@ -591,6 +774,77 @@ impl CompoundFace {
     }
 }
 
+/// How far a two-sided extrusion reaches to each side of the face it starts
+/// from.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Extent {
+    /// The total height, split evenly between the two sides.
+    Symmetric(f64),
+    /// How far to go along the normal, and how far against it.
+    Both(f64, f64),
+}
+
+impl Extent {
+    /// This extent with both distances multiplied by `factor`.
+    pub(crate) fn scaled(self, factor: f64) -> Extent {
+        match self {
+            Extent::Symmetric(total) => Extent::Symmetric(total * factor),
+            Extent::Both(front, back) => Extent::Both(front * factor, back * factor),
+        }
+    }
+
+    /// How far to go along the normal and against it, which must add up
+    /// to a positive height.
+    fn front_and_back(self) -> Result<(f64, f64), Error> {
+        let (front, back) = match self {
+            Extent::Symmetric(total) => (total / 2.0, total / 2.0),
+            Extent::Both(front, back) => (front, back),
+        };
+        let height = front + back;
+
+        // Written so that NaNs fail the check.
+        if !front.is_finite() || !back.is_finite() || height.is_nan() || height <= 0.0 {
+            return Err(Error::InvalidHeight { height });
+        }
+
+        Ok((front, back))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FaceType {
+    Plane,
+    Cylinder,
+    Cone,
+    Sphere,
+    Torus,
+    BezierSurface,
+    BSplineSurface,
+    SurfaceOfRevolution,
+    SurfaceOfExtrusion,
+    OffsetSurface,
+    OtherSurface,
+}
+
+impl From<ffi::GeomAbs_SurfaceType> for FaceType {
+    fn from(surface_type: ffi::GeomAbs_SurfaceType) -> Self {
+        match surface_type {
+            ffi::GeomAbs_SurfaceType::GeomAbs_Plane => Self::Plane,
+            ffi::GeomAbs_SurfaceType::GeomAbs_Cylinder => Self::Cylinder,
+            ffi::GeomAbs_SurfaceType::GeomAbs_Cone => Self::Cone,
+            ffi::GeomAbs_SurfaceType::GeomAbs_Sphere => Self::Sphere,
+            ffi::GeomAbs_SurfaceType::GeomAbs_Torus => Self::Torus,
+            ffi::GeomAbs_SurfaceType::GeomAbs_BezierSurface => Self::BezierSurface,
+            ffi::GeomAbs_SurfaceType::GeomAbs_BSplineSurface => Self::BSplineSurface,
+            ffi::GeomAbs_SurfaceType::GeomAbs_SurfaceOfRevolution => Self::SurfaceOfRevolution,
+            ffi::GeomAbs_SurfaceType::GeomAbs_SurfaceOfExtrusion => Self::SurfaceOfExtrusion,
+            ffi::GeomAbs_SurfaceType::GeomAbs_OffsetSurface => Self::OffsetSurface,
+            ffi::GeomAbs_SurfaceType::GeomAbs_OtherSurface => Self::OtherSurface,
+            ffi::GeomAbs_SurfaceType { repr } => panic!("Unexpected surface type: {repr}"),
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum FaceOrientation {
     Forward,
@@ -613,9 +867,52 @@ impl From<ffi::TopAbs_Orientation> for FaceOrientation {
     }
 }
 
+/// Points along `wire`, in order, at most `tolerance` away from the wire.
+fn wire_sample_points(wire: &Wire, tolerance: f64) -> Vec<Point3<f64>> {
+    let mut points: Vec<Point3<f64>> = vec![];
+
+    for edge in wire.edges() {
+        let mut edge_points: Vec<_> = edge.approximation_segments(tolerance.max(1.0e-7)).collect();
+
+        // Edges can run against the direction of the wire.
+        if let (Some(last), Some(first), Some(end)) =
+            (points.last(), edge_points.first(), edge_points.last())
+        {
+            if (last - end).norm() < (last - first).norm() {
+                edge_points.reverse();
+            }
+        }
+
+        // Each edge starts where the previous one ended.
+        let skip = usize::from(!points.is_empty());
+        points.extend(edge_points.into_iter().skip(skip));
+    }
+
+    points
+}
+
+/// The centroid and normal of the least squares plane through `points`.
+fn best_fit_plane(points: &[Point3<f64>]) -> Option<(Point3<f64>, Vector3<f64>)> {
+    if points.len() < 3 {
+        return None;
+    }
+
+    let centroid = points.iter().fold(Vector3::zeros(), |sum, p| sum + p.coords) / points.len() as f64;
+    let covariance = points.iter().fold(Matrix3::zeros(), |sum, p| {
+        let d = p.coords - centroid;
+        sum + d * d.transpose()
+    });
+
+    let eigen = covariance.symmetric_eigen();
+    let smallest = eigen.eigenvalues.imin();
+
+    Some((centroid.into(), eigen.eigenvectors.column(smallest).normalize()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::primitives::Edge;
 
     #[test]
     fn test_add() {
@@ -626,4 +923,94 @@ mod tests {
             face.surface_area()
         );
     }
+
+    #[test]
+    fn try_from_wire_reports_errors() {
+        let open = Wire::from_edges([
+            &Edge::segment(point![0.0, 0.0, 0.0], point![1.0, 0.0, 0.0]),
+            &Edge::segment(point![1.0, 0.0, 0.0], point![1.0, 1.0, 0.0]),
+        ])
+        .unwrap();
+        assert!(matches!(Face::try_from_wire(&open), Err(FaceError::NotClosed)));
+
+        let warped = Wire::from_ordered_points([
+            point![0.0, 0.0, 0.0],
+            point![10.0, 0.0, 0.0],
+            point![10.0, 10.0, 0.01],
+            point![0.0, 10.0, 0.0],
+        ])
+        .unwrap();
+
+        match Face::try_from_wire(&warped) {
+            Err(FaceError::NotPlanar { max_deviation, .. }) => {
+                assert!(max_deviation > 0.001 && max_deviation < 0.01)
+            },
+            _ => panic!("Expected a NotPlanar error"),
+        }
+    }
+
+    #[test]
+    fn try_from_wire_follows_winding() {
+        let counter_clockwise = Wire::from_ordered_points([
+            point![0.0, 0.0, 0.0],
+            point![10.0, 0.0, 0.0],
+            point![10.0, 10.0, 0.0],
+            point![0.0, 10.0, 0.0],
+        ])
+        .unwrap();
+        let clockwise = Wire::from_ordered_points([
+            point![0.0, 0.0, 0.0],
+            point![0.0, 10.0, 0.0],
+            point![10.0, 10.0, 0.0],
+            point![10.0, 0.0, 0.0],
+        ])
+        .unwrap();
+
+        let up = Face::try_from_wire(&counter_clockwise).unwrap();
+        let down = Face::try_from_wire(&clockwise).unwrap();
+
+        assert!(up.normal_at_center().z > 0.99);
+        assert!(down.normal_at_center().z < -0.99);
+    }
+
+    #[test]
+    fn extrude_symmetric_is_centered() {
+        let square = Wire::from_ordered_points([
+            point![0.0, 0.0, 0.0],
+            point![10.0, 0.0, 0.0],
+            point![10.0, 10.0, 0.0],
+            point![0.0, 10.0, 0.0],
+        ])
+        .unwrap();
+        let face = Face::try_from_wire(&square).unwrap();
+
+        let centered = face.extrude_symmetric(8.0).unwrap();
+        let bounds = centered.bounding_box();
+        assert!((bounds.min().z + 4.0).abs() < 1.0e-6);
+        assert!((bounds.max().z - 4.0).abs() < 1.0e-6);
+        assert!((centered.volume() - 800.0).abs() < 1.0e-6);
+
+        // One prism, with no seam face at z = 0.
+        let one_sided = Shape::from(face.extrude(vector![0.0, 0.0, 8.0]));
+        assert_eq!(centered.faces().count(), one_sided.faces().count());
+
+        let both = face.extrude_two_sided(Extent::Both(6.0, 2.0)).unwrap();
+        let bounds = both.bounding_box();
+        assert!((bounds.min().z + 2.0).abs() < 1.0e-6);
+        assert!((bounds.max().z - 6.0).abs() < 1.0e-6);
+        assert_eq!(both.faces().count(), 6);
+
+        for extent in [
+            Extent::Symmetric(0.0),
+            Extent::Symmetric(-8.0),
+            Extent::Symmetric(f64::NAN),
+            Extent::Both(2.0, -2.0),
+            Extent::Both(f64::INFINITY, 2.0),
+        ] {
+            assert!(matches!(
+                face.extrude_two_sided(extent),
+                Err(Error::InvalidHeight { .. })
+            ));
+        }
+    }
 }
@@ -0,0 +1,219 @@
+use crate::primitives::Edge;
+use crate::primitives::Face;
+use crate::primitives::Shape;
+use cxx::UniquePtr;
+use opencascade_sys::ffi;
+use std::pin::Pin;
+
+/// The parts of OCCT's boolean operations which `Shape`'s
+/// `*_with_history` booleans use, so they can share one implementation.
+pub(crate) trait BooleanOperation {
+    fn is_done(&self) -> bool;
+
+    fn section_edges(self: Pin<&mut Self>) -> &ffi::TopTools_ListOfShape;
+
+    fn shape(self: Pin<&mut Self>) -> &ffi::TopoDS_Shape;
+
+    fn history(
+        self: Pin<&mut Self>,
+        arguments: &ffi::TopTools_ListOfShape,
+    ) -> UniquePtr<ffi::BRepTools_History>;
+}
+
+impl BooleanOperation for ffi::BRepAlgoAPI_Fuse {
+    fn is_done(&self) -> bool {
+        self.IsDone()
+    }
+
+    fn section_edges(self: Pin<&mut Self>) -> &ffi::TopTools_ListOfShape {
+        self.SectionEdges()
+    }
+
+    fn shape(self: Pin<&mut Self>) -> &ffi::TopoDS_Shape {
+        self.Shape()
+    }
+
+    fn history(
+        self: Pin<&mut Self>,
+        arguments: &ffi::TopTools_ListOfShape,
+    ) -> UniquePtr<ffi::BRepTools_History> {
+        ffi::BRepTools_History_from_fuse(arguments, self)
+    }
+}
+
+impl BooleanOperation for ffi::BRepAlgoAPI_Cut {
+    fn is_done(&self) -> bool {
+        self.IsDone()
+    }
+
+    fn section_edges(self: Pin<&mut Self>) -> &ffi::TopTools_ListOfShape {
+        self.SectionEdges()
+    }
+
+    fn shape(self: Pin<&mut Self>) -> &ffi::TopoDS_Shape {
+        self.Shape()
+    }
+
+    fn history(
+        self: Pin<&mut Self>,
+        arguments: &ffi::TopTools_ListOfShape,
+    ) -> UniquePtr<ffi::BRepTools_History> {
+        ffi::BRepTools_History_from_cut(arguments, self)
+    }
+}
+
+impl BooleanOperation for ffi::BRepAlgoAPI_Common {
+    fn is_done(&self) -> bool {
+        self.IsDone()
+    }
+
+    fn section_edges(self: Pin<&mut Self>) -> &ffi::TopTools_ListOfShape {
+        self.SectionEdges()
+    }
+
+    fn shape(self: Pin<&mut Self>) -> &ffi::TopoDS_Shape {
+        self.Shape()
+    }
+
+    fn history(
+        self: Pin<&mut Self>,
+        arguments: &ffi::TopTools_ListOfShape,
+    ) -> UniquePtr<ffi::BRepTools_History> {
+        ffi::BRepTools_History_from_common(arguments, self)
+    }
+}
+
+/// Records how the sub-shapes (vertices, edges, faces, solids) of the input
+/// of an operation relate to the sub-shapes of its result.
+///
+/// Returned by the `*_with_history` variants of booleans, fillets, chamfers,
+/// offsets and sweeps, so that a face selected before an operation can still
+/// be found afterwards.
+pub struct History {
+    pub(crate) inner: UniquePtr<ffi::BRepTools_History>,
+    /// The shape the operation was performed on, or its first argument.
+    pub(crate) original: UniquePtr<ffi::TopoDS_Shape>,
+}
+
+impl History {
+    pub(crate) fn from_history(
+        inner: UniquePtr<ffi::BRepTools_History>,
+        original: &ffi::TopoDS_Shape,
+    ) -> Self {
+        let original = ffi::TopoDS_Shape_to_owned(original);
+
+        Self { inner, original }
+    }
+
+    /// Build the list of arguments an operation was performed on, in the
+    /// form `BRepTools_History` expects.
+    pub(crate) fn arguments<'a>(
+        shapes: impl IntoIterator<Item = &'a ffi::TopoDS_Shape>,
+    ) -> UniquePtr<ffi::TopTools_ListOfShape> {
+        let mut arguments = ffi::new_list_of_shape();
+
+        for shape in shapes {
+            ffi::shape_list_append_shape(arguments.pin_mut(), shape);
+        }
+
+        arguments
+    }
+
+    /// The shapes `original` was modified into. Empty if it was deleted or
+    /// left untouched.
+    pub fn modified(&self, original: &Shape) -> Vec<Shape> {
+        let modified = self.inner.Modified(&original.inner);
+
+        ffi::shape_list_to_vector(modified)
+            .iter()
+            .map(Shape::from_shape)
+            .collect()
+    }
+
+    /// The faces which `original` became after the operation. A face which
+    /// came through unchanged maps to itself, a deleted face maps to nothing.
+    pub fn modified_by(&self, original: &Face) -> Vec<Face> {
+        let original: Shape = original.into();
+
+        if self.inner.IsRemoved(&original.inner) {
+            return vec![];
+        }
+
+        let modified = self.modified(&original);
+
+        if modified.is_empty() {
+            return vec![Face::from(&original)];
+        }
+
+        modified.iter().map(Face::from).collect()
+    }
+
+    /// New shapes created from `original`, such as the fillet face built
+    /// along a filleted edge.
+    pub fn generated_from(&self, original: &Edge) -> Vec<Shape> {
+        let original: Shape = original.into();
+        let generated = self.inner.Generated(&original.inner);
+
+        ffi::shape_list_to_vector(generated)
+            .iter()
+            .map(Shape::from_shape)
+            .collect()
+    }
+
+    /// Whether `original` came through the operation unchanged: neither
+    /// deleted nor modified.
+    pub(crate) fn is_unchanged(&self, original: &ffi::TopoDS_Shape) -> bool {
+        !self.inner.IsRemoved(original)
+            && ffi::shape_list_to_vector(self.inner.Modified(original)).is_empty()
+    }
+
+    /// Returns true if `original` has no counterpart in the result.
+    pub fn is_deleted(&self, original: impl Into<Shape>) -> bool {
+        let original: Shape = original.into();
+
+        self.inner.IsRemoved(&original.inner)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::Direction;
+    use crate::primitives::ShapeType;
+
+    #[test]
+    fn fillet_history_tracks_adjacent_faces() {
+        let base = Shape::box_with_dimensions(10.0, 10.0, 10.0);
+
+        let top = base.faces().farthest(Direction::PosZ);
+        let side = base.faces().farthest(Direction::PosX);
+        let top_area = top.surface_area();
+        let side_area = side.surface_area();
+
+        let edge = base
+            .edges()
+            .find(|edge| {
+                let (start, end) = (edge.start_point(), edge.end_point());
+                [start, end]
+                    .iter()
+                    .all(|p| (p.x - 10.0).abs() < 1.0e-6 && (p.z - 10.0).abs() < 1.0e-6)
+            })
+            .expect("The box should have an edge between its top and +X faces");
+
+        let (_filleted, history) = base.fillet_edges_with_history(2.0, [&edge]);
+
+        let new_top = history.modified_by(&top);
+        let new_side = history.modified_by(&side);
+
+        assert_eq!(new_top.len(), 1);
+        assert_eq!(new_side.len(), 1);
+        assert!(new_top[0].surface_area() < top_area);
+        assert!(new_side[0].surface_area() < side_area);
+        assert!(!history.is_deleted(&top));
+
+        let generated = history.generated_from(&edge);
+
+        assert_eq!(generated.len(), 1);
+        assert_eq!(generated[0].shape_type(), ShapeType::Face);
+    }
+}
@@ -0,0 +1,458 @@
+use crate::primitives::Edge;
+use crate::primitives::Face;
+use crate::primitives::Shape;
+use crate::primitives::Solid;
+use crate::primitives::Wire;
+use crate::Error;
+use nalgebra::Point2;
+use nalgebra::Point3;
+use nalgebra::Vector3;
+use opencascade_sys::ffi;
+use std::collections::HashSet;
+
+/// Points closer to a plane or line than this fraction of the size of the
+/// point cloud are considered to lie on it.
+const RELATIVE_TOLERANCE: f64 = 1.0e-9;
+
+impl Shape {
+    /// The convex hull of `points`, computed with quickhull.
+    ///
+    /// The hull is normally a solid, with coplanar triangles of the hull
+    /// merged into polygonal faces. If the points are all coplanar the hull
+    /// is a planar `Face`, and if they're all collinear it's an `Edge`
+    /// between the two extreme points. Fewer than two distinct points return
+    /// `Error::DegenerateHull`.
+    pub fn convex_hull_of_points(points: &[Point3<f64>]) -> Result<Shape, Error> {
+        match quickhull(points) {
+            Hull::Point => Err(Error::DegenerateHull),
+            Hull::Segment(a, b) => Ok(Edge::segment(points[a], points[b]).into()),
+            Hull::Polygon(polygon) => {
+                let wire = Wire::from_ordered_points(polygon)?;
+                Ok(Face::from_wire(&wire)?.into())
+            }
+            Hull::Polyhedron(triangles) => polyhedron(points, &triangles),
+        }
+    }
+
+    /// The convex hull of the vertices of this shape's mesh. Curved faces
+    /// are approximated by their mesh, so the hull can lie inside them by up
+    /// to the mesh deflection.
+    pub fn convex_hull(&self) -> Result<Shape, Error> {
+        let mesh = self.mesh()?;
+
+        Self::convex_hull_of_points(&mesh.vertices)
+    }
+}
+
+/// Sew planar triangles into a closed shell, and fill it.
+fn polyhedron(points: &[Point3<f64>], triangles: &[[usize; 3]]) -> Result<Shape, Error> {
+    let mut sewing = ffi::BRepBuilderAPI_Sewing_ctor(tolerance(points) * 1000.0);
+
+    for triangle in triangles {
+        let wire = Wire::from_ordered_points(triangle.map(|index| points[index]))?;
+        let face = Face::from_wire(&wire)?;
+        sewing.pin_mut().Add(ffi::cast_face_to_shape(&face.inner));
+    }
+
+    sewing.pin_mut().Perform(&ffi::Message_ProgressRange_ctor());
+    let sewed = sewing.SewedShape();
+
+    if sewed.ShapeType() != ffi::TopAbs_ShapeEnum::TopAbs_SHELL {
+        return Err(Error::NotClosed);
+    }
+
+    let mut make_solid = ffi::BRepBuilderAPI_MakeSolid_ctor(ffi::TopoDS_cast_to_shell(sewed));
+    make_solid
+        .pin_mut()
+        .Build(&ffi::Message_ProgressRange_ctor());
+
+    if !make_solid.IsDone() {
+        return Err(Error::NotDone);
+    }
+
+    let solid = Solid::from_solid(ffi::TopoDS_cast_to_solid(make_solid.pin_mut().Shape()));
+
+    // Merge the triangles of each face of the hull.
+    Ok(Shape::from(solid).clean())
+}
+
+/// The size of the point cloud, times `RELATIVE_TOLERANCE`.
+fn tolerance(points: &[Point3<f64>]) -> f64 {
+    let Some(first) = points.first() else {
+        return 0.0;
+    };
+
+    let (min, max) = points.iter().fold((*first, *first), |(min, max), point| {
+        (min.inf(point), max.sup(point))
+    });
+
+    (max - min).norm() * RELATIVE_TOLERANCE
+}
+
+#[derive(Debug)]
+enum Hull {
+    /// No points, or all the points are the same.
+    Point,
+    /// The indices of the two ends of a line through all the points.
+    Segment(usize, usize),
+    /// The corners of a convex polygon containing all the points, in order.
+    Polygon(Vec<Point3<f64>>),
+    /// Triangles, as indices into the points, wound counter-clockwise when
+    /// seen from outside.
+    Polyhedron(Vec<[usize; 3]>),
+}
+
+struct HullFace {
+    vertices: [usize; 3],
+    normal: Vector3<f64>,
+    offset: f64,
+    /// Points in front of this face, which aren't in front of any face
+    /// created before it.
+    outside: Vec<usize>,
+}
+
+impl HullFace {
+    fn new(points: &[Point3<f64>], vertices: [usize; 3]) -> Self {
+        let [a, b, c] = vertices.map(|index| points[index]);
+        let normal = (b - a).cross(&(c - a)).normalize();
+
+        Self {
+            vertices,
+            normal,
+            offset: normal.dot(&a.coords),
+            outside: vec![],
+        }
+    }
+
+    fn distance(&self, point: &Point3<f64>) -> f64 {
+        self.normal.dot(&point.coords) - self.offset
+    }
+
+    fn edges(&self) -> [(usize, usize); 3] {
+        let [a, b, c] = self.vertices;
+        [(a, b), (b, c), (c, a)]
+    }
+}
+
+fn quickhull(points: &[Point3<f64>]) -> Hull {
+    let epsilon = tolerance(points);
+
+    if points.is_empty() || epsilon == 0.0 {
+        return Hull::Point;
+    }
+
+    // The two farthest apart of the extreme points along each axis.
+    let extremes: Vec<usize> = (0..3)
+        .flat_map(|axis| {
+            let by_axis = |a: &usize, b: &usize| points[*a][axis].total_cmp(&points[*b][axis]);
+            [
+                (0..points.len()).min_by(by_axis).unwrap(),
+                (0..points.len()).max_by(by_axis).unwrap(),
+            ]
+        })
+        .collect();
+    let (a, b) = extremes
+        .iter()
+        .flat_map(|&a| extremes.iter().map(move |&b| (a, b)))
+        .max_by(|(a1, b1), (a2, b2)| {
+            (points[*a1] - points[*b1])
+                .norm()
+                .total_cmp(&(points[*a2] - points[*b2]).norm())
+        })
+        .unwrap();
+
+    // The farthest point from the line through them.
+    let direction = (points[b] - points[a]).normalize();
+    let line_distance = |p: &Point3<f64>| (p - points[a]).cross(&direction).norm();
+    let c = (0..points.len())
+        .max_by(|i, j| line_distance(&points[*i]).total_cmp(&line_distance(&points[*j])))
+        .unwrap();
+
+    if line_distance(&points[c]) <= epsilon {
+        let along = |i: &usize| (points[*i] - points[a]).dot(&direction);
+        let first = (0..points.len())
+            .min_by(|i, j| along(i).total_cmp(&along(j)))
+            .unwrap();
+        let last = (0..points.len())
+            .max_by(|i, j| along(i).total_cmp(&along(j)))
+            .unwrap();
+
+        return Hull::Segment(first, last);
+    }
+
+    // The farthest point from the plane through all three.
+    let base = HullFace::new(points, [a, b, c]);
+    let d = (0..points.len())
+        .max_by(|i, j| {
+            base.distance(&points[*i])
+                .abs()
+                .total_cmp(&base.distance(&points[*j]).abs())
+        })
+        .unwrap();
+
+    if base.distance(&points[d]).abs() <= epsilon {
+        return Hull::Polygon(planar_hull(points, &base, epsilon));
+    }
+
+    // Start with a tetrahedron, with its faces pointing outwards.
+    let centroid = Point3::from(
+        (points[a].coords + points[b].coords + points[c].coords + points[d].coords) / 4.0,
+    );
+    let mut faces: Vec<HullFace> = [[a, b, c], [a, c, d], [a, d, b], [b, d, c]]
+        .into_iter()
+        .map(|[a, b, c]| {
+            let face = HullFace::new(points, [a, b, c]);
+            if face.distance(&centroid) > 0.0 {
+                HullFace::new(points, [a, c, b])
+            } else {
+                face
+            }
+        })
+        .collect();
+
+    assign(
+        points,
+        &mut faces,
+        0,
+        (0..points.len()).filter(|i| ![a, b, c, d].contains(i)),
+        epsilon,
+    );
+
+    // Only the faces of the hull so far are kept, so faces the eye can see
+    // are dropped as soon as they're replaced.
+    while let Some(face) = faces.iter().position(|face| !face.outside.is_empty()) {
+        let eye = *faces[face]
+            .outside
+            .iter()
+            .max_by(|i, j| {
+                faces[face]
+                    .distance(&points[**i])
+                    .total_cmp(&faces[face].distance(&points[**j]))
+            })
+            .unwrap();
+
+        // Every face the eye can see is replaced by a cone of faces from its
+        // horizon to the eye.
+        let (visible, kept): (Vec<HullFace>, Vec<HullFace>) = faces
+            .into_iter()
+            .partition(|face| face.distance(&points[eye]) > epsilon);
+        faces = kept;
+
+        let visible_edges: HashSet<(usize, usize)> =
+            visible.iter().flat_map(|face| face.edges()).collect();
+        let horizon: Vec<(usize, usize)> = visible_edges
+            .iter()
+            .filter(|(u, v)| !visible_edges.contains(&(*v, *u)))
+            .copied()
+            .collect();
+
+        let orphans: Vec<usize> = visible.into_iter().flat_map(|face| face.outside).collect();
+
+        let first_new = faces.len();
+        faces.extend(
+            horizon
+                .into_iter()
+                .map(|(u, v)| HullFace::new(points, [u, v, eye])),
+        );
+
+        assign(
+            points,
+            &mut faces,
+            first_new,
+            orphans.into_iter().filter(|&i| i != eye),
+            epsilon,
+        );
+    }
+
+    Hull::Polyhedron(faces.into_iter().map(|face| face.vertices).collect())
+}
+
+/// Add each point to the outside set of the first face from `first` on
+/// which it's in front of. Points behind all of them are inside the hull.
+fn assign(
+    points: &[Point3<f64>],
+    faces: &mut [HullFace],
+    first: usize,
+    candidates: impl IntoIterator<Item = usize>,
+    epsilon: f64,
+) {
+    for point in candidates {
+        if let Some(face) = faces[first..]
+            .iter_mut()
+            .find(|face| face.distance(&points[point]) > epsilon)
+        {
+            face.outside.push(point);
+        }
+    }
+}
+
+/// The convex hull of points in the plane of `base`, with Andrew's
+/// monotone chain.
+fn planar_hull(points: &[Point3<f64>], base: &HullFace, epsilon: f64) -> Vec<Point3<f64>> {
+    let origin = points[base.vertices[0]];
+    let u = (points[base.vertices[1]] - origin).normalize();
+    let v = base.normal.cross(&u);
+
+    let mut projected: Vec<(Point2<f64>, usize)> = points
+        .iter()
+        .enumerate()
+        .map(|(index, point)| {
+            (
+                Point2::new((point - origin).dot(&u), (point - origin).dot(&v)),
+                index,
+            )
+        })
+        .collect();
+    projected.sort_by(|(a, _), (b, _)| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+
+    let cross = |o: &Point2<f64>, a: &Point2<f64>, b: &Point2<f64>| (a - o).perp(&(b - o));
+    let scale = epsilon / RELATIVE_TOLERANCE;
+
+    // Build the lower and upper chains, dropping points which don't turn
+    // left.
+    let mut hull: Vec<(Point2<f64>, usize)> = vec![];
+    for pass in [projected.clone(), projected.into_iter().rev().collect()] {
+        let start = hull.len();
+        for point in pass {
+            while hull.len() >= start + 2
+                && cross(&hull[hull.len() - 2].0, &hull[hull.len() - 1].0, &point.0)
+                    <= epsilon * scale
+            {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // The last point of each chain starts the other one.
+        hull.pop();
+    }
+
+    hull.into_iter().map(|(_, index)| points[index]).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::ShapeType;
+    use nalgebra::point;
+
+    /// Deterministic points in a 10 x 10 x 10 cube.
+    fn cloud(count: usize) -> Vec<Point3<f64>> {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 10_000) as f64 / 1000.0
+        };
+
+        (0..count).map(|_| point![next(), next(), next()]).collect()
+    }
+
+    #[test]
+    fn quickhull_contains_every_point() {
+        let points = cloud(500);
+        let Hull::Polyhedron(triangles) = quickhull(&points) else {
+            panic!("expected a polyhedron");
+        };
+
+        let epsilon = tolerance(&points);
+        for triangle in &triangles {
+            let face = HullFace::new(&points, *triangle);
+            assert!(points.iter().all(|point| face.distance(point) <= epsilon));
+        }
+
+        // A closed triangulated surface of genus 0 has F = 2V - 4, and
+        // every edge is used once in each direction.
+        let vertices: HashSet<usize> = triangles.iter().flatten().copied().collect();
+        assert_eq!(triangles.len(), 2 * vertices.len() - 4);
+
+        let edges: HashSet<(usize, usize)> = triangles
+            .iter()
+            .flat_map(|&[a, b, c]| [(a, b), (b, c), (c, a)])
+            .collect();
+        assert_eq!(edges.len(), triangles.len() * 3);
+        assert!(edges.iter().all(|(a, b)| edges.contains(&(*b, *a))));
+    }
+
+    #[test]
+    fn hull_of_box_corners() {
+        let mut points: Vec<_> = (0..8)
+            .map(|i| {
+                point![
+                    (i & 1) as f64 * 10.0,
+                    (i >> 1 & 1) as f64 * 20.0,
+                    (i >> 2) as f64 * 30.0
+                ]
+            })
+            .collect();
+        // Points inside and on the faces don't change the hull.
+        points.extend([
+            point![5.0, 10.0, 15.0],
+            point![0.0, 10.0, 15.0],
+            point![5.0, 20.0, 30.0],
+        ]);
+
+        let hull = Shape::convex_hull_of_points(&points).unwrap();
+        assert_eq!(hull.shape_type(), ShapeType::Solid);
+        assert!((hull.volume() - 6000.0).abs() < 1.0e-6);
+
+        let summary = hull.topology_summary();
+        assert!(summary.closed && summary.valid, "{summary}");
+        assert_eq!((summary.faces, summary.edges, summary.vertices), (6, 12, 8));
+    }
+
+    #[test]
+    fn hull_of_meshed_sphere() {
+        let sphere = Shape::sphere(10.0).build();
+        let hull = sphere.convex_hull().unwrap();
+
+        let summary = hull.topology_summary();
+        assert!(summary.closed && summary.valid, "{summary}");
+
+        // Inscribed in the sphere, and close to it.
+        let volume = 4.0 / 3.0 * std::f64::consts::PI * 1000.0;
+        assert!(hull.volume() <= volume);
+        assert!(hull.volume() > volume * 0.98, "{}", hull.volume());
+
+        let extents = summary.extents.unwrap();
+        assert!((extents - Vector3::repeat(20.0)).norm() < 0.1);
+    }
+
+    #[test]
+    fn degenerate_hulls() {
+        // Coplanar points make a face.
+        let square = [
+            point![0.0, 0.0, 1.0],
+            point![2.0, 0.0, 1.0],
+            point![1.0, 1.0, 1.0],
+            point![2.0, 2.0, 1.0],
+            point![0.0, 2.0, 1.0],
+            point![1.0, 0.0, 1.0],
+        ];
+        let face = Shape::convex_hull_of_points(&square).unwrap();
+        assert_eq!(face.shape_type(), ShapeType::Face);
+        assert_eq!(face.topology_summary().edges, 4);
+        let face = face.faces().next().unwrap();
+        assert!((face.surface_area() - 4.0).abs() < 1.0e-9);
+
+        // Collinear points make an edge between the extremes.
+        let line = [
+            point![1.0, 1.0, 1.0],
+            point![3.0, 3.0, 3.0],
+            point![0.0, 0.0, 0.0],
+            point![2.0, 2.0, 2.0],
+        ];
+        let edge = Shape::convex_hull_of_points(&line).unwrap();
+        assert_eq!(edge.shape_type(), ShapeType::Edge);
+        let edge = edge.edges().next().unwrap();
+        assert!((edge.length() - 3.0f64.sqrt() * 3.0).abs() < 1.0e-9);
+
+        // A single point isn't a shape.
+        for points in [&[][..], &[point![1.0, 2.0, 3.0]; 3][..]] {
+            assert!(matches!(
+                Shape::convex_hull_of_points(points),
+                Err(Error::DegenerateHull)
+            ));
+        }
+    }
+}
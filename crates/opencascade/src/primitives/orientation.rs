@@ -0,0 +1,177 @@
+use crate::primitives::Face;
+use crate::primitives::FaceType;
+use crate::primitives::Shape;
+use crate::TandR;
+use nalgebra::UnitVector3;
+use nalgebra::Vector3;
+
+/// Faces whose areas differ by less than this fraction are equally big.
+const AREA_TOLERANCE: f64 = 1.0e-6;
+
+/// How far below a face the mesh may reach for the shape to still rest on
+/// it. Mesh vertices are on the surface, so this only needs to cover
+/// rounding.
+const REST_TOLERANCE: f64 = 1.0e-6;
+
+impl Shape {
+    /// Turn this shape to rest on its biggest flat face, the way it would
+    /// be put on the bed of a 3D printer: the face is turned to face -Z and
+    /// put on z = 0, and the shape's center of mass over the origin.
+    /// Returns the moved shape, and the transform which moved it, to move
+    /// other things, like fixtures, along with it.
+    ///
+    /// Of equally big faces, the one leaving the center of mass lowest is
+    /// chosen. Shapes without flat faces to rest on are only moved to sit
+    /// on z = 0 with their center of mass over the origin.
+    pub fn lay_flat(&self) -> (Shape, TandR<f64>) {
+        let transform = match self.orientations_on_faces().into_iter().next() {
+            Some((_, transform)) => transform,
+            None => {
+                let center = self.center_of_mass();
+                let bottom = self.bounding_box().min().z;
+
+                TandR::default().translation(Vector3::new(-center.x, -center.y, -bottom))
+            }
+        };
+
+        let mut shape = self.clone();
+        shape.transform(&transform);
+
+        (shape, transform)
+    }
+
+    /// Each flat face this shape can rest on, with nothing of the shape
+    /// below it, and the transform which puts the shape down on it like
+    /// `lay_flat` does. The biggest faces come first, and of equally big
+    /// ones, those leaving the center of mass lowest.
+    pub fn orientations_on_faces(&self) -> Vec<(Face, TandR<f64>)> {
+        // Without a mesh, every flat face is taken to be one the shape can
+        // rest on.
+        let points = self.mesh().map(|mesh| mesh.vertices).unwrap_or_default();
+        let center = self.center_of_mass();
+        let down = UnitVector3::new_unchecked(-Vector3::z());
+
+        let mut orientations: Vec<_> = self
+            .faces()
+            .filter(|face| face.face_type() == FaceType::Plane)
+            .filter_map(|face| {
+                let normal = face.normal_at_center();
+                let rotation = TandR::from_rotation_between(&normal, &down);
+                let bottom = rotation.transform_point(face.center_of_mass()).z;
+
+                let rests_on_face = points
+                    .iter()
+                    .all(|&point| rotation.transform_point(point).z >= bottom - REST_TOLERANCE);
+                if !rests_on_face {
+                    return None;
+                }
+
+                let rotated_center = rotation.transform_point(center);
+                let transform = rotation.translation(Vector3::new(
+                    -rotated_center.x,
+                    -rotated_center.y,
+                    -bottom,
+                ));
+                let center_height = rotated_center.z - bottom;
+
+                Some((face.surface_area(), center_height, face, transform))
+            })
+            .collect();
+
+        // Comparing areas with a tolerance isn't a total order, so sort by
+        // area alone, then by height within each run of equally big faces,
+        // measured against the biggest face of the run.
+        orientations.sort_by(|(area_a, ..), (area_b, ..)| area_b.total_cmp(area_a));
+
+        let mut start = 0;
+        while start < orientations.len() {
+            let biggest = orientations[start].0;
+            let end = orientations[start..]
+                .iter()
+                .position(|(area, ..)| biggest - area > AREA_TOLERANCE * biggest)
+                .map_or(orientations.len(), |length| start + length);
+
+            orientations[start..end]
+                .sort_by(|(_, height_a, ..), (_, height_b, ..)| height_a.total_cmp(height_b));
+            start = end;
+        }
+
+        orientations
+            .into_iter()
+            .map(|(_, _, face, transform)| (face, transform))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::Wire;
+    use nalgebra::point;
+    use nalgebra::Point3;
+
+    fn lowest(points: impl IntoIterator<Item = Point3<f64>>) -> f64 {
+        points
+            .into_iter()
+            .map(|point| point.z)
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// An L-shaped bracket, 30 long and 20 high with 4 thick legs, 10 deep
+    /// along z. Its biggest face, 30 x 10, is along the bottom of the long
+    /// leg, facing -y.
+    fn l_bracket() -> Shape {
+        let profile = Wire::from_ordered_points([
+            point![0.0, 0.0, 0.0],
+            point![30.0, 0.0, 0.0],
+            point![30.0, 4.0, 0.0],
+            point![4.0, 4.0, 0.0],
+            point![4.0, 20.0, 0.0],
+            point![0.0, 20.0, 0.0],
+        ])
+        .unwrap();
+
+        Face::from_wire(&profile)
+            .unwrap()
+            .extrude(Vector3::new(0.0, 0.0, 10.0))
+            .into()
+    }
+
+    #[test]
+    fn l_bracket_lays_on_its_long_leg() {
+        let bracket = l_bracket();
+        let (flat, transform) = bracket.lay_flat();
+
+        let bounds = flat.bounding_box();
+        assert!(bounds.min().z.abs() < 1.0e-6, "{}", bounds.min());
+        assert!((bounds.max().z - 20.0).abs() < 1.0e-6, "{}", bounds.max());
+        assert!((flat.volume() - bracket.volume()).abs() < 1.0e-6);
+
+        let center = flat.center_of_mass();
+        assert!(
+            center.x.abs() < 1.0e-6 && center.y.abs() < 1.0e-6,
+            "{center}"
+        );
+        assert!(lowest(flat.mesh().unwrap().vertices) > -1.0e-6);
+
+        // The transform moves the bottom of the long leg onto the bed.
+        let corner = transform.transform_point(point![30.0, 0.0, 10.0]);
+        assert!(corner.z.abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn resting_orientations() {
+        let orientations = l_bracket().orientations_on_faces();
+
+        // Not the faces in the inside corner, which the other leg is below.
+        assert_eq!(orientations.len(), 6);
+        assert!((orientations[0].0.surface_area() - 300.0).abs() < 1.0e-6);
+        assert!(orientations[0].0.normal_at_center().y < -0.999);
+
+        let areas: Vec<_> = orientations
+            .iter()
+            .map(|(face, _)| face.surface_area())
+            .collect();
+        assert!(areas.windows(2).all(|pair| pair[0] >= pair[1] - 1.0e-6));
+    }
+}
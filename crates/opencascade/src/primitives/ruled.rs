@@ -0,0 +1,192 @@
+use crate::primitives::Edge;
+use crate::primitives::Face;
+use crate::primitives::Shell;
+use crate::primitives::Wire;
+use crate::Error;
+use nalgebra::Vector3;
+use opencascade_sys::ffi;
+
+/// How many points along closed wires are used to find which way they wind.
+const WINDING_SAMPLES: usize = 32;
+
+impl Face {
+    /// The ruled surface between `a` and `b`: the face swept by the straight
+    /// line joining points the same fraction along each of them. If `b` runs
+    /// the other way to `a`, which would twist the surface, it's reversed
+    /// first. Use `ruled_as_given` to rule between them as they are.
+    pub fn ruled(a: &Edge, b: &Edge) -> Result<Face, Error> {
+        let (wire_a, wire_b) = (Wire::from_edges([a])?, Wire::from_edges([b])?);
+
+        if runs_against(&wire_a, &wire_b)? {
+            Self::ruled_as_given(a, &b.reversed())
+        } else {
+            Self::ruled_as_given(a, b)
+        }
+    }
+
+    /// The ruled surface between `a` and `b`, which joins their starts and
+    /// their ends, whichever way they run.
+    pub fn ruled_as_given(a: &Edge, b: &Edge) -> Result<Face, Error> {
+        let inner = ffi::BRepFill_Face(&a.inner, &b.inner);
+
+        if ffi::cast_face_to_shape(&inner).IsNull() {
+            return Err(Error::NotDone);
+        }
+
+        Ok(Face { inner })
+    }
+}
+
+impl Shell {
+    /// The ruled surface between the wires `a` and `b`, as one face for
+    /// each pair of their edges. Unlike `Shell::loft`, the faces are always
+    /// ruled, so they can be unrolled flat, and the shell can be sewn with
+    /// other faces into a solid. If `b` runs the other way to `a`, which
+    /// would twist the surface, it's reversed first. Use
+    /// `ruled_between_wires_as_given` to rule between them as they are.
+    pub fn ruled_between_wires(a: &Wire, b: &Wire) -> Result<Shell, Error> {
+        if runs_against(a, b)? {
+            Self::ruled_between_wires_as_given(a, &b.reversed()?)
+        } else {
+            Self::ruled_between_wires_as_given(a, b)
+        }
+    }
+
+    /// The ruled surface between the wires `a` and `b`, which pairs their
+    /// edges from their starts, whichever way they run. Wires with different
+    /// numbers of edges have their edges split to match, so the faces don't
+    /// pair one edge with one edge.
+    pub fn ruled_between_wires_as_given(a: &Wire, b: &Wire) -> Result<Shell, Error> {
+        if a.edges().count() == b.edges().count() {
+            let inner = ffi::BRepFill_Shell(&a.inner, &b.inner);
+
+            if ffi::cast_shell_to_shape(&inner).IsNull() {
+                return Err(Error::NotDone);
+            }
+
+            return Ok(Shell { inner });
+        }
+
+        let is_solid = false;
+        let ruled = true;
+        let mut make_loft = ffi::BRepOffsetAPI_ThruSections_ruled_ctor(is_solid, ruled);
+        make_loft.pin_mut().AddWire(&a.inner);
+        make_loft.pin_mut().AddWire(&b.inner);
+
+        // Checking compatibility would turn the wires to match.
+        make_loft.pin_mut().CheckCompatibility(false);
+
+        make_loft
+            .pin_mut()
+            .Build(&ffi::Message_ProgressRange_ctor());
+        if !make_loft.IsDone() {
+            return Err(Error::NotDone);
+        }
+
+        let shape = make_loft.pin_mut().Shape();
+        Ok(Shell::from_shell(ffi::TopoDS_cast_to_shell(shape)))
+    }
+}
+
+/// Whether ruling between `a` and `b` as they are would twist the surface:
+/// whether closed wires wind opposite ways, or open wires' starts are nearer
+/// the others' ends than their starts.
+fn runs_against(a: &Wire, b: &Wire) -> Result<bool, Error> {
+    if a.is_closed() && b.is_closed() {
+        return Ok(winding(a)?.dot(&winding(b)?) < 0.0);
+    }
+
+    let along = (a.start_point() - b.start_point()).norm() + (a.end_point() - b.end_point()).norm();
+    let against =
+        (a.start_point() - b.end_point()).norm() + (a.end_point() - b.start_point()).norm();
+
+    Ok(against < along)
+}
+
+/// The area vector of a closed wire, which points the way its right hand
+/// rule normal does.
+fn winding(wire: &Wire) -> Result<Vector3<f64>, Error> {
+    let points: Vec<_> = wire
+        .points_by_arc_length(WINDING_SAMPLES)?
+        .into_iter()
+        .map(|(point, _)| point.coords)
+        .collect();
+
+    Ok(points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(p, q)| p.cross(q))
+        .sum::<Vector3<f64>>()
+        / 2.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::Shape;
+    use nalgebra::point;
+    use std::f64::consts::PI;
+
+    fn area(shell: &Shell) -> f64 {
+        Shape::from(shell)
+            .faces()
+            .map(|face| face.surface_area())
+            .sum()
+    }
+
+    #[test]
+    fn ruled_between_circles_is_a_cone() {
+        let (bottom_radius, top_radius, height) = (5.0, 3.0, 4.0);
+        let bottom = Edge::circle(point![0.0, 0.0, 0.0], Vector3::z(), bottom_radius).unwrap();
+        let top = Edge::circle(point![0.0, 0.0, height], Vector3::z(), top_radius).unwrap();
+
+        let slant = (height * height + (bottom_radius - top_radius).powi(2)).sqrt();
+        let lateral_area = PI * (bottom_radius + top_radius) * slant;
+
+        let face = Face::ruled(&bottom, &top).unwrap();
+        assert!((face.surface_area() - lateral_area).abs() < 1.0e-6 * lateral_area);
+
+        // Running the top circle the other way would twist the surface.
+        let reversed = top.reversed();
+        let face = Face::ruled(&bottom, &reversed).unwrap();
+        assert!((face.surface_area() - lateral_area).abs() < 1.0e-6 * lateral_area);
+
+        let twisted = Face::ruled_as_given(&bottom, &reversed).unwrap();
+        assert!(twisted.surface_area() > lateral_area * 1.01);
+    }
+
+    #[test]
+    fn ruled_between_squares() {
+        let square = |z: f64| {
+            Wire::from_ordered_points([
+                point![0.0, 0.0, z],
+                point![10.0, 0.0, z],
+                point![10.0, 10.0, z],
+                point![0.0, 10.0, z],
+            ])
+            .unwrap()
+        };
+
+        let shell = Shell::ruled_between_wires(&square(0.0), &square(5.0)).unwrap();
+        assert_eq!(Shape::from(&shell).faces().count(), 4);
+        assert!((area(&shell) - 200.0).abs() < 1.0e-6);
+
+        // The same square with its sides split in two, so the bottom
+        // square's edges are split to match.
+        let split_square = Wire::from_ordered_points([
+            point![0.0, 0.0, 5.0],
+            point![5.0, 0.0, 5.0],
+            point![10.0, 0.0, 5.0],
+            point![10.0, 5.0, 5.0],
+            point![10.0, 10.0, 5.0],
+            point![5.0, 10.0, 5.0],
+            point![0.0, 10.0, 5.0],
+            point![0.0, 5.0, 5.0],
+        ])
+        .unwrap();
+
+        let shell = Shell::ruled_between_wires(&square(0.0), &split_square).unwrap();
+        assert_eq!(Shape::from(&shell).faces().count(), 8);
+        assert!((area(&shell) - 200.0).abs() < 1.0e-6);
+    }
+}
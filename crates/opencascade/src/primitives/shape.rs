@@ -1,20 +1,32 @@
+use crate::bounding_box;
+use crate::bounding_box::BoundingBox;
+use crate::context::ModelContext;
+use crate::convert::IntoPoint3;
+use crate::convert::IntoVector3;
 use crate::mesh;
 use crate::mesh::Mesh;
+use crate::mesh::MeshOptions;
 use crate::mesh::Mesher;
 use crate::point_to_gppnt;
+use crate::primitives::cache::MassProperties;
+use crate::primitives::cache::ShapeCache;
+use crate::primitives::history::BooleanOperation;
 use crate::primitives::make_axis_1;
 use crate::primitives::make_axis_2;
 use crate::primitives::make_dir;
 use crate::primitives::make_point;
 use crate::primitives::make_point2d;
 use crate::primitives::make_vec;
+use crate::primitives::tags::Tags;
 use crate::primitives::BooleanShape;
 use crate::primitives::Compound;
 use crate::primitives::CompoundFace;
+use crate::primitives::Detached;
 use crate::primitives::Edge;
 use crate::primitives::EdgeIterator;
 use crate::primitives::Face;
 use crate::primitives::FaceIterator;
+use crate::primitives::History;
 use crate::primitives::ShapeType;
 use crate::primitives::Shell;
 use crate::primitives::Solid;
@@ -23,22 +35,30 @@ use crate::primitives::Vertex;
 use crate::primitives::VertexIterator;
 use crate::primitives::Wire;
 use crate::primitives::WireIterator;
+use crate::translator_lock;
 use crate::Error;
 use crate::TandR;
 use crate::TopExpExplorerIter;
+use cxx::memory::UniquePtrTarget;
 use cxx::UniquePtr;
-use nalgebra::Matrix4;
 use nalgebra::point;
+use nalgebra::Matrix3;
+use nalgebra::Matrix4;
 use nalgebra::Point3;
+use nalgebra::Rotation3;
+use nalgebra::UnitQuaternion;
 use nalgebra::UnitVector3;
 use nalgebra::Vector3;
 use opencascade_sys::ffi;
 use opencascade_sys::ffi::TopAbs_ShapeEnum;
 use std::cmp::Ordering;
+use std::ops::BitOr;
 use std::path::Path;
 
 pub struct Shape {
     pub(crate) inner: UniquePtr<ffi::TopoDS_Shape>,
+    pub(crate) tags: Tags,
+    pub(crate) cache: ShapeCache,
 }
 
 impl AsRef<Shape> for Shape {
@@ -181,18 +201,90 @@ impl From<BooleanShape> for Shape {
     }
 }
 
+/// Cloning a shape is a deep copy, the same as `deep_copy`: the clone shares
+/// nothing with the original. Use `shallow_copy` to share its topology and
+/// geometry instead.
 impl Clone for Shape {
     fn clone(&self) -> Self {
+        self.deep_copy()
+    }
+}
+
+impl Shape {
+    /// A copy of this shape with its own topology and geometry, which shares
+    /// nothing with this one, so either can be changed in place without
+    /// affecting the other. Tags are carried over to the copy.
+    pub fn deep_copy(&self) -> Shape {
         let shape = &self.inner;
 
         let mut copier = ffi::BRepBuilderAPI_Copy_new(shape, true, false);
 
         let new_shape = copier.pin_mut().Shape();
 
-        Self::from_shape(new_shape)
+        let mut copy = Self::from_shape(new_shape);
+
+        if !self.tags.is_empty() {
+            let history = ffi::BRepTools_History_from_copy(
+                &History::arguments([&*self.inner]),
+                copier.pin_mut(),
+            );
+            let (tags, _) = self
+                .tags
+                .remap(&History::from_history(history, &self.inner));
+
+            copy.tags = tags;
+        }
+
+        copy
+    }
+
+    /// A copy of this shape which shares its topology and geometry, which is
+    /// much cheaper than `deep_copy`. Transforming the copy, or cutting it
+    /// with a boolean, builds new topology and leaves this shape as it was.
+    ///
+    /// Some operations do write to the shared data, though: `fix` and
+    /// `clean` fix sub-shapes in place, tolerance changes update them, and
+    /// making faces on them adds curves to their edges. Those show through
+    /// in every copy. Shapes aren't `Send`, so copies can't end up on
+    /// different threads; `detach` makes a deep copy which can be sent. Use
+    /// `deep_copy` for a copy which is independent.
+    pub fn shallow_copy(&self) -> Shape {
+        let mut copy = Self::from_shape(&self.inner);
+        copy.tags = self.tags.clone();
+
+        copy
+    }
+
+    /// The number of shapes sharing this shape's topology, this one
+    /// included, across shallow copies, patterns and any compounds or
+    /// results built from it which haven't been dropped. Useful for tracking
+    /// down where memory is going.
+    pub fn share_count(&self) -> usize {
+        ffi::TopoDS_Shape_share_count(&self.inner) as usize
+    }
+
+    /// A deep copy of this shape which can be sent to another thread, see
+    /// `Detached`. The derived data this shape has computed already, like
+    /// its meshes, moves over to the copy.
+    pub fn detach(mut self) -> Detached {
+        let mut copy = self.deep_copy();
+        copy.cache = std::mem::take(&mut self.cache);
+
+        Detached(copy)
     }
 }
 
+/// The mass properties of a solid, made with `Shape::inertial_properties`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct InertialProperties {
+    pub mass: f64,
+    /// The center of mass of the volume, which unlike
+    /// `Shape::center_of_mass` isn't that of the surface.
+    pub center_of_mass: Point3<f64>,
+    /// The inertia tensor about the center of mass, along the world axes.
+    pub inertia: Matrix3<f64>,
+}
+
 pub struct SphereBuilder {
     center: Point3<f64>,
     radius: f64,
@@ -331,7 +423,7 @@ impl Shape {
     pub(crate) fn from_shape(shape: &ffi::TopoDS_Shape) -> Self {
         let inner = ffi::TopoDS_Shape_to_owned(shape);
 
-        Self { inner }
+        Self { inner, tags: Tags::default(), cache: ShapeCache::default() }
     }
 
     /// Make a shape that models empty space.
@@ -347,12 +439,13 @@ impl Shape {
 
         let inner = ffi::TopoDS_Compound_as_shape(compound);
 
-        Self { inner }
+        Self { inner, tags: Tags::default(), cache: ShapeCache::default() }
     }
 
     /// Make a box with one corner at corner_1, and the opposite corner
     /// at corner_2.
-    pub fn box_from_corners(corner_1: Point3<f64>, corner_2: Point3<f64>) -> Self {
+    pub fn box_from_corners(corner_1: impl IntoPoint3, corner_2: impl IntoPoint3) -> Self {
+        let (corner_1, corner_2) = (corner_1.into_point3(), corner_2.into_point3());
         let (min_corner, max_corner) =
             match corner_1.partial_cmp(&corner_2).unwrap_or(Ordering::Equal) {
                 Ordering::Less | Ordering::Equal => (corner_1, corner_2),
@@ -399,8 +492,8 @@ impl Shape {
 
     /// Make a cylinder with base at point `p`, radius `r`, and height `h`.
     /// Extends from `p` along axis `dir`.
-    pub fn cylinder(p: Point3<f64>, r: f64, dir: Vector3<f64>, h: f64) -> Self {
-        let cylinder_coord_system = make_axis_2(p, dir);
+    pub fn cylinder(p: impl IntoPoint3, r: f64, dir: impl IntoVector3, h: f64) -> Self {
+        let cylinder_coord_system = make_axis_2(p.into_point3(), dir.into_vector3());
         let mut cylinder = ffi::BRepPrimAPI_MakeCylinder_ctor(&cylinder_coord_system, r, h);
 
         Self::from_shape(cylinder.pin_mut().Shape())
@@ -537,6 +630,50 @@ impl Shape {
         Self::from_shape(make_chamfer.pin_mut().Shape())
     }
 
+    /// Like `fillet_edges`, but also returns the `History` of the operation.
+    pub fn fillet_edges_with_history<T: AsRef<Edge>>(
+        &self,
+        radius: f64,
+        edges: impl IntoIterator<Item = T>,
+    ) -> (Self, History) {
+        let mut make_fillet = ffi::BRepFilletAPI_MakeFillet_ctor(&self.inner);
+
+        for edge in edges.into_iter() {
+            make_fillet.pin_mut().add_edge(radius, &edge.as_ref().inner);
+        }
+
+        let shape = Self::from_shape(make_fillet.pin_mut().Shape());
+        let history = ffi::BRepTools_History_from_fillet(
+            &History::arguments([&*self.inner]),
+            make_fillet.pin_mut(),
+        );
+
+        (shape, History::from_history(history, &self.inner))
+    }
+
+    /// Like `chamfer_edges`, but also returns the `History` of the operation.
+    pub fn chamfer_edges_with_history<T: AsRef<Edge>>(
+        &self,
+        distance: f64,
+        edges: impl IntoIterator<Item = T>,
+    ) -> (Self, History) {
+        let mut make_chamfer = ffi::BRepFilletAPI_MakeChamfer_ctor(&self.inner);
+
+        for edge in edges.into_iter() {
+            make_chamfer
+                .pin_mut()
+                .add_edge(distance, &edge.as_ref().inner);
+        }
+
+        let shape = Self::from_shape(make_chamfer.pin_mut().Shape());
+        let history = ffi::BRepTools_History_from_chamfer(
+            &History::arguments([&*self.inner]),
+            make_chamfer.pin_mut(),
+        );
+
+        (shape, History::from_history(history, &self.inner))
+    }
+
     /// Performs fillet of `radius` on all edges of the shape
     #[must_use]
     pub fn fillet(&self, radius: f64) -> Self {
@@ -560,7 +697,16 @@ impl Shape {
         Ok(Shape::from_shape(cut_operation.pin_mut().Shape()))
     }
 
+    /// Like `subtract`, but also returns the `History` of the operation.
+    pub fn subtract_with_history(&self, other: &Self) -> Result<(Self, History), Error> {
+        let cut_operation = ffi::BRepAlgoAPI_Cut_ctor(&self.inner, &other.inner);
+        let (boolean, history) = self.boolean_with_history(other, cut_operation)?;
+
+        Ok((boolean.shape, history))
+    }
+
     pub fn read_step(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let _guard = translator_lock();
         let mut reader = ffi::STEPControl_Reader_ctor();
 
         let status = ffi::read_step(
@@ -578,10 +724,30 @@ impl Shape {
 
         let inner = ffi::one_shape_step(&reader);
 
-        Ok(Self { inner })
+        Ok(Self { inner, tags: Tags::default(), cache: ShapeCache::default() })
     }
 
+    /// Write a STEP file, in the unit of the default context.
     pub fn write_step(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.write_step_with_context(path, &ModelContext::current())
+    }
+
+    /// Write a STEP file, in the unit of `context`. The file declares its
+    /// unit, so reading it back gives the same shape, in millimeters.
+    pub fn write_step_with_context(
+        &self,
+        path: impl AsRef<Path>,
+        context: &ModelContext,
+    ) -> Result<(), Error> {
+        let _guard = translator_lock();
+
+        // The unit is read when the writer is made.
+        if !ffi::Interface_Static_SetCVal(
+            "write.step.unit".to_string(),
+            context.unit.step_name().to_string(),
+        ) {
+            return Err(Error::StepWriteFailed);
+        }
         let mut writer = ffi::STEPControl_Writer_ctor();
 
         let status = ffi::transfer_shape(writer.pin_mut(), &self.inner);
@@ -603,6 +769,7 @@ impl Shape {
     }
 
     pub fn read_iges(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let _guard = translator_lock();
         let mut reader = ffi::IGESControl_Reader_ctor();
 
         let status = ffi::read_iges(
@@ -620,10 +787,11 @@ impl Shape {
 
         let inner = ffi::one_shape_iges(&reader);
 
-        Ok(Self { inner })
+        Ok(Self { inner, tags: Tags::default(), cache: ShapeCache::default() })
     }
 
     pub fn write_iges(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let _guard = translator_lock();
         let mut writer = ffi::IGESControl_Writer_ctor();
 
         let success = ffi::add_shape(writer.pin_mut(), &self.inner);
@@ -645,6 +813,32 @@ impl Shape {
         }
     }
 
+    /// Read a shape from a file in OCCT's native BREP format, which stores
+    /// shapes exactly as they are in memory, so it's the quickest way to
+    /// cache a part between runs.
+    pub fn read_brep(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let inner = ffi::read_brep(path.as_ref().to_string_lossy().to_string());
+
+        if inner.IsNull() {
+            return Err(Error::BrepReadFailed);
+        }
+
+        Ok(Self {
+            inner,
+            tags: Tags::default(),
+            cache: ShapeCache::default(),
+        })
+    }
+
+    /// Write this shape to a file in OCCT's native BREP format.
+    pub fn write_brep(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        if ffi::write_brep(&self.inner, path.as_ref().to_string_lossy().to_string()) {
+            Ok(())
+        } else {
+            Err(Error::BrepWriteFailed)
+        }
+    }
+
     #[must_use]
     pub fn union(&self, other: &Shape) -> BooleanShape {
         let mut fuse_operation = ffi::BRepAlgoAPI_Fuse_ctor(&self.inner, &other.inner);
@@ -662,6 +856,115 @@ impl Shape {
         BooleanShape { shape, new_edges }
     }
 
+    /// Fuse all of `shapes` together in a single boolean operation, which
+    /// is faster and more robust than fusing them one at a time.
+    pub fn union_all<T: AsRef<Shape>>(shapes: impl IntoIterator<Item = T>) -> Result<Shape, Error> {
+        let mut shapes = shapes.into_iter();
+
+        let Some(first) = shapes.next() else {
+            return Ok(Shape::empty());
+        };
+
+        let mut arguments = ffi::new_list_of_shape();
+        ffi::shape_list_append_shape(arguments.pin_mut(), &first.as_ref().inner);
+
+        let mut tools = ffi::new_list_of_shape();
+        let mut tool_count = 0;
+
+        for shape in shapes {
+            ffi::shape_list_append_shape(tools.pin_mut(), &shape.as_ref().inner);
+            tool_count += 1;
+        }
+
+        if tool_count == 0 {
+            return Ok(Self::from_shape(&first.as_ref().inner));
+        }
+
+        let mut fuse_operation = ffi::BRepAlgoAPI_Fuse_new();
+        fuse_operation.pin_mut().SetArguments(&arguments);
+        fuse_operation.pin_mut().SetTools(&tools);
+        fuse_operation.pin_mut().SetNonDestructive(true);
+        fuse_operation
+            .pin_mut()
+            .Build(&ffi::Message_ProgressRange_ctor());
+
+        if !fuse_operation.IsDone() {
+            return Err(Error::NotDone);
+        }
+
+        Ok(Self::from_shape(fuse_operation.pin_mut().Shape()))
+    }
+
+    /// Place `count` copies of this shape along `spine`, evenly spaced by arc
+    /// length. The origin of the shape is placed on the spine, and `orient`
+    /// controls how each copy is rotated. Copies are fused together if
+    /// `fuse` is set, and gathered into a compound otherwise. The copies
+    /// share this shape's topology, see `shallow_copy`. A spine with no
+    /// length is an `Error::ZeroLengthWire`.
+    pub fn pattern_along_wire(
+        &self,
+        spine: &Wire,
+        count: u32,
+        orient: PathOrientation,
+        fuse: bool,
+    ) -> Result<Shape, Error> {
+        self.pattern_along_wire_with(spine, count, orient, fuse, false)
+    }
+
+    /// Like `pattern_along_wire`, but with copies which share nothing with
+    /// this shape or each other, see `deep_copy`.
+    pub fn pattern_along_wire_deep(
+        &self,
+        spine: &Wire,
+        count: u32,
+        orient: PathOrientation,
+        fuse: bool,
+    ) -> Result<Shape, Error> {
+        self.pattern_along_wire_with(spine, count, orient, fuse, true)
+    }
+
+    fn pattern_along_wire_with(
+        &self,
+        spine: &Wire,
+        count: u32,
+        orient: PathOrientation,
+        fuse: bool,
+        deep: bool,
+    ) -> Result<Shape, Error> {
+        if count < 2 {
+            return Err(Error::TooFewInstances);
+        }
+
+        let instances: Vec<_> = spine
+            .points_by_arc_length(count as usize)?
+            .into_iter()
+            .map(|(point, tangent)| {
+                let rotation = match orient {
+                    PathOrientation::Fixed => UnitQuaternion::identity(),
+                    PathOrientation::Tangent => path_frame(tangent, Vector3::z()),
+                    PathOrientation::TangentAndNormal(face) => {
+                        path_frame(tangent, face.normal_at(point).into_inner())
+                    }
+                };
+
+                let mut instance = if deep {
+                    self.deep_copy()
+                } else {
+                    self.shallow_copy()
+                };
+                instance.transform(&TandR::new(point.coords, rotation));
+
+                instance
+            })
+            .collect();
+
+        if fuse {
+            Self::union_all(&instances)
+        } else {
+            Ok(Compound::from_shapes(&instances).into())
+        }
+    }
+
     #[must_use]
     pub fn intersect(&self, other: &Shape) -> Result<BooleanShape, Error> {
         let mut fuse_operation = ffi::BRepAlgoAPI_Common_ctor(&self.inner, &other.inner);
@@ -684,6 +987,47 @@ impl Shape {
         Ok(BooleanShape { shape, new_edges })
     }
 
+    /// Like `union`, but also returns the `History` of the operation.
+    pub fn union_with_history(&self, other: &Shape) -> Result<(BooleanShape, History), Error> {
+        let fuse_operation = ffi::BRepAlgoAPI_Fuse_ctor(&self.inner, &other.inner);
+
+        self.boolean_with_history(other, fuse_operation)
+    }
+
+    /// Like `intersect`, but also returns the `History` of the operation.
+    pub fn intersect_with_history(&self, other: &Shape) -> Result<(BooleanShape, History), Error> {
+        let common_operation = ffi::BRepAlgoAPI_Common_ctor(&self.inner, &other.inner);
+
+        self.boolean_with_history(other, common_operation)
+    }
+
+    /// The result of a boolean `operation` between this shape and `other`,
+    /// with the edges along which they meet, and the `History` of the
+    /// operation.
+    fn boolean_with_history<B: BooleanOperation + UniquePtrTarget>(
+        &self,
+        other: &Shape,
+        mut operation: UniquePtr<B>,
+    ) -> Result<(BooleanShape, History), Error> {
+        if !operation.is_done() {
+            return Err(Error::NotDone);
+        }
+
+        let new_edges = ffi::shape_list_to_vector(operation.pin_mut().section_edges())
+            .iter()
+            .map(|shape| Edge::from_edge(ffi::TopoDS_cast_to_edge(shape)))
+            .collect();
+
+        let shape = Self::from_shape(operation.pin_mut().shape());
+        let arguments = History::arguments([&*self.inner, &*other.inner]);
+        let history = operation.pin_mut().history(&arguments);
+
+        Ok((
+            BooleanShape { shape, new_edges },
+            History::from_history(history, &self.inner),
+        ))
+    }
+
     pub fn write_stl<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
         self.write_stl_with_tolerance(path, 0.001)
     }
@@ -724,9 +1068,15 @@ impl Shape {
 
         let location = ffi::TopLoc_Location_from_transform(&transform);
 
+        // Every sub-shape moves with the shape, so the tags are moved the
+        // same way.
+        let relative = ffi::TopLoc_Location_relative(self.inner.location(), &location);
+        self.tags = self.tags.moved(&relative);
+
         self.inner
             .pin_mut()
             .set_global_translation(&location, false);
+        self.cache.clear();
     }
 
     pub fn calculate_mesh(&self, tolerance: f64) -> Result<Mesher, Error> {
@@ -738,8 +1088,23 @@ impl Shape {
     }
 
     pub fn mesh_with_tolerance(&self, triangulation_tolerance: f64) -> Result<Mesh, Error> {
-        let mesher = Mesher::try_new(self, triangulation_tolerance)?;
-        mesher.mesh()
+        self.cache.mesh(triangulation_tolerance, || {
+            let mesher = Mesher::try_new(self, triangulation_tolerance)?;
+            mesher.mesh()
+        })
+    }
+
+    pub fn mesh_with_options(&self, options: &MeshOptions) -> Result<Mesh, Error> {
+        let mesh = if options.overrides.is_empty() {
+            self.mesh_with_tolerance(options.deflection)?
+        } else {
+            self.mesh_with_overrides(options)?
+        };
+
+        Ok(match options.weld_tolerance {
+            Some(tolerance) => mesh.welded(tolerance)?,
+            None => mesh,
+        })
     }
 
     pub fn edges(&self) -> EdgeIterator {
@@ -760,6 +1125,92 @@ impl Shape {
         WireIterator::new(self)
     }
 
+    /// Attach the name `name` to `face`, which should be a face of this
+    /// shape. A name can refer to several faces. Tags follow the shape
+    /// through `apply_with_history` and `clone`.
+    pub fn tag_face(&mut self, face: &Face, name: &str) {
+        self.tags.add(name, ffi::cast_face_to_shape(&face.inner));
+    }
+
+    /// Attach the name `name` to `edge`, which should be an edge of this
+    /// shape.
+    pub fn tag_edge(&mut self, edge: &Edge, name: &str) {
+        self.tags.add(name, ffi::cast_edge_to_shape(&edge.inner));
+    }
+
+    pub fn untag(&mut self, name: &str) {
+        self.tags.remove(name);
+    }
+
+    pub fn tag_names(&self) -> impl Iterator<Item = &str> {
+        self.tags.names()
+    }
+
+    /// The faces currently carrying the tag `name`.
+    pub fn find_tagged(&self, name: &str) -> Vec<Face> {
+        self.tags
+            .get(name)
+            .filter(|shape| shape.ShapeType() == TopAbs_ShapeEnum::TopAbs_FACE)
+            .map(|shape| Face::from_face(ffi::TopoDS_cast_to_face(shape)))
+            .collect()
+    }
+
+    /// The edges currently carrying the tag `name`.
+    pub fn find_tagged_edges(&self, name: &str) -> Vec<Edge> {
+        self.tags
+            .get(name)
+            .filter(|shape| shape.ShapeType() == TopAbs_ShapeEnum::TopAbs_EDGE)
+            .map(|shape| Edge::from_edge(ffi::TopoDS_cast_to_edge(shape)))
+            .collect()
+    }
+
+    /// Apply an operation which reports its `History` (such as
+    /// `fillet_edges_with_history` or `subtract_with_history`), and carry
+    /// the tags of this shape over to the result.
+    ///
+    /// Along with the new shape, returns the names of tags which lost faces
+    /// or edges deleted by the operation. A tag which lost all of them is no
+    /// longer present on the result, and one which lost some keeps the
+    /// rest.
+    pub fn apply_with_history<S: Into<Shape>>(
+        &self,
+        op: impl FnOnce(&Shape) -> Result<(S, History), Error>,
+    ) -> Result<(Shape, Vec<String>), Error> {
+        let (result, history) = op(self)?;
+        let mut result: Shape = result.into();
+
+        let (tags, dropped) = self.tags.remap(&history);
+        result.tags = tags;
+
+        Ok((result, dropped))
+    }
+
+    /// Edges which bound only a single face, i.e. the free boundary of the
+    /// shape. A watertight shape has none.
+    pub fn free_edges(&self) -> Vec<Edge> {
+        let mut edge_map = ffi::new_indexed_data_map_of_shape_list_of_shape();
+        ffi::map_shapes_and_unique_ancestors(
+            &self.inner,
+            ffi::TopAbs_ShapeEnum::TopAbs_EDGE,
+            ffi::TopAbs_ShapeEnum::TopAbs_FACE,
+            edge_map.pin_mut(),
+        );
+
+        (1..=edge_map.Extent())
+            .filter(|&i| edge_map.FindFromIndex(i).Size() == 1)
+            .map(|i| Edge::from_edge(ffi::TopoDS_cast_to_edge(edge_map.FindKey(i))))
+            .collect()
+    }
+
+    /// Returns true if this shape holds at least one solid, and none of its
+    /// solids have free edges. Booleans and fillets give compounds, so the
+    /// solids are looked for inside the shape, not just at its top.
+    pub fn is_closed_solid(&self) -> bool {
+        let solids: Vec<Shape> = self.solids().map(Shape::from).collect();
+
+        !solids.is_empty() && solids.iter().all(|solid| solid.free_edges().is_empty())
+    }
+
     pub fn faces(&self) -> FaceIterator {
         let explorer = ffi::TopExp_Explorer_ctor(&self.inner, ffi::TopAbs_ShapeEnum::TopAbs_FACE);
         FaceIterator { explorer }
@@ -824,6 +1275,36 @@ impl Shape {
         Self::from_shape(solid_maker.pin_mut().Shape())
     }
 
+    /// Like `hollow`, but also returns the `History` of the operation.
+    pub fn hollow_with_history<T: AsRef<Face>>(
+        &self,
+        offset: f64,
+        faces_to_remove: impl IntoIterator<Item = T>,
+    ) -> (Self, History) {
+        let mut faces_list = ffi::new_list_of_shape();
+
+        for face in faces_to_remove.into_iter() {
+            ffi::shape_list_append_face(faces_list.pin_mut(), &face.as_ref().inner);
+        }
+
+        let mut solid_maker = ffi::BRepOffsetAPI_MakeThickSolid_ctor();
+        ffi::MakeThickSolidByJoin(
+            solid_maker.pin_mut(),
+            &self.inner,
+            &faces_list,
+            offset,
+            0.001,
+        );
+
+        let shape = Self::from_shape(solid_maker.pin_mut().Shape());
+        let history = ffi::BRepTools_History_from_thick_solid(
+            &History::arguments([&*self.inner]),
+            solid_maker.pin_mut(),
+        );
+
+        (shape, History::from_history(history, &self.inner))
+    }
+
     #[must_use]
     pub fn offset_surface(&self, offset: f64) -> Self {
         let faces_to_remove: [Face; 0] = [];
@@ -845,12 +1326,110 @@ impl Shape {
         Self::from_shape(make_hole.pin_mut().Shape())
     }
 
-    pub fn fix(&mut self) {
+    /// Add a stiffening rib to this shape. The open `profile` is the
+    /// centerline of the rib's edge, and is extended along `direction`
+    /// until it runs into `target` (usually this shape itself). The rib is
+    /// `thickness` thick, centered on the plane containing `profile` and
+    /// `direction`.
+    ///
+    /// Fails with `Error::RibMissesTarget` if the extended profile never
+    /// reaches `target`.
+    pub fn rib(
+        &self,
+        profile: &Wire,
+        direction: Vector3<f64>,
+        thickness: f64,
+        target: &Shape,
+    ) -> Result<Shape, Error> {
+        let direction = direction.normalize();
+        let points: Vec<_> = profile
+            .edges()
+            .flat_map(|edge| [edge.start_point(), edge.end_point()])
+            .collect();
+        let start = *points.first().ok_or(Error::NotEnoughPoints)?;
+
+        // The thickness goes across the plane of the profile and direction.
+        let across = points
+            .iter()
+            .map(|point| (point - start).cross(&direction))
+            .max_by(|a, b| a.norm().total_cmp(&b.norm()))
+            .filter(|across| across.norm() > 1.0e-9)
+            .ok_or(Error::NotEnoughPoints)?
+            .normalize();
+
+        // Extend the profile far enough to pass all the way through the target.
+        let bounds = crate::bounding_box::aabb(target);
+        let (min, max) = (bounds.min(), bounds.max());
+        let reach = points
+            .iter()
+            .flat_map(|point| {
+                [min.x, max.x]
+                    .into_iter()
+                    .flat_map(|x| [min.y, max.y].map(|y| (x, y)))
+                    .flat_map(|(x, y)| [min.z, max.z].map(|z| point![x, y, z]))
+                    .map(move |corner| (corner - point).dot(&direction))
+            })
+            .fold(0.0, f64::max)
+            + thickness;
+
+        let mut sheet = Shape::from(profile).prism(direction * reach);
+        sheet.transform(&TandR::default().translation(-across * thickness / 2.0));
+        let slab = sheet.prism(across * thickness);
+
+        if slab.intersect(target)?.volume() <= f64::EPSILON {
+            let gap = slab.distance_between(target)?.unwrap_or(f64::INFINITY);
+            return Err(Error::RibMissesTarget { gap });
+        }
+
+        // Keep the part of the slab between the profile and the target.
+        let profile_shape = Shape::from(profile);
+        let pieces = slab.subtract(target)?;
+        let mut result = Shape::from_shape(&self.inner);
+
+        for piece in pieces.solids() {
+            let piece = Shape::from(piece);
+
+            if piece
+                .distance_between(&profile_shape)?
+                .is_some_and(|distance| distance < 1.0e-6)
+            {
+                result = result.union(&piece).into();
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Sweep this shape in a straight line along `dir`.
+    fn prism(&self, dir: Vector3<f64>) -> Shape {
+        let prism_vec = make_vec(dir);
+
+        let copy = false;
+        let canonize = true;
+
+        let mut make_prism =
+            ffi::BRepPrimAPI_MakePrism_ctor(&self.inner, &prism_vec, copy, canonize);
+
+        Shape::from_shape(make_prism.pin_mut().Shape())
+    }
+
+    /// Fix problems with the shape's topology and geometry in place, with
+    /// `ShapeFix_Shape`. Tags follow the faces and edges they were on.
+    ///
+    /// Returns the names of tags which lost faces or edges the fixes
+    /// removed.
+    pub fn fix(&mut self) -> Vec<String> {
         let mut shapefixer = ffi::ShapeFix_Shape_new(&self.inner);
         ffi::ShapeFix_Shape_perform(shapefixer.pin_mut());
-        let inner = ffi::ShapeFix_Shape_shape(shapefixer);
 
-        self.inner = inner;
+        let history = History::from_history(ffi::ShapeFix_Shape_history(&shapefixer), &self.inner);
+        let (tags, dropped) = self.tags.remap(&history);
+
+        self.inner = ffi::ShapeFix_Shape_shape(shapefixer);
+        self.tags = tags;
+        self.cache.clear();
+
+        dropped
     }
 
     pub fn distance_between(&self, other: &Shape) -> Result<Option<f64>, Error> {
@@ -901,13 +1480,29 @@ impl Shape {
             return Ok(false);
         }
 
-        self.calculate_mesh(mesh_tolerance)?;
-        other.calculate_mesh(mesh_tolerance)?;
+        // The proximity test works on the triangulations stored on the faces
+        // it's given, so mesh copies of the shapes and test those, rather
+        // than writing triangulations to faces other shapes may share.
+        let meshed_copy = |shape: &Shape| {
+            let copy_geometry = false;
+            let copy_mesh = false;
+            let mut copier = ffi::BRepBuilderAPI_Copy_new(&shape.inner, copy_geometry, copy_mesh);
+            let copy = Shape::from_shape(copier.pin_mut().Shape());
+
+            let mesher = ffi::BRepMesh_IncrementalMesh_ctor(&copy.inner, mesh_tolerance);
+            if !mesher.IsDone() {
+                return Err(Error::TriangulationFailed);
+            }
+
+            Ok(copy)
+        };
+        let self_copy = meshed_copy(self)?;
+        let other_copy = meshed_copy(other)?;
 
         let tolerance_mult = self.center_of_mass().coords.abs().max().max(other.center_of_mass().coords.abs().max()) + 1.0;
         let postmult_tolerance = tolerance * tolerance_mult;
 
-        let mut shape_prox = ffi::BRepExtrema_ShapeProximity(&self.inner, &other.inner, postmult_tolerance);
+        let mut shape_prox = ffi::BRepExtrema_ShapeProximity(&self_copy.inner, &other_copy.inner, postmult_tolerance);
 
         shape_prox.pin_mut().Perform();
 
@@ -945,20 +1540,80 @@ impl Shape {
     //    Ok(normal)
     //}
 
+    /// The volume enclosed by the solids of this shape.
+    pub fn volume(&self) -> f64 {
+        self.mass_properties().volume
+    }
+
     pub fn center_of_mass(&self) -> Point3<f64> {
-        let mut props = ffi::GProp_GProps_ctor();
+        self.mass_properties().center_of_mass
+    }
 
-        ffi::BRepGProp_SurfaceProperties(&self.inner, props.pin_mut());
+    /// The mass, center of mass and inertia of the solids of this shape,
+    /// made of a material of `density`.
+    pub fn inertial_properties(&self, density: f64) -> InertialProperties {
+        let mut props = ffi::GProp_GProps_ctor();
+        ffi::BRepGProp_VolumeProperties(&self.inner, props.pin_mut());
 
         let center = ffi::GProp_GProps_CentreOfMass(&props);
+        let matrix = ffi::GProp_GProps_MatrixOfInertia(&props);
+
+        InertialProperties {
+            mass: props.Mass() * density,
+            center_of_mass: point![center.X(), center.Y(), center.Z()],
+            inertia: Matrix3::from_fn(|row, column| {
+                matrix.Value(row as i32 + 1, column as i32 + 1) * density
+            }),
+        }
+    }
 
-        point![center.X(), center.Y(), center.Z()]
+    /// The axis-aligned bounding box of this shape. It's computed the first
+    /// time it's asked for, and kept until the shape is changed.
+    pub fn bounding_box(&self) -> &BoundingBox {
+        self.cache.bounding_box(|| bounding_box::aabb(self))
+    }
+
+    /// Compute the derived data selected by `what` now, rather than on first
+    /// use. Useful for warming the caches of shapes on worker threads before
+    /// handing them to a thread which needs answers quickly.
+    ///
+    /// Meshes are computed with the default deflection of `mesh`.
+    pub fn precompute(&self, what: PrecomputeFlags) -> Result<(), Error> {
+        if what.contains(PrecomputeFlags::BOUNDING_BOX) {
+            self.bounding_box();
+        }
+
+        if what.contains(PrecomputeFlags::MASS_PROPERTIES) {
+            self.mass_properties();
+        }
+
+        if what.contains(PrecomputeFlags::MESH) {
+            self.mesh()?;
+        }
+
+        Ok(())
+    }
+
+    fn mass_properties(&self) -> MassProperties {
+        self.cache.mass_properties(|| {
+            let mut volume_props = ffi::GProp_GProps_ctor();
+            ffi::BRepGProp_VolumeProperties(&self.inner, volume_props.pin_mut());
+
+            let mut surface_props = ffi::GProp_GProps_ctor();
+            ffi::BRepGProp_SurfaceProperties(&self.inner, surface_props.pin_mut());
+            let center = ffi::GProp_GProps_CentreOfMass(&surface_props);
+
+            MassProperties {
+                volume: volume_props.Mass(),
+                center_of_mass: point![center.X(), center.Y(), center.Z()],
+            }
+        })
     }
 
     pub fn transform(&mut self, tandr: &TandR<f64>) {
-        let shape = &self.inner;
+        let transform: UniquePtr<ffi::gp_Trsf> = tandr.into();
 
-        self.inner = tandr.transform_shape(shape);
+        self.apply_transform(&transform);
     }
 
     pub fn transform_affine(&mut self, matrix: &Matrix4<f64>) {
@@ -966,14 +1621,88 @@ impl Shape {
 
         transform.pin_mut().SetValues(matrix.m11, matrix.m12, matrix.m13, matrix.m14, matrix.m21, matrix.m22, matrix.m23, matrix.m24, matrix.m31, matrix.m32, matrix.m33, matrix.m34);
  
-        let mut transformer = ffi::BRepBuilderAPI_Transform_ctor(&self.inner, &transform, false);
+        self.apply_transform(&transform);
+    }
+
+    /// Replace this shape with it transformed by `transform`, carrying its
+    /// tags over to the transformed faces and edges.
+    fn apply_transform(&mut self, transform: &ffi::gp_Trsf) {
+        let mut transformer = ffi::BRepBuilderAPI_Transform_ctor(&self.inner, transform, false);
+        let new_shape = ffi::TopoDS_Shape_to_owned(transformer.pin_mut().Shape());
+
+        if !self.tags.is_empty() {
+            let history = ffi::BRepTools_History_from_transform(
+                &History::arguments([&*self.inner]),
+                transformer.pin_mut(),
+            );
+            // Transforms don't delete anything.
+            let (tags, _) = self
+                .tags
+                .remap(&History::from_history(history, &self.inner));
+            self.tags = tags;
+        }
+
+        self.inner = new_shape;
+        self.cache.clear();
+    }
+}
+
+/// Which derived data `Shape::precompute` should compute.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PrecomputeFlags(u8);
+
+impl PrecomputeFlags {
+    pub const NONE: Self = Self(0);
+    pub const BOUNDING_BOX: Self = Self(1 << 0);
+    /// Volume and center of mass.
+    pub const MASS_PROPERTIES: Self = Self(1 << 1);
+    pub const MESH: Self = Self(1 << 2);
+    pub const ALL: Self = Self(Self::BOUNDING_BOX.0 | Self::MASS_PROPERTIES.0 | Self::MESH.0);
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
 
-        let new_shape = transformer.pin_mut().Shape();
+impl BitOr for PrecomputeFlags {
+    type Output = Self;
 
-        self.inner = ffi::TopoDS_Shape_to_owned(new_shape)
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
     }
 }
 
+/// How copies of a shape are rotated when patterned along a path.
+#[derive(Copy, Clone)]
+pub enum PathOrientation<'a> {
+    /// Every copy keeps the orientation of the original.
+    Fixed,
+    /// The local X axis of each copy follows the path tangent, with local Z
+    /// kept as close to the global Z axis as possible.
+    Tangent,
+    /// The local X axis of each copy follows the path tangent, and local Z
+    /// follows the normal of the face at that point.
+    TangentAndNormal(&'a Face),
+}
+
+/// A rotation taking the X axis to `x_dir`, and the Z axis as close to `up`
+/// as possible.
+fn path_frame(x_dir: UnitVector3<f64>, up: Vector3<f64>) -> UnitQuaternion<f64> {
+    let perpendicular =
+        |v: Vector3<f64>| UnitVector3::try_new(v - x_dir.into_inner() * v.dot(&x_dir), 1.0e-9);
+
+    let z_dir = perpendicular(up)
+        .or_else(|| perpendicular(Vector3::y()))
+        .unwrap_or_else(|| UnitVector3::new_normalize(Vector3::z()));
+    let y_dir = z_dir.cross(&x_dir);
+
+    UnitQuaternion::from_rotation_matrix(&Rotation3::from_basis_unchecked(&[
+        x_dir.into_inner(),
+        y_dir,
+        z_dir.into_inner(),
+    ]))
+}
+
 /// Information about a point where a line hits (i.e. intersects) a face
 pub struct LineFaceHitPoint {
     /// The face that is hit
@@ -1009,3 +1738,182 @@ impl ChamferMaker {
         Shape::from_shape(self.inner.pin_mut().Shape())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nalgebra::vector;
+
+    fn shares_any(a: &Shape, b: &Shape, shape_type: TopAbs_ShapeEnum) -> bool {
+        let sub_shapes = |shape: &Shape| {
+            let mut map = ffi::new_indexed_map_of_shape();
+            ffi::map_shapes(&shape.inner, shape_type, map.pin_mut());
+            map
+        };
+
+        let (a, b) = (sub_shapes(a), sub_shapes(b));
+
+        (1..=a.Extent()).any(|i| (1..=b.Extent()).any(|j| a.FindKey(i).IsPartner(b.FindKey(j))))
+    }
+
+    #[test]
+    fn shallow_and_deep_copies() {
+        let prototype = Shape::box_with_dimensions(10.0, 10.0, 10.0);
+        let shared = prototype.share_count();
+
+        let mut shallow = prototype.shallow_copy();
+        assert_eq!(prototype.share_count(), shared + 1);
+        assert!(shallow.inner.IsPartner(&prototype.inner));
+
+        // Moving the shallow copy gives it a new location, but leaves the
+        // prototype where it was.
+        shallow.transform(&TandR::new(
+            vector![20.0, 0.0, 0.0],
+            UnitQuaternion::identity(),
+        ));
+        assert!((shallow.bounding_box().min() - point![20.0, 0.0, 0.0]).norm() < 1.0e-6);
+        assert!((prototype.bounding_box().min() - Point3::origin()).norm() < 1.0e-6);
+
+        // A deep copy shares nothing, down to the vertices.
+        let deep = prototype.deep_copy();
+        assert_eq!(prototype.share_count(), shared + 1);
+        assert_eq!(deep.share_count(), 1);
+        for shape_type in [
+            TopAbs_ShapeEnum::TopAbs_SOLID,
+            TopAbs_ShapeEnum::TopAbs_FACE,
+            TopAbs_ShapeEnum::TopAbs_EDGE,
+            TopAbs_ShapeEnum::TopAbs_VERTEX,
+        ] {
+            assert!(shares_any(&prototype, &shallow, shape_type));
+            assert!(!shares_any(&prototype, &deep, shape_type));
+        }
+
+        drop(shallow);
+        assert_eq!(prototype.share_count(), shared);
+
+        // Patterns share the prototype unless asked not to.
+        let spine =
+            Wire::from_edges([&Edge::segment(Point3::origin(), point![100.0, 0.0, 0.0])]).unwrap();
+        let pattern = prototype
+            .pattern_along_wire(&spine, 4, PathOrientation::Fixed, false)
+            .unwrap();
+        assert_eq!(prototype.share_count(), shared + 4);
+
+        let deep_pattern = prototype
+            .pattern_along_wire_deep(&spine, 4, PathOrientation::Fixed, false)
+            .unwrap();
+        assert_eq!(prototype.share_count(), shared + 4);
+        assert!(!shares_any(
+            &prototype,
+            &deep_pattern,
+            TopAbs_ShapeEnum::TopAbs_FACE
+        ));
+
+        drop(pattern);
+        assert_eq!(prototype.share_count(), shared);
+    }
+
+    #[test]
+    fn overlapping_boxes() {
+        let block = Shape::box_with_dimensions(10.0, 10.0, 10.0);
+        let overlapping = Shape::box_from_corners(point![5.0, 5.0, 5.0], point![15.0, 15.0, 15.0]);
+        let apart = Shape::box_from_corners(point![50.0, 0.0, 0.0], point![60.0, 10.0, 10.0]);
+
+        assert!(block
+            .overlaps(&overlapping, 0.1, 1.0e-3, None, None)
+            .unwrap());
+        assert!(!block.overlaps(&apart, 0.1, 1.0e-3, None, None).unwrap());
+    }
+
+    #[test]
+    fn union_of_many() {
+        let blocks: Vec<_> = (0..3)
+            .map(|i| {
+                let x = 5.0 * i as f64;
+                Shape::box_from_corners(point![x, 0.0, 0.0], point![x + 10.0, 10.0, 10.0])
+            })
+            .collect();
+
+        let fused = Shape::union_all(&blocks).unwrap();
+        assert_eq!(fused.solids().count(), 1);
+        assert!((fused.volume() - 20.0 * 10.0 * 10.0).abs() < 1.0e-6);
+
+        let single = Shape::union_all(&blocks[..1]).unwrap();
+        assert!((single.volume() - 1000.0).abs() < 1.0e-6);
+
+        let nothing = Shape::union_all(Vec::<Shape>::new()).unwrap();
+        assert_eq!(nothing.solids().count(), 0);
+    }
+
+    #[test]
+    fn patterns_along_wires() {
+        let block = Shape::box_with_dimensions(2.0, 2.0, 2.0);
+        let spine =
+            Wire::from_edges([&Edge::segment(Point3::origin(), point![100.0, 0.0, 0.0])]).unwrap();
+
+        // Copies are spaced evenly from one end of the spine to the other.
+        let pattern = block
+            .pattern_along_wire(&spine, 5, PathOrientation::Fixed, false)
+            .unwrap();
+        assert_eq!(pattern.solids().count(), 5);
+        assert!((pattern.volume() - 5.0 * 8.0).abs() < 1.0e-6);
+        assert!((pattern.bounding_box().max() - point![102.0, 2.0, 2.0]).norm() < 1.0e-6);
+
+        // Overlapping copies fuse into one solid.
+        let long_block = Shape::box_with_dimensions(30.0, 2.0, 2.0);
+        let fused = long_block
+            .pattern_along_wire(&spine, 5, PathOrientation::Fixed, true)
+            .unwrap();
+        assert_eq!(fused.solids().count(), 1);
+        assert!((fused.volume() - 130.0 * 4.0).abs() < 1.0e-6);
+
+        assert!(matches!(
+            block.pattern_along_wire(&spine, 1, PathOrientation::Fixed, false),
+            Err(Error::TooFewInstances)
+        ));
+
+        let empty = Wire::from_edges_unconnected(&[]);
+        assert!(matches!(
+            block.pattern_along_wire(&empty, 5, PathOrientation::Fixed, false),
+            Err(Error::ZeroLengthWire)
+        ));
+    }
+
+    #[test]
+    fn boolean_result_is_closed_solid() {
+        let block = Shape::box_with_dimensions(10.0, 10.0, 10.0);
+        let hole = Shape::cylinder(point![5.0, 5.0, -1.0], 2.0, Vector3::z(), 12.0);
+        let drilled: Shape = block.subtract(&hole).unwrap().into();
+
+        assert_eq!(drilled.shape_type(), ShapeType::Compound);
+        assert!(drilled.is_closed_solid());
+
+        let face = Shape::from(block.faces().next().unwrap());
+        assert!(!face.is_closed_solid());
+    }
+
+    #[test]
+    fn ribs() {
+        let block = Shape::box_from_corners(Point3::origin(), point![100.0, 20.0, 10.0]);
+        let profile = Wire::from_edges([&Edge::segment(
+            point![10.0, 10.0, 40.0],
+            point![90.0, 10.0, 40.0],
+        )])
+        .unwrap();
+
+        // The rib reaches down from the profile to the top of the block, and
+        // stops there instead of poking out underneath.
+        let ribbed = block.rib(&profile, -Vector3::z(), 2.0, &block).unwrap();
+        assert!((ribbed.volume() - (100.0 * 20.0 * 10.0 + 80.0 * 2.0 * 30.0)).abs() < 1.0e-3);
+
+        let bounds = ribbed.bounding_box();
+        assert!((bounds.min() - Point3::origin()).norm() < 1.0e-6);
+        assert!((bounds.max().z - 40.0).abs() < 1.0e-6);
+
+        match block.rib(&profile, Vector3::z(), 2.0, &block) {
+            Err(Error::RibMissesTarget { gap }) => assert!((gap - 30.0).abs() < 1.0e-6),
+            Err(error) => panic!("expected RibMissesTarget, got {error}"),
+            Ok(_) => panic!("a rib pointing away from the block can't reach it"),
+        }
+    }
+}
@@ -0,0 +1,349 @@
+use crate::primitives::Edge;
+use crate::primitives::EdgeType;
+use crate::primitives::Wire;
+use crate::Error;
+use crate::CONFUSION;
+use nalgebra::Point3;
+use nalgebra::Vector3;
+use std::f64::consts::PI;
+use std::f64::consts::TAU;
+
+/// Arcs are only fitted to runs of at least this many segments.
+const MIN_ARC_SEGMENTS: usize = 3;
+
+/// A piece of the wire being simplified.
+enum Run {
+    /// Consecutive straight segments, through these points.
+    Polyline(Vec<Point3<f64>>),
+    /// Any other edge, which is kept as it is.
+    Curve(Edge),
+}
+
+impl Wire {
+    /// A copy of this wire with fewer edges: zero-length edges are removed,
+    /// consecutive segments on a common line are merged, and with
+    /// `fit_arcs`, runs of segments on a common circle are replaced with
+    /// arcs.
+    ///
+    /// The simplified wire is no further than `tolerance` from this one, and
+    /// closed if this one is. Edges other than straight segments are kept
+    /// as they are.
+    pub fn simplified(&self, tolerance: f64, fit_arcs: bool) -> Result<Wire, Error> {
+        // The wire's edges come in order along it, and reversed edges run
+        // from the end of their curve to its start.
+        let ends: Vec<_> = self
+            .edges()
+            .map(|edge| {
+                let (start, end) = (edge.start_point(), edge.end_point());
+                if edge.is_reversed() {
+                    (end, start)
+                } else {
+                    (start, end)
+                }
+            })
+            .collect();
+
+        let mut runs: Vec<Run> = vec![];
+        for (edge, (start, finish)) in self.edges().zip(ends) {
+            if edge.edge_type() != EdgeType::Line {
+                runs.push(Run::Curve(edge));
+                continue;
+            }
+
+            if (finish - start).norm() < CONFUSION {
+                continue;
+            }
+
+            match runs.last_mut() {
+                Some(Run::Polyline(points)) => points.push(finish),
+                _ => runs.push(Run::Polyline(vec![start, finish])),
+            }
+        }
+
+        // A closed wire of segments only may be started anywhere, so start it
+        // at its sharpest corner, where no run could carry on through.
+        if let [Run::Polyline(points)] = runs.as_mut_slice() {
+            if self.is_closed() && points.len() > 3 {
+                points.pop();
+                let sharpest = (0..points.len())
+                    .max_by(|a, b| turn_at(points, *a).total_cmp(&turn_at(points, *b)))
+                    .unwrap();
+                points.rotate_left(sharpest);
+                points.push(points[0]);
+            }
+        }
+
+        let mut edges = vec![];
+        for run in runs {
+            match run {
+                Run::Polyline(points) => {
+                    simplify_polyline(&points, tolerance, fit_arcs, &mut edges)
+                }
+                Run::Curve(edge) => edges.push(edge),
+            }
+        }
+
+        Wire::from_edges_consuming(edges)
+    }
+}
+
+/// The angle the closed polygon `points` turns through at `index`.
+fn turn_at(points: &[Point3<f64>], index: usize) -> f64 {
+    let count = points.len();
+    let before = points[index] - points[(index + count - 1) % count];
+    let after = points[(index + 1) % count] - points[index];
+
+    before.angle(&after)
+}
+
+/// Append edges approximating the polyline through `points` to `edges`.
+fn simplify_polyline(
+    points: &[Point3<f64>],
+    tolerance: f64,
+    fit_arcs: bool,
+    edges: &mut Vec<Edge>,
+) {
+    let mut start = 0;
+
+    while start < points.len() - 1 {
+        // Grow the line and the arc from `start` as far as they stay within
+        // the tolerance, and keep whichever reaches further.
+        let mut line_end = start + 1;
+        while line_end + 1 < points.len() && fits_line(&points[start..=line_end + 1], tolerance) {
+            line_end += 1;
+        }
+
+        let mut arc_end = start;
+        if fit_arcs {
+            let mut end = start + MIN_ARC_SEGMENTS;
+            while end < points.len() && fits_arc(&points[start..=end], tolerance) {
+                arc_end = end;
+                end += 1;
+            }
+        }
+
+        if arc_end > line_end {
+            let middle = points[(start + arc_end) / 2];
+            edges.push(Edge::arc(points[start], middle, points[arc_end]));
+            start = arc_end;
+        } else {
+            edges.push(Edge::segment(points[start], points[line_end]));
+            start = line_end;
+        }
+    }
+}
+
+/// Whether all of `points` are within `tolerance` of the segment from the
+/// first to the last, in order.
+fn fits_line(points: &[Point3<f64>], tolerance: f64) -> bool {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let chord = last - first;
+    let length = chord.norm();
+
+    if length < CONFUSION {
+        return false;
+    }
+
+    let direction = chord / length;
+    let mut previous_along = 0.0;
+
+    points.iter().all(|point| {
+        let offset = point - first;
+        let along = offset.dot(&direction);
+        let in_order = along >= previous_along - tolerance && along <= length + tolerance;
+        previous_along = along;
+
+        in_order && (offset - direction * along).norm() <= tolerance
+    })
+}
+
+/// Whether the polyline through `points` is within `tolerance` of the arc
+/// through its first, middle and last points, running around it in order
+/// and by no more than half a turn.
+fn fits_arc(points: &[Point3<f64>], tolerance: f64) -> bool {
+    let (first, middle, last) = (
+        points[0],
+        points[(points.len() - 1) / 2],
+        points[points.len() - 1],
+    );
+
+    let Some((center, radius, normal)) = circle_through(&first, &middle, &last) else {
+        return false;
+    };
+
+    let distance_to_circle = |point: &Point3<f64>| {
+        let offset = point - center;
+        let height = offset.dot(&normal);
+        let radial = (offset - normal * height).norm();
+
+        (radial - radius).hypot(height)
+    };
+
+    let x_axis = (first - center) / radius;
+    let y_axis = normal.cross(&x_axis);
+    let angle = |point: &Point3<f64>| {
+        let offset = point - center;
+        offset
+            .dot(&y_axis)
+            .atan2(offset.dot(&x_axis))
+            .rem_euclid(TAU)
+    };
+
+    let mut previous_angle = 0.0;
+    for (i, point) in points.iter().enumerate().skip(1) {
+        let point_angle = angle(point);
+        if point_angle <= previous_angle || point_angle > PI + 1.0e-9 {
+            return false;
+        }
+        previous_angle = point_angle;
+
+        // Both the points and the segments between them, which stray
+        // furthest from the circle in their middles, must be close.
+        let segment_middle = nalgebra::center(&points[i - 1], point);
+        if distance_to_circle(point) > tolerance || distance_to_circle(&segment_middle) > tolerance
+        {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// The center, radius and normal of the circle through `a`, `b` and `c`,
+/// with the normal pointing the way which makes it run from `a` through `b`
+/// to `c` counter-clockwise, or `None` if they're in a line.
+fn circle_through(
+    a: &Point3<f64>,
+    b: &Point3<f64>,
+    c: &Point3<f64>,
+) -> Option<(Point3<f64>, f64, Vector3<f64>)> {
+    let (ab, ac) = (b - a, c - a);
+    let normal = ab.cross(&ac);
+    let denominator = 2.0 * normal.norm_squared();
+
+    if denominator <= f64::EPSILON * ab.norm_squared() * ac.norm_squared() {
+        return None;
+    }
+
+    let offset = (normal.cross(&ab) * ac.norm_squared() + ac.cross(&normal) * ab.norm_squared())
+        / denominator;
+
+    Some((a + offset, offset.norm(), normal.normalize()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nalgebra::point;
+
+    /// The distance from `point` to the nearest of the segments through
+    /// `polyline`.
+    fn distance_to_polyline(point: &Point3<f64>, polyline: &[Point3<f64>]) -> f64 {
+        polyline
+            .windows(2)
+            .map(|segment| {
+                let chord = segment[1] - segment[0];
+                let t = ((point - segment[0]).dot(&chord) / chord.norm_squared()).clamp(0.0, 1.0);
+                (point - (segment[0] + chord * t)).norm()
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    fn polygon(count: usize, radius: f64) -> Vec<Point3<f64>> {
+        (0..count)
+            .map(|i| {
+                let angle = TAU * i as f64 / count as f64;
+                point![radius * angle.cos(), radius * angle.sin(), 0.0]
+            })
+            .collect()
+    }
+
+    fn assert_within(simplified: &Wire, original: &[Point3<f64>], tolerance: f64) {
+        let mut closed = original.to_vec();
+        closed.push(original[0]);
+
+        for point in simplified
+            .edges()
+            .flat_map(|edge| edge.approximation_segments(tolerance / 10.0))
+        {
+            let distance = distance_to_polyline(&point, &closed);
+            assert!(distance <= tolerance * 1.01, "{point} is {distance} away");
+        }
+    }
+
+    #[test]
+    fn circle_collapses_to_arcs() {
+        let points = polygon(360, 10.0);
+        let wire = Wire::from_ordered_points(points.clone()).unwrap();
+        assert_eq!(wire.edges().count(), 360);
+
+        let simplified = wire.simplified(0.01, true).unwrap();
+        assert!(simplified.is_closed());
+        assert!(simplified.edges().count() <= 4);
+        assert!(simplified
+            .edges()
+            .all(|edge| edge.edge_type() == EdgeType::Circle));
+        assert_within(&simplified, &points, 0.01);
+    }
+
+    #[test]
+    fn circle_without_arcs() {
+        let points = polygon(360, 10.0);
+        let wire = Wire::from_ordered_points(points.clone()).unwrap();
+
+        let simplified = wire.simplified(0.01, false).unwrap();
+        assert!(simplified.is_closed());
+        assert!(simplified.edges().count() < 100);
+        assert!(simplified
+            .edges()
+            .all(|edge| edge.edge_type() == EdgeType::Line));
+        assert_within(&simplified, &points, 0.01);
+    }
+
+    #[test]
+    fn collinear_segments_merge() {
+        // A 4 x 2 rectangle, with every side cut into pieces.
+        let corners = [
+            point![0.0, 0.0, 0.0],
+            point![4.0, 0.0, 0.0],
+            point![4.0, 2.0, 0.0],
+            point![0.0, 2.0, 0.0],
+        ];
+        let points: Vec<_> = (0..4)
+            .flat_map(|side| {
+                let (from, to) = (corners[side], corners[(side + 1) % 4]);
+                (0..10).map(move |i| from + (to - from) * (i as f64 / 10.0))
+            })
+            .collect();
+        // Start in the middle of a side.
+        let mut rotated = points.clone();
+        rotated.rotate_left(5);
+
+        let wire = Wire::from_ordered_points(rotated).unwrap();
+        let simplified = wire.simplified(1.0e-6, true).unwrap();
+
+        assert!(simplified.is_closed());
+        assert_eq!(simplified.edges().count(), 4);
+        assert!((simplified.length() - 12.0).abs() < 1.0e-9);
+        assert_within(&simplified, &points, 1.0e-6);
+    }
+
+    #[test]
+    fn curves_are_kept() {
+        let wire = Wire::from_edges_consuming(vec![
+            Edge::segment(point![0.0, 0.0, 0.0], point![1.0, 0.0, 0.0]),
+            Edge::segment(point![1.0, 0.0, 0.0], point![2.0, 0.0, 0.0]),
+            Edge::arc(
+                point![2.0, 0.0, 0.0],
+                point![3.0, 1.0, 0.0],
+                point![2.0, 2.0, 0.0],
+            ),
+            Edge::segment(point![2.0, 2.0, 0.0], point![0.0, 2.0, 0.0]),
+        ])
+        .unwrap();
+
+        let simplified = wire.simplified(1.0e-6, true).unwrap();
+        let types: Vec<_> = simplified.edges().map(|edge| edge.edge_type()).collect();
+        assert_eq!(types, [EdgeType::Line, EdgeType::Circle, EdgeType::Line]);
+    }
+}
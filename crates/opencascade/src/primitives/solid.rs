@@ -3,6 +3,7 @@ use crate::primitives::Compound;
 use crate::primitives::Edge;
 use crate::primitives::Face;
 use crate::primitives::Shape;
+use crate::primitives::Vertex;
 use crate::primitives::Wire;
 use crate::Error;
 use cxx::UniquePtr;
@@ -62,6 +63,28 @@ impl Solid {
         Self::from_solid(solid)
     }
 
+    /// Loft through `wires` to a point at `apex`, closing the solid off
+    /// there like a cone or a pyramid.
+    pub fn loft_to_point<T: AsRef<Wire>>(
+        wires: impl IntoIterator<Item = T>,
+        apex: &Vertex,
+    ) -> Self {
+        let is_solid = true;
+        let mut make_loft = ffi::BRepOffsetAPI_ThruSections_ctor(is_solid);
+
+        for wire in wires.into_iter() {
+            make_loft.pin_mut().AddWire(&wire.as_ref().inner);
+        }
+        make_loft.pin_mut().AddVertex(&apex.inner);
+
+        make_loft.pin_mut().CheckCompatibility(true);
+
+        let shape = make_loft.pin_mut().Shape();
+        let solid = ffi::TopoDS_cast_to_solid(shape);
+
+        Self::from_solid(solid)
+    }
+
     #[must_use]
     pub fn subtract(&self, other: &Solid) -> Result<BooleanShape, Error> {
         let inner_shape = ffi::cast_solid_to_shape(&self.inner);
@@ -0,0 +1,261 @@
+use crate::primitives::Edge;
+use crate::primitives::Shape;
+use crate::primitives::Wire;
+use crate::sweep::FrameMode;
+use crate::sweep::PipeShellBuilder;
+use crate::Error;
+use crate::Z_NORMAL;
+use nalgebra::point;
+use nalgebra::vector;
+use std::f64::consts::TAU;
+
+/// Number of points per turn used to approximate the helix.
+const SAMPLES_PER_TURN: usize = 36;
+
+/// The closed end coils of a squared spring are left this fraction of the
+/// wire diameter apart, so the solid doesn't touch itself.
+const END_COIL_CLEARANCE: f64 = 0.02;
+
+/// How the ends of a compression spring are finished.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SpringEnds {
+    /// The wire simply stops, leaving a round end on each side.
+    Plain,
+    /// The coils run at full pitch to the ends, which are ground flat, so
+    /// the spring stands upright. Adds one inactive turn.
+    Ground,
+    /// The last turn on each side is closed against its neighbour, and the
+    /// ends are ground flat. Adds two inactive turns.
+    SquaredGround,
+}
+
+impl SpringEnds {
+    /// The number of turns of wire in a spring with `active_turns` active
+    /// turns.
+    pub fn total_turns(&self, active_turns: f64) -> f64 {
+        match self {
+            Self::Plain => active_turns,
+            Self::Ground => active_turns + 1.0,
+            Self::SquaredGround => active_turns + 2.0,
+        }
+    }
+
+    /// The height of the spring when fully compressed, with every turn
+    /// touching its neighbours.
+    pub fn solid_height(&self, wire_diameter: f64, active_turns: f64) -> f64 {
+        match self {
+            // The centerline of the wire rises one diameter a turn, and
+            // the round wire stands half a diameter proud of it at each end.
+            Self::Plain => wire_diameter * (self.total_turns(active_turns) + 1.0),
+            Self::Ground | Self::SquaredGround => wire_diameter * self.total_turns(active_turns),
+        }
+    }
+
+    /// The height of the uncompressed spring built by `spring`.
+    pub fn free_height(&self, wire_diameter: f64, pitch: f64, active_turns: f64) -> f64 {
+        match self {
+            Self::Plain => pitch * active_turns + wire_diameter,
+            Self::Ground => pitch * (active_turns + 1.0),
+            Self::SquaredGround => {
+                pitch * active_turns + 2.0 * wire_diameter * (1.0 + END_COIL_CLEARANCE)
+            }
+        }
+    }
+}
+
+/// A helical compression spring standing on the XY plane, coiled
+/// counter-clockwise around the Z axis, with a height of
+/// `ends.free_height(...)`.
+pub fn spring(
+    wire_diameter: f64,
+    coil_outer_diameter: f64,
+    pitch: f64,
+    active_turns: f64,
+    ends: SpringEnds,
+) -> Result<Shape, Error> {
+    let invalid = |reason| Err(Error::InvalidSpring { reason });
+
+    // Written so that NaNs fail every check.
+    if !wire_diameter.is_finite() || wire_diameter <= 0.0 {
+        return invalid("the wire diameter must be positive");
+    }
+    if !coil_outer_diameter.is_finite() || coil_outer_diameter <= 2.0 * wire_diameter {
+        return invalid("the coil must be more than twice as wide as the wire");
+    }
+    if !pitch.is_finite() || pitch <= wire_diameter * (1.0 + END_COIL_CLEARANCE) {
+        return invalid("the pitch must leave room between the turns");
+    }
+    if !active_turns.is_finite() || active_turns <= 0.0 {
+        return invalid("a spring needs active turns");
+    }
+
+    let radius = (coil_outer_diameter - wire_diameter) / 2.0;
+    let total_turns = ends.total_turns(active_turns);
+    let end_pitch = match ends {
+        SpringEnds::Plain | SpringEnds::Ground => pitch,
+        SpringEnds::SquaredGround => wire_diameter * (1.0 + END_COIL_CLEARANCE),
+    };
+
+    // The pitch at `turn` turns along the wire. Closed end turns blend into
+    // the active turns over half a turn, so the helix stays smooth.
+    let pitch_at = |turn: f64| {
+        if end_pitch == pitch {
+            return pitch;
+        }
+
+        let from_end = turn.min(total_turns - turn);
+        let blend = ((from_end - 0.75) / 0.5).clamp(0.0, 1.0);
+        let blend = blend * blend * (3.0 - 2.0 * blend);
+
+        end_pitch + (pitch - end_pitch) * blend
+    };
+
+    let samples = (total_turns * SAMPLES_PER_TURN as f64).ceil() as usize;
+    let step = total_turns / samples as f64;
+
+    // Integrate the pitch along the wire. Plain springs stand on the round
+    // end of the wire, ground springs are ground down to its center.
+    let bottom = match ends {
+        SpringEnds::Plain => wire_diameter / 2.0,
+        SpringEnds::Ground | SpringEnds::SquaredGround => 0.0,
+    };
+    let mut z = bottom;
+    let helix_points: Vec<_> = (0..=samples)
+        .map(|i| {
+            let turn = i as f64 * step;
+            if i > 0 {
+                z += pitch_at(turn - step / 2.0) * step;
+            }
+
+            let angle = turn * TAU;
+            point![radius * angle.cos(), radius * angle.sin(), z]
+        })
+        .collect();
+    let top = z;
+
+    let helix = Wire::from_edges([&Edge::spline_from_points(helix_points, None)])?;
+
+    let tangent = vector![0.0, TAU * radius, pitch_at(0.0)];
+    let profile = Wire::from_edges([&Edge::circle(
+        point![radius, 0.0, bottom],
+        tangent,
+        wire_diameter / 2.0,
+    )?])?;
+
+    let coil: Shape = PipeShellBuilder::new(&profile, &helix)
+        .frame_mode(FrameMode::Binormal(Z_NORMAL))
+        .build()?;
+
+    match ends {
+        SpringEnds::Plain => Ok(coil),
+        // Grind both ends flat at the center of the wire.
+        SpringEnds::Ground | SpringEnds::SquaredGround => {
+            let extent = coil_outer_diameter;
+            let below = Shape::box_from_corners(
+                point![-extent, -extent, -extent],
+                point![extent, extent, 0.0],
+            );
+            let above = Shape::box_from_corners(
+                point![-extent, -extent, top],
+                point![extent, extent, top + extent],
+            );
+
+            coil.subtract(&below)?.subtract(&above)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const WIRE: f64 = 1.0;
+    const OUTER: f64 = 10.0;
+    const PITCH: f64 = 3.0;
+    const ACTIVE: f64 = 4.0;
+
+    fn height(shape: &Shape) -> (f64, f64) {
+        let bounding_box = shape.bounding_box();
+        (bounding_box.min().z, bounding_box.max().z)
+    }
+
+    #[test]
+    fn ground_springs_stand_flat() {
+        for ends in [SpringEnds::Ground, SpringEnds::SquaredGround] {
+            let spring = spring(WIRE, OUTER, PITCH, ACTIVE, ends).unwrap();
+
+            let summary = spring.topology_summary();
+            assert!(summary.closed, "{ends:?}: {summary}");
+            assert!(summary.valid, "{ends:?}: {summary}");
+
+            let (bottom, top) = height(&spring);
+            let free_height = ends.free_height(WIRE, PITCH, ACTIVE);
+            assert!(bottom.abs() < 1.0e-3, "{ends:?}: {bottom}");
+            assert!((top - free_height).abs() < 1.0e-3, "{ends:?}: {top}");
+
+            // The ground ends are flat faces, a whole wire wide.
+            let flat_faces = spring
+                .faces()
+                .filter(|face| {
+                    let bounding_box = Shape::from(face).bounding_box().clone();
+                    bounding_box.max().z - bounding_box.min().z < 1.0e-6
+                })
+                .count();
+            assert_eq!(flat_faces, 2, "{ends:?}");
+        }
+    }
+
+    #[test]
+    fn solid_height() {
+        // Compressed until the turns touch, ground springs are about as tall
+        // as their turns of wire stacked up.
+        assert_eq!(SpringEnds::Ground.solid_height(WIRE, ACTIVE), 5.0);
+        assert_eq!(SpringEnds::SquaredGround.solid_height(WIRE, ACTIVE), 6.0);
+        assert_eq!(SpringEnds::Plain.solid_height(WIRE, ACTIVE), 5.0);
+
+        // Which is what a ground spring with the turns pitched a wire
+        // diameter apart comes to, give or take the clearance.
+        let pitch = WIRE * (1.0 + 2.0 * END_COIL_CLEARANCE);
+        let tight = spring(WIRE, OUTER, pitch, ACTIVE, SpringEnds::Ground).unwrap();
+        let (bottom, top) = height(&tight);
+        let solid_height = SpringEnds::Ground.solid_height(WIRE, ACTIVE);
+        assert!((top - bottom - solid_height).abs() < solid_height * 2.5 * END_COIL_CLEARANCE);
+    }
+
+    #[test]
+    fn plain_spring_volume() {
+        let spring = spring(WIRE, OUTER, PITCH, ACTIVE, SpringEnds::Plain).unwrap();
+
+        let summary = spring.topology_summary();
+        assert!(summary.closed && summary.valid, "{summary}");
+
+        let (bottom, top) = height(&spring);
+        assert!(bottom.abs() < 0.05, "{bottom}");
+        assert!((top - SpringEnds::Plain.free_height(WIRE, PITCH, ACTIVE)).abs() < 0.05);
+
+        // The wire's cross section times its length.
+        let radius = (OUTER - WIRE) / 2.0;
+        let length = ACTIVE * (TAU * radius).hypot(PITCH);
+        let expected = std::f64::consts::PI * (WIRE / 2.0).powi(2) * length;
+        assert!((spring.volume() - expected).abs() < expected * 0.01);
+    }
+
+    #[test]
+    fn invalid_springs() {
+        for (wire, outer, pitch, turns) in [
+            (0.0, OUTER, PITCH, ACTIVE),
+            (WIRE, 2.0 * WIRE, PITCH, ACTIVE),
+            (WIRE, OUTER, WIRE, ACTIVE),
+            (WIRE, OUTER, PITCH, 0.0),
+            (f64::NAN, OUTER, PITCH, ACTIVE),
+            (WIRE, f64::NAN, PITCH, ACTIVE),
+            (WIRE, OUTER, f64::NAN, ACTIVE),
+            (WIRE, OUTER, PITCH, f64::NAN),
+        ] {
+            assert!(matches!(
+                spring(wire, outer, pitch, turns, SpringEnds::Plain),
+                Err(Error::InvalidSpring { .. })
+            ));
+        }
+    }
+}
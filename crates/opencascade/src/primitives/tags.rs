@@ -0,0 +1,251 @@
+use crate::primitives::History;
+use cxx::UniquePtr;
+use opencascade_sys::ffi;
+
+/// Named references to sub-shapes (faces, edges) of a `Shape`.
+#[derive(Default)]
+pub(crate) struct Tags {
+    entries: Vec<Tag>,
+}
+
+struct Tag {
+    name: String,
+    shapes: Vec<UniquePtr<ffi::TopoDS_Shape>>,
+}
+
+impl Tags {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|tag| tag.name.as_str())
+    }
+
+    pub(crate) fn add(&mut self, name: &str, shape: &ffi::TopoDS_Shape) {
+        let tag = match self.entries.iter_mut().position(|tag| tag.name == name) {
+            Some(index) => &mut self.entries[index],
+            None => {
+                self.entries.push(Tag {
+                    name: name.to_string(),
+                    shapes: vec![],
+                });
+                self.entries.last_mut().unwrap()
+            }
+        };
+
+        tag.push_unique(shape);
+    }
+
+    pub(crate) fn remove(&mut self, name: &str) {
+        self.entries.retain(|tag| tag.name != name);
+    }
+
+    pub(crate) fn get(&self, name: &str) -> impl Iterator<Item = &ffi::TopoDS_Shape> {
+        self.entries
+            .iter()
+            .filter(move |tag| tag.name == name)
+            .flat_map(|tag| tag.shapes.iter().map(|shape| &**shape))
+    }
+
+    /// Map every tagged shape to its descendants in `history`. Returns the
+    /// remapped tags, along with the names of tags which lost any of their
+    /// shapes. Tags which lost all of them are removed.
+    pub(crate) fn remap(&self, history: &History) -> (Tags, Vec<String>) {
+        let mut remapped = Tags::default();
+        let mut dropped = vec![];
+
+        for tag in &self.entries {
+            let mut new_tag = Tag {
+                name: tag.name.clone(),
+                shapes: vec![],
+            };
+            let mut lost_any = false;
+
+            for shape in &tag.shapes {
+                if history.inner.IsRemoved(shape) {
+                    lost_any = true;
+                    continue;
+                }
+
+                let modified = ffi::shape_list_to_vector(history.inner.Modified(shape));
+
+                if modified.is_empty() {
+                    new_tag.push_unique(shape);
+                } else {
+                    for new_shape in modified.iter() {
+                        new_tag.push_unique(new_shape);
+                    }
+                }
+            }
+
+            if lost_any {
+                dropped.push(new_tag.name.clone());
+            }
+
+            if !new_tag.shapes.is_empty() {
+                remapped.entries.push(new_tag);
+            }
+        }
+
+        (remapped, dropped)
+    }
+
+    /// These tags, for the shape moved by `location`, as every one of its
+    /// sub-shapes is.
+    pub(crate) fn moved(&self, location: &ffi::TopLoc_Location) -> Tags {
+        let entries = self
+            .entries
+            .iter()
+            .map(|tag| Tag {
+                name: tag.name.clone(),
+                shapes: tag
+                    .shapes
+                    .iter()
+                    .map(|shape| ffi::TopoDS_Shape_moved(shape, location))
+                    .collect(),
+            })
+            .collect();
+
+        Self { entries }
+    }
+}
+
+impl Clone for Tags {
+    fn clone(&self) -> Self {
+        let entries = self
+            .entries
+            .iter()
+            .map(|tag| Tag {
+                name: tag.name.clone(),
+                shapes: tag
+                    .shapes
+                    .iter()
+                    .map(|shape| ffi::TopoDS_Shape_to_owned(shape))
+                    .collect(),
+            })
+            .collect();
+
+        Self { entries }
+    }
+}
+
+impl Tag {
+    fn push_unique(&mut self, shape: &ffi::TopoDS_Shape) {
+        if !self.shapes.iter().any(|existing| existing.IsSame(shape)) {
+            self.shapes.push(ffi::TopoDS_Shape_to_owned(shape));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::primitives::Direction;
+    use crate::primitives::Shape;
+    use crate::TandR;
+    use nalgebra::point;
+    use nalgebra::vector;
+    use nalgebra::Matrix4;
+    use nalgebra::UnitQuaternion;
+    use opencascade_sys::ffi;
+
+    #[test]
+    fn tags_survive_booleans_and_fillets() {
+        let mut base = Shape::box_with_dimensions(10.0, 10.0, 10.0);
+        let top = base.faces().farthest(Direction::PosZ);
+        base.tag_face(&top, "mounting_face");
+
+        let hole = Shape::box_from_corners(point![4.0, 4.0, -1.0], point![6.0, 6.0, 11.0]);
+        let notch = Shape::box_from_corners(point![-1.0, -1.0, -1.0], point![2.0, 11.0, 2.0]);
+
+        let (shape, dropped) = base
+            .apply_with_history(|shape| shape.subtract_with_history(&hole))
+            .unwrap();
+        assert!(dropped.is_empty());
+
+        let (shape, dropped) = shape
+            .apply_with_history(|shape| shape.subtract_with_history(&notch))
+            .unwrap();
+        assert!(dropped.is_empty());
+
+        let edge = shape
+            .faces()
+            .farthest(Direction::PosX)
+            .edges()
+            .next()
+            .unwrap();
+        let (shape, dropped) = shape
+            .apply_with_history(|shape| Ok(shape.fillet_edges_with_history(0.5, [&edge])))
+            .unwrap();
+        assert!(dropped.is_empty());
+
+        let mounting_faces = shape.find_tagged("mounting_face");
+        assert_eq!(mounting_faces.len(), 1);
+        assert!((mounting_faces[0].center_of_mass().z - 10.0).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn deleted_tags_are_reported() {
+        let mut base = Shape::box_with_dimensions(10.0, 10.0, 10.0);
+        let top = base.faces().farthest(Direction::PosZ);
+        base.tag_face(&top, "top");
+
+        let lid = Shape::box_from_corners(point![-1.0, -1.0, 5.0], point![11.0, 11.0, 11.0]);
+
+        let (shape, dropped) = base
+            .apply_with_history(|shape| shape.subtract_with_history(&lid))
+            .unwrap();
+
+        assert_eq!(dropped, vec!["top".to_string()]);
+        assert!(shape.find_tagged("top").is_empty());
+    }
+
+    #[test]
+    fn partly_deleted_tags_are_reported() {
+        let mut base = Shape::box_with_dimensions(10.0, 10.0, 10.0);
+        base.tag_face(&base.faces().farthest(Direction::PosZ), "ends");
+        base.tag_face(&base.faces().farthest(Direction::NegZ), "ends");
+
+        let lid = Shape::box_from_corners(point![-1.0, -1.0, 5.0], point![11.0, 11.0, 11.0]);
+
+        let (shape, dropped) = base
+            .apply_with_history(|shape| shape.subtract_with_history(&lid))
+            .unwrap();
+
+        assert_eq!(dropped, vec!["ends".to_string()]);
+        let ends = shape.find_tagged("ends");
+        assert_eq!(ends.len(), 1);
+        assert!(ends[0].center_of_mass().z.abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn tags_follow_transforms_and_fixes() {
+        let mut shape = Shape::box_with_dimensions(10.0, 10.0, 10.0);
+        shape.tag_face(&shape.faces().farthest(Direction::PosZ), "top");
+
+        let top_z = |shape: &Shape| {
+            let top = shape.find_tagged("top");
+            assert_eq!(top.len(), 1);
+            let top = ffi::cast_face_to_shape(&top[0].inner);
+            assert!(shape
+                .faces()
+                .any(|face| ffi::cast_face_to_shape(&face.inner).IsSame(top)));
+            shape.find_tagged("top")[0].center_of_mass().z
+        };
+
+        shape.set_global_translation(vector![0.0, 0.0, -10.0]);
+        assert!(top_z(&shape).abs() < 1.0e-6);
+
+        shape.transform(&TandR::new(
+            vector![0.0, 0.0, 5.0],
+            UnitQuaternion::identity(),
+        ));
+        assert!((top_z(&shape) - 5.0).abs() < 1.0e-6);
+
+        shape.transform_affine(&Matrix4::new_scaling(2.0));
+        assert!((top_z(&shape) - 10.0).abs() < 1.0e-6);
+
+        assert!(shape.fix().is_empty());
+        assert!((top_z(&shape) - 10.0).abs() < 1.0e-6);
+    }
+}
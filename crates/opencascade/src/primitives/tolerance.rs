@@ -0,0 +1,157 @@
+use crate::primitives::Shape;
+use opencascade_sys::ffi;
+
+/// How `Shape::with_tolerance` changes the tolerances of a shape.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ToleranceMode {
+    /// Set every tolerance to the new tolerance, whether higher or lower.
+    Set,
+    /// Raise tolerances below the new tolerance up to it.
+    AtLeast,
+    /// Lower tolerances above the new tolerance down to it.
+    Limit,
+}
+
+impl Shape {
+    /// The largest tolerance of any face, edge or vertex of this shape.
+    /// Boolean operations treat sub-shapes closer than their tolerances as
+    /// touching, so a shape with large tolerances, as imported from a sloppy
+    /// file, often makes them fail.
+    pub fn max_tolerance(&self) -> f64 {
+        self.tolerances().fold(0.0, f64::max)
+    }
+
+    /// How many faces, edges and vertices of this shape have tolerances in
+    /// each of `buckets` ranges, from the smallest tolerance to the largest.
+    /// Tolerances often differ by orders of magnitude, so the ranges are
+    /// evenly spaced on a log scale. Each bucket is given by the upper end
+    /// of its range.
+    pub fn tolerance_histogram(&self, buckets: usize) -> Vec<(f64, usize)> {
+        let tolerances: Vec<_> = self.tolerances().collect();
+        if buckets == 0 || tolerances.is_empty() {
+            return vec![];
+        }
+
+        let min = tolerances.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = tolerances.iter().copied().fold(0.0, f64::max);
+        let (log_min, log_max) = (min.ln(), max.ln());
+        let step = (log_max - log_min) / buckets as f64;
+
+        let mut histogram: Vec<_> = (1..=buckets)
+            .map(|i| ((log_min + step * i as f64).exp(), 0))
+            .collect();
+        // Exactly the largest tolerance, rather than rounded.
+        histogram[buckets - 1].0 = max;
+
+        for tolerance in tolerances {
+            let bucket = if step > 0.0 {
+                ((tolerance.ln() - log_min) / step) as usize
+            } else {
+                buckets - 1
+            };
+            histogram[bucket.min(buckets - 1)].1 += 1;
+        }
+
+        histogram
+    }
+
+    /// A copy of this shape, with the tolerances of its faces, edges and
+    /// vertices changed to `new_tolerance` as `mode` says. This shape is
+    /// left as it is.
+    ///
+    /// Raising tolerances is a last resort for getting boolean operations
+    /// to work on shapes whose pieces don't quite meet: it makes the shape
+    /// less precise, and sub-shapes closer than the new tolerance are
+    /// taken to touch. Lowering tolerances with `ToleranceMode::Limit` is
+    /// the usual fix for shapes imported with needlessly large ones.
+    #[must_use]
+    pub fn with_tolerance(&self, new_tolerance: f64, mode: ToleranceMode) -> Shape {
+        // Tolerances are changed in place, so work on a copy which doesn't
+        // share its topology with this shape.
+        let copy = self.clone();
+        let fixer = ffi::ShapeFix_ShapeTolerance_ctor();
+        let all = ffi::TopAbs_ShapeEnum::TopAbs_SHAPE;
+
+        match mode {
+            ToleranceMode::Set => fixer.SetTolerance(&copy.inner, new_tolerance, all),
+            ToleranceMode::AtLeast => {
+                // An upper limit of zero leaves tolerances above the new one.
+                fixer.LimitTolerance(&copy.inner, new_tolerance, 0.0, all);
+            }
+            ToleranceMode::Limit => {
+                fixer.LimitTolerance(&copy.inner, 0.0, new_tolerance, all);
+            }
+        }
+
+        copy
+    }
+
+    fn tolerances(&self) -> impl Iterator<Item = f64> + '_ {
+        let faces = self.faces().map(|face| face.tolerance());
+        let edges = self.edges().map(|edge| edge.tolerance());
+        let vertices = self.vertices().map(|vertex| vertex.tolerance());
+
+        faces.chain(edges).chain(vertices)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nalgebra::Vector3;
+    use std::path::Path;
+
+    #[test]
+    fn loosen_and_limit_tolerances() {
+        let block = Shape::box_centered(10.0, 10.0, 10.0);
+        let precise = block.max_tolerance();
+        assert!(precise > 0.0 && precise < 1.0e-5, "{precise}");
+
+        let sloppy = block.with_tolerance(0.01, ToleranceMode::Set);
+        assert!((sloppy.max_tolerance() - 0.01).abs() < 1.0e-12);
+        assert_eq!(block.max_tolerance(), precise);
+
+        // 6 faces, 12 edges and 8 vertices, all the same.
+        let histogram = sloppy.tolerance_histogram(4);
+        assert_eq!(histogram.len(), 4);
+        assert_eq!(histogram.iter().map(|&(_, count)| count).sum::<usize>(), 26);
+        assert_eq!(histogram[3], (0.01, 26));
+
+        let raised = block.with_tolerance(0.001, ToleranceMode::AtLeast);
+        assert!((raised.max_tolerance() - 0.001).abs() < 1.0e-12);
+        let still_sloppy = sloppy.with_tolerance(0.001, ToleranceMode::AtLeast);
+        assert!((still_sloppy.max_tolerance() - 0.01).abs() < 1.0e-12);
+
+        let limited = sloppy.with_tolerance(1.0e-5, ToleranceMode::Limit);
+        assert!(limited.max_tolerance() <= 1.0e-5);
+
+        // With the limited tolerances, the boxes 0.005 apart don't touch.
+        let mut neighbour = Shape::box_centered(10.0, 10.0, 10.0);
+        neighbour.set_global_translation(Vector3::new(10.005, 0.0, 0.0));
+        let fused = limited.union(&neighbour);
+        assert_eq!(Shape::from(fused).solids().count(), 2);
+    }
+
+    #[test]
+    fn limit_tolerances_of_sloppy_import() {
+        // A 10 mm box, from a file which gives its resolution as 0.1 mm.
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sloppy_box.igs");
+        let sloppy = Shape::read_iges(path).unwrap();
+        let max_tolerance = sloppy.max_tolerance();
+        assert!(max_tolerance >= 0.05, "{max_tolerance}");
+
+        // A box 0.02 mm away, which the sloppy box takes as touching, so
+        // the fuse glues the two together.
+        let mut neighbour = Shape::box_centered(10.0, 10.0, 10.0);
+        neighbour.set_global_translation(Vector3::new(15.02, 5.0, 5.0));
+        let glued = Shape::from(sloppy.union(&neighbour));
+        assert_ne!(glued.faces().count(), 12);
+
+        let limited = sloppy.with_tolerance(1.0e-5, ToleranceMode::Limit);
+        assert!(limited.max_tolerance() <= 1.0e-5);
+
+        let fused = Shape::from(limited.union(&neighbour));
+        assert_eq!(fused.solids().count(), 2);
+        assert_eq!(fused.faces().count(), 12);
+    }
+}
@@ -0,0 +1,240 @@
+use crate::primitives::Shape;
+use crate::primitives::ShapeType;
+use nalgebra::Vector3;
+use opencascade_sys::ffi;
+use std::fmt;
+
+/// An overview of the topology of a shape, for debugging.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TopologySummary {
+    pub shape_type: ShapeType,
+    pub solids: usize,
+    pub shells: usize,
+    pub faces: usize,
+    pub wires: usize,
+    pub edges: usize,
+    pub vertices: usize,
+    /// True if the shape has faces, and every edge is shared by at least
+    /// two of them.
+    pub closed: bool,
+    /// True if OCCT's shape checker finds no problems.
+    pub valid: bool,
+    /// The size of the bounding box, or `None` for an empty shape.
+    pub extents: Option<Vector3<f64>>,
+}
+
+impl fmt::Display for TopologySummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, Some(self.valid))
+    }
+}
+
+impl TopologySummary {
+    /// Write this summary, with `valid` if the shape was checked.
+    fn write(&self, f: &mut fmt::Formatter<'_>, valid: Option<bool>) -> fmt::Result {
+        let count = |count: usize, singular: &str, plural: &str| {
+            if count == 1 {
+                format!("{count} {singular}")
+            } else {
+                format!("{count} {plural}")
+            }
+        };
+
+        write!(
+            f,
+            "{:?}: {}, {}, {}, {}, {}, {}; {}",
+            self.shape_type,
+            count(self.solids, "solid", "solids"),
+            count(self.shells, "shell", "shells"),
+            count(self.faces, "face", "faces"),
+            count(self.wires, "wire", "wires"),
+            count(self.edges, "edge", "edges"),
+            count(self.vertices, "vertex", "vertices"),
+            if self.closed { "closed" } else { "open" },
+        )?;
+
+        match valid {
+            Some(true) => write!(f, ", valid")?,
+            Some(false) => write!(f, ", invalid")?,
+            None => {}
+        }
+
+        match self.extents {
+            Some(extents) => write!(f, "; {} x {} x {}", extents.x, extents.y, extents.z),
+            None => write!(f, "; empty"),
+        }
+    }
+}
+
+impl fmt::Debug for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.inner.IsNull() {
+            return write!(f, "Shape(null)");
+        }
+
+        // Checking the shape can take a long time, so it's left out.
+        write!(f, "Shape(")?;
+        self.summarize(false).write(f, None)?;
+        write!(f, ")")
+    }
+}
+
+impl Shape {
+    /// Count the distinct sub-shapes of this shape, check whether it's
+    /// closed, and check it with OCCT's shape checker, which can take a
+    /// while for large shapes.
+    pub fn topology_summary(&self) -> TopologySummary {
+        self.summarize(true)
+    }
+
+    /// Like `topology_summary`, but the shape checker is only run if
+    /// `check`, and `valid` is false otherwise.
+    fn summarize(&self, check: bool) -> TopologySummary {
+        let count = |shape_type| {
+            let mut shape_map = ffi::new_indexed_map_of_shape();
+            ffi::map_shapes(&self.inner, shape_type, shape_map.pin_mut());
+
+            shape_map.Extent() as usize
+        };
+
+        let faces = count(ffi::TopAbs_ShapeEnum::TopAbs_FACE);
+        let bounding_box = self.bounding_box();
+
+        TopologySummary {
+            shape_type: self.shape_type(),
+            solids: count(ffi::TopAbs_ShapeEnum::TopAbs_SOLID),
+            shells: count(ffi::TopAbs_ShapeEnum::TopAbs_SHELL),
+            faces,
+            wires: count(ffi::TopAbs_ShapeEnum::TopAbs_WIRE),
+            edges: count(ffi::TopAbs_ShapeEnum::TopAbs_EDGE),
+            vertices: count(ffi::TopAbs_ShapeEnum::TopAbs_VERTEX),
+            closed: faces > 0 && self.free_edges().is_empty(),
+            valid: check && ffi::BRepCheck_Analyzer_ctor(&self.inner).IsValid(),
+            extents: (!bounding_box.is_void()).then(|| bounding_box.max() - bounding_box.min()),
+        }
+    }
+
+    /// An indented tree of the types of this shape and its sub-shapes, down
+    /// to `max_depth` levels below this shape. Sub-shapes shared between
+    /// several parents appear under each of them.
+    pub fn dump_topology(&self, max_depth: usize) -> String {
+        let mut dump = String::new();
+        dump_shape(&self.inner, 0, max_depth, &mut dump);
+
+        dump
+    }
+}
+
+fn dump_shape(shape: &ffi::TopoDS_Shape, depth: usize, max_depth: usize, dump: &mut String) {
+    let indent = "  ".repeat(depth);
+    let shape_type: ShapeType = shape.ShapeType().into();
+    dump.push_str(&format!("{indent}{shape_type:?}\n"));
+
+    let mut children = ffi::TopoDS_Iterator_ctor(shape);
+
+    if depth == max_depth {
+        let mut count = 0;
+        while children.More() {
+            count += 1;
+            children.pin_mut().Next();
+        }
+
+        if count > 0 {
+            dump.push_str(&format!("{indent}  ... {count} more\n"));
+        }
+
+        return;
+    }
+
+    while children.More() {
+        dump_shape(children.Value(), depth + 1, max_depth, dump);
+        children.pin_mut().Next();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn box_summary() {
+        let summary = Shape::box_with_dimensions(10.0, 20.0, 30.0).topology_summary();
+
+        assert_eq!(summary.shape_type, ShapeType::Solid);
+        assert_eq!(
+            (
+                summary.solids,
+                summary.shells,
+                summary.faces,
+                summary.wires,
+                summary.edges,
+                summary.vertices
+            ),
+            (1, 1, 6, 6, 12, 8)
+        );
+        assert!(summary.closed);
+        assert!(summary.valid);
+
+        let extents = summary.extents.unwrap();
+        assert!((extents - Vector3::new(10.0, 20.0, 30.0)).norm() < 1.0e-3);
+
+        assert!(summary.to_string().starts_with(
+            "Solid: 1 solid, 1 shell, 6 faces, 6 wires, 12 edges, 8 vertices; closed, valid"
+        ));
+    }
+
+    #[test]
+    fn filleted_box_summary() {
+        let block = Shape::box_with_dimensions(10.0, 10.0, 10.0);
+        let edge = block.edges().next().unwrap();
+        let summary = block.fillet_edge(1.0, &edge).topology_summary();
+
+        // The fillet replaces one edge with a cylindrical face bounded by
+        // four new edges.
+        assert_eq!(
+            (
+                summary.solids,
+                summary.shells,
+                summary.faces,
+                summary.wires,
+                summary.edges,
+                summary.vertices
+            ),
+            (1, 1, 7, 7, 15, 10)
+        );
+        assert!(summary.closed);
+        assert!(summary.valid);
+    }
+
+    #[test]
+    fn topology_dump() {
+        let block = Shape::box_with_dimensions(1.0, 1.0, 1.0);
+
+        assert_eq!(block.dump_topology(0), "Solid\n  ... 1 more\n");
+
+        let dump = block.dump_topology(2);
+        let lines: Vec<_> = dump.lines().collect();
+        assert_eq!(lines[..3], ["Solid", "  Shell", "    Face"]);
+        assert_eq!(lines.iter().filter(|line| line.trim() == "Face").count(), 6);
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| line.trim() == "... 1 more")
+                .count(),
+            6
+        );
+
+        assert!(format!("{block:?}").starts_with("Shape(Solid: 1 solid"));
+    }
+
+    #[test]
+    fn debug_is_unchecked() {
+        let block = Shape::box_with_dimensions(1.0, 1.0, 1.0);
+        let debug = format!("{block:?}");
+        assert!(debug.contains("closed"), "{debug}");
+        assert!(!debug.contains("valid"), "{debug}");
+
+        let null = Shape::from_shape(&ffi::TopoDS_Compound_as_shape(ffi::TopoDS_Compound_ctor()));
+        assert_eq!(format!("{null:?}"), "Shape(null)");
+    }
+}
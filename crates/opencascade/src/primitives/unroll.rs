@@ -0,0 +1,397 @@
+use crate::primitives::Edge;
+use crate::primitives::Face;
+use crate::primitives::FaceType;
+use crate::primitives::Wire;
+use crate::Error;
+use nalgebra::point;
+use nalgebra::Point2;
+use nalgebra::Point3;
+use nalgebra::Rotation2;
+use nalgebra::Vector2;
+use nalgebra::Vector3;
+use opencascade_sys::ffi;
+use std::f64::consts::TAU;
+
+/// How far the flattened boundary may stray from the exact image of the
+/// edges, in model units.
+const DEFLECTION: f64 = 1.0e-3;
+
+/// Flattened edges closer than this to a straight line become segments.
+const STRAIGHTNESS_TOLERANCE: f64 = 1.0e-7;
+
+/// A coordinate frame, as in a `gp_Ax3`. Its y direction isn't necessarily
+/// `z.cross(x)`, since a surface's frame can be left-handed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Frame {
+    origin: Point3<f64>,
+    x: Vector3<f64>,
+    y: Vector3<f64>,
+    z: Vector3<f64>,
+}
+
+impl Frame {
+    fn local(&self, point: &Point3<f64>) -> Vector3<f64> {
+        let offset = point - self.origin;
+        Vector3::new(
+            offset.dot(&self.x),
+            offset.dot(&self.y),
+            offset.dot(&self.z),
+        )
+    }
+
+    fn world(&self, local: Vector3<f64>) -> Point3<f64> {
+        self.origin + self.x * local.x + self.y * local.y + self.z * local.z
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Development {
+    Plane,
+    Cylinder {
+        radius: f64,
+    },
+    /// Points on the cone are `slant` from the apex, along a generator at an
+    /// angle `semi_angle` to the axis. The flat pattern is a circular sector
+    /// around the apex, `sin(semi_angle)` of a full circle per turn.
+    Cone {
+        radius: f64,
+        semi_angle: f64,
+    },
+}
+
+/// Maps points between a developable face and its flat pattern, which lies
+/// on the XY plane.
+///
+/// Lengths along the face and in the flat pattern are the same.
+#[derive(Debug)]
+pub struct UnrollMap {
+    frame: Frame,
+    development: Development,
+    /// Where the angle around the axis of a cylinder or cone starts, so
+    /// the flat pattern of the face is in one piece.
+    first_angle: f64,
+    holes: Vec<Wire>,
+}
+
+impl UnrollMap {
+    /// The flattened inner wires of the face.
+    pub fn holes(&self) -> &[Wire] {
+        &self.holes
+    }
+
+    /// The image in the flat pattern of `point` on the face.
+    pub fn to_2d(&self, point: Point3<f64>) -> Point2<f64> {
+        let local = self.frame.local(&point);
+        let angle =
+            || self.first_angle + (local.y.atan2(local.x) - self.first_angle).rem_euclid(TAU);
+
+        match self.development {
+            Development::Plane => point![local.x, local.y],
+            Development::Cylinder { radius } => point![radius * angle(), local.z],
+            Development::Cone { radius, semi_angle } => {
+                let slant = radius / semi_angle.sin() + local.z / semi_angle.cos();
+                let sector_angle = angle() * semi_angle.sin();
+
+                point![slant * sector_angle.cos(), slant * sector_angle.sin()]
+            }
+        }
+    }
+
+    /// The point on the face whose image in the flat pattern is `point`.
+    pub fn to_3d(&self, point: Point2<f64>) -> Point3<f64> {
+        let around = |angle: f64, distance: f64, height: f64| {
+            self.frame.world(Vector3::new(
+                distance * angle.cos(),
+                distance * angle.sin(),
+                height,
+            ))
+        };
+
+        match self.development {
+            Development::Plane => self.frame.world(Vector3::new(point.x, point.y, 0.0)),
+            Development::Cylinder { radius } => around(point.x / radius, radius, point.y),
+            Development::Cone { radius, semi_angle } => {
+                let (sin, cos) = semi_angle.sin_cos();
+
+                // The slant is negative for cones which narrow along their
+                // axis, which turns the sector around.
+                let slant = sin.signum() * point.coords.norm();
+                let sector_angle = (point.y / slant).atan2(point.x / slant);
+                let start = (self.first_angle * sin).min((self.first_angle + TAU) * sin);
+                let sector_angle = start + (sector_angle - start).rem_euclid(TAU);
+
+                let along_generator = slant - radius / sin;
+                around(
+                    sector_angle / sin,
+                    radius + along_generator * sin,
+                    along_generator * cos,
+                )
+            }
+        }
+    }
+
+    /// Flatten the edges of `wire`, which is on the face.
+    fn flatten(&self, wire: &Wire) -> Result<Wire, Error> {
+        // The wire's edges come in order along it, and reversed edges run
+        // from the end of their curve, which is where approximating starts.
+        let polylines: Vec<Vec<Point3<f64>>> = wire
+            .edges()
+            .map(|edge| {
+                let mut points: Vec<_> = edge.approximation_segments(DEFLECTION).collect();
+                if edge.is_reversed() {
+                    points.reverse();
+                }
+                points
+            })
+            .collect();
+
+        // Around a full turn of a cylinder or cone, the same point has two
+        // images, so follow the wire to pick the one next to the previous
+        // point.
+        let mut previous: Option<Point2<f64>> = None;
+        let mut flattened: Vec<Vec<Point2<f64>>> = polylines
+            .iter()
+            .map(|polyline| {
+                polyline
+                    .iter()
+                    .map(|point| {
+                        let image = self.to_2d(*point);
+                        let image =
+                            previous.map_or(image, |previous| self.nearest_image(image, previous));
+                        previous = Some(image);
+                        image
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Snap the ends of neighbouring edges together, so they connect.
+        let count = flattened.len();
+        for i in 0..count {
+            let end = *flattened[i].last().unwrap();
+            flattened[(i + 1) % count][0] = end;
+        }
+
+        let edges: Vec<_> = flattened
+            .into_iter()
+            .map(|points| {
+                let to_3d = |point: &Point2<f64>| point![point.x, point.y, 0.0];
+                let (first, last) = (points[0], points[points.len() - 1]);
+                let chord = last - first;
+
+                let straight = points.iter().all(|point| {
+                    let offset = point - first;
+                    let along = offset.dot(&chord) / chord.norm_squared().max(f64::MIN_POSITIVE);
+                    (offset - chord * along).norm() < STRAIGHTNESS_TOLERANCE
+                });
+
+                if straight {
+                    Edge::segment(to_3d(&first), to_3d(&last))
+                } else {
+                    Edge::spline_from_points(points.iter().map(to_3d), None)
+                }
+            })
+            .collect();
+
+        Wire::from_edges(&edges)
+    }
+
+    /// The image of the same point as `image` which is nearest to
+    /// `previous`. Cylinders wrap around along x, cones by turning around
+    /// the apex.
+    fn nearest_image(&self, image: Point2<f64>, previous: Point2<f64>) -> Point2<f64> {
+        let candidates = match self.development {
+            Development::Plane => [image; 3],
+            Development::Cylinder { radius } => {
+                let turn = Vector2::new(radius * TAU, 0.0);
+                [image, image + turn, image - turn]
+            }
+            Development::Cone { semi_angle, .. } => {
+                let turn = TAU * semi_angle.sin();
+                [0.0, turn, -turn].map(|angle| Rotation2::new(angle) * image)
+            }
+        };
+
+        candidates
+            .into_iter()
+            .min_by(|a, b| (a - previous).norm().total_cmp(&(b - previous).norm()))
+            .unwrap()
+    }
+}
+
+impl Face {
+    /// Flatten a developable face, that is a planar, cylindrical or conical
+    /// one, onto the XY plane without stretching it. Returns the flattened
+    /// outer wire, and an `UnrollMap` which maps points between the face and
+    /// its flat pattern, and holds the flattened holes.
+    ///
+    /// Cylinders unroll into rectangles, with the angle around the axis
+    /// along x and the height along y. Cones unroll into sectors of a
+    /// circle around the apex.
+    pub fn unroll(&self) -> Result<(Wire, UnrollMap), Error> {
+        let face_type = self.face_type();
+        let surface = ffi::BRepAdaptor_Surface_ctor(&self.inner, true);
+
+        let development = match face_type {
+            FaceType::Plane => Development::Plane,
+            FaceType::Cylinder => Development::Cylinder {
+                radius: ffi::BRepAdaptor_Surface_radius(&surface),
+            },
+            FaceType::Cone => Development::Cone {
+                radius: ffi::BRepAdaptor_Surface_radius(&surface),
+                semi_angle: ffi::BRepAdaptor_Surface_cone_semi_angle(&surface),
+            },
+            _ => return Err(Error::NotDevelopable { face_type }),
+        };
+
+        let position = ffi::BRepAdaptor_Surface_position(&surface);
+        let vector = |dir: &ffi::gp_Dir| Vector3::new(dir.X(), dir.Y(), dir.Z());
+        let origin = position.Location();
+        let frame = Frame {
+            origin: point![origin.X(), origin.Y(), origin.Z()],
+            x: vector(position.XDirection()),
+            y: vector(position.YDirection()),
+            z: vector(position.Direction()),
+        };
+
+        let mut map = UnrollMap {
+            frame,
+            development,
+            first_angle: surface.FirstUParameter(),
+            holes: vec![],
+        };
+
+        let outer_wire = self.outer_wire();
+        let flattened_outer = map.flatten(&outer_wire)?;
+
+        map.holes = self
+            .wires()
+            .filter(|wire| {
+                !ffi::cast_wire_to_shape(&wire.inner)
+                    .IsSame(ffi::cast_wire_to_shape(&outer_wire.inner))
+            })
+            .map(|wire| map.flatten(&wire))
+            .collect::<Result<_, _>>()?;
+
+        Ok((flattened_outer, map))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::EdgeType;
+    use crate::primitives::Shape;
+    use std::f64::consts::FRAC_PI_2;
+
+    fn wire_bounds(wire: &Wire) -> (Point3<f64>, Point3<f64>) {
+        let points: Vec<_> = wire
+            .edges()
+            .flat_map(|edge| edge.approximation_segments(1.0e-4))
+            .collect();
+
+        points[1..]
+            .iter()
+            .fold((points[0], points[0]), |(min, max), point| {
+                (min.inf(point), max.sup(point))
+            })
+    }
+
+    fn faces_of_type(shape: &Shape, face_type: FaceType) -> Vec<Face> {
+        shape
+            .faces()
+            .filter(|face| face.face_type() == face_type)
+            .collect()
+    }
+
+    #[test]
+    fn quarter_cylinder_unrolls_to_rectangle() {
+        let (radius, height) = (4.0, 10.0);
+        let cylinder = Shape::cylinder_radius_height(radius, height);
+        let quarter = Shape::box_from_corners(point![0.0, 0.0, -1.0], point![5.0, 5.0, 11.0]);
+        let wedge = cylinder.intersect(&quarter).unwrap();
+
+        let faces = faces_of_type(&wedge, FaceType::Cylinder);
+        assert_eq!(faces.len(), 1);
+        let face = &faces[0];
+
+        let (outer, map) = face.unroll().unwrap();
+        assert!(outer.is_closed());
+        assert!(map.holes().is_empty());
+
+        let (min, max) = wire_bounds(&outer);
+        let size = max - min;
+        assert!((size.x - FRAC_PI_2 * radius).abs() < 1.0e-6, "{size}");
+        assert!((size.y - height).abs() < 1.0e-6, "{size}");
+        assert!(size.z.abs() < 1.0e-9);
+
+        // Arcs unroll into straight lines.
+        assert_eq!(outer.edges().count(), 4);
+        assert!(outer.edges().all(|edge| edge.edge_type() == EdgeType::Line));
+
+        let area = outer.to_face().surface_area();
+        assert!((area - face.surface_area()).abs() < 1.0e-6, "{area}");
+
+        // Points on the face go back and forth.
+        let on_face = point![radius * 0.3f64.cos(), radius * 0.3f64.sin(), 2.5];
+        let image = map.to_2d(on_face);
+        assert!((map.to_3d(image) - on_face).norm() < 1.0e-9);
+    }
+
+    #[test]
+    fn cone_keeps_its_area() {
+        let cone = Shape::cone()
+            .bottom_radius(6.0)
+            .top_radius(2.0)
+            .height(8.0)
+            .build();
+
+        let faces = faces_of_type(&cone, FaceType::Cone);
+        assert_eq!(faces.len(), 1);
+        let face = &faces[0];
+
+        let (outer, map) = face.unroll().unwrap();
+        let area = outer.to_face().surface_area();
+        assert!(
+            (area - face.surface_area()).abs() < 1.0e-3 * face.surface_area(),
+            "{area}"
+        );
+
+        let on_face = point![4.0 * 1.0f64.cos(), 4.0 * 1.0f64.sin(), 4.0];
+        assert!((map.to_3d(map.to_2d(on_face)) - on_face).norm() < 1.0e-9);
+    }
+
+    #[test]
+    fn planar_face_keeps_its_holes() {
+        let plate = Shape::box_with_dimensions(20.0, 20.0, 2.0);
+        let hole = Shape::cylinder(point![10.0, 10.0, -1.0], 3.0, Vector3::z(), 4.0);
+        let part = plate.subtract(&hole).unwrap();
+
+        let top = part
+            .faces()
+            .find(|face| {
+                face.face_type() == FaceType::Plane
+                    && (face.center_of_mass().z - 2.0).abs() < 1.0e-9
+            })
+            .unwrap();
+
+        let (outer, map) = top.unroll().unwrap();
+        assert_eq!(map.holes().len(), 1);
+
+        let outer_area = outer.to_face().surface_area();
+        assert!((outer_area - 400.0).abs() < 1.0e-6, "{outer_area}");
+    }
+
+    #[test]
+    fn spheres_cannot_be_unrolled() {
+        let sphere = Shape::sphere(1.0).build();
+        let face = sphere.faces().next().unwrap();
+
+        assert!(matches!(
+            face.unroll(),
+            Err(Error::NotDevelopable {
+                face_type: FaceType::Sphere
+            })
+        ));
+    }
+}
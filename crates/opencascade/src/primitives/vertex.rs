@@ -4,6 +4,8 @@ use cxx::UniquePtr;
 use nalgebra::point;
 use nalgebra::Point3;
 use opencascade_sys::ffi::{self};
+use std::hash::Hash;
+use std::hash::Hasher;
 
 pub struct Vertex {
     pub(crate) inner: UniquePtr<ffi::TopoDS_Vertex>,
@@ -39,13 +41,47 @@ impl Vertex {
         Self { inner }
     }
 
-    pub fn coords(&self) -> Point3<f64> {
+    /// Where this vertex is.
+    pub fn point(&self) -> Point3<f64> {
         let vertex = &self.inner;
 
         let coords = ffi::BRep_Tool_Pnt(vertex);
 
         point![coords.X(), coords.Y(), coords.Z()]
     }
+
+    /// The same as `point`.
+    pub fn coords(&self) -> Point3<f64> {
+        self.point()
+    }
+
+    /// How far from `point` the edges meeting at this vertex may end and
+    /// still be taken to meet there.
+    pub fn tolerance(&self) -> f64 {
+        ffi::BRep_Tool_Tolerance_vertex(&self.inner)
+    }
+}
+
+/// Vertices are equal when they are the same vertex of the topology, such as
+/// where two edges of a wire meet, not just when they are at the same point.
+impl PartialEq for Vertex {
+    fn eq(&self, other: &Self) -> bool {
+        ffi::cast_vertex_to_shape(&self.inner).IsSame(ffi::cast_vertex_to_shape(&other.inner))
+    }
+}
+
+impl Eq for Vertex {}
+
+/// The same vertex is always at the same point, so vertices hash by their
+/// point.
+impl Hash for Vertex {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let point = self.point();
+
+        for coordinate in [point.x, point.y, point.z] {
+            coordinate.to_bits().hash(state);
+        }
+    }
 }
 
 impl From<Shape> for Vertex {
@@ -63,3 +99,66 @@ impl From<&Shape> for Vertex {
         Vertex::from_vertex(topo_vertex)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::Edge;
+    use crate::primitives::Solid;
+    use crate::primitives::Wire;
+    use std::collections::HashSet;
+
+    #[test]
+    fn segment_vertices_are_its_points() {
+        let (a, b) = (point![1.5, -2.25, 3.0], point![-4.0, 0.125, 7.5]);
+        let segment = Edge::segment(a, b);
+
+        assert_eq!(segment.start_vertex().point(), a);
+        assert_eq!(segment.end_vertex().point(), b);
+        assert!(segment.start_vertex().tolerance() > 0.0);
+        assert_eq!(Vertex::new(a).point(), a);
+    }
+
+    #[test]
+    fn wire_vertices_are_shared() {
+        let points = [
+            point![0.0, 0.0, 0.0],
+            point![10.0, 0.0, 0.0],
+            point![10.0, 5.0, 0.0],
+            point![0.0, 5.0, 0.0],
+        ];
+        let wire = Wire::from_ordered_points(points).unwrap();
+
+        let vertices = wire.vertices();
+        assert_eq!(vertices.len(), 4);
+        for (vertex, point) in vertices.iter().zip(points) {
+            assert_eq!(vertex.point(), point);
+        }
+
+        // Consecutive edges meet at the same vertex, not just the same point.
+        let edges: Vec<_> = wire.edges().collect();
+        assert!(edges[0].end_vertex() == edges[1].start_vertex());
+        assert!(Vertex::new(points[1]) != edges[1].start_vertex());
+
+        // A box's vertices are each visited by several of its edges.
+        let block = Shape::box_centered(1.0, 1.0, 1.0);
+        let unique: HashSet<_> = block.vertices().collect();
+        assert_eq!(unique.len(), 8);
+    }
+
+    #[test]
+    fn loft_to_a_point() {
+        let base = Wire::from_ordered_points([
+            point![0.0, 0.0, 0.0],
+            point![6.0, 0.0, 0.0],
+            point![6.0, 6.0, 0.0],
+            point![0.0, 6.0, 0.0],
+        ])
+        .unwrap();
+
+        let pyramid = Solid::loft_to_point([&base], &Vertex::new(point![3.0, 3.0, 5.0]));
+        let volume = Shape::from(pyramid).volume();
+
+        assert!((volume - 36.0 * 5.0 / 3.0).abs() < 1.0e-6, "{volume}");
+    }
+}
@@ -7,17 +7,21 @@ use crate::make_pipe_shell::make_pipe_shell_with_law_function;
 use crate::primitives::make_dir;
 use crate::primitives::make_point;
 use crate::primitives::make_vec;
+use crate::primitives::DatumAxis;
 use crate::primitives::Edge;
 use crate::primitives::Face;
 use crate::primitives::JoinType;
 use crate::primitives::Shape;
 use crate::primitives::Shell;
+use crate::primitives::Vertex;
 use crate::Error;
 use crate::WireExplorerIter;
+use crate::CONFUSION;
 use cxx::UniquePtr;
 use nalgebra::point;
 use nalgebra::vector;
 use nalgebra::Point3;
+use nalgebra::UnitVector3;
 use nalgebra::Vector3;
 use opencascade_sys::ffi;
 
@@ -31,6 +35,37 @@ impl AsRef<Wire> for Wire {
     }
 }
 
+/// Equal distances to two edges, within this, are a tie.
+const TIE_TOLERANCE: f64 = 1.0e-9;
+
+/// Points further than this from a wire aren't on it.
+const ON_WIRE_TOLERANCE: f64 = 1.0e-6;
+
+/// Which way around a closed wire `Wire::trim_between` goes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TrimDirection {
+    /// Whichever way is shorter.
+    Shorter,
+    /// The way the wire runs.
+    Forwards,
+    /// Against the way the wire runs.
+    Backwards,
+}
+
+/// The point on a wire nearest to another point, found with
+/// `Wire::closest_point`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WireProjection {
+    pub point: Point3<f64>,
+    pub distance: f64,
+    /// The index of the edge the point is on, in the order of `Wire::edges`.
+    /// Points on the vertex between two edges are on the first of them.
+    pub edge_index: usize,
+    /// How far along the wire the point is, by arc length, from 0.0 at its
+    /// start to 1.0 at its end.
+    pub parameter: f64,
+}
+
 /// Provides control over how an edge is considered "connected" to another edge.
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum EdgeConnection {
@@ -157,23 +192,12 @@ impl Wire {
         Self::from_make_wire(make_wire)
     }
 
+    /// Like `mirrored`, in the line through `axis_origin` along `axis_dir`.
     #[must_use]
     pub fn mirror_along_axis(&self, axis_origin: Point3<f64>, axis_dir: Vector3<f64>) -> Self {
-        let axis_dir = make_dir(axis_dir);
-        let axis = ffi::gp_Ax1_ctor(&make_point(axis_origin), &axis_dir);
-
-        let mut transform = ffi::new_transform();
-
-        transform.pin_mut().set_mirror_axis(&axis);
-
-        let wire_shape = ffi::cast_wire_to_shape(&self.inner);
-
-        let mut brep_transform = ffi::BRepBuilderAPI_Transform_ctor(wire_shape, &transform, false);
+        let axis = DatumAxis::new(axis_origin, UnitVector3::new_normalize(axis_dir));
 
-        let mirrored_shape = brep_transform.pin_mut().Shape();
-        let mirrored_wire = ffi::TopoDS_cast_to_wire(mirrored_shape);
-
-        Self::from_wire(mirrored_wire)
+        self.mirrored(&axis)
     }
 
     pub fn rect(width: f64, height: f64) -> Result<Self, Error> {
@@ -224,6 +248,38 @@ impl Wire {
         Self::from_wire(result_wire)
     }
 
+    /// Offset the wire across `face`, which the wire must lie on. Positive
+    /// distances move the wire into the region it bounds on the face,
+    /// negative distances move it outwards. Corners are joined with arcs.
+    ///
+    /// The offset is made in the face's parameter space, so on curved faces
+    /// the distance is only exact where the surface's parameters measure
+    /// length, like along the axis of a cylinder.
+    ///
+    /// Returns an empty `Vec` if an inward offset consumes the whole region.
+    pub fn offset_on_face(&self, face: &Face, distance: f64) -> Result<Vec<Wire>, Error> {
+        let region = Face::from_wire_on_surface(self, &face.surface())?;
+
+        let mut make_offset = ffi::BRepOffsetAPI_MakeOffset_face_ctor(
+            &region.inner,
+            JoinType::Arc.into(),
+        );
+        make_offset.pin_mut().Perform(-distance, 0.0);
+
+        if !make_offset.IsDone() {
+            // Inward offsets fail when they collapse the region.
+            return if distance > 0.0 {
+                Ok(vec![])
+            } else {
+                Err(Error::NotDone)
+            };
+        }
+
+        let offset_shape = Shape::from_shape(make_offset.pin_mut().Shape());
+
+        Ok(offset_shape.wires().collect())
+    }
+
     /// Sweep the wire along a path to produce a shell
     #[must_use]
     pub fn sweep_along(&self, path: &Wire) -> Shell {
@@ -299,6 +355,409 @@ impl Wire {
         WireExplorerIter::new(self)
     }
 
+    /// The vertices of the wire in order along it, each once. A closed
+    /// wire's vertices start and end at its start, and an open wire's run
+    /// from its start vertex to its end vertex.
+    pub fn vertices(&self) -> Vec<Vertex> {
+        let mut vertices = vec![];
+        let mut last = None;
+
+        for edge in self.edges() {
+            // Reversed edges run along the wire from the end of their curve.
+            let (start, end) = if edge.is_reversed() {
+                (edge.end_vertex(), edge.start_vertex())
+            } else {
+                (edge.start_vertex(), edge.end_vertex())
+            };
+
+            vertices.push(start);
+            last = Some(end);
+        }
+
+        if let Some(end) = last {
+            if !self.is_closed() {
+                vertices.push(end);
+            }
+        }
+
+        vertices
+    }
+
+    /// Returns true if the wire forms a closed loop.
+    pub fn is_closed(&self) -> bool {
+        ffi::BRep_Tool_IsClosed(ffi::cast_wire_to_shape(&self.inner))
+    }
+
+    /// The total length of the wire.
+    pub fn length(&self) -> f64 {
+        let curve = ffi::BRepAdaptor_CompCurve_ctor(&self.inner);
+
+        ffi::BRepAdaptor_CompCurve_length(&curve)
+    }
+
+    /// `count` points along the wire, evenly spaced by arc length, along
+    /// with the unit tangent at each point. Open wires are sampled from end
+    /// to end; closed wires are sampled all the way around without
+    /// repeating the start point. Fails with `Error::ZeroLengthWire` for a
+    /// wire shorter than `CONFUSION`.
+    pub fn points_by_arc_length(
+        &self,
+        count: usize,
+    ) -> Result<Vec<(Point3<f64>, UnitVector3<f64>)>, Error> {
+        let map = self.arc_length_param();
+        if map.total_length() < CONFUSION {
+            return Err(Error::ZeroLengthWire);
+        }
+
+        let intervals = if self.is_closed() {
+            count
+        } else {
+            count.saturating_sub(1).max(1)
+        };
+
+        (0..count)
+            .map(|i| {
+                let length = map.total_length() * i as f64 / intervals as f64;
+                map.point_and_tangent_at_length(length)
+            })
+            .collect()
+    }
+
+    /// The point on this wire nearest to `point`. Fails with
+    /// `Error::ZeroLengthWire` for a wire with no length, or no edges.
+    pub fn closest_point(&self, point: Point3<f64>) -> Result<WireProjection, Error> {
+        Ok(self.closest_points(&[point])?.remove(0))
+    }
+
+    /// The points on this wire nearest to each of `points`. Fails like
+    /// `closest_point`.
+    pub fn closest_points(&self, points: &[Point3<f64>]) -> Result<Vec<WireProjection>, Error> {
+        // Each edge's curve, whether it runs against the wire, and the length
+        // of the wire before it.
+        let mut length_before = 0.0;
+        let edges: Vec<_> = self
+            .edges()
+            .map(|edge| {
+                let curve = ffi::BRepAdaptor_Curve_ctor(&edge.inner);
+                let reversed = edge.is_reversed();
+                let start = length_before;
+                length_before += ffi::EdgeLength(&curve);
+
+                (curve, reversed, start)
+            })
+            .collect();
+        let total_length = length_before;
+
+        if total_length < CONFUSION {
+            return Err(Error::ZeroLengthWire);
+        }
+
+        Ok(points
+            .iter()
+            .map(|point| {
+                let target = make_point(*point);
+
+                edges
+                    .iter()
+                    .enumerate()
+                    .map(|(edge_index, (curve, reversed, start))| {
+                        let u = ffi::BRepAdaptor_Curve_nearest_parameter(curve, &target);
+                        let nearest = ffi::BRepAdaptor_Curve_value(curve, u);
+                        let nearest = point![nearest.X(), nearest.Y(), nearest.Z()];
+
+                        let along = if *reversed {
+                            ffi::BRepAdaptor_Curve_length_between(curve, u, curve.LastParameter())
+                        } else {
+                            ffi::BRepAdaptor_Curve_length_between(curve, curve.FirstParameter(), u)
+                        };
+
+                        WireProjection {
+                            point: nearest,
+                            distance: (nearest - point).norm(),
+                            edge_index,
+                            parameter: (start + along) / total_length,
+                        }
+                    })
+                    .reduce(|best, projection| {
+                        if projection.distance < best.distance - TIE_TOLERANCE {
+                            projection
+                        } else {
+                            best
+                        }
+                    })
+                    .expect("a wire with length has at least one edge")
+            })
+            .collect())
+    }
+
+    /// The point the wire starts at.
+    pub fn start_point(&self) -> Point3<f64> {
+        let curve = ffi::BRepAdaptor_CompCurve_ctor(&self.inner);
+        let point = ffi::BRepAdaptor_CompCurve_value(&curve, curve.FirstParameter());
+
+        point![point.X(), point.Y(), point.Z()]
+    }
+
+    /// The point the wire ends at, which is its start if it's closed.
+    pub fn end_point(&self) -> Point3<f64> {
+        let curve = ffi::BRepAdaptor_CompCurve_ctor(&self.inner);
+        let point = ffi::BRepAdaptor_CompCurve_value(&curve, curve.LastParameter());
+
+        point![point.X(), point.Y(), point.Z()]
+    }
+
+    /// This wire, running the other way: its edges in the opposite order,
+    /// each reversed. Faces made from a reversed planar wire face the other
+    /// way.
+    pub fn reversed(&self) -> Result<Wire, Error> {
+        let mut edges: Vec<_> = self.edges().map(|edge| edge.reversed()).collect();
+        edges.reverse();
+
+        let mut make_wire = ffi::BRepBuilderAPI_MakeWire_ctor();
+        for edge in &edges {
+            make_wire.pin_mut().add_edge(&edge.inner);
+        }
+
+        Self::from_make_wire(make_wire)
+    }
+
+    /// This wire followed by `other`, which is reversed if it's `other`'s
+    /// end which meets this wire's end. Ends which are apart, but within
+    /// `tolerance`, are joined with a short segment.
+    pub fn concat(&self, other: &Wire, tolerance: f64) -> Result<Wire, Error> {
+        if self.is_closed() || other.is_closed() {
+            return Err(Error::ClosedWire);
+        }
+
+        let end = self.end_point();
+        let to_start = (other.start_point() - end).norm();
+        let to_end = (other.end_point() - end).norm();
+
+        let (other, gap) = if to_start <= to_end {
+            (Wire::from_wire(&other.inner), to_start)
+        } else {
+            (other.reversed()?, to_end)
+        };
+
+        if gap > tolerance {
+            return Err(Error::WiresApart { gap });
+        }
+
+        let mut edges: Vec<_> = self.edges().collect();
+        if gap > CONFUSION {
+            edges.push(Edge::segment(end, other.start_point()));
+        }
+        edges.extend(other.edges());
+
+        Wire::from_edges_consuming(edges)
+    }
+
+    /// This wire, with any gaps of no more than `tolerance` between its
+    /// edges closed up by moving their vertices together, rather than by
+    /// adding short edges across them. Its ends are joined too, if they're
+    /// that close. A larger gap between two edges is an
+    /// `Error::EdgesApart`.
+    pub fn healed(&self, tolerance: f64) -> Result<Wire, Error> {
+        Ok(self.healed_with_gap(tolerance)?.0)
+    }
+
+    /// Like `healed`, also returning the largest gap which was closed up.
+    pub fn healed_with_gap(&self, tolerance: f64) -> Result<(Wire, f64), Error> {
+        // A `BRepTools_WireExplorer` stops at the first gap, so take the
+        // edges in the order they're stored in.
+        let edges: Vec<_> = Shape::from(self).edges().collect();
+
+        Self::from_edges_healed(&edges, tolerance, false)
+    }
+
+    /// Build a wire from `edges`, in order, healing gaps of no more than
+    /// `tolerance` between them, and the largest gap healed. If `close`,
+    /// the last edge must end within `tolerance` of the start of the first.
+    pub(crate) fn from_edges_healed(
+        edges: &[Edge],
+        tolerance: f64,
+        close: bool,
+    ) -> Result<(Wire, f64), Error> {
+        if edges.is_empty() {
+            return Err(Error::NotEnoughPoints);
+        }
+
+        // Where each edge starts and ends, in the direction the wire runs.
+        let ends: Vec<_> = edges
+            .iter()
+            .map(|edge| {
+                let (start, end) = (edge.start_point(), edge.end_point());
+                if edge.is_reversed() {
+                    (end, start)
+                } else {
+                    (start, end)
+                }
+            })
+            .collect();
+
+        let mut largest_gap: f64 = 0.0;
+
+        for first in 0..ends.len() {
+            let second = (first + 1) % ends.len();
+            let gap = (ends[second].0 - ends[first].1).norm();
+
+            if gap <= tolerance {
+                largest_gap = largest_gap.max(gap);
+            } else if second != 0 || close {
+                return Err(Error::EdgesApart { first, second, gap });
+            }
+        }
+
+        let unconnected = Self::from_edges_unconnected(edges);
+        let mut fix = ffi::ShapeFix_Wire_new(&unconnected.inner, tolerance);
+
+        // These say whether they changed anything, which they needn't if
+        // the edges already share their vertices, so look for failures in
+        // their statuses instead.
+        fix.pin_mut().FixConnected(tolerance);
+        fix.pin_mut().FixClosed(tolerance);
+        if ffi::ShapeFix_Wire_failed(&fix) {
+            return Err(Error::NotDone);
+        }
+
+        let healed = Wire::from_wire(&ffi::ShapeFix_Wire_wire(&fix));
+        if close && !healed.is_closed() {
+            return Err(Error::NotClosed);
+        }
+
+        Ok((healed, largest_gap))
+    }
+
+    /// Put `edges` in a wire as they are, without the connection checks
+    /// `BRepBuilderAPI_MakeWire` makes, for ShapeFix_Wire to mend.
+    pub(crate) fn from_edges_unconnected(edges: &[Edge]) -> Wire {
+        let brep_builder = ffi::BRep_Builder_ctor();
+        let topods_builder = ffi::BRep_Builder_upcast_to_topods_builder(&brep_builder);
+        let mut wire = ffi::TopoDS_Wire_ctor();
+        topods_builder.MakeWire(wire.pin_mut());
+
+        let mut wire_shape = ffi::TopoDS_Shape_to_owned(ffi::cast_wire_to_shape(&wire));
+        for edge in edges {
+            topods_builder.Add(wire_shape.pin_mut(), ffi::cast_edge_to_shape(&edge.inner));
+        }
+
+        Wire::from_wire(ffi::TopoDS_cast_to_wire(&wire_shape))
+    }
+
+    /// This closed wire, starting and ending at the point on it nearest to
+    /// `point`. The edge that point is on is split in two there, unless it's
+    /// already at a vertex.
+    pub fn with_seam_at(&self, point: Point3<f64>) -> Result<Wire, Error> {
+        if !self.is_closed() {
+            return Err(Error::NotClosed);
+        }
+
+        let (before, after) = self.split_edges_at(&self.closest_point(point)?)?;
+
+        Wire::from_edges_consuming(after.into_iter().chain(before).collect())
+    }
+
+    /// Split this open wire in two at `point`, which must be on it, and not
+    /// at either end.
+    pub fn split_at_point(&self, point: Point3<f64>) -> Result<(Wire, Wire), Error> {
+        if self.is_closed() {
+            return Err(Error::ClosedWire);
+        }
+
+        let projection = self.closest_point(point)?;
+        if projection.distance > ON_WIRE_TOLERANCE {
+            return Err(Error::PointOffWire {
+                distance: projection.distance,
+            });
+        }
+
+        let (before, after) = self.split_edges_at(&projection)?;
+        if before.is_empty() || after.is_empty() {
+            return Err(Error::ParameterOutOfRange {
+                parameter: projection.parameter,
+            });
+        }
+
+        Ok((
+            Wire::from_edges_consuming(before)?,
+            Wire::from_edges_consuming(after)?,
+        ))
+    }
+
+    /// The part of this closed wire from `start` to `end`, which must both be
+    /// on it, going the way `direction` says.
+    pub fn trim_between(
+        &self,
+        start: Point3<f64>,
+        end: Point3<f64>,
+        direction: TrimDirection,
+    ) -> Result<Wire, Error> {
+        if !self.is_closed() {
+            return Err(Error::NotClosed);
+        }
+
+        for point in [start, end] {
+            let distance = self.closest_point(point)?.distance;
+            if distance > ON_WIRE_TOLERANCE {
+                return Err(Error::PointOffWire { distance });
+            }
+        }
+
+        // With the seam at `start`, the wire runs forwards to `end`, and on
+        // from there back to `start`.
+        let from_start = self.with_seam_at(start)?;
+        let projection = from_start.closest_point(end)?;
+        let (forwards, backwards) = from_start.split_edges_at(&projection)?;
+
+        if forwards.is_empty() || backwards.is_empty() {
+            return Err(Error::ParameterOutOfRange {
+                parameter: projection.parameter,
+            });
+        }
+
+        let forwards = Wire::from_edges_consuming(forwards)?;
+        let backwards = Wire::from_edges_consuming(backwards)?.reversed()?;
+
+        Ok(match direction {
+            TrimDirection::Forwards => forwards,
+            TrimDirection::Backwards => backwards,
+            TrimDirection::Shorter if backwards.length() < forwards.length() => backwards,
+            TrimDirection::Shorter => forwards,
+        })
+    }
+
+    /// The edges of this wire before and after the point `projection` is
+    /// at, in order, splitting the edge it's on unless it's at a vertex.
+    fn split_edges_at(&self, projection: &WireProjection) -> Result<(Vec<Edge>, Vec<Edge>), Error> {
+        let mut before: Vec<_> = self.edges().collect();
+        let mut after = before.split_off(projection.edge_index);
+        let edge = after.remove(0);
+
+        let curve = ffi::BRepAdaptor_Curve_ctor(&edge.inner);
+        let (first, last) = (curve.FirstParameter(), curve.LastParameter());
+        let u = ffi::BRepAdaptor_Curve_nearest_parameter(&curve, &make_point(projection.point));
+        let piece = |from: f64, to: f64| {
+            ((to - from).abs() > CONFUSION)
+                .then(|| edge.between_parameters(from, to))
+                .transpose()
+        };
+
+        // Reversed edges run from the end of their curve to its start.
+        let (start, end) = if edge.is_reversed() {
+            (last, first)
+        } else {
+            (first, last)
+        };
+        let (head, tail) = (piece(start, u)?, piece(u, end)?);
+
+        before.extend(head);
+        if let Some(tail) = tail {
+            after.insert(0, tail);
+        }
+
+        Ok((before, after))
+    }
+
     // Create a closure-based API
     pub fn freeform() {}
 }
@@ -311,8 +770,11 @@ impl From<Shape> for Wire {
     }
 }
 
+/// Builds a wire one edge at a time, keeping a single OCCT wire builder
+/// alive for the whole wire.
 pub struct WireBuilder {
     inner: UniquePtr<ffi::BRepBuilderAPI_MakeWire>,
+    len: usize,
 }
 
 impl Default for WireBuilder {
@@ -325,14 +787,481 @@ impl WireBuilder {
     pub fn new() -> Self {
         let make_wire = ffi::BRepBuilderAPI_MakeWire_ctor();
 
-        Self { inner: make_wire }
+        Self {
+            inner: make_wire,
+            len: 0,
+        }
+    }
+
+    /// The number of edges pushed so far, including rejected ones.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 
     pub fn add_edge(&mut self, edge: &Edge) {
         self.inner.pin_mut().add_edge(&edge.inner);
+        self.len += 1;
+    }
+
+    /// Append `edge` to the end of the wire. An edge which doesn't connect
+    /// to the wire built so far is rejected with its index, and the builder
+    /// stays usable.
+    pub fn push(&mut self, edge: Edge) -> Result<(), Error> {
+        let index = self.len;
+        self.add_edge(&edge);
+
+        match self.inner.Error() {
+            ffi::BRepBuilderAPI_WireError::BRepBuilderAPI_WireDone => Ok(()),
+            ffi::BRepBuilderAPI_WireError::BRepBuilderAPI_DisconnectedWire => {
+                Err(Error::DisconnectedEdge { index })
+            }
+            ffi::BRepBuilderAPI_WireError::BRepBuilderAPI_NonManifoldWire => {
+                Err(Error::NonManifoldEdge { index })
+            }
+            _ => Err(Error::NotDone),
+        }
     }
 
     pub fn build(self) -> Result<Wire, Error> {
         Wire::from_make_wire(self.inner)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::EdgeType;
+    use crate::primitives::FaceType;
+    use crate::workplane::Workplane;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn heal_gappy_wire() {
+        let corners = [
+            point![0.0, 0.0, 0.0],
+            point![10.0, 0.0, 0.0],
+            point![10.0, 10.0, 0.0],
+            point![0.0, 10.0, 0.0],
+        ];
+        // Each edge ends a little short of where the next one starts.
+        let edges: Vec<_> = (0..4)
+            .map(|i| {
+                let (start, end) = (corners[i], corners[(i + 1) % 4]);
+                Edge::segment(start, end - (end - start) * 1.0e-5)
+            })
+            .collect();
+        let gappy = Wire::from_edges_unconnected(&edges);
+
+        let (healed, gap) = gappy.healed_with_gap(1.0e-3).unwrap();
+        assert!((gap - 1.0e-4).abs() < 1.0e-9, "{gap}");
+        assert!(healed.is_closed());
+        assert_eq!(healed.edges().count(), 4);
+
+        match gappy.healed(1.0e-5) {
+            Err(Error::EdgesApart { first, second, .. }) => assert_eq!((first, second), (0, 1)),
+            other => panic!("expected the edges to be apart, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn offset_on_face_insets_and_collapses() {
+        let rim = Workplane::xy().circle(0.0, 0.0, 10.0).unwrap();
+        let lid = Face::from_wire(&rim).unwrap();
+
+        let grooves = rim.offset_on_face(&lid, 2.0).unwrap();
+        assert_eq!(grooves.len(), 1);
+
+        let groove_length: f64 = grooves[0].edges().map(|edge| edge.length()).sum();
+        assert!((groove_length - 2.0 * std::f64::consts::PI * 8.0).abs() < 1.0e-3);
+
+        assert!(rim.offset_on_face(&lid, 20.0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn offset_on_curved_face() {
+        // A lid curving gently along x, cut from a big cylinder.
+        let cylinder = Shape::cylinder(point![0.0, 0.0, -50.0], 50.0, Vector3::y(), 20.0);
+        let lid: Shape = cylinder
+            .intersect(&Shape::box_from_corners(
+                point![-10.0, -10.0, -5.0],
+                point![10.0, 30.0, 10.0],
+            ))
+            .unwrap()
+            .into();
+        let surface = lid
+            .faces()
+            .find(|face| face.face_type() == FaceType::Cylinder)
+            .unwrap();
+        let rim = surface.outer_wire();
+
+        let grooves = rim.offset_on_face(&surface, 0.05).unwrap();
+        assert_eq!(grooves.len(), 1);
+        assert!(grooves[0].is_closed());
+
+        // The groove stays on the lid, inside its rim.
+        for vertex in grooves[0].vertices() {
+            let point = vertex.point();
+            assert!((vector![point.x, point.z + 50.0].norm() - 50.0).abs() < 1.0e-6);
+        }
+        assert!(grooves[0].length() < rim.length());
+
+        assert!(rim.offset_on_face(&surface, 50.0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn points_by_arc_length() {
+        // A closed 4 x 2 rectangle is sampled all the way around.
+        let rectangle = Wire::from_ordered_points([
+            point![0.0, 0.0, 0.0],
+            point![4.0, 0.0, 0.0],
+            point![4.0, 2.0, 0.0],
+            point![0.0, 2.0, 0.0],
+        ])
+        .unwrap();
+        assert!((rectangle.length() - 12.0).abs() < 1.0e-9);
+
+        let samples = rectangle.points_by_arc_length(6).unwrap();
+        assert_eq!(samples.len(), 6);
+
+        let expected = [
+            point![0.0, 0.0, 0.0],
+            point![2.0, 0.0, 0.0],
+            point![4.0, 0.0, 0.0],
+            point![4.0, 2.0, 0.0],
+            point![2.0, 2.0, 0.0],
+            point![0.0, 2.0, 0.0],
+        ];
+        for ((point, _), expected) in samples.iter().zip(expected) {
+            assert!((point - expected).norm() < 1.0e-9, "{point}");
+        }
+        assert!((samples[1].1.into_inner() - Vector3::x()).norm() < 1.0e-9);
+        assert!((samples[4].1.into_inner() + Vector3::x()).norm() < 1.0e-9);
+
+        // An open wire is sampled from end to end.
+        let polyline = open_polyline();
+        assert!((polyline.length() - (1.0 + 2.0f64.sqrt())).abs() < 1.0e-9);
+
+        let samples = polyline.points_by_arc_length(3).unwrap();
+        assert!((samples[0].0 - point![0.0, 0.0, 0.0]).norm() < 1.0e-9);
+        assert!((samples[2].0 - point![2.0, 1.0, 0.0]).norm() < 1.0e-9);
+        assert!((samples[2].1.into_inner() - vector![1.0, 1.0, 0.0].normalize()).norm() < 1.0e-9);
+
+        assert!(matches!(
+            Wire::from_edges_unconnected(&[]).points_by_arc_length(3),
+            Err(Error::ZeroLengthWire)
+        ));
+    }
+
+    #[test]
+    fn closest_point_on_rectangle() {
+        // A 4 x 2 rectangle, 12 around, starting at the origin.
+        let rectangle = Wire::from_ordered_points([
+            point![0.0, 0.0, 0.0],
+            point![4.0, 0.0, 0.0],
+            point![4.0, 2.0, 0.0],
+            point![0.0, 2.0, 0.0],
+        ])
+        .unwrap();
+
+        for (point, nearest, distance, edge_index, length) in [
+            (point![1.0, -1.0, 0.0], point![1.0, 0.0, 0.0], 1.0, 0, 1.0),
+            (point![5.0, 1.0, 0.0], point![4.0, 1.0, 0.0], 1.0, 1, 5.0),
+            (point![3.0, 1.5, 0.0], point![3.0, 2.0, 0.0], 0.5, 2, 7.0),
+            (
+                point![0.5, 1.0, 3.0],
+                point![0.0, 1.0, 0.0],
+                0.5f64.hypot(3.0),
+                3,
+                11.0,
+            ),
+            // Equally near the ends of two edges.
+            (
+                point![-1.0, -1.0, 0.0],
+                point![0.0, 0.0, 0.0],
+                2.0f64.sqrt(),
+                0,
+                0.0,
+            ),
+            (
+                point![5.0, 3.0, 0.0],
+                point![4.0, 2.0, 0.0],
+                2.0f64.sqrt(),
+                1,
+                6.0,
+            ),
+        ] {
+            let projection = rectangle.closest_point(point).unwrap();
+
+            assert!((projection.point - nearest).norm() < 1.0e-9, "{point}");
+            assert!((projection.distance - distance).abs() < 1.0e-9, "{point}");
+            assert_eq!(projection.edge_index, edge_index, "{point}");
+            assert!(
+                (projection.parameter - length / 12.0).abs() < 1.0e-9,
+                "{point}"
+            );
+        }
+
+        let points = [point![1.0, -1.0, 0.0], point![2.0, 1.0, 0.0]];
+        let batch = rectangle.closest_points(&points).unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0], rectangle.closest_point(points[0]).unwrap());
+        assert_eq!(batch[1], rectangle.closest_point(points[1]).unwrap());
+
+        let empty = Wire::from_edges_unconnected(&[]);
+        assert!(matches!(
+            empty.closest_point(point![0.0, 0.0, 0.0]),
+            Err(Error::ZeroLengthWire)
+        ));
+    }
+
+    fn open_polyline() -> Wire {
+        Wire::from_edges_consuming(vec![
+            Edge::segment(point![0.0, 0.0, 0.0], point![1.0, 0.0, 0.0]),
+            Edge::segment(point![1.0, 0.0, 0.0], point![2.0, 1.0, 0.0]),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn reversed_wires() {
+        let wire = open_polyline();
+        let reversed = wire.reversed().unwrap();
+
+        assert_eq!(wire.start_point(), point![0.0, 0.0, 0.0]);
+        assert_eq!(wire.end_point(), point![2.0, 1.0, 0.0]);
+        assert_eq!(reversed.start_point(), point![2.0, 1.0, 0.0]);
+        assert_eq!(reversed.end_point(), point![0.0, 0.0, 0.0]);
+        assert!((reversed.length() - wire.length()).abs() < 1.0e-9);
+
+        // Faces from a reversed loop face the other way, and so extrude the
+        // other way.
+        let square = Workplane::xy().rect(2.0, 2.0).unwrap();
+        let face = Face::from_wire(&square).unwrap();
+        let reversed_face = Face::from_wire(&square.reversed().unwrap()).unwrap();
+        let (normal, reversed_normal) = (face.normal(), reversed_face.normal());
+        assert!(
+            (normal + reversed_normal).norm() < 1.0e-9,
+            "{normal} {reversed_normal}"
+        );
+
+        // Extruded out of the face, the two solids end up either side of it.
+        let middle_height = |face: &Face| {
+            let solid = Shape::from(face.extrude(face.normal().into_inner() * 3.0));
+            let bounds = solid.bounding_box();
+            (bounds.min().z + bounds.max().z) / 2.0
+        };
+        assert!((middle_height(&face) - 1.5 * normal.z).abs() < 1.0e-6);
+        assert!((middle_height(&reversed_face) + 1.5 * normal.z).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn concatenated_wires() {
+        let wire = open_polyline();
+        let onward =
+            Wire::from_edges([&Edge::segment(point![2.0, 1.0, 0.0], point![2.0, 3.0, 0.0])])
+                .unwrap();
+
+        let joined = wire.concat(&onward, 1.0e-6).unwrap();
+        assert_eq!(joined.edges().count(), 3);
+        assert_eq!(joined.start_point(), point![0.0, 0.0, 0.0]);
+        assert_eq!(joined.end_point(), point![2.0, 3.0, 0.0]);
+
+        // The other wire is turned around to meet this one.
+        let joined = wire.concat(&onward.reversed().unwrap(), 1.0e-6).unwrap();
+        assert_eq!(joined.end_point(), point![2.0, 3.0, 0.0]);
+
+        // Small gaps are bridged, large ones aren't.
+        let near = Wire::from_edges([&Edge::segment(
+            point![2.0, 1.001, 0.0],
+            point![2.0, 3.0, 0.0],
+        )])
+        .unwrap();
+        assert_eq!(wire.concat(&near, 0.01).unwrap().edges().count(), 4);
+        assert!(matches!(
+            wire.concat(&near, 1.0e-6),
+            Err(Error::WiresApart { .. })
+        ));
+
+        let square = Workplane::xy().rect(2.0, 2.0).unwrap();
+        assert!(matches!(wire.concat(&square, 1.0), Err(Error::ClosedWire)));
+    }
+
+    #[test]
+    fn seam_moves() {
+        let square = Wire::from_ordered_points([
+            point![0.0, 0.0, 0.0],
+            point![2.0, 0.0, 0.0],
+            point![2.0, 2.0, 0.0],
+            point![0.0, 2.0, 0.0],
+        ])
+        .unwrap();
+
+        // In the middle of an edge, which is split.
+        let moved = square.with_seam_at(point![2.5, 0.5, 0.0]).unwrap();
+        assert!(moved.is_closed());
+        assert_eq!(moved.edges().count(), 5);
+        assert!((moved.start_point() - point![2.0, 0.5, 0.0]).norm() < 1.0e-9);
+        assert!((moved.end_point() - point![2.0, 0.5, 0.0]).norm() < 1.0e-9);
+        assert!((moved.length() - 8.0).abs() < 1.0e-9);
+        let projection = moved.closest_point(point![2.0, 1.0, 0.0]).unwrap();
+        assert!((projection.parameter - 0.5 / 8.0).abs() < 1.0e-9);
+
+        // At a vertex, which stays as it is.
+        let moved = square.with_seam_at(point![2.1, 2.1, 0.0]).unwrap();
+        assert_eq!(moved.edges().count(), 4);
+        assert!((moved.start_point() - point![2.0, 2.0, 0.0]).norm() < 1.0e-9);
+
+        assert!(matches!(
+            open_polyline().with_seam_at(point![0.0, 0.0, 0.0]),
+            Err(Error::NotClosed)
+        ));
+    }
+
+    #[test]
+    fn split_and_trimmed_edges() {
+        // A quarter circle of radius 2, pi long.
+        let arc = Edge::arc(
+            point![2.0, 0.0, 0.0],
+            point![2.0f64.sqrt(), 2.0f64.sqrt(), 0.0],
+            point![0.0, 2.0, 0.0],
+        );
+
+        let (first, second) = arc.split_at(0.25).unwrap();
+        assert_eq!(first.edge_type(), EdgeType::Circle);
+        assert!((first.length() - PI / 4.0).abs() < 1.0e-9);
+        assert!((second.length() - 3.0 * PI / 4.0).abs() < 1.0e-9);
+        assert!((first.end_point() - second.start_point()).norm() < 1.0e-9);
+
+        // Reversed edges are measured from their own start.
+        let reversed = arc.reversed();
+        let piece = reversed.trimmed(0.0, 0.5).unwrap();
+        let rejoined = Wire::from_edges([&piece, &reversed.trimmed(0.5, 1.0).unwrap()]).unwrap();
+        assert!((rejoined.start_point() - point![0.0, 2.0, 0.0]).norm() < 1.0e-9);
+        assert!((rejoined.length() - arc.length()).abs() < 1.0e-9);
+
+        for (start, end) in [(-0.1, 0.5), (0.5, 1.1), (0.5, 0.5), (0.6, 0.4)] {
+            assert!(matches!(
+                arc.trimmed(start, end),
+                Err(Error::ParameterOutOfRange { .. })
+            ));
+        }
+        assert!(arc.split_at(0.0).is_err() && arc.split_at(1.0).is_err());
+    }
+
+    #[test]
+    fn split_open_wire_at_point() {
+        let wire = open_polyline();
+
+        let (head, tail) = wire.split_at_point(point![0.5, 0.0, 0.0]).unwrap();
+        assert_eq!(head.end_point(), point![0.5, 0.0, 0.0]);
+        assert_eq!(tail.start_point(), point![0.5, 0.0, 0.0]);
+        assert_eq!((head.edges().count(), tail.edges().count()), (1, 2));
+        assert!((head.length() + tail.length() - wire.length()).abs() < 1.0e-9);
+
+        let rejoined = head.concat(&tail, 1.0e-9).unwrap();
+        assert!((rejoined.length() - wire.length()).abs() < 1.0e-9);
+
+        assert!(matches!(
+            wire.split_at_point(point![0.5, 0.1, 0.0]),
+            Err(Error::PointOffWire { .. })
+        ));
+        assert!(matches!(
+            wire.split_at_point(point![0.0, 0.0, 0.0]),
+            Err(Error::ParameterOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn trim_closed_wire_between_points() {
+        // A 4 x 2 rectangle, 12 around.
+        let rectangle = Wire::from_ordered_points([
+            point![0.0, 0.0, 0.0],
+            point![4.0, 0.0, 0.0],
+            point![4.0, 2.0, 0.0],
+            point![0.0, 2.0, 0.0],
+        ])
+        .unwrap();
+        let (start, end) = (point![1.0, 0.0, 0.0], point![4.0, 1.0, 0.0]);
+
+        let forwards = rectangle
+            .trim_between(start, end, TrimDirection::Forwards)
+            .unwrap();
+        assert!((forwards.length() - 4.0).abs() < 1.0e-9);
+        assert!((forwards.start_point() - start).norm() < 1.0e-9);
+        assert!((forwards.end_point() - end).norm() < 1.0e-9);
+
+        let backwards = rectangle
+            .trim_between(start, end, TrimDirection::Backwards)
+            .unwrap();
+        assert!((backwards.length() - 8.0).abs() < 1.0e-9);
+        assert!((backwards.start_point() - start).norm() < 1.0e-9);
+        assert!((backwards.end_point() - end).norm() < 1.0e-9);
+
+        let shorter = rectangle
+            .trim_between(start, end, TrimDirection::Shorter)
+            .unwrap();
+        assert!((shorter.length() - 4.0).abs() < 1.0e-9);
+
+        // Both ways round make up the whole wire.
+        assert!((forwards.length() + backwards.length() - rectangle.length()).abs() < 1.0e-9);
+
+        assert!(matches!(
+            rectangle.trim_between(start, point![2.0, 1.0, 0.0], TrimDirection::Shorter),
+            Err(Error::PointOffWire { .. })
+        ));
+    }
+
+    #[test]
+    fn builder_rejects_disconnected_edges() {
+        let mut builder = WireBuilder::new();
+
+        builder
+            .push(Edge::segment(point![0.0, 0.0, 0.0], point![1.0, 0.0, 0.0]))
+            .unwrap();
+        builder
+            .push(Edge::segment(point![1.0, 0.0, 0.0], point![1.0, 1.0, 0.0]))
+            .unwrap();
+
+        let stray = Edge::segment(point![5.0, 5.0, 0.0], point![6.0, 5.0, 0.0]);
+        assert!(matches!(
+            builder.push(stray),
+            Err(Error::DisconnectedEdge { index: 2 })
+        ));
+
+        // The builder carries on from the last edge it accepted.
+        builder
+            .push(Edge::segment(point![1.0, 1.0, 0.0], point![0.0, 1.0, 0.0]))
+            .unwrap();
+        assert_eq!(builder.len(), 4);
+        assert_eq!(builder.build().unwrap().edges().count(), 3);
+    }
+
+    #[test]
+    fn builder_reports_offending_index() {
+        let points: Vec<_> = (0..=100)
+            .map(|i| point![i as f64, (i % 2) as f64, 0.0])
+            .collect();
+        let mut edges: Vec<_> = points
+            .windows(2)
+            .map(|pair| Edge::segment(pair[0], pair[1]))
+            .collect();
+        edges.insert(
+            40,
+            Edge::segment(point![0.0, 10.0, 0.0], point![1.0, 10.0, 0.0]),
+        );
+
+        let mut builder = WireBuilder::new();
+        let rejected: Vec<_> = edges
+            .into_iter()
+            .filter_map(|edge| builder.push(edge).err())
+            .collect();
+
+        assert_eq!(rejected.len(), 1);
+        assert!(matches!(rejected[0], Error::DisconnectedEdge { index: 40 }));
+        assert_eq!(builder.build().unwrap().edges().count(), 100);
+    }
+}
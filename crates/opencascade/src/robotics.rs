@@ -0,0 +1,371 @@
+//! Describing robots made of shapes, for simulators and motion planners.
+
+use crate::export::ExportPipeline;
+use crate::mesh::MeshOptions;
+use crate::primitives::Shape;
+use crate::Error;
+use crate::TandR;
+use nalgebra::Isometry3;
+use nalgebra::Matrix3;
+use nalgebra::Translation3;
+use nalgebra::Vector3;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::path::Path;
+
+/// The format of the mesh files `export_urdf` writes for each link.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum UrdfMeshFormat {
+    #[default]
+    Stl,
+    Gltf,
+}
+
+impl UrdfMeshFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            UrdfMeshFormat::Stl => "stl",
+            UrdfMeshFormat::Gltf => "gltf",
+        }
+    }
+}
+
+/// A rigid link of a robot, for `export_urdf`.
+///
+/// URDF is in meters and kilograms, so the shape should be modelled in
+/// meters, and the density be in kilograms per cubic meter.
+pub struct UrdfLink {
+    pub name: String,
+    /// The link's shape, in the coordinates of the whole robot.
+    pub shape: Shape,
+    pub density: f64,
+    /// Where the link's frame is, in the coordinates of the whole robot.
+    /// Each link after the first is fixed to the one before it where their
+    /// frames are.
+    pub origin: TandR<f64>,
+    /// How to mesh the shape for the visual and collision mesh files.
+    pub mesh_options: MeshOptions,
+    pub mesh_format: UrdfMeshFormat,
+}
+
+impl UrdfLink {
+    /// A link with its frame at the origin, made of a material of `density`,
+    /// meshed with the default options to STL files.
+    pub fn new(name: impl Into<String>, shape: Shape, density: f64) -> Self {
+        Self {
+            name: name.into(),
+            shape,
+            density,
+            origin: TandR::default(),
+            mesh_options: MeshOptions::default(),
+            mesh_format: UrdfMeshFormat::default(),
+        }
+    }
+}
+
+/// Write a URDF file describing a robot made of `links` to `path`, named
+/// after the file. Each link's mass, center of mass and inertia are
+/// computed from its shape and density, and its shape is written to a mesh
+/// file next to the URDF file, used for both its visual and its collision
+/// geometry. The links form a chain, each fixed to the one before it.
+///
+/// Mesh files are named after their links, with any character other than
+/// ASCII letters, digits, `-` and `_` replaced by `_`, so names can't
+/// reach outside the URDF file's directory. Links whose names end up the
+/// same get their index in `links` appended until they differ.
+pub fn export_urdf(links: &[UrdfLink], path: impl AsRef<Path>) -> Result<(), Error> {
+    let path = path.as_ref();
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let robot_name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "robot".to_string());
+
+    let mut urdf = String::new();
+    writeln!(urdf, r#"<?xml version="1.0"?>"#).unwrap();
+    writeln!(urdf, r#"<robot name="{}">"#, escape(&robot_name)).unwrap();
+
+    let mut mesh_files = HashSet::new();
+
+    for (index, link) in links.iter().enumerate() {
+        let frame = isometry(&link.origin);
+
+        let mut stem = file_stem(&link.name);
+        while mesh_files.contains(&stem) {
+            stem = format!("{stem}_{index}");
+        }
+        mesh_files.insert(stem.clone());
+        let mesh_file = format!("{stem}.{}", link.mesh_format.extension());
+
+        let pipeline = ExportPipeline::new(&link.shape, &link.mesh_options)?;
+        match link.mesh_format {
+            UrdfMeshFormat::Stl => pipeline.to_stl(File::create(directory.join(&mesh_file))?)?,
+            UrdfMeshFormat::Gltf => pipeline.to_gltf(directory.join(&mesh_file))?,
+        }
+
+        // The inertia is given about the center of mass, along the axes of
+        // the link's frame.
+        let properties = link.shape.inertial_properties(link.density);
+        let rotation: Matrix3<f64> = frame.rotation.to_rotation_matrix().into_inner();
+        let inertia = rotation.transpose() * properties.inertia * rotation;
+        let center = frame.inverse_transform_point(&properties.center_of_mass);
+
+        // The meshes are in the coordinates of the whole robot.
+        let mesh_origin = origin_element(&frame.inverse());
+        let geometry = format!(
+            r#"<geometry><mesh filename="{}"/></geometry>"#,
+            escape(&mesh_file)
+        );
+
+        writeln!(urdf, r#"  <link name="{}">"#, escape(&link.name)).unwrap();
+        writeln!(urdf, "    <inertial>").unwrap();
+        writeln!(
+            urdf,
+            "      {}",
+            origin_element(&Isometry3::translation(center.x, center.y, center.z))
+        )
+        .unwrap();
+        writeln!(urdf, r#"      <mass value="{}"/>"#, properties.mass).unwrap();
+        writeln!(
+            urdf,
+            r#"      <inertia ixx="{}" ixy="{}" ixz="{}" iyy="{}" iyz="{}" izz="{}"/>"#,
+            inertia[(0, 0)],
+            inertia[(0, 1)],
+            inertia[(0, 2)],
+            inertia[(1, 1)],
+            inertia[(1, 2)],
+            inertia[(2, 2)]
+        )
+        .unwrap();
+        writeln!(urdf, "    </inertial>").unwrap();
+        for element in ["visual", "collision"] {
+            writeln!(urdf, "    <{element}>{mesh_origin}{geometry}</{element}>").unwrap();
+        }
+        writeln!(urdf, "  </link>").unwrap();
+    }
+
+    for pair in links.windows(2) {
+        let (parent, child) = (&pair[0], &pair[1]);
+        let relative = isometry(&parent.origin).inverse() * isometry(&child.origin);
+
+        writeln!(
+            urdf,
+            r#"  <joint name="{}" type="fixed">"#,
+            escape(&format!("{}_to_{}", parent.name, child.name))
+        )
+        .unwrap();
+        writeln!(urdf, r#"    <parent link="{}"/>"#, escape(&parent.name)).unwrap();
+        writeln!(urdf, r#"    <child link="{}"/>"#, escape(&child.name)).unwrap();
+        writeln!(urdf, "    {}", origin_element(&relative)).unwrap();
+        writeln!(urdf, "  </joint>").unwrap();
+    }
+
+    writeln!(urdf, "</robot>").unwrap();
+
+    std::fs::write(path, urdf)?;
+
+    Ok(())
+}
+
+/// `transform` as an isometry, whichever way round it applies its
+/// translation and rotation.
+fn isometry(transform: &TandR<f64>) -> Isometry3<f64> {
+    let rotation = transform.rotation_quat;
+    let translation = if transform.inverse {
+        rotation * transform.translation
+    } else {
+        transform.translation
+    };
+
+    Isometry3::from_parts(Translation3::from(translation), rotation)
+}
+
+/// A URDF `<origin>` element. URDF's roll, pitch and yaw are rotations about
+/// the fixed X, Y and Z axes, in that order, as nalgebra's Euler angles are.
+fn origin_element(transform: &Isometry3<f64>) -> String {
+    let xyz: Vector3<f64> = transform.translation.vector;
+    let (roll, pitch, yaw) = transform.rotation.euler_angles();
+
+    format!(
+        r#"<origin xyz="{} {} {}" rpy="{roll} {pitch} {yaw}"/>"#,
+        xyz.x, xyz.y, xyz.z
+    )
+}
+
+/// `name` with every character which could be special in a path replaced.
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if stem.is_empty() {
+        "link".to_string()
+    } else {
+        stem
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nalgebra::point;
+    use nalgebra::UnitQuaternion;
+    use std::path::PathBuf;
+
+    /// An empty directory for `test` to write to, only used by this run.
+    fn output_directory(test: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("opencascade-urdf-{test}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn read_urdf(path: &Path) -> urdf_rs::Robot {
+        urdf_rs::read_file(path).unwrap()
+    }
+
+    fn mesh_filename(geometry: &urdf_rs::Geometry) -> &str {
+        match geometry {
+            urdf_rs::Geometry::Mesh { filename, .. } => filename,
+            other => panic!("expected a mesh, not {other:?}"),
+        }
+    }
+
+    #[test]
+    fn single_link() {
+        let directory = output_directory("single");
+        let path = directory.join("block.urdf");
+
+        // 0.1 x 0.2 x 0.3 m of water, 6 kg.
+        let block = Shape::box_from_corners(point![0.0, 0.0, 0.0], point![0.1, 0.2, 0.3]);
+        export_urdf(&[UrdfLink::new("block", block, 1000.0)], &path).unwrap();
+
+        let robot = read_urdf(&path);
+        assert_eq!(robot.name, "block");
+        assert_eq!(robot.links.len(), 1);
+        assert!(robot.joints.is_empty());
+
+        let link = &robot.links[0];
+        assert_eq!(link.name, "block");
+
+        let inertial = &link.inertial;
+        assert!((inertial.mass.value - 6.0).abs() < 1.0e-9);
+
+        let center = &inertial.origin.xyz;
+        for (value, expected) in center.iter().zip([0.05, 0.1, 0.15]) {
+            assert!((value - expected).abs() < 1.0e-9, "{center:?}");
+        }
+
+        let inertia = &inertial.inertia;
+        assert!((inertia.ixx - 6.0 * (0.04 + 0.09) / 12.0).abs() < 1.0e-9);
+        assert!((inertia.iyy - 6.0 * (0.01 + 0.09) / 12.0).abs() < 1.0e-9);
+        assert!((inertia.izz - 6.0 * (0.01 + 0.04) / 12.0).abs() < 1.0e-9);
+        assert!(inertia.ixy.abs() < 1.0e-9);
+
+        assert_eq!(link.visual.len(), 1);
+        assert_eq!(link.collision.len(), 1);
+        for geometry in [&link.visual[0].geometry, &link.collision[0].geometry] {
+            let filename = mesh_filename(geometry);
+            assert_eq!(filename, "block.stl");
+            assert!(directory.join(filename).exists());
+        }
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn fixed_joint_chain() {
+        let directory = output_directory("chain");
+        let path = directory.join("arm.urdf");
+
+        let base = Shape::box_from_corners(point![-0.1, -0.1, 0.0], point![0.1, 0.1, 0.05]);
+        let upper = Shape::box_from_corners(point![-0.02, -0.02, 0.05], point![0.02, 0.02, 0.5]);
+
+        // The upper arm's frame is on top of the base, turned a quarter turn
+        // about z.
+        let mut upper = UrdfLink::new("upper_arm", upper, 2700.0);
+        upper.origin = TandR::new(
+            Vector3::new(0.0, 0.0, 0.05),
+            UnitQuaternion::from_axis_angle(&Vector3::z_axis(), std::f64::consts::FRAC_PI_2),
+        );
+        upper.mesh_format = UrdfMeshFormat::Gltf;
+
+        export_urdf(&[UrdfLink::new("base", base, 7800.0), upper], &path).unwrap();
+
+        let robot = read_urdf(&path);
+        let links: Vec<_> = robot.links.iter().map(|link| link.name.as_str()).collect();
+        assert_eq!(links, ["base", "upper_arm"]);
+        assert!(directory.join("upper_arm.gltf").exists());
+
+        assert_eq!(robot.joints.len(), 1);
+        let joint = &robot.joints[0];
+        assert!(matches!(joint.joint_type, urdf_rs::JointType::Fixed));
+        assert_eq!(joint.parent.link, "base");
+        assert_eq!(joint.child.link, "upper_arm");
+
+        let (xyz, rpy) = (&joint.origin.xyz, &joint.origin.rpy);
+        assert!((xyz[2] - 0.05).abs() < 1.0e-12, "{xyz:?}");
+        assert!(
+            (rpy[2] - std::f64::consts::FRAC_PI_2).abs() < 1.0e-12,
+            "{rpy:?}"
+        );
+
+        // In its own frame, the upper arm's center of mass is straight up.
+        let center = &robot.links[1].inertial.origin.xyz;
+        assert!(
+            center[0].abs() < 1.0e-9 && center[1].abs() < 1.0e-9,
+            "{center:?}"
+        );
+        assert!((center[2] - 0.225).abs() < 1.0e-9, "{center:?}");
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn link_names_stay_in_the_directory() {
+        let directory = output_directory("names");
+        let path = directory.join("robot.urdf");
+
+        let cube = || Shape::box_from_corners(point![0.0, 0.0, 0.0], point![0.1, 0.1, 0.1]);
+        let links = [
+            UrdfLink::new("../escaped", cube(), 1000.0),
+            UrdfLink::new("a/b", cube(), 1000.0),
+            UrdfLink::new("a_b", cube(), 1000.0),
+        ];
+        export_urdf(&links, &path).unwrap();
+
+        let robot = read_urdf(&path);
+        let files: Vec<_> = robot
+            .links
+            .iter()
+            .map(|link| mesh_filename(&link.visual[0].geometry).to_string())
+            .collect();
+        assert_eq!(files, ["___escaped.stl", "a_b.stl", "a_b_2.stl"]);
+
+        // The link names themselves are kept.
+        assert_eq!(robot.links[0].name, "../escaped");
+
+        for file in &files {
+            assert!(directory.join(file).exists(), "{file}");
+        }
+        assert!(!directory.parent().unwrap().join("escaped.stl").exists());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
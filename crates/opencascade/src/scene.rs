@@ -0,0 +1,293 @@
+//! Scenes of parts placed by transforms, with each part cached as a BREP
+//! file, so an assembly can be put back together without rebuilding its
+//! parts when only their placements change.
+
+use crate::convert::IntoUnitQuaternion;
+use crate::primitives::Compound;
+use crate::primitives::Shape;
+use crate::Error;
+use crate::TandR;
+use nalgebra::Quaternion;
+use nalgebra::Vector3;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// A part in a scene: the BREP file it's cached in, and where it goes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneEntry {
+    pub name: String,
+    pub brep_path: PathBuf,
+    pub placement: TandR<f64>,
+    /// The RGBA color to show the part in, for exporters which take one.
+    pub color: Option<[f32; 4]>,
+}
+
+/// The JSON representation of a scene.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SceneFile {
+    version: u32,
+    entries: Vec<SceneFileEntry>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SceneFileEntry {
+    name: String,
+    brep_path: PathBuf,
+    translation: [f64; 3],
+    /// The rotation as a quaternion, `[x, y, z, w]`.
+    rotation: [f64; 4],
+    #[serde(default)]
+    inverse: bool,
+    color: Option<[f32; 4]>,
+}
+
+const FORMAT_VERSION: u32 = 1;
+
+/// Write `entries` to `path` as JSON. BREP paths are written as they are,
+/// so relative paths are relative to wherever the scene is realized from.
+pub fn save(path: impl AsRef<Path>, entries: &[SceneEntry]) -> Result<(), Error> {
+    let entries = entries
+        .iter()
+        .map(|entry| {
+            let rotation = entry.placement.rotation_quat.coords;
+
+            SceneFileEntry {
+                name: entry.name.clone(),
+                brep_path: entry.brep_path.clone(),
+                translation: entry.placement.translation.into(),
+                rotation: rotation.into(),
+                inverse: entry.placement.inverse,
+                color: entry.color,
+            }
+        })
+        .collect();
+
+    let writer = BufWriter::new(File::create(path)?);
+    let scene = SceneFile {
+        version: FORMAT_VERSION,
+        entries,
+    };
+    serde_json::to_writer_pretty(writer, &scene).map_err(std::io::Error::from)?;
+
+    Ok(())
+}
+
+/// Read the entries of a scene written by `save`. An entry whose rotation
+/// is zero, or isn't finite, is an `Error::InvalidRotation`.
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<SceneEntry>, Error> {
+    let reader = BufReader::new(File::open(path)?);
+    let scene: SceneFile = serde_json::from_reader(reader).map_err(std::io::Error::from)?;
+
+    if scene.version != FORMAT_VERSION {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "unsupported scene format version",
+        )
+        .into());
+    }
+
+    scene
+        .entries
+        .into_iter()
+        .map(|entry| {
+            let [x, y, z, w] = entry.rotation;
+
+            Ok(SceneEntry {
+                name: entry.name,
+                brep_path: entry.brep_path,
+                placement: TandR {
+                    translation: Vector3::from(entry.translation),
+                    rotation_quat: Quaternion::new(w, x, y, z).into_unit_quaternion()?,
+                    inverse: entry.inverse,
+                },
+                color: entry.color,
+            })
+        })
+        .collect()
+}
+
+/// Read the parts of a scene and put them in place, as a compound ready to
+/// export. Each BREP file is read once, and the entries placing it share its
+/// shape. Fails with `Error::SceneBrepFailed`, naming every entry whose BREP
+/// file can't be read, along with its path.
+pub fn realize(entries: &[SceneEntry]) -> Result<Compound, Error> {
+    let mut parts: HashMap<&Path, Option<Shape>> = HashMap::new();
+    let mut instances = Vec::with_capacity(entries.len());
+    let mut failures = Vec::new();
+
+    for entry in entries {
+        let path = entry.brep_path.as_path();
+        let part = parts
+            .entry(path)
+            .or_insert_with(|| Shape::read_brep(path).ok());
+
+        match part {
+            Some(part) => {
+                let mut instance = part.shallow_copy();
+                instance.transform(&entry.placement);
+                instances.push(instance);
+            }
+            None => failures.push((entry.name.clone(), entry.brep_path.clone())),
+        }
+    }
+
+    if !failures.is_empty() {
+        return Err(Error::SceneBrepFailed { failures });
+    }
+
+    Ok(Compound::from_shapes(&instances))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::ShapeType;
+    use nalgebra::point;
+    use nalgebra::vector;
+    use nalgebra::UnitQuaternion;
+
+    fn output_directory(test: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("opencascade-scene-{test}-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn round_trip_shares_cached_part() {
+        let directory = output_directory("round-trip");
+        let brep_path = directory.join("block.brep");
+        Shape::box_with_dimensions(10.0, 20.0, 30.0)
+            .write_brep(&brep_path)
+            .unwrap();
+
+        let quarter_turn =
+            UnitQuaternion::from_axis_angle(&Vector3::z_axis(), 90.0f64.to_radians());
+        let entries = vec![
+            SceneEntry {
+                name: "left".to_string(),
+                brep_path: brep_path.clone(),
+                placement: TandR::new(vector![-100.0, 0.0, 0.0], UnitQuaternion::identity()),
+                color: Some([1.0, 0.0, 0.0, 1.0]),
+            },
+            SceneEntry {
+                name: "right".to_string(),
+                brep_path: brep_path.clone(),
+                placement: TandR::new(vector![100.0, 0.0, 5.0], quarter_turn),
+                color: None,
+            },
+        ];
+
+        let scene_path = directory.join("scene.json");
+        save(&scene_path, &entries).unwrap();
+        let loaded = load(&scene_path).unwrap();
+        assert_eq!(loaded.len(), entries.len());
+        for (loaded, entry) in loaded.iter().zip(&entries) {
+            assert_eq!(loaded.name, entry.name);
+            assert_eq!(loaded.brep_path, entry.brep_path);
+            assert_eq!(loaded.color, entry.color);
+            assert_eq!(loaded.placement.translation, entry.placement.translation);
+            assert!(
+                loaded
+                    .placement
+                    .rotation_quat
+                    .angle_to(&entry.placement.rotation_quat)
+                    < 1.0e-12
+            );
+        }
+
+        let compound = Shape::from(realize(&loaded).unwrap());
+        let solids: Vec<Shape> = compound.solids().map(Shape::from).collect();
+        assert_eq!(solids.len(), 2);
+        assert_eq!(solids[0].shape_type(), ShapeType::Solid);
+
+        // Both instances are the one part read from the cache.
+        assert!(solids[0].inner.IsPartner(&solids[1].inner));
+
+        let left = solids[0].bounding_box();
+        assert!((left.min() - point![-100.0, 0.0, 0.0]).norm() < 1.0e-6);
+        assert!((left.max() - point![-90.0, 20.0, 30.0]).norm() < 1.0e-6);
+
+        // Turned a quarter about Z, the block's 10 x 20 footprint becomes
+        // 20 x 10, reaching back along X.
+        let right = solids[1].bounding_box();
+        assert!((right.min() - point![80.0, 0.0, 5.0]).norm() < 1.0e-6);
+        assert!((right.max() - point![100.0, 10.0, 35.0]).norm() < 1.0e-6);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn zero_rotations_are_rejected() {
+        let directory = output_directory("zero-rotation");
+        let scene_path = directory.join("scene.json");
+        std::fs::write(
+            &scene_path,
+            r#"{
+                "version": 1,
+                "entries": [{
+                    "name": "block",
+                    "brep_path": "block.brep",
+                    "translation": [0.0, 0.0, 0.0],
+                    "rotation": [0.0, 0.0, 0.0, 0.0],
+                    "color": null
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(load(&scene_path), Err(Error::InvalidRotation)));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn missing_breps_name_every_entry() {
+        let directory = output_directory("missing");
+        let brep_path = directory.join("block.brep");
+        Shape::box_with_dimensions(1.0, 1.0, 1.0)
+            .write_brep(&brep_path)
+            .unwrap();
+
+        let garbage_path = directory.join("garbage.brep");
+        std::fs::write(
+            &garbage_path,
+            "DBRep_DrawableShape\nCASCADE Topology V1\nLocations 7\n",
+        )
+        .unwrap();
+
+        let entry = |name: &str, brep_path: PathBuf| SceneEntry {
+            name: name.to_string(),
+            brep_path,
+            placement: TandR::default(),
+            color: None,
+        };
+        let missing_path = directory.join("does-not-exist.brep");
+        let entries = vec![
+            entry("ghost", missing_path.clone()),
+            entry("present", brep_path),
+            entry("garbage", garbage_path.clone()),
+            entry("ghost again", missing_path.clone()),
+        ];
+
+        match realize(&entries) {
+            Err(Error::SceneBrepFailed { failures }) => {
+                assert_eq!(
+                    failures,
+                    vec![
+                        ("ghost".to_string(), missing_path.clone()),
+                        ("garbage".to_string(), garbage_path),
+                        ("ghost again".to_string(), missing_path),
+                    ]
+                );
+            }
+            other => panic!("expected a missing BREP error, got {:?}", other.err()),
+        }
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
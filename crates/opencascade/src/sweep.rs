@@ -0,0 +1,661 @@
+use crate::angle::Angle;
+use crate::law_function::Law;
+use crate::primitives::make_dir;
+use crate::primitives::Edge;
+use crate::primitives::History;
+use crate::primitives::Shape;
+use crate::primitives::Shell;
+use crate::primitives::Solid;
+use crate::primitives::Wire;
+use crate::workplane::Workplane;
+use crate::Error;
+use cxx::UniquePtr;
+use nalgebra::point;
+use nalgebra::vector;
+use nalgebra::Point3;
+use nalgebra::UnitQuaternion;
+use nalgebra::UnitVector3;
+use nalgebra::Vector3;
+use opencascade_sys::ffi;
+
+/// The number of stations along the spine used to build the auxiliary
+/// spine which encodes a twist law.
+const TWIST_LAW_SAMPLES: usize = 128;
+
+/// Controls how the trihedron (the local frame the profile is carried in)
+/// evolves along the spine of a sweep.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FrameMode {
+    /// The classic Frenet trihedron. Follows the curvature of the spine,
+    /// which can flip the profile at inflection points.
+    Frenet,
+
+    /// A Frenet trihedron corrected to minimize rotation about the
+    /// tangent. This is the default, and what you want most of the time.
+    CorrectedFrenet,
+
+    /// The binormal of the trihedron is held constant.
+    Binormal(UnitVector3<f64>),
+}
+
+impl Default for FrameMode {
+    fn default() -> Self {
+        Self::CorrectedFrenet
+    }
+}
+
+/// How the ends of a swept solid are closed off.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CapStyle {
+    /// Close each end with a planar face bounded by the profile.
+    Planar,
+
+    /// Don't add caps. Only valid when the swept shell closes on itself.
+    None,
+}
+
+/// A point on a spine along with its local frame.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct SpineFrame {
+    pub(crate) point: Point3<f64>,
+    pub(crate) tangent: UnitVector3<f64>,
+    pub(crate) normal: UnitVector3<f64>,
+}
+
+/// A wrapper around `BRepOffsetAPI_MakePipeShell`, for sweeping a profile
+/// along a spine with optional scale and twist laws.
+pub struct PipeShellBuilder {
+    profile: Wire,
+    spine: Wire,
+    frame_mode: FrameMode,
+    scale_law: Option<Law>,
+    twist_law: Option<Law>,
+}
+
+impl PipeShellBuilder {
+    pub fn new(profile: &Wire, spine: &Wire) -> Self {
+        Self {
+            profile: Wire::from_wire(&profile.inner),
+            spine: Wire::from_wire(&spine.inner),
+            frame_mode: FrameMode::default(),
+            scale_law: None,
+            twist_law: None,
+        }
+    }
+
+    pub fn frame_mode(mut self, frame_mode: FrameMode) -> Self {
+        self.frame_mode = frame_mode;
+        self
+    }
+
+    /// Scale the profile along the spine. A value of 1.0 keeps the profile
+    /// at its original size.
+    pub fn with_scale_law(mut self, law: &Law) -> Self {
+        self.scale_law = Some(law.clone());
+        self
+    }
+
+    /// Rotate the profile about the spine tangent by `law(t)` radians, where
+    /// `t` is the normalized spine parameter. The twist is applied on top of
+    /// the trihedron selected with `frame_mode`.
+    pub fn with_twist_law(mut self, law: &Law) -> Self {
+        self.twist_law = Some(law.clone());
+        self
+    }
+
+    /// Shortcut for a linear twist law going from 0 at the start of the
+    /// spine to `total` at its end.
+    pub fn twist(self, total: Angle) -> Self {
+        self.with_twist_law(&Law::linear(0.0, total.radians()))
+    }
+
+    /// Build the sweep. Closed profiles produce a capped solid, open
+    /// profiles produce a shell.
+    pub fn build(self) -> Result<Shape, Error> {
+        if self.profile.is_closed() {
+            self.build_solid(CapStyle::Planar).map(Shape::from)
+        } else {
+            self.build_shell().map(Shape::from)
+        }
+    }
+
+    /// Like `build`, but also returns the `History` of the sweep, relating
+    /// the edges and vertices of the profile to the faces and edges they
+    /// generated.
+    pub fn build_with_history(self) -> Result<(Shape, History), Error> {
+        let mut make_pipe_shell = self.build_pipe_shell()?;
+
+        if self.profile.is_closed() && !make_pipe_shell.pin_mut().MakeSolid() {
+            return Err(Error::NotDone);
+        }
+
+        let shape = Shape::from_shape(make_pipe_shell.pin_mut().Shape());
+        let history = ffi::BRepTools_History_from_pipe_shell(
+            &History::arguments([ffi::cast_wire_to_shape(&self.profile.inner)]),
+            make_pipe_shell.pin_mut(),
+        );
+
+        let profile = ffi::cast_wire_to_shape(&self.profile.inner);
+
+        Ok((shape, History::from_history(history, profile)))
+    }
+
+    /// Build the swept surface as an open shell, without end caps.
+    pub fn build_shell(self) -> Result<Shell, Error> {
+        let mut make_pipe_shell = self.build_pipe_shell()?;
+        let pipe_shape = make_pipe_shell.pin_mut().Shape();
+
+        Ok(Shell::from_shell(ffi::TopoDS_cast_to_shell(pipe_shape)))
+    }
+
+    /// Build the sweep as a solid. The profile must be closed.
+    ///
+    /// With `CapStyle::None` the swept shell has to close on itself (for
+    /// example when the spine is closed), otherwise `Error::NotClosed` is
+    /// returned.
+    pub fn build_solid(self, cap_style: CapStyle) -> Result<Solid, Error> {
+        if !self.profile.is_closed() {
+            return Err(Error::OpenProfile);
+        }
+
+        let mut make_pipe_shell = self.build_pipe_shell()?;
+
+        match cap_style {
+            CapStyle::Planar => {
+                if !make_pipe_shell.pin_mut().MakeSolid() {
+                    return Err(Error::NotDone);
+                }
+
+                let pipe_shape = make_pipe_shell.pin_mut().Shape();
+
+                Ok(Solid::from_solid(ffi::TopoDS_cast_to_solid(pipe_shape)))
+            }
+            CapStyle::None => {
+                let pipe_shape = make_pipe_shell.pin_mut().Shape();
+
+                if !ffi::BRep_Tool_IsClosed(pipe_shape) {
+                    return Err(Error::NotClosed);
+                }
+
+                let shell = ffi::TopoDS_cast_to_shell(pipe_shape);
+                let mut make_solid = ffi::BRepBuilderAPI_MakeSolid_ctor(shell);
+
+                make_solid
+                    .pin_mut()
+                    .Build(&ffi::Message_ProgressRange_ctor());
+
+                if !make_solid.IsDone() {
+                    return Err(Error::NotDone);
+                }
+
+                Ok(Solid::from_solid(ffi::TopoDS_cast_to_solid(
+                    make_solid.pin_mut().Shape(),
+                )))
+            }
+        }
+    }
+
+    /// Compute `sections` evenly spaced copies of the profile as they would
+    /// be placed along the spine, without building the swept surface. Handy
+    /// for previewing a sweep cheaply. Fails if the pipe shell couldn't be
+    /// set up.
+    pub fn simulate(&self, sections: u32) -> Result<Vec<Wire>, Error> {
+        let mut make_pipe_shell = self.make_pipe_shell()?;
+
+        let mut section_list = ffi::new_list_of_shape();
+        make_pipe_shell
+            .pin_mut()
+            .Simulate(sections as i32, section_list.pin_mut());
+
+        Ok(ffi::shape_list_to_vector(&section_list)
+            .iter()
+            .map(|shape| Wire::from_wire(ffi::TopoDS_cast_to_wire(shape)))
+            .collect())
+    }
+
+    fn build_pipe_shell(&self) -> Result<UniquePtr<ffi::BRepOffsetAPI_MakePipeShell>, Error> {
+        let mut make_pipe_shell = self.make_pipe_shell()?;
+
+        make_pipe_shell
+            .pin_mut()
+            .Build(&ffi::Message_ProgressRange_ctor());
+
+        if !make_pipe_shell.IsDone() {
+            return Err(Error::NotDone);
+        }
+
+        Ok(make_pipe_shell)
+    }
+
+    fn make_pipe_shell(&self) -> Result<UniquePtr<ffi::BRepOffsetAPI_MakePipeShell>, Error> {
+        let mut make_pipe_shell = ffi::BRepOffsetAPI_MakePipeShell_ctor(&self.spine.inner);
+
+        match (&self.twist_law, self.frame_mode) {
+            (Some(twist_law), frame_mode) => {
+                // OCCT has no notion of a twist law, but an auxiliary spine
+                // fully determines the normal of the trihedron at every
+                // station. So we build one which winds around the spine.
+                let auxiliary_spine = twisted_auxiliary_spine(&self.spine, frame_mode, twist_law)?;
+                let curvilinear_equivalence = false;
+
+                make_pipe_shell.pin_mut().set_mode_auxiliary_spine(
+                    &auxiliary_spine.inner,
+                    curvilinear_equivalence,
+                    ffi::BRepFill_TypeOfContact::BRepFill_NoContact,
+                );
+            }
+            (None, FrameMode::Frenet) => make_pipe_shell.pin_mut().SetMode(true),
+            (None, FrameMode::CorrectedFrenet) => make_pipe_shell.pin_mut().SetMode(false),
+            (None, FrameMode::Binormal(binormal)) => make_pipe_shell
+                .pin_mut()
+                .set_mode_binormal(&make_dir(binormal.into_inner())),
+        }
+
+        let profile_shape = ffi::cast_wire_to_shape(&self.profile.inner);
+        let with_contact = false;
+        let with_correction = true;
+
+        match &self.scale_law {
+            Some(scale_law) => {
+                let law_handle = ffi::Law_Function_to_handle(scale_law.to_law_function());
+
+                make_pipe_shell.pin_mut().SetLaw(
+                    profile_shape,
+                    &law_handle,
+                    with_contact,
+                    with_correction,
+                );
+            }
+            None => {
+                make_pipe_shell
+                    .pin_mut()
+                    .Add(profile_shape, with_contact, with_correction);
+            }
+        }
+
+        Ok(make_pipe_shell)
+    }
+}
+
+/// Sweep a profile which changes along `spine` in a way no single law
+/// describes. `f` is called at `n_sections` stations, evenly spaced by arc
+/// length from the start of the spine to its end, with `t` going from 0 to
+/// 1 and a workplane centered on the spine at the station. The workplane's
+/// normal is the spine tangent and its x direction is the corrected Frenet
+/// normal, as `FrameMode::CorrectedFrenet` sweeps use. The profiles `f`
+/// returns are lofted through into a solid.
+///
+/// If `f` fails, the sweep is abandoned with `Error::ProfileFailed`, giving
+/// the station it failed at.
+pub fn from_profile_fn(
+    spine: &Wire,
+    n_sections: u32,
+    f: impl Fn(f64, &Workplane) -> Result<Wire, Error>,
+) -> Result<Shape, Error> {
+    if n_sections < 2 {
+        return Err(Error::NotEnoughPoints);
+    }
+
+    let intervals = (n_sections - 1) as usize;
+    let map = spine.arc_length_param();
+
+    let profiles = (0..=intervals)
+        .map(|station| {
+            let length = map.total_length() * station as f64 / intervals as f64;
+            let frame = map.frame_at_length(length)?;
+
+            let mut workplane = Workplane::new(
+                frame.rotation_quat * Vector3::x_axis(),
+                frame.rotation_quat * Vector3::z_axis(),
+            );
+            workplane.set_translation(frame.translation);
+
+            f(station as f64 / intervals as f64, &workplane).map_err(|source| {
+                Error::ProfileFailed {
+                    station,
+                    source: Box::new(source),
+                }
+            })
+        })
+        .collect::<Result<Vec<Wire>, Error>>()?;
+
+    let is_solid = true;
+    let mut make_loft = ffi::BRepOffsetAPI_ThruSections_ctor(is_solid);
+
+    for profile in &profiles {
+        make_loft.pin_mut().AddWire(&profile.inner);
+    }
+
+    // Match up the vertices of the profiles, so the loft doesn't twist.
+    make_loft.pin_mut().CheckCompatibility(true);
+    make_loft
+        .pin_mut()
+        .Build(&ffi::Message_ProgressRange_ctor());
+
+    if !make_loft.IsDone() {
+        return Err(Error::NotDone);
+    }
+
+    Ok(Shape::from_shape(make_loft.pin_mut().Shape()))
+}
+
+/// Sample `samples + 1` evenly spaced (by parameter) frames along `spine`,
+/// with normals following `frame_mode`.
+pub(crate) fn spine_frames(spine: &Wire, frame_mode: FrameMode, samples: usize) -> Vec<SpineFrame> {
+    let curve = ffi::BRepAdaptor_CompCurve_ctor(&spine.inner);
+    let (first, last) = (curve.FirstParameter(), curve.LastParameter());
+
+    let parameters = (0..=samples).map(|i| first + (last - first) * (i as f64 / samples as f64));
+
+    frames_at(&curve, frame_mode, parameters)
+}
+
+/// The frames of `curve` at `parameters`, in order, with normals following
+/// `frame_mode`. Normals which are carried from one frame to the next are
+/// only as good as the parameters are close together.
+fn frames_at(
+    curve: &ffi::BRepAdaptor_CompCurve,
+    frame_mode: FrameMode,
+    parameters: impl Iterator<Item = f64>,
+) -> Vec<SpineFrame> {
+    let mut stations = Vec::with_capacity(parameters.size_hint().0);
+
+    for u in parameters {
+        let mut p = ffi::new_point(0.0, 0.0, 0.0);
+        let mut d1 = ffi::new_vec(0.0, 0.0, 0.0);
+        let mut d2 = ffi::new_vec(0.0, 0.0, 0.0);
+        curve.D2(u, p.pin_mut(), d1.pin_mut(), d2.pin_mut());
+
+        stations.push((
+            point![p.X(), p.Y(), p.Z()],
+            UnitVector3::new_normalize(vector![d1.X(), d1.Y(), d1.Z()]),
+            vector![d2.X(), d2.Y(), d2.Z()],
+        ));
+    }
+
+    let frenet_normal = |tangent: &UnitVector3<f64>, d2: &Vector3<f64>| {
+        let normal = d2 - tangent.into_inner() * d2.dot(tangent);
+        UnitVector3::try_new(normal, 1.0e-9)
+    };
+
+    let mut frames: Vec<SpineFrame> = Vec::with_capacity(stations.len());
+
+    for (point, tangent, d2) in &stations {
+        let normal = match frame_mode {
+            FrameMode::Binormal(binormal) => UnitVector3::try_new(binormal.cross(tangent), 1.0e-9)
+                .unwrap_or_else(|| any_perpendicular(tangent)),
+            FrameMode::Frenet => {
+                frenet_normal(tangent, d2).unwrap_or_else(|| match frames.last() {
+                    Some(previous) => rotation_minimizing_normal(previous, point, tangent),
+                    None => any_perpendicular(tangent),
+                })
+            }
+            FrameMode::CorrectedFrenet => match frames.last() {
+                Some(previous) => rotation_minimizing_normal(previous, point, tangent),
+                None => frenet_normal(tangent, d2).unwrap_or_else(|| any_perpendicular(tangent)),
+            },
+        };
+
+        frames.push(SpineFrame {
+            point: *point,
+            tangent: *tangent,
+            normal,
+        });
+    }
+
+    frames
+}
+
+/// Propagate the normal of `previous` to the next station using the double
+/// reflection method, which yields a rotation minimizing frame.
+pub(crate) fn rotation_minimizing_normal(
+    previous: &SpineFrame,
+    point: &Point3<f64>,
+    tangent: &UnitVector3<f64>,
+) -> UnitVector3<f64> {
+    let reflect =
+        |v: Vector3<f64>, axis: &Vector3<f64>, c: f64| v - axis * (2.0 / c) * axis.dot(&v);
+
+    let v1 = point - previous.point;
+    let c1 = v1.dot(&v1);
+
+    let (normal_l, tangent_l) = if c1 > f64::EPSILON {
+        (
+            reflect(previous.normal.into_inner(), &v1, c1),
+            reflect(previous.tangent.into_inner(), &v1, c1),
+        )
+    } else {
+        (previous.normal.into_inner(), previous.tangent.into_inner())
+    };
+
+    let v2 = tangent.into_inner() - tangent_l;
+    let c2 = v2.dot(&v2);
+
+    let normal = if c2 > f64::EPSILON {
+        reflect(normal_l, &v2, c2)
+    } else {
+        normal_l
+    };
+
+    // Re-orthogonalize against accumulated floating point error.
+    UnitVector3::try_new(normal - tangent.into_inner() * normal.dot(tangent), 1.0e-9)
+        .unwrap_or_else(|| any_perpendicular(tangent))
+}
+
+pub(crate) fn any_perpendicular(v: &UnitVector3<f64>) -> UnitVector3<f64> {
+    let least_aligned = if v.x.abs() < 0.9 {
+        Vector3::x()
+    } else {
+        Vector3::y()
+    };
+
+    UnitVector3::new_normalize(v.cross(&least_aligned))
+}
+
+fn twisted_auxiliary_spine(
+    spine: &Wire,
+    frame_mode: FrameMode,
+    twist_law: &Law,
+) -> Result<Wire, Error> {
+    let spine_length: f64 = spine.edges().map(|edge| edge.length()).sum();
+    let offset = (spine_length * 0.25).max(1.0e-3);
+
+    let mut twist = twist_law.evaluator();
+    let frames = spine_frames(spine, frame_mode, TWIST_LAW_SAMPLES);
+
+    let points = frames.iter().enumerate().map(|(i, frame)| {
+        let t = i as f64 / TWIST_LAW_SAMPLES as f64;
+        let rotation = UnitQuaternion::from_axis_angle(&frame.tangent, twist.value_at(t));
+
+        frame.point + (rotation * frame.normal).into_inner() * offset
+    });
+
+    let auxiliary_edge = Edge::spline_from_points(points, None);
+
+    Wire::from_edges([&auxiliary_edge])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::angle::ToAngle;
+    use crate::primitives::Direction;
+    use crate::workplane::Workplane;
+
+    fn face_corners(shape: &Shape, direction: Direction) -> Vec<Point3<f64>> {
+        let face = shape.faces().farthest(direction);
+        let face_shape: Shape = (&face).into();
+
+        let mut corners: Vec<Point3<f64>> = vec![];
+        for vertex in face_shape.vertices() {
+            let p = vertex.coords();
+            if !corners.iter().any(|c| (c - p).norm() < 1.0e-6) {
+                corners.push(p);
+            }
+        }
+
+        corners
+    }
+
+    #[test]
+    fn linear_twist_rotates_top_face() {
+        const HEIGHT: f64 = 50.0;
+
+        // A square looks the same after a 90 degree turn, so use a 2:1
+        // rectangle to make the rotation observable.
+        let profile = Workplane::xy().rect(20.0, 10.0).unwrap();
+        let spine =
+            Wire::from_edges([&Edge::segment(Point3::origin(), point![0.0, 0.0, HEIGHT])]).unwrap();
+
+        let shape = PipeShellBuilder::new(&profile, &spine)
+            .twist(90.degrees())
+            .build()
+            .unwrap();
+
+        let bottom = face_corners(&shape, Direction::NegZ);
+        let top = face_corners(&shape, Direction::PosZ);
+
+        assert_eq!(bottom.len(), 4);
+        assert_eq!(top.len(), 4);
+
+        let quarter_turn =
+            UnitQuaternion::from_axis_angle(&Vector3::z_axis(), 90.0f64.to_radians());
+
+        for corner in &bottom {
+            let expected = quarter_turn * corner + vector![0.0, 0.0, HEIGHT];
+
+            assert!(
+                top.iter().any(|p| (p - expected).norm() < 1.0e-4),
+                "Expected a top corner near {expected:?}, found {top:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn closed_profile_solid_is_watertight() {
+        let profile = Workplane::xy().rect(10.0, 10.0).unwrap();
+        let spine = Wire::from_edges([&Edge::spline_from_points(
+            [
+                point![0.0, 0.0, 0.0],
+                point![5.0, 0.0, 20.0],
+                point![0.0, 5.0, 40.0],
+            ],
+            None,
+        )])
+        .unwrap();
+
+        let solid = PipeShellBuilder::new(&profile, &spine)
+            .build_solid(CapStyle::Planar)
+            .unwrap();
+
+        assert!(Shape::from(solid).is_closed_solid());
+    }
+
+    /// A square of side `2.0 * half_side` on `workplane` with its sides
+    /// bulging out into a circle through its corners as `t` goes from 0 to 1.
+    fn square_to_circle(workplane: &Workplane, half_side: f64, t: f64) -> Result<Wire, Error> {
+        let a = half_side;
+        let bulge = a + t * (a * 2.0f64.sqrt() - a);
+        let corners = [(a, -a), (a, a), (-a, a), (-a, -a)];
+        let midpoints = [(0.0, -bulge), (bulge, 0.0), (0.0, bulge), (-bulge, 0.0)];
+
+        let mut sketch = workplane.sketch().move_to(-a, -a);
+        for (&(x, y), &mid) in corners.iter().zip(&midpoints) {
+            sketch = if t > 0.0 {
+                sketch.three_point_arc(mid, (x, y))
+            } else {
+                sketch.line_to(x, y)
+            };
+        }
+
+        sketch.wire()
+    }
+
+    #[test]
+    fn profile_fn_morphs_square_into_circle() {
+        const RADIUS: f64 = 50.0;
+        const HALF_SIDE: f64 = 5.0;
+
+        // A quarter turn from the origin, heading up Z, to (50, 0, 50),
+        // heading along X.
+        let diagonal = RADIUS * (1.0 - 0.5f64.sqrt());
+        let spine = Wire::from_edges([&Edge::arc(
+            Point3::origin(),
+            point![diagonal, 0.0, RADIUS - diagonal],
+            point![RADIUS, 0.0, RADIUS],
+        )])
+        .unwrap();
+
+        let shape = from_profile_fn(&spine, 6, |t, workplane| {
+            square_to_circle(workplane, HALF_SIDE, t)
+        })
+        .unwrap();
+
+        assert!(shape.is_closed_solid());
+        assert!(shape.free_edges().is_empty());
+
+        // The start is the square, in the XY plane.
+        let start = face_corners(&shape, Direction::NegZ);
+        assert_eq!(start.len(), 4);
+        for corner in &start {
+            assert!((corner.x.abs() - HALF_SIDE).abs() < 1.0e-6, "{corner:?}");
+            assert!((corner.y.abs() - HALF_SIDE).abs() < 1.0e-6, "{corner:?}");
+            assert!(corner.z.abs() < 1.0e-6, "{corner:?}");
+        }
+
+        // The end is the circle through the square's corners, facing along X.
+        let end = shape.faces().farthest(Direction::PosX);
+        let circle_radius = HALF_SIDE * 2.0f64.sqrt();
+        let center = point![RADIUS, 0.0, RADIUS];
+
+        assert!((end.center_of_mass() - center).norm() < 1.0e-3);
+        assert!(
+            (end.surface_area() - std::f64::consts::PI * circle_radius.powi(2)).abs() < 1.0e-3,
+            "{}",
+            end.surface_area()
+        );
+    }
+
+    #[test]
+    fn profile_fn_error_names_station() {
+        let spine =
+            Wire::from_edges([&Edge::segment(Point3::origin(), point![0.0, 0.0, 20.0])]).unwrap();
+
+        let result = from_profile_fn(&spine, 5, |t, workplane| {
+            if t > 0.6 {
+                Err(Error::NotDone)
+            } else {
+                workplane.rect(4.0, 4.0)
+            }
+        });
+
+        assert!(matches!(
+            result,
+            Err(Error::ProfileFailed { station: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn open_profile_shell_has_free_boundary() {
+        let profile = Wire::from_edges([&Edge::segment(
+            point![-5.0, 0.0, 0.0],
+            point![5.0, 0.0, 0.0],
+        )])
+        .unwrap();
+        let spine =
+            Wire::from_edges([&Edge::segment(Point3::origin(), point![0.0, 0.0, 20.0])]).unwrap();
+
+        let builder = PipeShellBuilder::new(&profile, &spine);
+        assert_eq!(builder.simulate(5).unwrap().len(), 5);
+        assert!(matches!(
+            PipeShellBuilder::new(&profile, &spine).build_solid(CapStyle::Planar),
+            Err(Error::OpenProfile)
+        ));
+
+        let shell = Shape::from(builder.build_shell().unwrap());
+
+        // A single swept segment is one quad, bounded entirely by free edges.
+        assert_eq!(shell.faces().count(), 1);
+        assert_eq!(shell.free_edges().len(), 4);
+    }
+}
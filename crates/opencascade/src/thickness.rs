@@ -0,0 +1,223 @@
+use crate::primitives::Shape;
+use crate::Error;
+use nalgebra::Point3;
+use nalgebra::Vector3;
+
+/// Where `Shape::wall_thickness` samples the surface of a shape.
+#[derive(Debug, Copy, Clone)]
+pub struct ThicknessSampling {
+    /// The mesh deflection. Samples are taken on the mesh triangles, so
+    /// on curved faces they may be this far from the real surface.
+    pub deflection: f64,
+    /// The rough distance between samples. Mesh triangles bigger than this
+    /// are subdivided, so flat faces are sampled as densely as curved ones.
+    pub spacing: f64,
+}
+
+impl Default for ThicknessSampling {
+    fn default() -> Self {
+        Self {
+            deflection: 0.01,
+            spacing: 1.0,
+        }
+    }
+}
+
+impl ThicknessSampling {
+    pub fn with_spacing(spacing: f64) -> Self {
+        Self {
+            spacing,
+            ..Default::default()
+        }
+    }
+}
+
+/// The wall thickness at samples over the surface of a shape, made with
+/// `Shape::wall_thickness`.
+#[derive(Debug, Clone, Default)]
+pub struct ThicknessReport {
+    /// Each sample point, with the thickness of the wall behind it.
+    pub samples: Vec<(Point3<f64>, f64)>,
+}
+
+impl ThicknessReport {
+    pub fn min(&self) -> Option<f64> {
+        self.samples
+            .iter()
+            .map(|&(_, thickness)| thickness)
+            .reduce(f64::min)
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let total: f64 = self.samples.iter().map(|&(_, thickness)| thickness).sum();
+
+        Some(total / self.samples.len() as f64)
+    }
+
+    /// The samples where the wall is thinner than `threshold`.
+    pub fn below(&self, threshold: f64) -> Vec<(Point3<f64>, f64)> {
+        self.samples
+            .iter()
+            .copied()
+            .filter(|&(_, thickness)| thickness < threshold)
+            .collect()
+    }
+}
+
+impl Shape {
+    /// Measure how thick the walls of this solid are, by casting a ray into
+    /// it from sample points over its surface, against its own outward
+    /// normal, and taking the distance to the first face the ray hits on
+    /// the other side. Rays are cast against the exact faces, not the mesh.
+    ///
+    /// Samples whose rays hit nothing, as on the faces of open shells, are
+    /// left out of the report. The deflection and spacing of `sampling`
+    /// must be positive.
+    pub fn wall_thickness(&self, sampling: ThicknessSampling) -> Result<ThicknessReport, Error> {
+        let ThicknessSampling {
+            deflection,
+            spacing,
+        } = sampling;
+        if deflection.is_nan() || deflection <= 0.0 {
+            return Err(Error::InvalidTolerance {
+                tolerance: deflection,
+            });
+        }
+        if spacing.is_nan() || spacing <= 0.0 {
+            return Err(Error::InvalidSpacing { spacing });
+        }
+
+        let mesh = self.mesh_with_tolerance(sampling.deflection)?;
+
+        // Rays start on the mesh rather than on the faces, so they can hit
+        // the face they start from up to the deflection away.
+        let self_hit_distance = 2.0 * sampling.deflection;
+
+        let mut samples = vec![];
+
+        for triangle in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i]]);
+
+            // Triangles are wound counter-clockwise seen from outside.
+            let Some(inward) = (c - a).cross(&(b - a)).try_normalize(f64::EPSILON) else {
+                continue;
+            };
+
+            for point in triangle_samples(a, b, c, sampling.spacing) {
+                let thickness = self
+                    .faces_along_line(point, inward)
+                    .into_iter()
+                    .map(|hit| hit.t)
+                    .filter(|&t| t > self_hit_distance)
+                    .reduce(f64::min);
+
+                if let Some(thickness) = thickness {
+                    samples.push((point, thickness));
+                }
+            }
+        }
+
+        Ok(ThicknessReport { samples })
+    }
+}
+
+/// Points spread evenly over the triangle `a`, `b`, `c`, no further than
+/// about `spacing` apart: the centers of the triangles of a grid which
+/// splits each side into equal pieces. `spacing` must be positive, or the
+/// grid has no end.
+fn triangle_samples(
+    a: Point3<f64>,
+    b: Point3<f64>,
+    c: Point3<f64>,
+    spacing: f64,
+) -> impl Iterator<Item = Point3<f64>> {
+    let longest = [(b - a).norm(), (c - b).norm(), (a - c).norm()]
+        .into_iter()
+        .fold(0.0, f64::max);
+    let divisions = ((longest / spacing).ceil() as usize).max(1);
+
+    let (u, v): (Vector3<f64>, Vector3<f64>) = (b - a, c - a);
+    let at = move |i: f64, j: f64| a + u * (i / divisions as f64) + v * (j / divisions as f64);
+
+    (0..divisions).flat_map(move |i| {
+        (0..divisions - i).flat_map(move |j| {
+            let (i, j) = (i as f64, j as f64);
+            let upward = at(i + 1.0 / 3.0, j + 1.0 / 3.0);
+            let downward =
+                (i + j + 2.0 <= divisions as f64).then(|| at(i + 2.0 / 3.0, j + 2.0 / 3.0));
+
+            std::iter::once(upward).chain(downward)
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn shelled_box() {
+        let block = Shape::box_centered(20.0, 20.0, 20.0);
+        let top = block
+            .faces()
+            .find(|face| face.normal_at_center().z > 0.99)
+            .unwrap();
+        let shelled = block.hollow(-2.0, [top]);
+
+        let report = shelled
+            .wall_thickness(ThicknessSampling::with_spacing(2.0))
+            .unwrap();
+
+        let min = report.min().unwrap();
+        assert!((min - 2.0).abs() < 1.0e-3, "{min}");
+        assert!(report.mean().unwrap() >= min);
+        assert!(report.below(1.5).is_empty());
+        assert!(!report.below(2.5).is_empty());
+    }
+
+    #[test]
+    fn samples_cover_triangles() {
+        let (a, b, c) = (
+            Point3::origin(),
+            Point3::new(4.0, 0.0, 0.0),
+            Point3::new(0.0, 4.0, 0.0),
+        );
+
+        // Each side in 4, so 16 samples.
+        let samples: Vec<_> = triangle_samples(a, b, c, 1.5).collect();
+        assert_eq!(samples.len(), 16);
+        assert!(samples
+            .iter()
+            .all(|p| p.x > 0.0 && p.y > 0.0 && p.x + p.y < 4.0));
+
+        assert_eq!(triangle_samples(a, b, c, 10.0).count(), 1);
+    }
+
+    #[test]
+    fn sampling_must_be_positive() {
+        let block = Shape::box_centered(10.0, 10.0, 10.0);
+
+        for spacing in [0.0, -1.0, f64::NAN] {
+            let sampling = ThicknessSampling::with_spacing(spacing);
+            assert!(matches!(
+                block.wall_thickness(sampling),
+                Err(Error::InvalidSpacing { .. })
+            ));
+        }
+
+        for deflection in [0.0, -0.01, f64::NAN] {
+            let sampling = ThicknessSampling {
+                deflection,
+                ..Default::default()
+            };
+            assert!(matches!(
+                block.wall_thickness(sampling),
+                Err(Error::InvalidTolerance { .. })
+            ));
+        }
+    }
+}
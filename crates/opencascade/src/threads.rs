@@ -0,0 +1,408 @@
+//! ISO metric screw threads (60° thread form), for modelling bolts, nuts and
+//! threaded holes.
+//!
+//! All threads are built in phase with their origin: the helix passes through
+//! angle zero at every whole pitch along the axis. A bolt and a nut therefore
+//! mesh when their origins are a whole number of pitches apart.
+
+use crate::primitives::DatumAxis;
+use crate::primitives::Edge;
+use crate::primitives::Face;
+use crate::primitives::Shape;
+use crate::primitives::Wire;
+use crate::sweep::FrameMode;
+use crate::sweep::PipeShellBuilder;
+use crate::Error;
+use crate::TandR;
+use crate::Z_NORMAL;
+use nalgebra::point;
+use nalgebra::Point3;
+use nalgebra::UnitVector3;
+use nalgebra::Vector3;
+
+/// Number of points per turn used to approximate the helix.
+const HELIX_SAMPLES_PER_TURN: usize = 36;
+
+/// How much room to leave between mating threads, e.g. to account for the
+/// inaccuracy of 3D printers.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ThreadFit {
+    /// The basic ISO profile, without any clearance.
+    Exact,
+    Tight,
+    Normal,
+    Loose,
+}
+
+impl ThreadFit {
+    /// The gap left between each flank of the thread and the basic ISO
+    /// profile, in mm. Mating threads end up twice this far apart.
+    pub fn clearance(&self) -> f64 {
+        match self {
+            Self::Exact => 0.0,
+            Self::Tight => 0.05,
+            Self::Normal => 0.1,
+            Self::Loose => 0.2,
+        }
+    }
+}
+
+/// The cross section of a thread tooth, measured radially.
+struct ToothProfile {
+    core_radius: f64,
+    tip_radius: f64,
+    core_half_width: f64,
+    tip_half_width: f64,
+}
+
+impl ToothProfile {
+    /// The profile of a thread `length` long, checking the thread can be
+    /// built.
+    fn new(
+        diameter: f64,
+        pitch: f64,
+        length: f64,
+        external: bool,
+        clearance: f64,
+    ) -> Result<Self, Error> {
+        let invalid = |reason| Err(Error::InvalidThread { reason });
+
+        // Written so that NaNs fail every check.
+        if !diameter.is_finite() || diameter <= 0.0 {
+            return invalid("the diameter must be positive");
+        }
+        if !pitch.is_finite() || pitch <= 0.0 {
+            return invalid("the pitch must be positive");
+        }
+
+        // Height of the fundamental triangle.
+        let h = pitch * 3.0f64.sqrt() / 2.0;
+        let minor_diameter = diameter - 2.0 * (5.0 * h / 8.0);
+
+        // Moving a 30° flank `clearance` along its normal changes the
+        // width of the tooth by this much, per side.
+        let flank_shift =
+            clearance * (1.0 / 30.0f64.to_radians().cos() - 30.0f64.to_radians().tan());
+
+        let profile = if external {
+            Self {
+                core_radius: minor_diameter / 2.0 - clearance,
+                tip_radius: diameter / 2.0 - clearance,
+                core_half_width: 3.0 * pitch / 8.0 - flank_shift,
+                tip_half_width: (pitch / 16.0 - flank_shift).max(pitch / 100.0),
+            }
+        } else {
+            Self {
+                core_radius: minor_diameter / 2.0 + clearance,
+                tip_radius: diameter / 2.0 + clearance,
+                core_half_width: 3.0 * pitch / 8.0 + flank_shift,
+                tip_half_width: pitch / 16.0 + flank_shift,
+            }
+        };
+
+        if profile.root_radius() <= 0.0 {
+            return invalid("the diameter is too small for the depth of the thread");
+        }
+        // The ends are chamfered or countersunk down to the depth of the
+        // thread.
+        if !length.is_finite() || length < 2.0 * profile.depth() {
+            return invalid("the thread must be at least twice as long as it is deep");
+        }
+
+        Ok(profile)
+    }
+
+    /// The depth of the thread, from core to tip.
+    fn depth(&self) -> f64 {
+        self.tip_radius - self.core_radius
+    }
+
+    /// How far the tooth is sunk into the core, so the union is clean.
+    fn root_radius(&self) -> f64 {
+        self.core_radius - self.depth() * 0.25
+    }
+}
+
+/// Build an ISO metric thread of the given nominal `diameter` and `pitch`,
+/// running `length` along +Z from the origin.
+///
+/// An `external` thread is a threaded rod with chamfered ends. An internal
+/// thread is the tool to subtract from a part to make a threaded hole: it
+/// extends past both ends of `length` and includes a countersink at each
+/// end, so no knife edges are left behind.
+///
+/// Fails with `Error::InvalidThread` unless the diameter and pitch are
+/// positive, the diameter is wider than the thread is deep, and the thread
+/// is long enough for the chamfers or countersinks at its ends.
+pub fn iso_metric(
+    diameter: f64,
+    pitch: f64,
+    length: f64,
+    external: bool,
+    tolerance_class: ThreadFit,
+) -> Result<Shape, Error> {
+    let profile = ToothProfile::new(
+        diameter,
+        pitch,
+        length,
+        external,
+        tolerance_class.clearance(),
+    )?;
+
+    if external {
+        let core = Shape::cylinder(Point3::origin(), profile.core_radius, Vector3::z(), length);
+        let tooth = helical_tooth(&profile, pitch, -pitch, length + pitch)?;
+        let thread: Shape = core.union(&tooth).into();
+
+        // Chamfer both ends at 45°, from the tip down to the core.
+        let depth = profile.depth();
+        let envelope = revolved_polygon([
+            point![0.0, 0.0],
+            point![profile.core_radius, 0.0],
+            point![profile.tip_radius, depth],
+            point![profile.tip_radius, length - depth],
+            point![profile.core_radius, length],
+            point![0.0, length],
+        ])?;
+
+        Ok(thread.intersect(&envelope)?.into())
+    } else {
+        let core = Shape::cylinder(
+            point![0.0, 0.0, -pitch],
+            profile.core_radius,
+            Vector3::z(),
+            length + 2.0 * pitch,
+        );
+        let tooth = helical_tooth(&profile, pitch, -2.0 * pitch, length + 2.0 * pitch)?;
+
+        // 90° countersinks, reaching just past the thread tip.
+        let flare = profile.tip_radius + pitch - profile.core_radius;
+        let depth = profile.depth();
+        let bottom_countersink = revolved_polygon([
+            point![0.0, -pitch],
+            point![profile.core_radius + flare, -pitch],
+            point![profile.core_radius, depth],
+            point![0.0, depth],
+        ])?;
+        let top_countersink = revolved_polygon([
+            point![0.0, length - depth],
+            point![profile.core_radius, length - depth],
+            point![profile.core_radius + flare, length + pitch],
+            point![0.0, length + pitch],
+        ])?;
+
+        let tool: Shape = core.union(&tooth).into();
+        let tool: Shape = tool.union(&bottom_countersink).into();
+
+        Ok(tool.union(&top_countersink).into())
+    }
+}
+
+/// Replace the part of `rod` within `length` of `origin` along `direction`
+/// with an external ISO metric thread. The rod should be `diameter` across
+/// there, as material outside it is left alone.
+pub fn add_external_thread(
+    rod: &Shape,
+    origin: Point3<f64>,
+    direction: Vector3<f64>,
+    diameter: f64,
+    pitch: f64,
+    length: f64,
+    fit: ThreadFit,
+) -> Result<Shape, Error> {
+    let placement = axis_placement(origin, direction);
+    let profile = ToothProfile::new(diameter, pitch, length, true, fit.clearance())?;
+
+    // Remove the material of the rod around the threaded section, out to
+    // the major radius.
+    let mut outside = Shape::cylinder(Point3::origin(), diameter / 2.0, Vector3::z(), length);
+    let inside = Shape::cylinder(Point3::origin(), profile.core_radius, Vector3::z(), length);
+    outside = outside.subtract(&inside)?;
+    outside.transform(&placement);
+
+    let mut thread = iso_metric(diameter, pitch, length, true, fit)?;
+    thread.transform(&placement);
+
+    Ok(rod.subtract(&outside)?.union(&thread).into())
+}
+
+/// Cut an internal ISO metric thread into `target`, starting at `origin` and
+/// running `length` along `direction`.
+pub fn cut_internal_thread(
+    target: &Shape,
+    origin: Point3<f64>,
+    direction: Vector3<f64>,
+    diameter: f64,
+    pitch: f64,
+    length: f64,
+    fit: ThreadFit,
+) -> Result<Shape, Error> {
+    let mut tool = iso_metric(diameter, pitch, length, false, fit)?;
+    tool.transform(&axis_placement(origin, direction));
+
+    target.subtract(&tool)
+}
+
+fn axis_placement(origin: Point3<f64>, direction: Vector3<f64>) -> TandR<f64> {
+    TandR::from_rotation_between(&Z_NORMAL, &UnitVector3::new_normalize(direction))
+        .translation(origin.coords)
+}
+
+/// Sweep the tooth profile along a helix from `z_start` to `z_end`. The
+/// start is expected to be a whole number of pitches from the origin.
+fn helical_tooth(
+    profile: &ToothProfile,
+    pitch: f64,
+    z_start: f64,
+    z_end: f64,
+) -> Result<Shape, Error> {
+    if !(pitch > 0.0 && z_end > z_start) {
+        return Err(Error::InvalidThread {
+            reason: "the helix must have a positive pitch and length",
+        });
+    }
+
+    let turns = (z_end - z_start) / pitch;
+    let samples = (turns * HELIX_SAMPLES_PER_TURN as f64).ceil() as usize;
+
+    let helix_points = (0..=samples).map(|i| {
+        let turn = turns * i as f64 / samples as f64;
+        let angle = turn * std::f64::consts::TAU;
+
+        point![
+            profile.core_radius * angle.cos(),
+            profile.core_radius * angle.sin(),
+            z_start + turn * pitch
+        ]
+    });
+    let helix = Wire::from_edges([&Edge::spline_from_points(helix_points, None)])?;
+
+    let root_radius = profile.root_radius();
+    let tooth = Wire::from_ordered_points([
+        point![root_radius, 0.0, z_start - profile.core_half_width],
+        point![profile.core_radius, 0.0, z_start - profile.core_half_width],
+        point![profile.tip_radius, 0.0, z_start - profile.tip_half_width],
+        point![profile.tip_radius, 0.0, z_start + profile.tip_half_width],
+        point![profile.core_radius, 0.0, z_start + profile.core_half_width],
+        point![root_radius, 0.0, z_start + profile.core_half_width],
+    ])?;
+
+    PipeShellBuilder::new(&tooth, &helix)
+        .frame_mode(FrameMode::Binormal(Z_NORMAL))
+        .build()
+}
+
+/// Revolve a closed polygon, given as (radius, z) pairs, around the Z axis.
+fn revolved_polygon(
+    points: impl IntoIterator<Item = nalgebra::Point2<f64>>,
+) -> Result<Shape, Error> {
+    let wire = Wire::from_ordered_points(points.into_iter().map(|p| point![p.x, 0.0, p.y]))?;
+    let face = Face::from_wire(&wire)?;
+
+    Ok(face.revolve_about(DatumAxis::z(), None).into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DIAMETER: f64 = 8.0;
+    const PITCH: f64 = 1.25;
+
+    fn bolt_and_nut() -> (Shape, Shape) {
+        let rod = Shape::cylinder(Point3::origin(), DIAMETER / 2.0, Vector3::z(), 20.0);
+        let bolt = add_external_thread(
+            &rod,
+            Point3::origin(),
+            Vector3::z(),
+            DIAMETER,
+            PITCH,
+            20.0,
+            ThreadFit::Normal,
+        )
+        .unwrap();
+
+        // The nut starts 4 pitches up the bolt, so the threads are in phase.
+        let nut_origin = point![0.0, 0.0, 4.0 * PITCH];
+        let blank = Shape::box_from_corners(
+            point![-7.0, -7.0, nut_origin.z],
+            point![7.0, 7.0, nut_origin.z + 8.0],
+        );
+        let nut = cut_internal_thread(
+            &blank,
+            nut_origin,
+            Vector3::z(),
+            DIAMETER,
+            PITCH,
+            8.0,
+            ThreadFit::Normal,
+        )
+        .unwrap();
+
+        (bolt, nut)
+    }
+
+    #[test]
+    fn m8_bolt_and_nut_mesh() {
+        let (bolt, nut) = bolt_and_nut();
+
+        let overlap = bolt.intersect(&nut).unwrap();
+        assert!(
+            overlap.volume() < 1.0e-3,
+            "Bolt and nut overlap by {}",
+            overlap.volume()
+        );
+    }
+
+    #[test]
+    fn m8_bolt_is_m8_across() {
+        let (bolt, _) = bolt_and_nut();
+        let bounds = bolt.bounding_box();
+        let size = bounds.max() - bounds.min();
+
+        // The tips of the thread sit the clearance inside the major radius.
+        let across = DIAMETER - 2.0 * ThreadFit::Normal.clearance();
+        assert!((size.x - across).abs() < 0.05, "{size}");
+        assert!((size.y - across).abs() < 0.05, "{size}");
+        assert!((size.z - 20.0).abs() < 0.05, "{size}");
+    }
+
+    #[test]
+    fn invalid_threads() {
+        for (diameter, pitch, length) in [
+            (8.0, 0.0, 20.0),
+            (8.0, -1.25, 20.0),
+            (8.0, f64::NAN, 20.0),
+            (0.0, 1.25, 20.0),
+            (f64::INFINITY, 1.25, 20.0),
+            // Deeper than its radius.
+            (1.0, 1.25, 20.0),
+            // Too short for the chamfers.
+            (8.0, 1.25, 1.0),
+            (8.0, 1.25, f64::NAN),
+        ] {
+            for external in [true, false] {
+                assert!(
+                    matches!(
+                        iso_metric(diameter, pitch, length, external, ThreadFit::Normal),
+                        Err(Error::InvalidThread { .. })
+                    ),
+                    "{diameter} {pitch} {length}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn m8_bolt_and_nut_out_of_phase_interfere() {
+        let (mut bolt, nut) = bolt_and_nut();
+        bolt.transform(&TandR::default().translation(Vector3::z() * PITCH / 2.0));
+
+        let overlap = bolt.intersect(&nut).unwrap();
+        assert!(
+            overlap.volume() > 1.0,
+            "Bolt and nut only overlap by {}",
+            overlap.volume()
+        );
+    }
+}
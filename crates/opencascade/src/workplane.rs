@@ -1,4 +1,10 @@
+use crate::angle::Angle;
+use crate::context::ModelContext;
+use crate::convert::IntoUnitVector3;
 use crate::primitives::Edge;
+use crate::primitives::Extent;
+use crate::primitives::Face;
+use crate::primitives::Shape;
 use crate::primitives::Wire;
 use crate::Error;
 use crate::TandR;
@@ -7,6 +13,7 @@ use crate::X_NORMAL;
 use crate::Y_NORMAL;
 use nalgebra::point;
 use nalgebra::Point3;
+use nalgebra::Rotation3;
 use nalgebra::UnitQuaternion;
 use nalgebra::UnitVector3;
 use nalgebra::Vector3;
@@ -60,59 +67,117 @@ impl Plane {
             Self::YX => TandR::from_rotation_between(&BASE_NORMAL, &-BASE_NORMAL),
             Self::ZY => TandR::from_rotation_between(&BASE_NORMAL, &-X_NORMAL),
             Self::XZ => TandR::from_rotation_between(&BASE_NORMAL, &-Y_NORMAL),
-            _ => todo!(),
+            Self::Custom { x_dir, normal_dir } => {
+                // Make sure the basis is orthonormal, even if `x_dir` isn't
+                // exactly perpendicular to the normal.
+                let y_dir = UnitVector3::new_normalize(normal_dir.cross(x_dir));
+                let x_dir = UnitVector3::new_normalize(y_dir.cross(normal_dir));
+
+                let rotation = Rotation3::from_basis_unchecked(&[
+                    x_dir.into_inner(),
+                    y_dir.into_inner(),
+                    normal_dir.into_inner(),
+                ]);
+
+                TandR::new(
+                    Vector3::zeros(),
+                    UnitQuaternion::from_rotation_matrix(&rotation),
+                )
+            }
         }
     }
 }
 
+/// A plane to build geometry on. Coordinates and lengths of what is built
+/// on a workplane, and on sketches on it, are in the unit of its
+/// `ModelContext`. Where the workplane itself is placed, as set and read by
+/// its translation methods, `origin` and `transform`, is in millimeters,
+/// like every shape.
 #[derive(Debug, Clone)]
 pub struct Workplane {
     transform: TandR<f64>,
+    context: ModelContext,
 }
 
 impl Workplane {
     pub fn new(x_dir: UnitVector3<f64>, normal_dir: UnitVector3<f64>) -> Self {
         Self {
             transform: Plane::Custom { x_dir, normal_dir }.transform(),
+            context: ModelContext::current(),
         }
     }
 
+    /// Like `new`, with directions which needn't be normalized. A zero or
+    /// non-finite direction is an `Error::InvalidDirection`.
+    pub fn from_vectors(
+        x_dir: impl IntoUnitVector3,
+        normal_dir: impl IntoUnitVector3,
+    ) -> Result<Self, Error> {
+        Ok(Self::new(
+            x_dir.into_unit_vector3()?,
+            normal_dir.into_unit_vector3()?,
+        ))
+    }
+
     pub fn xy() -> Self {
         Self {
             transform: Plane::XY.transform(),
+            context: ModelContext::current(),
         }
     }
 
     pub fn yz() -> Self {
         Self {
             transform: Plane::YZ.transform(),
+            context: ModelContext::current(),
         }
     }
 
     pub fn zx() -> Self {
         Self {
             transform: Plane::ZX.transform(),
+            context: ModelContext::current(),
         }
     }
 
     pub fn xz() -> Self {
         Self {
             transform: Plane::XZ.transform(),
+            context: ModelContext::current(),
         }
     }
 
     pub fn zy() -> Self {
         Self {
             transform: Plane::ZY.transform(),
+            context: ModelContext::current(),
         }
     }
 
     pub fn yx() -> Self {
         Self {
             transform: Plane::YX.transform(),
+            context: ModelContext::current(),
         }
     }
 
+    /// This workplane, taking coordinates and lengths in the unit of
+    /// `context` rather than the default context's.
+    pub fn with_context(mut self, context: ModelContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    pub fn context(&self) -> &ModelContext {
+        &self.context
+    }
+
+    /// The transform from coordinates on this workplane, in millimeters, to
+    /// world coordinates.
+    pub fn transform(&self) -> TandR<f64> {
+        self.transform
+    }
+
     pub fn origin(&self) -> Vector3<f64> {
         self.transform.translation
     }
@@ -137,14 +202,18 @@ impl Workplane {
         self.transform.rotation_quat = rotation_quat * self.transform.rotation_quat;
     }
 
+    /// Move this workplane's origin to `pos`, in millimeters.
     pub fn set_translation(&mut self, pos: Vector3<f64>) {
         self.transform.translation = pos;
     }
 
+    /// Move this workplane by `offset`, in world directions and millimeters.
     pub fn translate_by(&mut self, offset: Vector3<f64>) {
         self.transform.translation += offset;
     }
 
+    /// This workplane, moved by the translation of `tandr`, in millimeters,
+    /// and turned by its rotation.
     pub fn transformed(&self, tandr: TandR<f64>) -> Self {
         let mut new = self.clone();
         new.transform.translation += tandr.translation;
@@ -154,10 +223,10 @@ impl Workplane {
         new
     }
 
+    /// This workplane, moved by `offset` along its own axes, in millimeters.
     pub fn translated(&self, offset: Vector3<f64>) -> Self {
         let mut new = self.clone();
-        let new_origin = new.to_world_pos(offset.into());
-        new.transform.translation = new_origin.coords;
+        new.transform.translation += new.transform.rotation_quat * offset;
 
         new
     }
@@ -169,12 +238,86 @@ impl Workplane {
         new
     }
 
+    /// The world position of `pos`, which is in this workplane's
+    /// coordinates, in the unit of its context.
     pub fn to_world_pos(&self, pos: Point3<f64>) -> Point3<f64> {
-        self.transform.transform_point(pos)
+        let mut positions = [pos];
+        self.to_world_positions(&mut positions);
+
+        positions[0]
+    }
+
+    /// Like `to_world_pos`, for all of `positions` in place.
+    pub fn to_world_positions(&self, positions: &mut [Point3<f64>]) {
+        let scale = self.context.to_millimeters(1.0);
+        for pos in positions.iter_mut() {
+            *pos *= scale;
+        }
+
+        self.transform.transform_points(positions);
     }
 
+    /// The position of `pos`, a world position, in this workplane's
+    /// coordinates, in the unit of its context.
     pub fn to_local_pos(&self, pos: Point3<f64>) -> Point3<f64> {
-        self.transform.inverse().transform_point(pos)
+        self.transform.inverse().transform_point(pos) * self.context.from_millimeters(1.0)
+    }
+
+    /// The line where this workplane's plane meets `other`'s, as the point
+    /// on it nearest this workplane's origin, in world coordinates, and its
+    /// direction. `None` if the planes are parallel, to within this
+    /// workplane's angular tolerance.
+    pub fn intersection_line(&self, other: &Workplane) -> Option<(Point3<f64>, UnitVector3<f64>)> {
+        let (normal, other_normal) = (self.normal(), other.normal());
+        let direction = normal.cross(&other_normal);
+
+        if direction.norm() <= self.context.angular_tolerance.radians().sin() {
+            return None;
+        }
+
+        // Any point on the line, from the planes' equations `n . x = h`.
+        let (h1, h2) = (
+            normal.dot(&self.origin()),
+            other_normal.dot(&other.origin()),
+        );
+        let cos = normal.dot(&other_normal);
+        let on_line = ((h1 - h2 * cos) * normal.into_inner()
+            + (h2 - h1 * cos) * other_normal.into_inner())
+            / direction.norm_squared();
+
+        let direction = UnitVector3::new_normalize(direction);
+        let origin = Point3::from(self.origin());
+        let nearest = Point3::from(on_line)
+            + direction.into_inner() * direction.dot(&(origin.coords - on_line));
+
+        Some((nearest, direction))
+    }
+
+    /// The angle between the normals of this workplane and `other`, from 0
+    /// for planes facing the same way to 180 degrees for planes facing each
+    /// other.
+    pub fn angle_to(&self, other: &Workplane) -> Angle {
+        Angle::Radians(self.normal().angle(&other.normal()))
+    }
+
+    /// The plane halfway between the planes of `a` and `b`, through the line
+    /// they meet along, with its normal halfway between theirs. Mitering two
+    /// pieces cuts both along it. The workplane's origin is the point on the
+    /// line nearest `a`'s origin, and its x direction is along the line.
+    ///
+    /// Parallel planes have no line to meet along, and give
+    /// `Error::ParallelPlanes` with the distance between them.
+    pub fn bisecting(a: &Workplane, b: &Workplane) -> Result<Workplane, Error> {
+        let Some((point, direction)) = a.intersection_line(b) else {
+            let distance = a.normal().dot(&(b.origin() - a.origin()));
+            return Err(Error::ParallelPlanes { distance });
+        };
+
+        let normal = a.normal().into_inner() + b.normal().into_inner();
+        let mut bisector = Workplane::from_vectors(direction, normal)?.with_context(a.context);
+        bisector.set_translation(point.coords);
+
+        Ok(bisector)
     }
 
     pub fn rect(&self, width: f64, height: f64) -> Result<Wire, Error> {
@@ -197,7 +340,7 @@ impl Workplane {
     pub fn circle(&self, x: f64, y: f64, radius: f64) -> Result<Wire, Error> {
         let center = self.to_world_pos(point![x, y, 0.0]);
 
-        let circle = Edge::circle(center, self.normal(), radius);
+        let circle = Edge::circle(center, self.normal(), self.context.to_millimeters(radius))?;
 
         Wire::from_edges([&circle])
     }
@@ -248,6 +391,72 @@ impl Sketch {
         self
     }
 
+    /// Add a line from the cursor through each of `points` in turn, like
+    /// chained calls to `line_to`.
+    pub fn extend_lines(mut self, points: &[(f64, f64)]) -> Self {
+        let mut world_points: Vec<_> = points.iter().map(|&(x, y)| point![x, y, 0.0]).collect();
+        self.workplane.to_world_positions(&mut world_points);
+
+        if self.first_point.is_none() && !world_points.is_empty() {
+            self.first_point = Some(self.cursor);
+        }
+
+        self.edges.reserve(world_points.len());
+
+        for new_point in world_points {
+            self.edges.push(Edge::segment(self.cursor, new_point));
+            self.cursor = new_point;
+        }
+
+        self
+    }
+
+    /// Add `edges`, which are already in global coordinates, to the end of
+    /// the sketch. The cursor moves to the end of the last edge. Each edge
+    /// must start where the one before it ends, and the first where the
+    /// cursor is if the sketch has edges already, to within the linear
+    /// tolerance of the workplane's `ModelContext`. A gap is an
+    /// `Error::EdgesApart` naming the edges either side of it, counting the
+    /// sketch's edges, with the gap in the context's unit.
+    pub fn extend_edges(mut self, edges: Vec<Edge>) -> Result<Self, Error> {
+        let (Some(first), Some(last)) = (edges.first(), edges.last()) else {
+            return Ok(self);
+        };
+
+        let context = self.workplane.context;
+        let tolerance = context.to_millimeters(context.linear_tolerance);
+        // Where each edge must start, with the sketch's own edges counted
+        // before these. An empty sketch starts wherever the first edge does.
+        let skip = usize::from(self.edges.is_empty());
+        let ends = (!self.edges.is_empty())
+            .then_some(self.cursor)
+            .into_iter()
+            .chain(edges.iter().map(Edge::end_point));
+
+        for ((i, edge), end) in edges.iter().enumerate().skip(skip).zip(ends) {
+            let gap = (edge.start_point() - end).norm();
+
+            if gap > tolerance {
+                let second = self.edges.len() + i;
+
+                return Err(Error::EdgesApart {
+                    first: second - 1,
+                    second,
+                    gap: context.from_millimeters(gap),
+                });
+            }
+        }
+
+        if self.first_point.is_none() {
+            self.first_point = Some(first.start_point());
+        }
+
+        self.cursor = last.end_point();
+        self.edges.extend(edges);
+
+        Ok(self)
+    }
+
     pub fn line_dx(mut self, dx: f64) -> Self {
         let cursor = self.workplane.to_local_pos(self.cursor);
         let new_point = self
@@ -317,4 +526,422 @@ impl Sketch {
         self.add_edge(new_edge);
         Wire::from_edges(&self.edges)
     }
+
+    /// Close the sketch without adding a line back to its start, healing
+    /// gaps of no more than `tolerance` between its edges, and between its
+    /// end and start, by moving their vertices together. This suits edges
+    /// from `extend_edges` whose ends only nearly meet, within a linear
+    /// tolerance set loose enough to let them in, where `close` would leave
+    /// a sliver of a line. A larger gap is an `Error::EdgesApart`
+    /// naming the edges either side of it, with the gap in the unit of the
+    /// workplane's `ModelContext`, like `tolerance`.
+    pub fn close_tolerant(self, tolerance: f64) -> Result<Wire, Error> {
+        let context = self.workplane.context;
+        let tolerance = context.to_millimeters(tolerance);
+
+        match Wire::from_edges_healed(&self.edges, tolerance, true) {
+            Ok((wire, _)) => Ok(wire),
+            Err(Error::EdgesApart { first, second, gap }) => Err(Error::EdgesApart {
+                first,
+                second,
+                gap: context.from_millimeters(gap),
+            }),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Close the sketch, and extrude it both ways along the workplane
+    /// normal, `height / 2.0` to each side, so the solid is centered on the
+    /// workplane.
+    pub fn extrude_symmetric(self, height: f64) -> Result<Shape, Error> {
+        self.extrude_two_sided(Extent::Symmetric(height))
+    }
+
+    /// Close the sketch, and extrude it to both sides of the workplane, as
+    /// far as `extent` says. The front is along the workplane normal. The
+    /// total height must be positive, or this returns
+    /// `Error::InvalidHeight`, with the height in the unit of the
+    /// workplane's `ModelContext`.
+    pub fn extrude_two_sided(self, extent: Extent) -> Result<Shape, Error> {
+        let normal = self.workplane.normal();
+        let context = self.workplane.context;
+        let extent = extent.scaled(context.to_millimeters(1.0));
+        let face = Face::from_wire(&self.close()?)?;
+
+        match face.extrude_along(normal, extent) {
+            Err(Error::InvalidHeight { height }) => Err(Error::InvalidHeight {
+                height: context.from_millimeters(height),
+            }),
+            result => result,
+        }
+    }
+}
+
+/// A `Sketch` on a planar face, made with `Face::sketch`, whose region is
+/// clipped to the face when it's closed.
+pub struct FaceSketch {
+    sketch: Sketch,
+    face: Face,
+}
+
+impl FaceSketch {
+    pub(crate) fn new(face: &Face) -> Self {
+        Self {
+            sketch: face.workplane().sketch(),
+            face: Face::from_face(&face.inner),
+        }
+    }
+
+    pub fn move_to(self, x: f64, y: f64) -> Self {
+        self.map(|sketch| sketch.move_to(x, y))
+    }
+
+    pub fn line_to(self, x: f64, y: f64) -> Self {
+        self.map(|sketch| sketch.line_to(x, y))
+    }
+
+    pub fn extend_lines(self, points: &[(f64, f64)]) -> Self {
+        self.map(|sketch| sketch.extend_lines(points))
+    }
+
+    pub fn extend_edges(self, edges: Vec<Edge>) -> Result<Self, Error> {
+        let Self { sketch, face } = self;
+
+        Ok(Self {
+            sketch: sketch.extend_edges(edges)?,
+            face,
+        })
+    }
+
+    pub fn line_dx(self, dx: f64) -> Self {
+        self.map(|sketch| sketch.line_dx(dx))
+    }
+
+    pub fn line_dy(self, dy: f64) -> Self {
+        self.map(|sketch| sketch.line_dy(dy))
+    }
+
+    pub fn line_dx_dy(self, dx: f64, dy: f64) -> Self {
+        self.map(|sketch| sketch.line_dx_dy(dx, dy))
+    }
+
+    pub fn arc(self, p1: (f64, f64), p2: (f64, f64), p3: (f64, f64)) -> Self {
+        self.map(|sketch| sketch.arc(p1, p2, p3))
+    }
+
+    pub fn three_point_arc(self, p2: (f64, f64), p3: (f64, f64)) -> Self {
+        self.map(|sketch| sketch.three_point_arc(p2, p3))
+    }
+
+    fn map(self, f: impl FnOnce(Sketch) -> Sketch) -> Self {
+        Self {
+            sketch: f(self.sketch),
+            face: self.face,
+        }
+    }
+
+    /// Close the sketch, if it isn't already, and clip the region it
+    /// encloses to the face. Sketches entirely off the face are an error
+    /// with the distance to it, in the unit of the workplane's
+    /// `ModelContext`, as are sketches which the face cuts into several
+    /// pieces.
+    pub fn close(self) -> Result<Face, Error> {
+        let Self { mut sketch, face } = self;
+
+        let Some(start_point) = sketch.first_point else {
+            return Err(Error::NotEnoughPoints);
+        };
+        let context = sketch.workplane.context;
+        if (sketch.cursor - start_point).norm() > context.to_millimeters(context.linear_tolerance) {
+            sketch.add_edge(Edge::segment(sketch.cursor, start_point));
+        }
+
+        let region = Shape::from(&Face::from_wire(&sketch.wire()?)?);
+        let face = Shape::from(&face);
+        let clipped: Vec<_> = region.intersect(&face)?.faces().collect();
+
+        match clipped.len() {
+            0 => {
+                let distance = region.distance_between(&face)?.unwrap_or(f64::INFINITY);
+                Err(Error::SketchOutsideFace {
+                    distance: context.from_millimeters(distance),
+                })
+            }
+            1 => Ok(clipped.into_iter().next().unwrap()),
+            pieces => Err(Error::SketchSplitByFace { pieces }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::context::Unit;
+
+    #[test]
+    fn bisecting_planes_at_sixty_degrees() {
+        let a = Workplane::xy().translated(Vector3::new(0.0, 0.0, 5.0));
+        let b = Workplane::xy()
+            .rotated(UnitQuaternion::from_axis_angle(
+                &Vector3::x_axis(),
+                60.0f64.to_radians(),
+            ))
+            .translated(Vector3::new(3.0, 0.0, 0.0));
+
+        assert!((a.angle_to(&b).degrees() - 60.0).abs() < 1.0e-9);
+
+        let (point, direction) = a.intersection_line(&b).unwrap();
+        assert!(direction.dot(&Vector3::x_axis()).abs() > 1.0 - 1.0e-9);
+        for plane in [&a, &b] {
+            assert!(plane.to_local_pos(point).z.abs() < 1.0e-9);
+        }
+
+        // The point given is the one nearest `a`'s origin.
+        assert!(point.x.abs() < 1.0e-9);
+
+        let bisector = Workplane::bisecting(&a, &b).unwrap();
+        assert!((bisector.angle_to(&a).degrees() - 30.0).abs() < 1.0e-9);
+        assert!((bisector.angle_to(&b).degrees() - 30.0).abs() < 1.0e-9);
+
+        // The bisector contains the intersection line.
+        for t in [-10.0, 0.0, 25.0] {
+            let on_line = point + direction.into_inner() * t;
+            assert!(bisector.to_local_pos(on_line).z.abs() < 1.0e-9);
+        }
+    }
+
+    #[test]
+    fn parallel_planes_have_no_bisector() {
+        let a = Workplane::xy();
+        let b = Workplane::xy().translated(Vector3::new(0.0, 0.0, 4.0));
+
+        assert!(a.intersection_line(&b).is_none());
+        assert!(matches!(
+            Workplane::bisecting(&a, &b),
+            Err(Error::ParallelPlanes { distance }) if (distance - 4.0).abs() < 1.0e-9
+        ));
+    }
+
+    #[test]
+    fn extend_lines_matches_line_to() {
+        let workplane = Workplane::new(
+            UnitVector3::new_normalize(Vector3::new(1.0, 1.0, 0.0)),
+            UnitVector3::new_normalize(Vector3::new(-1.0, 1.0, 1.0)),
+        )
+        .translated(Vector3::new(1.0, 2.0, 3.0));
+
+        let points: Vec<_> = (1..=50)
+            .map(|i| {
+                let angle = i as f64 * 0.1;
+                (angle.cos() * i as f64, angle.sin() * i as f64)
+            })
+            .collect();
+
+        let chained = points
+            .iter()
+            .fold(workplane.sketch().move_to(1.0, 0.0), |sketch, &(x, y)| {
+                sketch.line_to(x, y)
+            });
+        let bulk = workplane.sketch().move_to(1.0, 0.0).extend_lines(&points);
+
+        assert_eq!(chained.edges.len(), bulk.edges.len());
+        assert!((chained.cursor - bulk.cursor).norm() < 1.0e-9);
+
+        for (a, b) in chained.edges.iter().zip(&bulk.edges) {
+            assert!((a.start_point() - b.start_point()).norm() < 1.0e-9);
+            assert!((a.end_point() - b.end_point()).norm() < 1.0e-9);
+        }
+
+        let wire = bulk.close().unwrap();
+        assert_eq!(wire.edges().count(), points.len() + 1);
+    }
+
+    /// A circle of radius 2 around `(x, 0)` on the 10 x 6 rectangle around
+    /// the origin.
+    fn circle_on_rectangle(x: f64) -> Result<Face, Error> {
+        let rectangle = Face::from_wire(&Workplane::xy().rect(10.0, 6.0).unwrap()).unwrap();
+
+        rectangle
+            .sketch()?
+            .move_to(x - 2.0, 0.0)
+            .three_point_arc((x, 2.0), (x + 2.0, 0.0))
+            .three_point_arc((x, -2.0), (x - 2.0, 0.0))
+            .close()
+    }
+
+    #[test]
+    fn face_sketch_is_clipped() {
+        // Centered on the right edge, so half of it is on the face.
+        let clipped = circle_on_rectangle(5.0).unwrap();
+        let area = clipped.surface_area();
+        assert!((area - 2.0 * std::f64::consts::PI).abs() < 1.0e-6, "{area}");
+
+        let bounding_box = Shape::from(&clipped).bounding_box().max();
+        assert!(bounding_box.x < 5.0 + 1.0e-6);
+
+        // Entirely on the face, so nothing is clipped.
+        let whole = circle_on_rectangle(0.0).unwrap();
+        assert!((whole.surface_area() - 4.0 * std::f64::consts::PI).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn face_sketch_off_the_face() {
+        match circle_on_rectangle(20.0) {
+            Err(Error::SketchOutsideFace { distance }) => {
+                assert!((distance - 13.0).abs() < 1.0e-6, "{distance}");
+            }
+            _ => panic!("the sketch is off the face"),
+        }
+
+        // The distance is in the unit the sketch was drawn in.
+        let _meters = ModelContext::new(Unit::Meter).scoped();
+        match circle_on_rectangle(20.0) {
+            Err(Error::SketchOutsideFace { distance }) => {
+                assert!((distance - 13.0).abs() < 1.0e-6, "{distance}");
+            }
+            _ => panic!("the sketch is off the face"),
+        }
+    }
+
+    #[test]
+    fn sketch_extrude_symmetric() {
+        let mut workplane = Workplane::yz();
+        workplane.set_translation(Vector3::new(1.0, 2.0, 3.0));
+
+        let solid = workplane
+            .sketch()
+            .move_to(-2.0, -2.0)
+            .line_dx(4.0)
+            .line_dy(4.0)
+            .line_dx(-4.0)
+            .extrude_symmetric(6.0)
+            .unwrap();
+
+        // Centered on the workplane, which is the plane x = 1.
+        let bounds = solid.bounding_box();
+        assert!((bounds.min().x + 2.0).abs() < 1.0e-6, "{}", bounds.min());
+        assert!((bounds.max().x - 4.0).abs() < 1.0e-6, "{}", bounds.max());
+        assert_eq!(solid.faces().count(), 6);
+
+        for height in [0.0, -6.0] {
+            let extruded = workplane
+                .sketch()
+                .move_to(-2.0, -2.0)
+                .line_dx(4.0)
+                .line_dy(4.0)
+                .line_dx(-4.0)
+                .extrude_symmetric(height);
+            assert!(matches!(extruded, Err(Error::InvalidHeight { .. })));
+        }
+    }
+
+    /// The edges of a 20 x 10 rectangle, each starting 5e-5 away from the
+    /// corner the edge before it ends at.
+    fn perturbed_rectangle() -> Vec<Edge> {
+        let corners = [
+            point![0.0, 0.0, 0.0],
+            point![20.0, 0.0, 0.0],
+            point![20.0, 10.0, 0.0],
+            point![0.0, 10.0, 0.0],
+        ];
+        let nudges = [
+            Vector3::new(5.0e-5, 0.0, 0.0),
+            Vector3::new(0.0, -5.0e-5, 0.0),
+            Vector3::new(3.0e-5, 4.0e-5, 0.0),
+            Vector3::new(-4.0e-5, -3.0e-5, 0.0),
+        ];
+
+        (0..4)
+            .map(|i| Edge::segment(corners[i] + nudges[i], corners[(i + 1) % 4]))
+            .collect()
+    }
+
+    /// A workplane which takes points 1e-4 apart to be the same, so
+    /// `extend_edges` lets in the edges of `perturbed_rectangle`.
+    fn loose_workplane() -> Workplane {
+        Workplane::xy().with_context(ModelContext {
+            linear_tolerance: 1.0e-4,
+            ..ModelContext::default()
+        })
+    }
+
+    #[test]
+    fn extend_edges_rejects_gaps() {
+        match Workplane::xy().sketch().extend_edges(perturbed_rectangle()) {
+            Err(Error::EdgesApart { first, second, gap }) => {
+                assert_eq!((first, second), (0, 1));
+                assert!((gap - 5.0e-5).abs() < 1.0e-9, "{gap}");
+            }
+            other => panic!("expected the edges to be apart, got {:?}", other.err()),
+        }
+
+        // The first edge must start at the cursor of a sketch with edges.
+        let sketch = Workplane::xy().sketch().line_to(5.0, 0.0);
+        match sketch.extend_edges(vec![Edge::segment(
+            point![5.0, 1.0, 0.0],
+            point![5.0, 5.0, 0.0],
+        )]) {
+            Err(Error::EdgesApart { first, second, gap }) => {
+                assert_eq!((first, second), (0, 1));
+                assert!((gap - 1.0).abs() < 1.0e-9, "{gap}");
+            }
+            other => panic!("expected the edges to be apart, got {:?}", other.err()),
+        }
+
+        let wire = Workplane::xy()
+            .sketch()
+            .line_to(5.0, 0.0)
+            .extend_edges(vec![
+                Edge::segment(point![5.0, 0.0, 0.0], point![5.0, 5.0, 0.0]),
+                Edge::segment(point![5.0, 5.0, 0.0], point![0.0, 5.0, 0.0]),
+            ])
+            .unwrap()
+            .close()
+            .unwrap();
+        assert_eq!(wire.edges().count(), 4);
+    }
+
+    #[test]
+    fn close_tolerant_heals_perturbed_rectangle() {
+        let (healed, gap) = Wire::from_edges_healed(&perturbed_rectangle(), 1.0e-4, true).unwrap();
+        assert!((gap - 5.0e-5).abs() < 1.0e-9, "{gap}");
+        assert!(healed.is_closed());
+
+        let wire = loose_workplane()
+            .sketch()
+            .extend_edges(perturbed_rectangle())
+            .unwrap()
+            .close_tolerant(1.0e-4)
+            .unwrap();
+        assert!(wire.is_closed());
+        // The gaps were closed up by moving vertices, not bridged.
+        assert_eq!(wire.edges().count(), 4);
+
+        let solid = Shape::from(
+            Face::from_wire(&wire)
+                .unwrap()
+                .extrude(Vector3::new(0.0, 0.0, 5.0)),
+        );
+        assert!(
+            (solid.volume() - 1000.0).abs() < 1.0e-2,
+            "{}",
+            solid.volume()
+        );
+    }
+
+    #[test]
+    fn close_tolerant_rejects_wide_gap() {
+        match loose_workplane()
+            .sketch()
+            .extend_edges(perturbed_rectangle())
+            .unwrap()
+            .close_tolerant(1.0e-5)
+        {
+            Err(Error::EdgesApart { first, second, gap }) => {
+                assert_eq!((first, second), (0, 1));
+                assert!((gap - 5.0e-5).abs() < 1.0e-9, "{gap}");
+            }
+            other => panic!("expected the edges to be apart, got {:?}", other.err()),
+        }
+    }
 }